@@ -0,0 +1,26 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use wasm_game_of_life::{bench_universe, Engine};
+
+// cargo bench, the browser version of this is bench_tick()
+fn tick(c: &mut Criterion) {
+    let engines = [
+        ("naive", Engine::Naive),
+        ("packed", Engine::Packed),
+        ("simd", Engine::Simd),
+        ("parallel", Engine::Parallel),
+        ("hashlife", Engine::HashLife),
+    ];
+    let mut group = c.benchmark_group("tick");
+    for size in [64, 256].iter() {
+        for (name, engine) in engines.iter() {
+            group.bench_with_input(BenchmarkId::new(*name, size), size, |b, size| {
+                let mut universe = bench_universe(*engine, *size);
+                b.iter(|| universe.tick());
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, tick);
+criterion_main!(benches);
@@ -0,0 +1,34 @@
+use std::process::Command;
+
+// Passes the commit being built to the crate as GIT_COMMIT for build_info(). Builds from
+// outside a git checkout, like a published crate, can set GIT_COMMIT themselves or go
+// without.
+fn main() {
+    println!("cargo:rerun-if-env-changed=GIT_COMMIT");
+    if std::env::var_os("GIT_COMMIT").is_some() {
+        return;
+    }
+
+    let git = |arguments: &[&str]| {
+        Command::new("git")
+            .args(arguments)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| String::from_utf8(output.stdout).ok())
+            .map(|output| output.trim().to_string())
+    };
+    let commit = match git(&["rev-parse", "--short=12", "HEAD"]) {
+        Some(commit) => commit,
+        None => return,
+    };
+    println!("cargo:rustc-env=GIT_COMMIT={}", commit);
+
+    // rebuild when HEAD moves to another branch or the branch gets a new commit
+    if let Some(git_dir) = git(&["rev-parse", "--absolute-git-dir"]) {
+        println!("cargo:rerun-if-changed={}/HEAD", git_dir);
+        if let Some(branch) = git(&["symbolic-ref", "-q", "HEAD"]) {
+            println!("cargo:rerun-if-changed={}/{}", git_dir, branch);
+        }
+    }
+}
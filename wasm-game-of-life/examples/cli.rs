@@ -0,0 +1,123 @@
+// The game in a terminal, with nothing from the browser involved:
+//
+//     cargo run --example cli
+//
+// Arrow keys move the cursor, space toggles the cell under it, p pauses and resumes,
+// n steps once while paused, r fills the board randomly, c clears it and q quits.
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use wasm_game_of_life::{BoundaryMode, Cell, Universe};
+
+const TICK_INTERVAL: Duration = Duration::from_millis(100);
+
+struct Game {
+    universe: Universe,
+    cursor: (u32, u32),
+    paused: bool,
+}
+
+impl Game {
+    fn draw(&self, out: &mut impl Write) -> io::Result<()> {
+        for (row, cells) in self.universe.rows().enumerate() {
+            queue!(out, MoveTo(0, row as u16))?;
+            for (column, cell) in cells.enumerate() {
+                let glyph = if cell == Cell::Alive { "█" } else { " " };
+                if (row as u32, column as u32) == self.cursor {
+                    queue!(
+                        out,
+                        SetAttribute(Attribute::Reverse),
+                        Print(glyph),
+                        SetAttribute(Attribute::NoReverse)
+                    )?;
+                } else {
+                    queue!(out, Print(glyph))?;
+                }
+            }
+        }
+        let status = format!(
+            "generation {}  population {}  {}  (arrows, space, p, n, r, c, q)",
+            self.universe.generation(),
+            self.universe.population(),
+            if self.paused { "paused" } else { "running" }
+        );
+        queue!(
+            out,
+            MoveTo(0, self.universe.height() as u16),
+            terminal::Clear(terminal::ClearType::CurrentLine),
+            Print(status)
+        )?;
+        out.flush()
+    }
+
+    // False once the player wants to quit
+    fn handle_key(&mut self, key: KeyCode) -> bool {
+        let (row, column) = self.cursor;
+        let (width, height) = (self.universe.width(), self.universe.height());
+        match key {
+            KeyCode::Up => self.cursor = ((row + height - 1) % height, column),
+            KeyCode::Down => self.cursor = ((row + 1) % height, column),
+            KeyCode::Left => self.cursor = (row, (column + width - 1) % width),
+            KeyCode::Right => self.cursor = (row, (column + 1) % width),
+            KeyCode::Char(' ') => self.universe.toggle_cell(row, column),
+            KeyCode::Char('p') => self.paused = !self.paused,
+            KeyCode::Char('n') if self.paused => self.universe.tick(),
+            KeyCode::Char('r') => self.universe.randomize(),
+            KeyCode::Char('c') => self.universe.clear(),
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            _ => {}
+        }
+        true
+    }
+}
+
+fn run(out: &mut impl Write) -> io::Result<()> {
+    let (columns, rows) = terminal::size()?;
+    // the last line is for the status
+    let mut universe =
+        Universe::new_with_dimensions(u32::from(columns).max(1), u32::from(rows).max(2) - 1);
+    universe.set_boundary_mode(BoundaryMode::Toroidal);
+    universe.randomize();
+    let mut game = Game {
+        universe,
+        cursor: (0, 0),
+        paused: false,
+    };
+
+    let mut last_tick = Instant::now();
+    loop {
+        game.draw(out)?;
+        let timeout = TICK_INTERVAL.saturating_sub(last_tick.elapsed());
+        if event::poll(timeout)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && !game.handle_key(key.code) {
+                    return Ok(());
+                }
+            }
+        }
+        if last_tick.elapsed() >= TICK_INTERVAL {
+            if !game.paused {
+                game.universe.tick();
+            }
+            last_tick = Instant::now();
+        }
+    }
+}
+
+fn main() -> io::Result<()> {
+    let mut out = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(out, EnterAlternateScreen, Hide)?;
+
+    let result = run(&mut out);
+
+    // put the terminal back even when the game failed
+    execute!(out, Show, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    result
+}
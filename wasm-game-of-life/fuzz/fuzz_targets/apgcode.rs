@@ -0,0 +1,9 @@
+// apgcodes come from links to Catagolue and the soup search, Universe::from_apgcode()
+// has to turn anything else into a ParseError. Run with cargo +nightly fuzz run apgcode
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use wasm_game_of_life::Universe;
+
+fuzz_target!(|apgcode: &str| {
+    let _ = Universe::from_apgcode(apgcode);
+});
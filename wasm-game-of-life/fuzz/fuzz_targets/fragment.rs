@@ -0,0 +1,13 @@
+// The URL fragment of a shared link is whatever the link says it is, reading it has to
+// end in a DecodeError for anything that isn't a board. Run with cargo +nightly fuzz run
+// fragment
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use wasm_game_of_life::Universe;
+
+fuzz_target!(|fragment: &str| {
+    if let Ok(universe) = Universe::decode_from_url_fragment(fragment) {
+        let fragment = universe.encode_to_url_fragment();
+        assert!(Universe::decode_from_url_fragment(&fragment).is_ok());
+    }
+});
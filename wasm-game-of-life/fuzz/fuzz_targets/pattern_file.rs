@@ -0,0 +1,12 @@
+// Dropped or pasted pattern files go to Universe::from_pattern_file(), which picks RLE,
+// plaintext, Life 1.05 or Life 1.06 from the text. Run with cargo +nightly fuzz run
+// pattern_file
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use wasm_game_of_life::Universe;
+
+fuzz_target!(|text: &str| {
+    if let Ok(universe) = Universe::from_pattern_file(text) {
+        assert!(Universe::from_rle(&universe.to_rle()).is_ok());
+    }
+});
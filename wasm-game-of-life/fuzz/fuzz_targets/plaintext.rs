@@ -0,0 +1,13 @@
+// Plaintext patterns pasted into the page go straight to Universe::from_plaintext(),
+// anything it is given has to come back as a ParseError instead of a panic. Run with
+// cargo +nightly fuzz run plaintext
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use wasm_game_of_life::Universe;
+
+fuzz_target!(|plaintext: &str| {
+    if let Ok(universe) = Universe::from_plaintext(plaintext) {
+        // whatever was read has to survive being written back out
+        assert!(Universe::from_plaintext(&universe.to_plaintext()).is_ok());
+    }
+});
@@ -0,0 +1,12 @@
+// Patterns pasted into the page go straight to Universe::from_rle(), anything it is given
+// has to come back as a ParseError instead of a panic. Run with cargo +nightly fuzz run rle
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use wasm_game_of_life::Universe;
+
+fuzz_target!(|rle: &str| {
+    if let Ok(universe) = Universe::from_rle(rle) {
+        // whatever was read has to survive being written back out
+        assert!(Universe::from_rle(&universe.to_rle()).is_ok());
+    }
+});
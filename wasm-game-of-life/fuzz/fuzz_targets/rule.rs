@@ -0,0 +1,13 @@
+// Rules typed into the page and read from pattern headers go through RuleSet's FromStr,
+// anything it is given has to come back as a ParseError instead of a panic. Run with
+// cargo +nightly fuzz run rule
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use wasm_game_of_life::RuleSet;
+
+fuzz_target!(|rule: &str| {
+    if let Ok(parsed) = rule.parse::<RuleSet>() {
+        // the rule's own text parses back to the same rule
+        assert_eq!(Ok(parsed), parsed.to_string().parse::<RuleSet>());
+    }
+});
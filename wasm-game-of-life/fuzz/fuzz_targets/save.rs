@@ -0,0 +1,14 @@
+// Saves come back from local storage and files the user picks, and serialized boards
+// from wherever the page kept them, so neither reader can trust the bytes. Run with
+// cargo +nightly fuzz run save
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use wasm_game_of_life::{SaveManager, Universe};
+
+fuzz_target!(|bytes: &[u8]| {
+    let mut saves = SaveManager::new();
+    if saves.import("fuzz", bytes).is_ok() {
+        assert!(saves.load("fuzz").is_ok());
+    }
+    let _ = Universe::deserialize(bytes);
+});
@@ -0,0 +1,188 @@
+#[cfg(feature = "allocator_metrics")]
+use std::alloc::{GlobalAlloc, Layout};
+#[cfg(feature = "allocator_metrics")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+// wasm builds get Rust's dlmalloc unless the wee_alloc feature swaps it out, native builds
+// use the system allocator
+#[cfg(all(feature = "wee_alloc", target_arch = "wasm32"))]
+const NAME: &str = "wee_alloc";
+#[cfg(all(not(feature = "wee_alloc"), target_arch = "wasm32"))]
+const NAME: &str = "dlmalloc";
+#[cfg(not(target_arch = "wasm32"))]
+const NAME: &str = "system";
+
+#[cfg(all(feature = "wee_alloc", target_arch = "wasm32"))]
+type Inner = wee_alloc::WeeAlloc<'static>;
+#[cfg(all(feature = "wee_alloc", target_arch = "wasm32"))]
+const INNER: Inner = wee_alloc::WeeAlloc::INIT;
+// counting allocations wraps whichever allocator would have been used anyway
+#[cfg(all(
+    feature = "allocator_metrics",
+    not(all(feature = "wee_alloc", target_arch = "wasm32"))
+))]
+type Inner = std::alloc::System;
+#[cfg(all(
+    feature = "allocator_metrics",
+    not(all(feature = "wee_alloc", target_arch = "wasm32"))
+))]
+const INNER: Inner = std::alloc::System;
+
+#[cfg(all(
+    not(feature = "allocator_metrics"),
+    feature = "wee_alloc",
+    target_arch = "wasm32"
+))]
+#[global_allocator]
+static ALLOCATOR: Inner = INNER;
+
+#[cfg(feature = "allocator_metrics")]
+#[global_allocator]
+static ALLOCATOR: Counting = Counting(INNER);
+
+#[cfg(feature = "allocator_metrics")]
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+#[cfg(feature = "allocator_metrics")]
+static DEALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+#[cfg(feature = "allocator_metrics")]
+static REALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+#[cfg(feature = "allocator_metrics")]
+static BYTES_IN_USE: AtomicUsize = AtomicUsize::new(0);
+#[cfg(feature = "allocator_metrics")]
+static PEAK_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+// Passes everything through to the real allocator, keeping count on the way
+#[cfg(feature = "allocator_metrics")]
+struct Counting(Inner);
+
+#[cfg(feature = "allocator_metrics")]
+impl Counting {
+    fn grew(&self, bytes: usize) {
+        let in_use = BYTES_IN_USE.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        PEAK_BYTES.fetch_max(in_use, Ordering::Relaxed);
+    }
+}
+
+#[cfg(feature = "allocator_metrics")]
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let pointer = self.0.alloc(layout);
+        if !pointer.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            self.grew(layout.size());
+        }
+        pointer
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let pointer = self.0.alloc_zeroed(layout);
+        if !pointer.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            self.grew(layout.size());
+        }
+        pointer
+    }
+
+    unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+        self.0.dealloc(pointer, layout);
+        DEALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES_IN_USE.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, pointer: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_pointer = self.0.realloc(pointer, layout, new_size);
+        if !new_pointer.is_null() {
+            REALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            if new_size > layout.size() {
+                self.grew(new_size - layout.size());
+            } else {
+                BYTES_IN_USE.fetch_sub(layout.size() - new_size, Ordering::Relaxed);
+            }
+        }
+        new_pointer
+    }
+}
+
+// Which allocator the module was built with and, with the allocator_metrics feature, how
+// much it has been used since the module started. Building the demo with and without
+// wee_alloc and comparing these with the .wasm size shows what the smaller allocator costs.
+// The counters are all 0 when metrics_enabled is false.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AllocatorInfo {
+    name: &'static str,
+    pub metrics_enabled: bool,
+    // calls to alloc, including the ones asking for zeroed memory
+    pub allocations: usize,
+    pub deallocations: usize,
+    // a Vec or String growing in place or moving somewhere bigger
+    pub reallocations: usize,
+    // what has been asked for and not freed yet, not counting the allocator's own overhead
+    pub bytes_in_use: usize,
+    pub peak_bytes: usize,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl AllocatorInfo {
+    // "wee_alloc", "dlmalloc", or "system" natively
+    pub fn name(&self) -> String {
+        self.name.to_string()
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn allocator_info() -> AllocatorInfo {
+    #[cfg(feature = "allocator_metrics")]
+    {
+        AllocatorInfo {
+            name: NAME,
+            metrics_enabled: true,
+            allocations: ALLOCATIONS.load(Ordering::Relaxed),
+            deallocations: DEALLOCATIONS.load(Ordering::Relaxed),
+            reallocations: REALLOCATIONS.load(Ordering::Relaxed),
+            bytes_in_use: BYTES_IN_USE.load(Ordering::Relaxed),
+            peak_bytes: PEAK_BYTES.load(Ordering::Relaxed),
+        }
+    }
+    #[cfg(not(feature = "allocator_metrics"))]
+    {
+        AllocatorInfo {
+            name: NAME,
+            metrics_enabled: false,
+            allocations: 0,
+            deallocations: 0,
+            reallocations: 0,
+            bytes_in_use: 0,
+            peak_bytes: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_allocator_info() {
+        let before = allocator_info();
+        assert_eq!("system", before.name());
+        assert_eq!(cfg!(feature = "allocator_metrics"), before.metrics_enabled);
+
+        let buffer = vec![0u8; 1 << 20];
+        let during = allocator_info();
+        drop(buffer);
+        if during.metrics_enabled {
+            // other tests allocate on their own threads at the same time, so only what
+            // has to have happened is checked
+            assert!(during.allocations > before.allocations);
+            assert!(during.peak_bytes >= 1 << 20);
+            assert!(allocator_info().deallocations > before.deallocations);
+        } else {
+            assert_eq!(0, during.allocations);
+            assert_eq!(0, during.peak_bytes);
+        }
+    }
+}
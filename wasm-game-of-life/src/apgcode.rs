@@ -0,0 +1,322 @@
+// apgcodes are the names Catagolue and apgsearch give objects, such as xs4_33 for the
+// block. The prefix is xs and the population for still lifes, xp and the period for
+// oscillators, xq and the period for spaceships. After the underscore the cells are
+// written in the extended Wechsler format: the object is cut into strips 5 rows tall
+// and each column of a strip is one character from 0-9 and a-v, the top row being the
+// lowest bit. Strips are separated by z, trailing empty columns are left out and runs
+// of empty columns are shortened to w (2), x (3) or y followed by the count less 4.
+// Of every phase and orientation of the object the shortest code is used, with ties
+// going to the one that sorts first.
+// https://conwaylife.com/wiki/Apgcode
+use std::collections::BTreeMap;
+
+use crate::census::{self, CensusObject};
+use crate::formats::MAX_CELLS;
+use crate::{ParseError, RuleSet, Universe};
+
+// The longest period looked for when working out what an object is, the same as the
+// soup search waits for
+const MAX_PERIOD: u32 = 30;
+const STRIP_HEIGHT: u32 = 5;
+const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+// (row, column) of live cells
+type Cells = Vec<(u32, u32)>;
+
+// The apgcode of an object once it is run on its own under `rule`, None when it dies,
+// grows or doesn't repeat within 30 generations
+pub fn encode(object: &CensusObject, rule: RuleSet) -> Option<String> {
+    // room for a spaceship moving at the speed of light to come back around
+    let margin = MAX_PERIOD + 2;
+    let mut universe =
+        Universe::new_with_dimensions(object.width + 2 * margin, object.height + 2 * margin);
+    universe.rule = rule;
+    for (row, column) in object.cells.iter() {
+        let index = universe.get_index(row + margin, column + margin);
+        universe.cells.set(index, true);
+    }
+
+    let mut phases = vec![object.cells.clone()];
+    for period in 1..=MAX_PERIOD {
+        universe.tick();
+        let (row, column, cells) = normalized(&universe)?;
+        if cells == object.cells {
+            let moved = (row, column) != (margin, margin);
+            let prefix = match (moved, period) {
+                (true, _) => format!("xq{}", period),
+                (false, 1) => format!("xs{}", cells.len()),
+                (false, _) => format!("xp{}", period),
+            };
+            return Some(format!("{}_{}", prefix, canonical(&phases)));
+        }
+        phases.push(cells);
+    }
+
+    None
+}
+
+// How many of each object there are by apgcode, anything that can't be given one is
+// counted as "other"
+pub fn count_apgcodes(universe: &Universe) -> BTreeMap<String, u32> {
+    let mut counts = BTreeMap::new();
+    for object in census::find_objects(universe) {
+        let apgcode = encode(&object, universe.rule).unwrap_or_else(|| "other".to_string());
+        *counts.entry(apgcode).or_insert(0) += 1;
+    }
+
+    counts
+}
+
+// The (row, column) of every live cell of an apgcode, with the top left of the object
+// at 0, 0
+pub fn decode(apgcode: &str) -> Result<Vec<(u32, u32)>, ParseError> {
+    let valid_prefix = ["xs", "xp", "xq"].iter().any(|prefix| {
+        apgcode.starts_with(prefix)
+            && apgcode[2..]
+                .split('_')
+                .next()
+                .is_some_and(|number| number.parse::<u32>().is_ok())
+    });
+    let underscore = apgcode.find('_');
+    let (start, wechsler) = match underscore {
+        Some(underscore) if valid_prefix => (underscore + 1, &apgcode[underscore + 1..]),
+        _ => {
+            return Err(ParseError::new(
+                1,
+                1,
+                "expected an apgcode like xs4_33, xp2_7 or xq4_153",
+            ))
+        }
+    };
+
+    let mut cells = vec![];
+    let mut strip = 0u32;
+    let mut column = 0u32;
+    let mut characters = wechsler.char_indices();
+    while let Some((index, character)) = characters.next() {
+        let error = |index: usize| {
+            ParseError::new(
+                1,
+                start + index + 1,
+                format!("unexpected character '{}'", character),
+            )
+        };
+        match character {
+            'z' => {
+                strip = strip.saturating_add(1);
+                column = 0;
+            }
+            'w' => column = column.saturating_add(2),
+            'x' => column = column.saturating_add(3),
+            'y' => match characters.next() {
+                Some((index, count)) => {
+                    let count = 4 + digit(count).ok_or_else(|| error(index))? as u32;
+                    column = column.saturating_add(count);
+                }
+                None => return Err(error(index)),
+            },
+            _ => {
+                let bits = digit(character)
+                    .filter(|bits| *bits < 1 << STRIP_HEIGHT)
+                    .ok_or_else(|| error(index))?;
+                for bit in 0..STRIP_HEIGHT {
+                    if bits & 1 << bit != 0 {
+                        let row = strip.saturating_mul(STRIP_HEIGHT).saturating_add(bit);
+                        cells.push((row, column));
+                    }
+                }
+                column = column.saturating_add(1);
+            }
+        }
+    }
+
+    let top = cells.iter().map(|(row, _)| *row).min().unwrap_or(0);
+    let left = cells.iter().map(|(_, column)| *column).min().unwrap_or(0);
+    let bottom = cells.iter().map(|(row, _)| *row).max().unwrap_or(0);
+    let right = cells.iter().map(|(_, column)| *column).max().unwrap_or(0);
+    let width = u64::from(right - left) + 1;
+    let height = u64::from(bottom - top) + 1;
+    if width * height > MAX_CELLS {
+        return Err(ParseError::new(1, 1, "the pattern is too large"));
+    }
+    let mut cells: Vec<(u32, u32)> = cells
+        .into_iter()
+        .map(|(row, column)| (row - top, column - left))
+        .collect();
+    cells.sort_unstable();
+
+    Ok(cells)
+}
+
+// The extended Wechsler code of cells as they are, without turning them
+pub fn wechsler(cells: &[(u32, u32)]) -> String {
+    let height = cells.iter().map(|(row, _)| row + 1).max().unwrap_or(0);
+    let width = cells
+        .iter()
+        .map(|(_, column)| column + 1)
+        .max()
+        .unwrap_or(0);
+    let strips = height.div_ceil(STRIP_HEIGHT);
+    let mut columns = vec![0u8; (strips * width) as usize];
+    for (row, column) in cells {
+        columns[((row / STRIP_HEIGHT) * width + column) as usize] |= 1 << (row % STRIP_HEIGHT);
+    }
+
+    let mut code = String::new();
+    for (strip, strip_columns) in columns.chunks(width.max(1) as usize).enumerate() {
+        if strip > 0 {
+            code.push('z');
+        }
+        let mut empty = 0;
+        for bits in strip_columns {
+            if *bits == 0 {
+                empty += 1;
+                continue;
+            }
+            push_empty_columns(&mut code, empty);
+            empty = 0;
+            code.push(DIGITS[*bits as usize] as char);
+        }
+    }
+
+    code
+}
+
+fn push_empty_columns(code: &mut String, mut count: usize) {
+    while count > 0 {
+        let run = count.min(39);
+        match run {
+            1 => code.push('0'),
+            2 => code.push('w'),
+            3 => code.push('x'),
+            _ => {
+                code.push('y');
+                code.push(DIGITS[run - 4] as char);
+            }
+        }
+        count -= run;
+    }
+}
+
+fn canonical(phases: &[Cells]) -> String {
+    phases
+        .iter()
+        .flat_map(|phase| census::orientations(phase))
+        .map(|cells| wechsler(&cells))
+        .min_by(|first, second| first.len().cmp(&second.len()).then(first.cmp(second)))
+        .unwrap_or_default()
+}
+
+fn digit(character: char) -> Option<usize> {
+    DIGITS.iter().position(|digit| *digit as char == character)
+}
+
+// Where the top left of the live cells is and the cells relative to it, None once they
+// have died out or reached the edge of the board
+fn normalized(universe: &Universe) -> Option<(u32, u32, Cells)> {
+    let mut cells = vec![];
+    for row in 0..universe.height {
+        for column in 0..universe.width {
+            if universe.cells.get(universe.get_index(row, column)) {
+                cells.push((row, column));
+            }
+        }
+    }
+    let top = cells.iter().map(|(row, _)| *row).min()?;
+    let left = cells.iter().map(|(_, column)| *column).min()?;
+    let bottom = cells.iter().map(|(row, _)| *row).max()?;
+    let right = cells.iter().map(|(_, column)| *column).max()?;
+    if top == 0 || left == 0 || bottom + 1 == universe.height || right + 1 == universe.width {
+        return None;
+    }
+
+    let cells = cells
+        .into_iter()
+        .map(|(row, column)| (row - top, column - left))
+        .collect();
+    Some((top, left, cells))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Pattern;
+
+    fn apgcode(rows: &[&str]) -> Option<String> {
+        let mut universe = Universe::new(20);
+        for (row, column) in census::live_cells(rows) {
+            universe.set_cell(row + 2, column + 2, crate::Cell::Alive);
+        }
+        let objects = census::find_objects(&universe);
+        encode(&objects[0], RuleSet::default())
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(Some("xs4_33".to_string()), apgcode(&["OO", "OO"]));
+        assert_eq!(
+            Some("xs6_696".to_string()),
+            apgcode(&[".OO.", "O..O", ".OO."])
+        );
+        assert_eq!(
+            Some("xs7_2596".to_string()),
+            apgcode(&[".OO.", "O..O", ".O.O", "..O."])
+        );
+        assert_eq!(Some("xp2_7".to_string()), apgcode(&["O", "O", "O"]));
+        assert_eq!(Some("xp2_7e".to_string()), apgcode(&[".OOO", "OOO."]));
+        assert_eq!(Some("xq4_153".to_string()), apgcode(&["O..", ".OO", "OO."]));
+        assert_eq!(
+            Some("xq4_6frc".to_string()),
+            apgcode(&["O..O.", "....O", "O...O", ".OOOO"])
+        );
+        // a domino dies out
+        assert_eq!(None, apgcode(&["OO"]));
+    }
+
+    #[test]
+    fn test_encode_pulsar() {
+        let mut universe = Universe::new(24);
+        universe.insert_pattern(Pattern::Pulsar, 4, 4);
+
+        assert_eq!(
+            Some(&1),
+            count_apgcodes(&universe).get("xp3_co9nas0san9oczgoldlo0oldlogz1047210127401")
+        );
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(census::live_cells(&["OO", "OO"]), decode("xs4_33").unwrap());
+        assert_eq!(
+            census::live_cells(&["OOO", "..O", ".O."]),
+            decode("xq4_153").unwrap()
+        );
+        assert_eq!(
+            census::live_cells(&["O.....O"]),
+            decode("xs2_1y11").unwrap()
+        );
+
+        let pulsar = "xp3_co9nas0san9oczgoldlo0oldlogz1047210127401";
+        assert_eq!(&pulsar[4..], wechsler(&decode(pulsar).unwrap()));
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(
+            ParseError::new(1, 1, "expected an apgcode like xs4_33, xp2_7 or xq4_153"),
+            decode("33").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(1, 6, "unexpected character '!'"),
+            decode("xs4_3!").unwrap_err()
+        );
+
+        // cells thousands of strips apart and millions of columns apart
+        let huge = format!("xs2_1{}1{}1", "z".repeat(5000), "yv".repeat(200_000));
+        assert_eq!(
+            ParseError::new(1, 1, "the pattern is too large"),
+            decode(&huge).unwrap_err()
+        );
+        assert!(Universe::from_apgcode(&huge).is_err());
+    }
+}
@@ -0,0 +1,241 @@
+use wasm_bindgen::prelude::*;
+use web_sys::{AudioContext, OscillatorType};
+
+use crate::Universe;
+
+// What the ticks sound like
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SonifyMode {
+    // a drone that rises with the population, a high note for births and a low one for
+    // deaths, each louder the more cells changed
+    Statistics,
+    // a step sequencer, every cell born in one of the chosen rows plays that row's note
+    Sequencer,
+}
+
+// The minor pentatonic scale in semitones above the root, no two of its notes clash
+const SCALE: [u32; 5] = [0, 3, 5, 7, 10];
+// A3
+const ROOT_FREQUENCY: f64 = 220.0;
+// Steps of the scale the population drone covers, four octaves
+const DRONE_STEPS: u32 = 20;
+// More notes than this at once is just noise, the rest are dropped
+const MAX_NOTES: usize = 8;
+
+// One tone for a tick, the gain is before the volume is applied
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Note {
+    frequency: f64,
+    gain: f32,
+}
+
+// The frequency of a step up the scale from the root, 5 steps are an octave
+fn scale_frequency(step: u32) -> f64 {
+    let semitones = SCALE[(step % 5) as usize] + 12 * (step / 5);
+    ROOT_FREQUENCY * 2f64.powf(f64::from(semitones) / 12.0)
+}
+
+fn statistics_notes(universe: &Universe) -> Vec<Note> {
+    let area = (universe.width() * universe.height()).max(1);
+    let share = |count: u32| f64::from(count) / f64::from(area);
+    let mut notes = vec![];
+
+    let population = universe.population();
+    if population > 0 {
+        let step = (share(population) * f64::from(DRONE_STEPS)).round() as u32;
+        notes.push(Note {
+            frequency: scale_frequency(step.min(DRONE_STEPS - 1)),
+            gain: 0.2,
+        });
+    }
+    // a tenth of the board changing is as loud as it gets
+    let loudness = |count: u32| (share(count) * 10.0).min(1.0) as f32 * 0.3;
+    if universe.births_last_tick() > 0 {
+        notes.push(Note {
+            frequency: scale_frequency(DRONE_STEPS + 5),
+            gain: loudness(universe.births_last_tick()),
+        });
+    }
+    if universe.deaths_last_tick() > 0 {
+        notes.push(Note {
+            frequency: scale_frequency(0) / 2.0,
+            gain: loudness(universe.deaths_last_tick()),
+        });
+    }
+
+    notes
+}
+
+// The first of the rows gets the highest note, so the rows read like a piano roll
+fn sequencer_notes(universe: &Universe, rows: &[u32]) -> Vec<Note> {
+    let width = universe.width().max(1);
+    let mut playing = vec![false; rows.len()];
+    for &index in universe.changed_cells.iter() {
+        if !universe.cells.get(index as usize) {
+            continue;
+        }
+        let row = index / width;
+        for (track, _) in rows.iter().enumerate().filter(|(_, &chosen)| chosen == row) {
+            playing[track] = true;
+        }
+    }
+
+    let notes = playing
+        .iter()
+        .enumerate()
+        .filter(|(_, &playing)| playing)
+        .map(|(track, _)| (rows.len() - 1 - track) as u32)
+        .take(MAX_NOTES)
+        .collect::<Vec<_>>();
+    let gain = 0.6 / notes.len().max(1) as f32;
+    notes
+        .into_iter()
+        .map(|step| Note {
+            frequency: scale_frequency(step),
+            gain,
+        })
+        .collect()
+}
+
+// Plays the universe through the speakers with the Web Audio API, call play() after each
+// tick. Browsers only let audio start after the user has clicked or pressed a key on the
+// page, so a Sonifier made before that needs resume() from an event handler.
+#[wasm_bindgen]
+pub struct Sonifier {
+    context: AudioContext,
+    mode: SonifyMode,
+    rows: Vec<u32>,
+    volume: f32,
+    // seconds
+    note_length: f64,
+}
+
+#[wasm_bindgen]
+impl Sonifier {
+    #[wasm_bindgen(constructor)]
+    pub fn new(mode: SonifyMode) -> Result<Sonifier, JsValue> {
+        Ok(Self {
+            context: AudioContext::new()?,
+            mode,
+            rows: vec![],
+            volume: 0.5,
+            note_length: 0.15,
+        })
+    }
+
+    pub fn mode(&self) -> SonifyMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: SonifyMode) {
+        self.mode = mode;
+    }
+
+    pub fn rows(&self) -> Vec<u32> {
+        self.rows.clone()
+    }
+
+    // The rows the sequencer listens to, each one a step higher up the scale than the row
+    // after it
+    pub fn set_rows(&mut self, rows: Vec<u32>) {
+        self.rows = rows;
+    }
+
+    pub fn volume(&self) -> f32 {
+        self.volume
+    }
+
+    // 0 is silent and 1 is full volume
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume.clamp(0.0, 1.0);
+    }
+
+    pub fn note_length(&self) -> f64 {
+        self.note_length
+    }
+
+    pub fn set_note_length(&mut self, seconds: f64) {
+        self.note_length = seconds.max(0.01);
+    }
+
+    pub fn resume(&self) -> Result<js_sys::Promise, JsValue> {
+        self.context.resume()
+    }
+
+    // Plays the notes for the last tick, ticks skipped over by tick_n() aren't heard
+    pub fn play(&self, universe: &Universe) -> Result<(), JsValue> {
+        let notes = match self.mode {
+            SonifyMode::Statistics => statistics_notes(universe),
+            SonifyMode::Sequencer => sequencer_notes(universe, &self.rows),
+        };
+        for note in notes {
+            self.play_note(note)?;
+        }
+        Ok(())
+    }
+}
+
+impl Sonifier {
+    // A short sine tone that fades out instead of stopping with a click
+    fn play_note(&self, note: Note) -> Result<(), JsValue> {
+        let start = self.context.current_time();
+        let end = start + self.note_length;
+        let oscillator = self.context.create_oscillator()?;
+        oscillator.set_type(OscillatorType::Sine);
+        oscillator.frequency().set_value(note.frequency as f32);
+        let gain = self.context.create_gain()?;
+        gain.gain()
+            .set_value_at_time(note.gain * self.volume, start)?;
+        // an exponential ramp can't reach 0
+        gain.gain().exponential_ramp_to_value_at_time(0.0001, end)?;
+
+        oscillator.connect_with_audio_node(&gain)?;
+        gain.connect_with_audio_node(&self.context.destination())?;
+        oscillator.start_with_when(start)?;
+        oscillator.stop_with_when(end)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Pattern;
+
+    #[test]
+    fn test_scale_frequency() {
+        assert_eq!(220.0, scale_frequency(0));
+        assert_eq!(440.0, scale_frequency(5));
+        assert!((scale_frequency(3) - 329.63).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_statistics_notes() {
+        let mut universe = Universe::new(10);
+        assert!(statistics_notes(&universe).is_empty());
+
+        universe.insert_pattern(Pattern::Blinker, 4, 4);
+        universe.tick();
+        let notes = statistics_notes(&universe);
+        assert_eq!(3, notes.len());
+        assert_eq!(scale_frequency(1), notes[0].frequency);
+        // 2 of 100 cells were born
+        assert!((notes[1].gain - 0.06).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_sequencer_notes() {
+        let mut universe = Universe::new(10);
+        universe.insert_pattern(Pattern::Blinker, 4, 4);
+        universe.tick();
+
+        // the vertical blinker is born into rows 3 and 5, row 4 already had a live cell
+        assert!(sequencer_notes(&universe, &[4]).is_empty());
+        let notes = sequencer_notes(&universe, &[3, 4, 5]);
+        assert_eq!(
+            vec![scale_frequency(2), scale_frequency(0)],
+            notes.iter().map(|note| note.frequency).collect::<Vec<_>>()
+        );
+        assert_eq!(0.3, notes[0].gain);
+    }
+}
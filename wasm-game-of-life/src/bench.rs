@@ -0,0 +1,37 @@
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::{utils, BoundaryMode, Engine, Universe};
+
+// Ticks a seeded square universe with the engine and returns how many milliseconds the
+// ticks took, building the universe isn't counted. The same seed is used every time so
+// engines are compared on the same board, benches/engines.rs runs the native version.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn bench_tick(engine: Engine, size: u32, generations: u32) -> f64 {
+    let mut universe = bench_universe(engine, size);
+    let start = utils::now();
+    universe.tick_n(generations);
+    utils::now() - start
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn bench_universe(engine: Engine, size: u32) -> Universe {
+    let mut universe = Universe::new(size).with_engine(engine);
+    universe.set_boundary_mode(BoundaryMode::Toroidal);
+    universe.randomize_seeded(42, 0.35);
+    universe
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bench_tick() {
+        assert!(bench_tick(Engine::Packed, 32, 5) >= 0.0);
+        assert_eq!(
+            bench_universe(Engine::Naive, 16).cells(),
+            bench_universe(Engine::HashLife, 16).cells()
+        );
+    }
+}
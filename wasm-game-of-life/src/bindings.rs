@@ -0,0 +1,163 @@
+// The exports that take or give back JavaScript values, only built with the "wasm"
+// feature. Everything else in the crate is plain Rust, these just convert between it and
+// what the page passes in.
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::formats::{self, DecodeError, EncodeError, ParseError};
+use crate::{BuildInfo, KeyBindings, ReplayLog, SoupConfig, SoupSearch, Universe, UniverseError};
+
+#[wasm_bindgen]
+extern "C" {
+    fn alert(string: &str);
+}
+
+#[wasm_bindgen]
+pub fn alert_the_page(string: &str) {
+    alert(string);
+}
+
+// The errors arrive in JavaScript as Error objects with the Display message
+macro_rules! js_error {
+    ($($error:ty),*) => {
+        $(
+            impl From<$error> for JsValue {
+                fn from(error: $error) -> Self {
+                    JsError::new(&error.to_string()).into()
+                }
+            }
+        )*
+    };
+}
+
+js_error!(ParseError, DecodeError, EncodeError, UniverseError);
+
+#[wasm_bindgen]
+impl Universe {
+    // Reads the object produced by to_json()
+    pub fn from_json(value: JsValue) -> Result<Universe, JsValue> {
+        let data: formats::json::UniverseData = serde_wasm_bindgen::from_value(value)?;
+        Ok(data.into_universe()?)
+    }
+
+    // A plain object with the dimensions, rule, generation and cells
+    pub fn to_json(&self) -> Result<JsValue, JsValue> {
+        Ok(serde_wasm_bindgen::to_value(
+            &formats::json::UniverseData::from(self),
+        )?)
+    }
+
+    // Calls callback with a UniverseEvent object after every tick, so the page can react
+    // to the universe dying out or settling down without checking after each frame. The
+    // universe is still borrowed while the callback runs, calling back into it has to
+    // wait, with setTimeout for example. A new callback replaces the old one.
+    pub fn on_event(&mut self, callback: js_sys::Function) {
+        self.set_event_handler(move |event| {
+            let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+            // errors thrown by the callback are dropped so one bad handler can't stop the
+            // ticks
+            if let Ok(event) = event.serialize(&serializer) {
+                let _ = callback.call1(&JsValue::NULL, &event);
+            }
+        });
+    }
+
+    // A plain object that survives JSON.stringify, with the starting board as an array
+    // of bytes and an entry for every action
+    pub fn export_replay(&self) -> Result<JsValue, JsValue> {
+        let replay = self
+            .replay_log()
+            .ok_or_else(|| JsValue::from_str("nothing has been recorded"))?;
+        Ok(replay.serialize(&serde_wasm_bindgen::Serializer::json_compatible())?)
+    }
+
+    // Plays back what export_replay() returned on a new universe
+    pub fn replay(log: JsValue) -> Result<Universe, JsValue> {
+        let log: ReplayLog = serde_wasm_bindgen::from_value(log)?;
+        Ok(log.play()?)
+    }
+
+    // Keeps the whole simulation in the page's localStorage under key, in the format
+    // SaveManager uses written out as base64 text, replacing anything already there
+    pub fn save_to_local_storage(&self, key: &str) -> Result<(), JsValue> {
+        let bytes = formats::compressed::compress(&formats::save::write(self));
+        let text = formats::base64::encode(&bytes);
+        local_storage()?.set_item(key, &text)
+    }
+
+    // undefined when nothing was saved under key
+    pub fn load_from_local_storage(key: &str) -> Result<Option<Universe>, JsValue> {
+        match local_storage()?.get_item(key)? {
+            Some(text) => Ok(Some(formats::save::read(&formats::base64::decode(&text)?)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl KeyBindings {
+    // The default keys with the actions in config rebound, config is an object of
+    // KeyAction names to arrays of KeyboardEvent.key values, as loaded from JSON like
+    // {"Pause": [" "], "Step": ["n", "ArrowRight"]}
+    pub fn from_json(config: JsValue) -> Result<KeyBindings, JsValue> {
+        let mut bindings = KeyBindings::default();
+        bindings.configure(serde_wasm_bindgen::from_value(config)?);
+        Ok(bindings)
+    }
+}
+
+// Downloads a pattern file in any of the text formats, RLE and the .cells plaintext files
+// from the LifeWiki pattern collection included. The server has to allow the page to read
+// it with CORS. The promise resolves to a Universe just big enough for the pattern.
+#[wasm_bindgen]
+pub async fn fetch_pattern(url: String) -> Result<Universe, JsValue> {
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("no global window"))?;
+    let response: web_sys::Response = JsFuture::from(window.fetch_with_str(&url))
+        .await?
+        .dyn_into()?;
+    if !response.ok() {
+        return Err(JsError::new(&format!(
+            "could not download {}: {} {}",
+            url,
+            response.status(),
+            response.status_text()
+        ))
+        .into());
+    }
+    let text = JsFuture::from(response.text()?)
+        .await?
+        .as_string()
+        .unwrap_or_default();
+    Ok(Universe::from_pattern_file(&text)?)
+}
+
+// Fails when the page isn't allowed storage, like in some private browsing windows
+fn local_storage() -> Result<web_sys::Storage, JsValue> {
+    web_sys::window()
+        .ok_or_else(|| JsValue::from_str("no global window"))?
+        .local_storage()?
+        .ok_or_else(|| JsValue::from_str("localStorage is not available"))
+}
+
+// Takes a SoupConfig shaped object and returns a SoupResults shaped one, with the census
+// and apgcodes as plain objects of counts keyed by name
+#[wasm_bindgen]
+pub fn run_soup_search(config: JsValue) -> Result<JsValue, JsValue> {
+    let config: SoupConfig = if config.is_undefined() {
+        SoupConfig::default()
+    } else {
+        serde_wasm_bindgen::from_value(config)?
+    };
+    let mut search = SoupSearch::new(config);
+    let results = search.run()?;
+    Ok(results.serialize(&serde_wasm_bindgen::Serializer::json_compatible())?)
+}
+
+// A plain object with the crate version, the enabled features, the git commit and the
+// engines, for the demo page and for bug reports
+#[wasm_bindgen]
+pub fn build_info() -> Result<JsValue, JsValue> {
+    Ok(BuildInfo::current().serialize(&serde_wasm_bindgen::Serializer::json_compatible())?)
+}
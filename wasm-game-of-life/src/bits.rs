@@ -0,0 +1,235 @@
+use std::iter::FromIterator;
+
+use crate::Cell;
+
+// Stores one bit per cell packed into u32 words, bit `index % 32` of word `index / 32`
+// holds the cell at `index`. This is 8 times smaller than a Vec<Cell>.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CellBits {
+    words: Vec<u32>,
+    len: usize,
+}
+
+impl CellBits {
+    pub fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(32)],
+            len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn get(&self, index: usize) -> bool {
+        self.words[index / 32] & (1 << (index % 32)) != 0
+    }
+
+    pub fn set(&mut self, index: usize, alive: bool) {
+        if alive {
+            self.words[index / 32] |= 1 << (index % 32);
+        } else {
+            self.words[index / 32] &= !(1 << (index % 32));
+        }
+    }
+
+    pub fn toggle(&mut self, index: usize) {
+        self.words[index / 32] ^= 1 << (index % 32);
+    }
+
+    pub fn memory_usage(&self) -> usize {
+        crate::memory::vec_bytes(&self.words)
+    }
+
+    pub fn clear(&mut self) {
+        for word in self.words.iter_mut() {
+            *word = 0;
+        }
+    }
+
+    pub fn count_ones(&self) -> u32 {
+        self.words.iter().map(|word| word.count_ones()).sum()
+    }
+
+    // The number of cells that are set in `next` but not here, and the other way around
+    pub fn count_changes(&self, next: &CellBits) -> (u32, u32) {
+        self.words
+            .iter()
+            .zip(next.words.iter())
+            .fold((0, 0), |(set, unset), (current, next)| {
+                (
+                    set + (next & !current).count_ones(),
+                    unset + (current & !next).count_ones(),
+                )
+            })
+    }
+
+    // Indexes of the cells that differ between the two, in order
+    pub fn differences<'a>(&'a self, other: &'a CellBits) -> impl Iterator<Item = usize> + 'a {
+        self.words
+            .iter()
+            .zip(other.words.iter())
+            .enumerate()
+            .flat_map(|(word_index, (word, other))| {
+                let mut changed = word ^ other;
+                std::iter::from_fn(move || {
+                    if changed == 0 {
+                        return None;
+                    }
+                    let bit = changed.trailing_zeros() as usize;
+                    changed &= changed - 1;
+                    Some(word_index * 32 + bit)
+                })
+            })
+    }
+
+    pub fn words(&self) -> &[u32] {
+        &self.words
+    }
+
+    // `count` cells starting at `start` as the low bits of a word, count is at most 32
+    pub fn read_bits(&self, start: usize, count: usize) -> u32 {
+        let word = start / 32;
+        let offset = start % 32;
+        let mut value = self.words[word] >> offset;
+        if offset != 0 && word + 1 < self.words.len() {
+            value |= self.words[word + 1] << (32 - offset);
+        }
+
+        value & low_bits(count)
+    }
+
+    // Sets `count` cells starting at `start` from the low bits of `value`
+    pub fn write_bits(&mut self, start: usize, count: usize, value: u32) {
+        let mask = low_bits(count);
+        let value = value & mask;
+        let word = start / 32;
+        let offset = start % 32;
+        self.words[word] = (self.words[word] & !(mask << offset)) | (value << offset);
+        if offset + count > 32 {
+            let spilled = 32 - offset;
+            self.words[word + 1] = (self.words[word + 1] & !(mask >> spilled)) | (value >> spilled);
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.len).map(move |index| self.get(index))
+    }
+}
+
+fn low_bits(count: usize) -> u32 {
+    if count >= 32 {
+        u32::MAX
+    } else {
+        (1 << count) - 1
+    }
+}
+
+impl FromIterator<Cell> for CellBits {
+    fn from_iter<I: IntoIterator<Item = Cell>>(iter: I) -> Self {
+        let cells: Vec<Cell> = iter.into_iter().collect();
+        let mut bits = CellBits::new(cells.len());
+        for (index, cell) in cells.into_iter().enumerate() {
+            bits.set(index, cell == Cell::Alive);
+        }
+
+        bits
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_new_cell_bits() {
+        let bits = CellBits::new(33);
+
+        assert_eq!(33, bits.len());
+        assert_eq!(&[0, 0], bits.words());
+    }
+
+    #[test]
+    fn test_set_and_get() {
+        let mut bits = CellBits::new(40);
+
+        bits.set(0, true);
+        bits.set(33, true);
+        bits.set(34, true);
+        bits.set(34, false);
+
+        assert!(bits.get(0));
+        assert!(bits.get(33));
+        assert!(!bits.get(34));
+        assert_eq!(&[1, 2], bits.words());
+    }
+
+    #[test]
+    fn test_toggle() {
+        let mut bits = CellBits::new(8);
+
+        bits.toggle(3);
+        assert!(bits.get(3));
+        bits.toggle(3);
+        assert!(!bits.get(3));
+    }
+
+    #[test]
+    fn test_count_ones() {
+        let mut bits = CellBits::new(70);
+        bits.set(1, true);
+        bits.set(35, true);
+        bits.set(69, true);
+
+        assert_eq!(3, bits.count_ones());
+    }
+
+    #[test]
+    fn test_count_changes() {
+        let mut current = CellBits::new(40);
+        let mut next = CellBits::new(40);
+        current.set(0, true);
+        current.set(1, true);
+        next.set(1, true);
+        next.set(35, true);
+        next.set(36, true);
+
+        assert_eq!((2, 1), current.count_changes(&next));
+    }
+
+    #[test]
+    fn test_differences() {
+        let mut current = CellBits::new(70);
+        let mut next = CellBits::new(70);
+        current.set(3, true);
+        current.set(40, true);
+        next.set(40, true);
+        next.set(31, true);
+        next.set(69, true);
+
+        assert_eq!(
+            vec![3, 31, 69],
+            current.differences(&next).collect::<Vec<usize>>()
+        );
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut bits = CellBits::new(3);
+        bits.set(1, true);
+
+        assert_eq!(vec![false, true, false], bits.iter().collect::<Vec<bool>>());
+    }
+
+    #[test]
+    fn test_read_and_write_bits() {
+        let mut bits = CellBits::new(70);
+        bits.write_bits(30, 5, 0b10111);
+
+        assert_eq!(&[0b11 << 30, 0b101, 0], bits.words());
+        assert_eq!(0b10111, bits.read_bits(30, 5));
+        assert_eq!(0b1011, bits.read_bits(31, 4));
+        assert_eq!(0, bits.read_bits(64, 6));
+    }
+}
@@ -0,0 +1,92 @@
+use serde::Serialize;
+
+use crate::Engine;
+
+// What was compiled into this build, for the demo page to show and for bug reports to paste
+// in. From JavaScript build_info() gives it back as a plain object.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    // the cargo features that change what the module does, in the order of Cargo.toml
+    pub features: Vec<&'static str>,
+    // the short hash of the commit it was built from, None when it wasn't built from a git
+    // checkout and GIT_COMMIT wasn't set
+    pub git_commit: Option<&'static str>,
+    pub engines: Vec<EngineInfo>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct EngineInfo {
+    pub name: &'static str,
+    // false for Simd and Parallel when they were built without the vectors or threads they
+    // need, they still work but run like Packed and Naive
+    pub accelerated: bool,
+}
+
+impl BuildInfo {
+    pub fn current() -> Self {
+        let features = [
+            ("wasm", cfg!(feature = "wasm")),
+            (
+                "console_error_panic_hook",
+                cfg!(feature = "console_error_panic_hook"),
+            ),
+            ("audio", cfg!(feature = "audio")),
+            ("profiling", cfg!(feature = "profiling")),
+            ("simd", cfg!(feature = "simd")),
+            ("parallel", cfg!(feature = "parallel")),
+            ("serde", cfg!(feature = "serde")),
+            ("allocator_metrics", cfg!(feature = "allocator_metrics")),
+            ("wee_alloc", cfg!(feature = "wee_alloc")),
+        ];
+        let simd = cfg!(all(
+            feature = "simd",
+            target_arch = "wasm32",
+            target_feature = "simd128"
+        ));
+        let engines = Engine::ALL
+            .iter()
+            .map(|engine| EngineInfo {
+                name: match engine {
+                    Engine::Naive => "Naive",
+                    Engine::HashLife => "HashLife",
+                    Engine::Packed => "Packed",
+                    Engine::Simd => "Simd",
+                    Engine::Parallel => "Parallel",
+                },
+                accelerated: match engine {
+                    Engine::Simd => simd,
+                    Engine::Parallel => cfg!(feature = "parallel"),
+                    _ => true,
+                },
+            })
+            .collect();
+
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            features: features
+                .iter()
+                .filter(|(_, enabled)| *enabled)
+                .map(|(feature, _)| *feature)
+                .collect(),
+            git_commit: option_env!("GIT_COMMIT"),
+            engines,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_current() {
+        let info = BuildInfo::current();
+        assert_eq!("0.1.0", info.version);
+        assert_eq!(cfg!(feature = "wasm"), info.features.contains(&"wasm"));
+        assert!(!info.features.contains(&"simd") || cfg!(feature = "simd"));
+        assert_eq!(Engine::ALL.len(), info.engines.len());
+        assert_eq!("HashLife", info.engines[1].name);
+        assert!(!info.engines[3].accelerated);
+    }
+}
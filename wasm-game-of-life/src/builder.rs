@@ -0,0 +1,216 @@
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::{BoundaryMode, Engine, Pattern, RuleSet, Topology, Universe, UniverseError};
+
+// Sets up a universe in one go instead of creating one and calling setters on it, with
+// anything that can't work reported by build() rather than ignored:
+//
+//     const universe = new UniverseBuilder()
+//         .dimensions(128, 96)
+//         .rule("B36/S23")
+//         .boundary_mode(BoundaryMode.Toroidal)
+//         .seed(42n, 0.3)
+//         .build();
+//
+// Every setting has a default, an empty builder gives the same board as Universe::new(64).
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug)]
+pub struct UniverseBuilder {
+    width: u32,
+    height: u32,
+    rule: String,
+    edge_topology: Topology,
+    engine: Engine,
+    // seed and the chance of each cell starting alive, the board starts empty without one
+    seed: Option<(u64, f64)>,
+    // stamped on after the random cells
+    pattern: Option<(Pattern, u32, u32)>,
+}
+
+impl Default for UniverseBuilder {
+    fn default() -> Self {
+        Self {
+            width: 64,
+            height: 64,
+            rule: RuleSet::default().to_string(),
+            edge_topology: Topology::Plane,
+            engine: Engine::Naive,
+            seed: None,
+            pattern: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl UniverseBuilder {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn dimensions(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    pub fn rule(mut self, rule: &str) -> Self {
+        self.rule = rule.to_owned();
+        self
+    }
+
+    pub fn boundary_mode(mut self, boundary_mode: BoundaryMode) -> Self {
+        self.edge_topology = Topology::from(boundary_mode);
+        self
+    }
+
+    pub fn edge_topology(mut self, topology: Topology) -> Self {
+        self.edge_topology = topology;
+        self
+    }
+
+    pub fn engine(mut self, engine: Engine) -> Self {
+        self.engine = engine;
+        self
+    }
+
+    // Fills the board the same way randomize_seeded() does
+    pub fn seed(mut self, seed: u64, alive_probability: f64) -> Self {
+        self.seed = Some((seed, alive_probability));
+        self
+    }
+
+    pub fn pattern(mut self, pattern: Pattern, row: u32, column: u32) -> Self {
+        self.pattern = Some((pattern, row, column));
+        self
+    }
+
+    pub fn build(&self) -> Result<Universe, UniverseError> {
+        if Universe::check_dimensions(self.width, self.height)? == 0 {
+            return Err(UniverseError::InvalidConfig(format!(
+                "a {} by {} universe can't be made",
+                self.width, self.height
+            )));
+        }
+        if let Some((_, alive_probability)) = self.seed {
+            if !(0.0..=1.0).contains(&alive_probability) {
+                return Err(UniverseError::InvalidConfig(format!(
+                    "the chance of a cell being alive has to be between 0 and 1, not {}",
+                    alive_probability
+                )));
+            }
+        }
+        // boards with joined up edges wrap the pattern around instead
+        if let Some((pattern, row, column)) = self.pattern {
+            for (row_offset, column_offset) in pattern.live_cells() {
+                let (row, column) = (row + row_offset, column + column_offset);
+                let placed = self.edge_topology.map(
+                    i64::from(row),
+                    i64::from(column),
+                    self.width,
+                    self.height,
+                );
+                if placed.is_none() {
+                    return Err(UniverseError::OutOfBounds {
+                        row,
+                        column,
+                        width: self.width,
+                        height: self.height,
+                    });
+                }
+            }
+        }
+
+        let mut universe =
+            Universe::new_with_dimensions(self.width, self.height).with_engine(self.engine);
+        universe.set_rule(&self.rule)?;
+        universe.set_edge_topology(self.edge_topology);
+        if let Some((seed, alive_probability)) = self.seed {
+            universe.randomize_seeded(seed, alive_probability);
+        }
+        if let Some((pattern, row, column)) = self.pattern {
+            universe.insert_pattern(pattern, row, column);
+        }
+
+        Ok(universe)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_build() {
+        let universe = UniverseBuilder::new()
+            .dimensions(12, 8)
+            .rule("B36/S23")
+            .boundary_mode(BoundaryMode::Toroidal)
+            .engine(Engine::Packed)
+            .pattern(Pattern::Glider, 6, 10)
+            .build()
+            .unwrap();
+
+        assert_eq!((12, 8), (universe.width(), universe.height()));
+        assert_eq!("B36/S23", universe.rule());
+        assert_eq!(BoundaryMode::Toroidal, universe.boundary_mode());
+        assert_eq!(Engine::Packed, universe.engine());
+        assert_eq!(5, universe.population());
+    }
+
+    #[test]
+    fn test_seeded_builds_match() {
+        let builder = UniverseBuilder::new().dimensions(20, 10).seed(7, 0.4);
+        let mut expected = Universe::new_with_dimensions(20, 10);
+        expected.randomize_seeded(7, 0.4);
+
+        assert_eq!(expected.cells(), builder.build().unwrap().cells());
+        assert_eq!(
+            Universe::new(64).cells(),
+            UniverseBuilder::new().build().unwrap().cells()
+        );
+    }
+
+    #[test]
+    fn test_build_errors() {
+        assert_eq!(
+            UniverseError::InvalidConfig("a 0 by 5 universe can't be made".to_string()),
+            UniverseBuilder::new().dimensions(0, 5).build().unwrap_err()
+        );
+        assert!(matches!(
+            UniverseBuilder::new().rule("B3/S2x").build(),
+            Err(UniverseError::InvalidRule { .. })
+        ));
+        assert!(matches!(
+            UniverseBuilder::new().seed(1, 1.5).build(),
+            Err(UniverseError::InvalidConfig(_))
+        ));
+        assert_eq!(
+            UniverseError::OutOfBounds {
+                row: 7,
+                column: 12,
+                width: 12,
+                height: 8
+            },
+            UniverseBuilder::new()
+                .dimensions(12, 8)
+                .pattern(Pattern::Glider, 6, 10)
+                .build()
+                .unwrap_err()
+        );
+        // a sphere that isn't square has edges like a plane
+        assert!(UniverseBuilder::new()
+            .dimensions(12, 8)
+            .edge_topology(Topology::Sphere)
+            .pattern(Pattern::Glider, 6, 10)
+            .build()
+            .is_err());
+        assert!(UniverseBuilder::new()
+            .dimensions(12, 8)
+            .edge_topology(Topology::KleinBottle)
+            .pattern(Pattern::Glider, 6, 10)
+            .build()
+            .is_ok());
+    }
+}
@@ -0,0 +1,248 @@
+use std::collections::BTreeMap;
+
+use crate::{Transform, Universe};
+
+// Still lifes and oscillators that make up most of the ash a soup settles into, every
+// phase of an oscillator is listed but only one orientation, the others are found by
+// turning and mirroring. Drawn like the LifeWiki plaintext format.
+const KNOWN_OBJECTS: &[(&str, &[&str])] = &[
+    ("block", &["OO", "OO"]),
+    ("beehive", &[".OO.", "O..O", ".OO."]),
+    ("loaf", &[".OO.", "O..O", ".O.O", "..O."]),
+    ("boat", &["OO.", "O.O", ".O."]),
+    ("ship", &["OO.", "O.O", ".OO"]),
+    ("tub", &[".O.", "O.O", ".O."]),
+    ("pond", &[".OO.", "O..O", "O..O", ".OO."]),
+    ("long boat", &["OO..", "O.O.", ".O.O", "..O."]),
+    ("barge", &[".O..", "O.O.", ".O.O", "..O."]),
+    ("blinker", &["OOO"]),
+    ("toad", &[".OOO", "OOO."]),
+    ("toad", &["..O.", "O..O", "O..O", ".O.."]),
+    ("beacon", &["OO..", "OO..", "..OO", "..OO"]),
+    ("beacon", &["OO..", "O...", "...O", "..OO"]),
+    ("glider", &[".O.", "..O", "OOO"]),
+    ("glider", &["O.O", ".OO", ".O."]),
+    ("glider", &["..O", "O.O", ".OO"]),
+    ("glider", &["O..", ".OO", "OO."]),
+];
+
+// Groups of live cells that sit within two cells of each other, close enough that they
+// would affect each other's next generation. Toad and beacon phases come apart into
+// pieces that are one dead cell apart, so touching cells alone isn't enough.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CensusObject {
+    // None for anything that isn't in the list of known objects
+    pub name: Option<&'static str>,
+    // the top left of the bounding box
+    pub row: u32,
+    pub column: u32,
+    pub width: u32,
+    pub height: u32,
+    // (row, column) of every live cell relative to row, column
+    pub cells: Vec<(u32, u32)>,
+}
+
+// Objects aren't followed across the edges of a toroidal universe, one that straddles
+// an edge is seen as two pieces
+pub fn find_objects(universe: &Universe) -> Vec<CensusObject> {
+    let width = universe.width();
+    let height = universe.height();
+    let mut seen = vec![false; (width * height) as usize];
+    let mut objects = vec![];
+
+    for start in 0..seen.len() {
+        if seen[start] || !universe.cells.get(start) {
+            continue;
+        }
+        seen[start] = true;
+        let mut stack = vec![start];
+        let mut cells = vec![];
+        while let Some(index) = stack.pop() {
+            let row = index as u32 / width;
+            let column = index as u32 % width;
+            cells.push((row, column));
+            for neighbor_row in row.saturating_sub(2)..(row + 3).min(height) {
+                for neighbor_column in column.saturating_sub(2)..(column + 3).min(width) {
+                    let neighbor = (neighbor_row * width + neighbor_column) as usize;
+                    if !seen[neighbor] && universe.cells.get(neighbor) {
+                        seen[neighbor] = true;
+                        stack.push(neighbor);
+                    }
+                }
+            }
+        }
+        objects.push(CensusObject::new(cells));
+    }
+
+    objects
+}
+
+// How many of each known object there are, anything else is counted as "other"
+pub fn count_objects(universe: &Universe) -> BTreeMap<String, u32> {
+    let mut counts = BTreeMap::new();
+    for object in find_objects(universe) {
+        *counts
+            .entry(object.name.unwrap_or("other").to_string())
+            .or_insert(0) += 1;
+    }
+
+    counts
+}
+
+impl CensusObject {
+    fn new(cells: Vec<(u32, u32)>) -> Self {
+        let row = cells.iter().map(|(row, _)| *row).min().unwrap_or(0);
+        let column = cells.iter().map(|(_, column)| *column).min().unwrap_or(0);
+        let mut cells: Vec<(u32, u32)> = cells
+            .into_iter()
+            .map(|(cell_row, cell_column)| (cell_row - row, cell_column - column))
+            .collect();
+        cells.sort_unstable();
+        let (width, height) = dimensions(&cells);
+
+        Self {
+            name: identify(&cells),
+            row,
+            column,
+            width,
+            height,
+            cells,
+        }
+    }
+}
+
+fn dimensions(cells: &[(u32, u32)]) -> (u32, u32) {
+    let width = cells
+        .iter()
+        .map(|(_, column)| column + 1)
+        .max()
+        .unwrap_or(0);
+    let height = cells.iter().map(|(row, _)| row + 1).max().unwrap_or(0);
+    (width, height)
+}
+
+// The 8 ways to turn and mirror a shape, each one a quarter turn or not followed by two
+// mirrors, which together make up the half turn
+pub fn symmetries() -> Vec<[Transform; 3]> {
+    let mut symmetries = vec![];
+    for rotation in [Transform::Identity, Transform::RotateClockwise].iter() {
+        for mirrors in [
+            [Transform::Identity, Transform::Identity],
+            [Transform::FlipHorizontal, Transform::Identity],
+            [Transform::FlipVertical, Transform::Identity],
+            [Transform::FlipHorizontal, Transform::FlipVertical],
+        ]
+        .iter()
+        {
+            symmetries.push([*rotation, mirrors[0], mirrors[1]]);
+        }
+    }
+
+    symmetries
+}
+
+// The cells after each transform of the symmetry in turn, sorted so they can be compared
+pub fn orient(cells: &[(u32, u32)], symmetry: &[Transform; 3]) -> Vec<(u32, u32)> {
+    let (width, height) = dimensions(cells);
+    let mut oriented: Vec<(u32, u32)> = cells
+        .iter()
+        .map(|(row, column)| {
+            let (mut row, mut column) = (*row, *column);
+            let (mut width, mut height) = (width, height);
+            for transform in symmetry.iter() {
+                let moved = transform.apply(row, column, width, height);
+                row = moved.0;
+                column = moved.1;
+                let turned = transform.dimensions(width, height);
+                width = turned.0;
+                height = turned.1;
+            }
+            (row, column)
+        })
+        .collect();
+    oriented.sort_unstable();
+    oriented
+}
+
+pub fn orientations(cells: &[(u32, u32)]) -> Vec<Vec<(u32, u32)>> {
+    symmetries()
+        .iter()
+        .map(|symmetry| orient(cells, symmetry))
+        .collect()
+}
+
+pub fn live_cells(rows: &[&str]) -> Vec<(u32, u32)> {
+    let mut cells: Vec<(u32, u32)> = rows
+        .iter()
+        .enumerate()
+        .flat_map(|(row, line)| {
+            line.chars()
+                .enumerate()
+                .filter(|(_, character)| *character == 'O')
+                .map(move |(column, _)| (row as u32, column as u32))
+        })
+        .collect();
+    cells.sort_unstable();
+    cells
+}
+
+fn identify(cells: &[(u32, u32)]) -> Option<&'static str> {
+    KNOWN_OBJECTS
+        .iter()
+        .find(|(_, rows)| {
+            let known = live_cells(rows);
+            known.len() == cells.len() && orientations(&known).iter().any(|known| known == cells)
+        })
+        .map(|(name, _)| *name)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Pattern;
+
+    #[test]
+    fn test_find_objects() {
+        let mut universe = Universe::new(12);
+        universe.insert_pattern(Pattern::Glider, 0, 0);
+        universe.insert_pattern(Pattern::Beacon, 6, 6);
+        universe.insert_pattern_transformed(Pattern::Blinker, 0, 8, Transform::RotateClockwise);
+        universe.tick();
+
+        let objects = find_objects(&universe);
+        let names: Vec<_> = objects.iter().map(|object| object.name).collect();
+        assert_eq!(vec![Some("glider"), Some("blinker"), Some("beacon")], names);
+        let blinker = &objects[1];
+        assert_eq!(
+            (1, 7, 3, 1),
+            (blinker.row, blinker.column, blinker.width, blinker.height)
+        );
+    }
+
+    #[test]
+    fn test_count_objects() {
+        let mut universe = Universe::new(16);
+        universe.set_cell(0, 0, crate::Cell::Alive);
+        universe.insert_pattern(Pattern::Toad, 5, 5);
+        universe.insert_pattern(Pattern::Blinker, 12, 1);
+        universe.insert_pattern(Pattern::Blinker, 12, 8);
+
+        let counts = count_objects(&universe);
+        assert_eq!(Some(&1), counts.get("toad"));
+        assert_eq!(Some(&2), counts.get("blinker"));
+        assert_eq!(Some(&1), counts.get("other"));
+
+        universe.tick();
+        assert_eq!(Some(&1), count_objects(&universe).get("toad"));
+    }
+
+    #[test]
+    fn test_orientations() {
+        let glider = live_cells(&[".O.", "..O", "OOO"]);
+        let orientations = orientations(&glider);
+
+        assert_eq!(8, orientations.len());
+        assert!(orientations.contains(&live_cells(&["OOO", "O..", ".O."])));
+        assert!(orientations.contains(&live_cells(&["OO.", "O.O", "O.."])));
+    }
+}
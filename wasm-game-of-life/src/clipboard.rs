@@ -0,0 +1,222 @@
+use std::fmt::{self, Display, Formatter};
+
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::bits::CellBits;
+use crate::{Cell, Pattern, Transform};
+
+// How pasted cells are combined with the cells already on the board
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PasteMode {
+    // every cell of the region is replaced, dead cells included
+    Overwrite,
+    // live cells are added and nothing is removed
+    Or,
+    // live cells flip the cell under them
+    Xor,
+}
+
+// A rectangle of cells copied out of a universe, it keeps its dead cells too so pasting
+// with PasteMode::Overwrite clears the space around the live ones
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Clipboard {
+    width: u32,
+    height: u32,
+    cells: CellBits,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Clipboard {
+    // Just big enough for the pattern's live cells
+    pub fn from_pattern(pattern: Pattern) -> Clipboard {
+        let cells = pattern.live_cells();
+        let width = cells
+            .iter()
+            .map(|(_, column)| column + 1)
+            .max()
+            .unwrap_or(0);
+        let height = cells.iter().map(|(row, _)| row + 1).max().unwrap_or(0);
+        let mut clipboard = Clipboard::new(width, height);
+        for (row, column) in cells {
+            clipboard.set(row, column, true);
+        }
+        clipboard
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn population(&self) -> u32 {
+        self.cells.count_ones()
+    }
+
+    pub fn get_cell(&self, row: u32, column: u32) -> Cell {
+        if row < self.height && column < self.width {
+            Cell::from(self.cells.get(self.get_index(row, column)))
+        } else {
+            Cell::Dead
+        }
+    }
+
+    pub fn render(&self) -> String {
+        self.to_string()
+    }
+
+    pub fn rotate_cw(&self) -> Clipboard {
+        self.transformed(Transform::RotateClockwise)
+    }
+
+    pub fn rotate_ccw(&self) -> Clipboard {
+        self.transformed(Transform::RotateCounterClockwise)
+    }
+
+    pub fn flip_horizontal(&self) -> Clipboard {
+        self.transformed(Transform::FlipHorizontal)
+    }
+
+    pub fn flip_vertical(&self) -> Clipboard {
+        self.transformed(Transform::FlipVertical)
+    }
+
+    pub fn transformed(&self, transform: Transform) -> Clipboard {
+        let (width, height) = transform.dimensions(self.width, self.height);
+        let mut clipboard = Clipboard::new(width, height);
+        for row in 0..self.height {
+            for column in 0..self.width {
+                let (new_row, new_column) = transform.apply(row, column, self.width, self.height);
+                clipboard.set(
+                    new_row,
+                    new_column,
+                    self.cells.get(self.get_index(row, column)),
+                );
+            }
+        }
+
+        clipboard
+    }
+}
+
+impl Clipboard {
+    // Clipboards are at most as big as a board, so the cells can always be counted
+    pub(crate) fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            cells: CellBits::new(width as usize * height as usize),
+        }
+    }
+
+    pub(crate) fn set(&mut self, row: u32, column: u32, alive: bool) {
+        let index = self.get_index(row, column);
+        self.cells.set(index, alive);
+    }
+
+    fn get_index(&self, row: u32, column: u32) -> usize {
+        row as usize * self.width as usize + column as usize
+    }
+}
+
+impl Display for Clipboard {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for row in 0..self.height {
+            for column in 0..self.width {
+                write!(f, "{}", self.get_cell(row, column))?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+// Replays keep pasted clipboards, with the cells packed 32 to a number like a save
+#[derive(Serialize, Deserialize)]
+struct ClipboardState {
+    width: u32,
+    height: u32,
+    cells: Vec<u32>,
+}
+
+impl Serialize for Clipboard {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ClipboardState {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.words().to_vec(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Clipboard {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let state = ClipboardState::deserialize(deserializer)?;
+        let cell_count = u64::from(state.width) * u64::from(state.height);
+        if cell_count > u64::from(u32::MAX) || state.cells.len() as u64 != cell_count.div_ceil(32) {
+            return Err(D::Error::custom("cell data does not match the dimensions"));
+        }
+        let cell_count = cell_count as usize;
+        let mut cells = CellBits::new(cell_count);
+        for (word, bits) in state.cells.iter().enumerate() {
+            cells.write_bits(word * 32, (cell_count - word * 32).min(32), *bits);
+        }
+
+        Ok(Clipboard {
+            width: state.width,
+            height: state.height,
+            cells,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn l_shape() -> Clipboard {
+        // O.
+        // O.
+        // OO
+        let mut clipboard = Clipboard::new(2, 3);
+        for (row, column) in [(0, 0), (1, 0), (2, 0), (2, 1)].iter() {
+            clipboard.set(*row, *column, true);
+        }
+        clipboard
+    }
+
+    #[test]
+    fn test_from_pattern() {
+        let glider = Clipboard::from_pattern(Pattern::Glider);
+        assert_eq!(
+            (3, 3, 5),
+            (glider.width(), glider.height(), glider.population())
+        );
+    }
+
+    #[test]
+    fn test_rotations() {
+        assert_eq!("◼◼◼\n◼◻◻\n", l_shape().rotate_cw().render());
+        assert_eq!("◻◻◼\n◼◼◼\n", l_shape().rotate_ccw().render());
+        assert_eq!(l_shape(), l_shape().rotate_cw().rotate_ccw());
+        assert_eq!(
+            l_shape().flip_horizontal().flip_vertical(),
+            l_shape().rotate_cw().rotate_cw()
+        );
+    }
+
+    #[test]
+    fn test_flips() {
+        assert_eq!("◻◼\n◻◼\n◼◼\n", l_shape().flip_horizontal().render());
+        assert_eq!("◼◼\n◼◻\n◼◻\n", l_shape().flip_vertical().render());
+    }
+}
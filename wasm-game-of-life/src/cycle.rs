@@ -0,0 +1,74 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+
+// The longest period detect_cycle() can find, covers every common oscillator with room to
+// spare while keeping the hashes at 8KB
+pub const MAX_PERIOD: usize = 1024;
+
+// Hashes of the generations leading up to the current one, newest at the back. Two
+// generations with the same hash are taken to be the same, with 64 bit hashes a false
+// match is far less likely than the page being reloaded.
+#[derive(Clone, Debug, Default)]
+pub struct CycleDetector {
+    hashes: VecDeque<u64>,
+}
+
+impl CycleDetector {
+    pub fn record(&mut self, hash: u64) {
+        if self.hashes.len() == MAX_PERIOD {
+            self.hashes.pop_front();
+        }
+        self.hashes.push_back(hash);
+    }
+
+    // The smallest period up to max_period that brings the generation hashed as `current`
+    // back around, 1 for a still life
+    pub fn period(&self, current: u64, max_period: u32) -> Option<u32> {
+        self.hashes
+            .iter()
+            .rev()
+            .take(max_period as usize)
+            .position(|hash| *hash == current)
+            .map(|position| position as u32 + 1)
+    }
+
+    pub fn clear(&mut self) {
+        self.hashes.clear();
+    }
+}
+
+pub fn hash<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_period() {
+        let mut detector = CycleDetector::default();
+        for generation in [1, 2, 3, 1, 2].iter() {
+            detector.record(*generation);
+        }
+
+        assert_eq!(Some(3), detector.period(3, 10));
+        assert_eq!(None, detector.period(3, 2));
+        assert_eq!(Some(1), detector.period(2, 10));
+        assert_eq!(None, detector.period(4, 10));
+    }
+
+    #[test]
+    fn test_only_keeps_max_period_hashes() {
+        let mut detector = CycleDetector::default();
+        for generation in 0..MAX_PERIOD as u64 + 1 {
+            detector.record(generation);
+        }
+
+        assert_eq!(None, detector.period(0, u32::MAX));
+        assert_eq!(Some(MAX_PERIOD as u32), detector.period(1, u32::MAX));
+    }
+}
@@ -0,0 +1,126 @@
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::Universe;
+
+// How two universes differ, from Universe::diff()
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UniverseDiff {
+    only_in_self: u32,
+    only_in_other: u32,
+    same_dimensions: bool,
+    // indexes into the universe diff() was called on
+    indices: Vec<u32>,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl UniverseDiff {
+    // Cells alive in the universe diff() was called on and dead in the other
+    pub fn only_in_self(&self) -> u32 {
+        self.only_in_self
+    }
+
+    pub fn only_in_other(&self) -> u32 {
+        self.only_in_other
+    }
+
+    pub fn count(&self) -> u32 {
+        self.only_in_self + self.only_in_other
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count() == 0 && self.same_dimensions
+    }
+
+    // False when the boards are different sizes, only the part they share from the top
+    // left corner is compared then
+    pub fn same_dimensions(&self) -> bool {
+        self.same_dimensions
+    }
+
+    // Every differing cell in row major order
+    pub fn indices(&self) -> Vec<u32> {
+        self.indices.clone()
+    }
+}
+
+pub fn diff(universe: &Universe, other: &Universe) -> UniverseDiff {
+    if (universe.width, universe.height) == (other.width, other.height) {
+        let (only_in_other, only_in_self) = universe.cells.count_changes(&other.cells);
+        return UniverseDiff {
+            only_in_self,
+            only_in_other,
+            same_dimensions: true,
+            indices: universe
+                .cells
+                .differences(&other.cells)
+                .map(|index| index as u32)
+                .collect(),
+        };
+    }
+
+    let mut diff = UniverseDiff::default();
+    for row in 0..universe.height.min(other.height) {
+        for column in 0..universe.width.min(other.width) {
+            let index = universe.get_index(row, column);
+            let alive = universe.cells.get(index);
+            if alive == other.cells.get(other.get_index(row, column)) {
+                continue;
+            }
+            if alive {
+                diff.only_in_self += 1;
+            } else {
+                diff.only_in_other += 1;
+            }
+            diff.indices.push(index as u32);
+        }
+    }
+
+    diff
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Engine, Pattern};
+
+    #[test]
+    fn test_diff() {
+        let mut universe = Universe::new(8);
+        universe.insert_pattern(Pattern::Blinker, 3, 2);
+        let mut other = universe.clone();
+        other.tick();
+
+        let diff = diff(&universe, &other);
+        assert_eq!(2, diff.only_in_self());
+        assert_eq!(2, diff.only_in_other());
+        assert_eq!(vec![19, 26, 28, 35], diff.indices());
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn test_engines_match() {
+        let mut naive = Universe::new_with_dimensions(70, 40);
+        naive.randomize_seeded(11, 0.35);
+        let mut packed = naive.clone_universe().with_engine(Engine::Packed);
+        naive.tick_n(30);
+        packed.tick_n(30);
+
+        assert!(diff(&naive, &packed).is_empty());
+    }
+
+    #[test]
+    fn test_diff_of_different_sizes() {
+        let mut universe = Universe::new(4);
+        universe.set_cell(1, 1, crate::Cell::Alive);
+        universe.set_cell(3, 3, crate::Cell::Alive);
+        let mut other = Universe::new_with_dimensions(3, 5);
+        other.set_cell(0, 2, crate::Cell::Alive);
+
+        let diff = diff(&universe, &other);
+        assert!(!diff.same_dimensions());
+        assert_eq!((1, 1), (diff.only_in_self(), diff.only_in_other()));
+        assert_eq!(vec![2, 5], diff.indices());
+    }
+}
@@ -0,0 +1,440 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::Topology;
+
+// The (row, column) cells that make up simple shapes, for editing a universe with a drag
+// of the mouse. Points can land past the edges of the board, Universe decides what
+// happens to those. Every shape is cut down to its Bounds before its points are listed,
+// so a shape billions of cells across only lists the ones that can reach the board.
+
+// The part of the plane a shape's points can land on the board from. Boards whose edges
+// join up repeat every period, filled shapes are cut down to one period and lines and
+// circles are moved next to the board and cut down to a period on each side of it, so
+// they wrap round once.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bounds {
+    // start included, end not
+    rows: (i64, i64),
+    columns: (i64, i64),
+    // the rows and columns between two copies of the same cell
+    period: Option<(i64, i64)>,
+}
+
+impl Bounds {
+    pub fn new(width: u32, height: u32, topology: Topology) -> Self {
+        let (width, height) = (i64::from(width), i64::from(height));
+        let board = Bounds {
+            rows: (0, height),
+            columns: (0, width),
+            period: None,
+        };
+        if width == 0 || height == 0 {
+            return board;
+        }
+        // a trip across a twisted edge mirrors the cells, it takes two to get back
+        let period = match topology {
+            Topology::Plane => return board,
+            Topology::Sphere if width != height => return board,
+            // the corners turn twice, which can bring back cells up to two boards out
+            Topology::Sphere => {
+                return Bounds {
+                    rows: (-2 * height, 3 * height),
+                    columns: (-2 * width, 3 * width),
+                    period: None,
+                }
+            }
+            Topology::Torus => (height, width),
+            Topology::KleinBottle => (2 * height, width),
+            Topology::CrossSurface => (2 * height, 2 * width),
+        };
+        Bounds {
+            rows: (-period.0, 2 * period.0),
+            columns: (-period.1, 2 * period.1),
+            period: Some(period),
+        }
+    }
+
+    // Moves a point by whole periods to the first copy of the board, where the lines and
+    // circles that start there are cut down from
+    fn nearest(&self, (row, column): (i64, i64)) -> (i64, i64) {
+        match self.period {
+            Some((rows, columns)) => (row.rem_euclid(rows), column.rem_euclid(columns)),
+            None => (row, column),
+        }
+    }
+
+    fn contains(&self, (row, column): (i64, i64)) -> bool {
+        (self.rows.0..self.rows.1).contains(&row)
+            && (self.columns.0..self.columns.1).contains(&column)
+    }
+
+    // The rows of a run of length rows from start that have to be painted
+    fn cut_rows(&self, start: i64, length: i64) -> std::ops::Range<i64> {
+        cut(start, length, self.rows, self.period.map(|(rows, _)| rows))
+    }
+
+    fn cut_columns(&self, start: i64, length: i64) -> std::ops::Range<i64> {
+        cut(
+            start,
+            length,
+            self.columns,
+            self.period.map(|(_, columns)| columns),
+        )
+    }
+}
+
+// A run along one side of a filled shape, a period or more of it paints every cell once
+fn cut(start: i64, length: i64, bounds: (i64, i64), period: Option<i64>) -> std::ops::Range<i64> {
+    if length <= 0 {
+        return 0..0;
+    }
+    match period {
+        Some(period) if length >= period => 0..period,
+        Some(period) => {
+            let start = start.rem_euclid(period);
+            start..start + length
+        }
+        None => start.max(bounds.0)..start.saturating_add(length).min(bounds.1),
+    }
+}
+
+// The steps along a line or round a circle that land inside bounds, for a coordinate
+// of origin + sign * step
+fn steps_inside(origin: i64, sign: i64, bounds: (i64, i64)) -> std::ops::Range<i64> {
+    if sign > 0 {
+        bounds.0 - origin..bounds.1 - origin
+    } else {
+        origin - bounds.1 + 1..origin - bounds.0 + 1
+    }
+}
+
+fn square_root(value: i128) -> i64 {
+    if value <= 0 {
+        return 0;
+    }
+    let mut root = (value as f64).sqrt() as i128;
+    while root * root > value {
+        root -= 1;
+    }
+    while (root + 1) * (root + 1) <= value {
+        root += 1;
+    }
+    root as i64
+}
+
+// Bresenham's line from one end to the other, both ends included
+// https://en.wikipedia.org/wiki/Bresenham%27s_line_algorithm
+// Each step moves one cell along the longer side and the shorter side moves by the
+// rounded share of it, which is where Bresenham's error term would put it. Working that
+// out directly means the steps outside bounds never have to be walked.
+pub fn line(start: (i64, i64), end: (i64, i64), bounds: Bounds) -> Vec<(i64, i64)> {
+    let moved = bounds.nearest(start);
+    let end = (end.0 + moved.0 - start.0, end.1 + moved.1 - start.1);
+    let start = moved;
+    let row_distance = (end.0 - start.0).abs();
+    let column_distance = (end.1 - start.1).abs();
+    let row_step = if start.0 < end.0 { 1 } else { -1 };
+    let column_step = if start.1 < end.1 { 1 } else { -1 };
+    let steps = row_distance.max(column_distance);
+    let along_columns = column_distance >= row_distance;
+    let share = |step: i64, distance: i64| {
+        if steps == 0 {
+            return 0;
+        }
+        let doubled = 2 * i128::from(step) * i128::from(distance) + i128::from(steps);
+        (doubled / (2 * i128::from(steps))) as i64
+    };
+
+    let inside = if along_columns {
+        steps_inside(start.1, column_step, bounds.columns)
+    } else {
+        steps_inside(start.0, row_step, bounds.rows)
+    };
+    (inside.start.max(0)..inside.end.min(steps + 1))
+        .map(|step| {
+            if along_columns {
+                (
+                    start.0 + row_step * share(step, row_distance),
+                    start.1 + column_step * step,
+                )
+            } else {
+                (
+                    start.0 + row_step * step,
+                    start.1 + column_step * share(step, column_distance),
+                )
+            }
+        })
+        .filter(|point| bounds.contains(*point))
+        .collect()
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BrushShape {
+    Circle,
+    Square,
+}
+
+// Every cell within radius of the center, a radius of 0 is the center on its own
+pub fn brush(
+    center: (i64, i64),
+    radius: i64,
+    shape: BrushShape,
+    bounds: Bounds,
+) -> Vec<(i64, i64)> {
+    let (center_row, center_column) = center;
+    if shape == BrushShape::Square {
+        return filled_rectangle(
+            center_row - radius,
+            center_column - radius,
+            2 * radius + 1,
+            2 * radius + 1,
+            bounds,
+        );
+    }
+    // the extra radius rounds off the single cells that would stick out of each side
+    let reach = i128::from(radius) * i128::from(radius) + i128::from(radius);
+    let offsets = match bounds.period {
+        // a circle with a whole period inside it paints the whole board, the square that
+        // fits in it says so. Any smaller and it's no more than a period and a half across.
+        Some((rows, columns)) => {
+            let inside = square_root(reach / 2);
+            if 2 * inside + 1 >= rows.max(columns) {
+                return filled_rectangle(0, 0, columns, rows, bounds);
+            }
+            -radius..=radius
+        }
+        None => {
+            let rows = bounds.cut_rows(center_row - radius, 2 * radius + 1);
+            rows.start - center_row..=rows.end - 1 - center_row
+        }
+    };
+
+    let mut points = vec![];
+    for row_offset in offsets {
+        let half = square_root(reach - i128::from(row_offset) * i128::from(row_offset));
+        let row = bounds.cut_rows(center_row + row_offset, 1);
+        for column in bounds.cut_columns(center_column - half, 2 * half + 1) {
+            points.extend(row.clone().map(|row| (row, column)));
+        }
+    }
+
+    points
+}
+
+// The border of a width by height rectangle with its top left at row, column
+pub fn rectangle(
+    row: i64,
+    column: i64,
+    width: i64,
+    height: i64,
+    bounds: Bounds,
+) -> Vec<(i64, i64)> {
+    if width <= 0 || height <= 0 {
+        return vec![];
+    }
+    let bottom = row + height - 1;
+    let right = column + width - 1;
+    let mut points = filled_rectangle(row, column, width, 1, bounds);
+    if bottom != row {
+        points.extend(filled_rectangle(bottom, column, width, 1, bounds));
+    }
+    points.extend(filled_rectangle(row + 1, column, 1, height - 2, bounds));
+    if right != column {
+        points.extend(filled_rectangle(row + 1, right, 1, height - 2, bounds));
+    }
+
+    points
+}
+
+pub fn filled_rectangle(
+    row: i64,
+    column: i64,
+    width: i64,
+    height: i64,
+    bounds: Bounds,
+) -> Vec<(i64, i64)> {
+    let columns = bounds.cut_columns(column, width);
+    bounds
+        .cut_rows(row, height)
+        .flat_map(|row| columns.clone().map(move |column| (row, column)))
+        .collect()
+}
+
+// The outline of a circle from the midpoint circle algorithm, each octant is worked out
+// and mirrored into the other seven
+// https://en.wikipedia.org/wiki/Midpoint_circle_algorithm
+// The algorithm's column for each row is the largest one with column * (column - 1) less
+// than radius² - row², which picks the rows inside bounds out without walking the rest.
+pub fn circle(center: (i64, i64), radius: i64, bounds: Bounds) -> Vec<(i64, i64)> {
+    let (center_row, center_column) = bounds.nearest(center);
+    let column_at = |row: i64| {
+        let left = i128::from(radius) * i128::from(radius) - i128::from(row) * i128::from(row);
+        let mut column = square_root(left) + 1;
+        while column > 0 && i128::from(column) * i128::from(column - 1) >= left {
+            column -= 1;
+        }
+        column
+    };
+    // the octant ends where the row catches up with the column
+    let (mut last, mut past) = (0, radius + 1);
+    while past - last > 1 {
+        let middle = last + (past - last) / 2;
+        if middle <= column_at(middle) {
+            last = middle;
+        } else {
+            past = middle;
+        }
+    }
+
+    let mut points = vec![];
+    // (row and column swapped, sign of the row, sign of the column) of each octant,
+    // mirrored octants that would repeat a point on an axis or a diagonal skip it
+    for &(swapped, row_sign, column_sign) in [
+        (false, 1, 1),
+        (true, 1, 1),
+        (false, -1, 1),
+        (true, -1, 1),
+        (false, 1, -1),
+        (true, 1, -1),
+        (false, -1, -1),
+        (true, -1, -1),
+    ]
+    .iter()
+    {
+        let (step_sign, other_sign, inside) = if swapped {
+            (
+                column_sign,
+                row_sign,
+                steps_inside(center_column, column_sign, bounds.columns),
+            )
+        } else {
+            (
+                row_sign,
+                column_sign,
+                steps_inside(center_row, row_sign, bounds.rows),
+            )
+        };
+        for row in inside.start.max(0)..inside.end.min(last + 1) {
+            let column = column_at(row);
+            if (row == 0 && step_sign < 0)
+                || (column == 0 && other_sign < 0)
+                || (row == column && swapped)
+            {
+                continue;
+            }
+            let (row_offset, column_offset) = if swapped {
+                (column, row)
+            } else {
+                (row, column)
+            };
+            let point = (
+                center_row + row_sign * row_offset,
+                center_column + column_sign * column_offset,
+            );
+            if bounds.contains(point) {
+                points.push(point);
+            }
+        }
+    }
+
+    points
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // room for every shape below, points off the top and left included
+    const OPEN: Bounds = Bounds {
+        rows: (-100, 100),
+        columns: (-100, 100),
+        period: None,
+    };
+
+    #[test]
+    fn test_line() {
+        assert_eq!(vec![(0, 0), (1, 1), (2, 2)], line((0, 0), (2, 2), OPEN));
+        assert_eq!(vec![(2, 3), (2, 2), (2, 1)], line((2, 3), (2, 1), OPEN));
+        assert_eq!(
+            vec![(0, 0), (0, 1), (1, 2), (1, 3)],
+            line((0, 0), (1, 3), OPEN)
+        );
+        assert_eq!(vec![(4, 4)], line((4, 4), (4, 4), OPEN));
+    }
+
+    #[test]
+    fn test_rectangles() {
+        assert_eq!(8, rectangle(0, 0, 3, 3, OPEN).len());
+        assert_eq!(vec![(1, 1), (1, 2)], rectangle(1, 1, 2, 1, OPEN));
+        assert!(rectangle(0, 0, 0, 3, OPEN).is_empty());
+        assert_eq!(6, filled_rectangle(-1, -1, 3, 2, OPEN).len());
+    }
+
+    #[test]
+    fn test_brush() {
+        assert_eq!(vec![(2, 2)], brush((2, 2), 0, BrushShape::Circle, OPEN));
+        assert_eq!(9, brush((2, 2), 1, BrushShape::Circle, OPEN).len());
+        assert_eq!(21, brush((2, 2), 2, BrushShape::Circle, OPEN).len());
+        assert_eq!(25, brush((2, 2), 2, BrushShape::Square, OPEN).len());
+    }
+
+    #[test]
+    fn test_circle() {
+        let mut points = circle((0, 0), 1, OPEN);
+        points.sort_unstable();
+        assert_eq!(vec![(-1, 0), (0, -1), (0, 1), (1, 0)], points);
+        assert_eq!(24, circle((5, 5), 4, OPEN).len());
+        assert_eq!(vec![(3, 3)], circle((3, 3), 0, OPEN));
+    }
+
+    #[test]
+    fn test_circle_has_no_repeats() {
+        for radius in 0..40 {
+            let mut points = circle((0, 0), radius, OPEN);
+            let count = points.len();
+            points.sort_unstable();
+            points.dedup();
+            assert_eq!(count, points.len());
+        }
+    }
+
+    #[test]
+    fn test_cut_to_the_board() {
+        let huge = i64::from(u32::MAX);
+        let plane = Bounds::new(4, 3, Topology::Plane);
+        assert_eq!(
+            12,
+            filled_rectangle(-huge, -huge, 2 * huge, 2 * huge, plane).len()
+        );
+        assert_eq!(6, rectangle(0, 0, huge, huge, plane).len());
+        assert_eq!(12, brush((1, 1), huge, BrushShape::Circle, plane).len());
+        assert_eq!(
+            vec![(1, 0), (1, 1), (1, 2), (1, 3)],
+            line((1, -huge), (1, huge), plane)
+        );
+        assert_eq!(
+            vec![(0, 0), (1, 1), (2, 2)],
+            line((0, 0), (huge, huge), plane)
+        );
+        assert!(circle((1, 1), huge, plane).is_empty());
+        // the circle around a far away center still crosses the board
+        assert_eq!(vec![(0, 0), (0, 1), (0, 2), (0, 3)], {
+            let mut points = circle((huge, 1), huge, plane);
+            points.sort_unstable();
+            points
+        });
+
+        let torus = Bounds::new(4, 3, Topology::Torus);
+        assert_eq!(12, filled_rectangle(-huge, -huge, huge, huge, torus).len());
+        assert_eq!(12, brush((1, 1), huge, BrushShape::Circle, torus).len());
+        assert_eq!(
+            vec![(0, 3), (0, 4)],
+            filled_rectangle(huge, huge, 2, 1, torus)
+        );
+        // lines and circles wrap round once
+        assert_eq!(6, line((0, 0), (huge, huge), torus).len());
+    }
+}
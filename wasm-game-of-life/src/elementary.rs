@@ -0,0 +1,251 @@
+use std::fmt::{self, Display, Formatter};
+
+use rand::prelude::*;
+use rand_pcg::Pcg32;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::bits::CellBits;
+use crate::stochastic::clamp_chance;
+use crate::{BoundaryMode, Cell};
+
+// A one dimensional automaton where each cell looks at itself and its two neighbors, bit n
+// of the Wolfram rule number is the next state for the neighborhood whose cells read as n
+// in binary with the left neighbor as the high bit. Every generation is kept as a row of
+// an image that scrolls up once it is full.
+// https://en.wikipedia.org/wiki/Elementary_cellular_automaton
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug)]
+pub struct ElementaryAutomaton {
+    width: u32,
+    height: u32,
+    rule: u8,
+    boundary_mode: BoundaryMode,
+    image: CellBits,
+    // the number of image rows filled in so far, the last one is the current generation
+    rows: u32,
+    generation: u32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ElementaryAutomaton {
+    // Starts with a single live cell in the middle of the first row
+    pub fn new(width: u32, height: u32, rule: u8) -> Self {
+        let mut automaton = Self {
+            width,
+            height,
+            rule,
+            boundary_mode: BoundaryMode::Clamped,
+            image: CellBits::new((width * height) as usize),
+            rows: 1,
+            generation: 0,
+        };
+        automaton.set_cell(width / 2, Cell::Alive);
+        automaton
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    // How many rows of the image hold a generation, this stops growing at height()
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    pub fn rule(&self) -> u8 {
+        self.rule
+    }
+
+    pub fn set_rule(&mut self, rule: u8) {
+        self.rule = rule;
+    }
+
+    pub fn boundary_mode(&self) -> BoundaryMode {
+        self.boundary_mode
+    }
+
+    pub fn set_boundary_mode(&mut self, boundary_mode: BoundaryMode) {
+        self.boundary_mode = boundary_mode;
+    }
+
+    // The image packed 32 cells to a word in the same layout as Universe::bits_ptr()
+    pub fn bits_ptr(&self) -> *const u32 {
+        self.image.words().as_ptr()
+    }
+
+    pub fn bits_len(&self) -> usize {
+        self.image.words().len()
+    }
+
+    // Cells of the image, rows that haven't been reached yet are dead
+    pub fn get_cell(&self, row: u32, column: u32) -> Cell {
+        if row < self.height && column < self.width {
+            Cell::from(self.image.get((row * self.width + column) as usize))
+        } else {
+            Cell::Dead
+        }
+    }
+
+    // Edits the current generation, which is the last row that was filled in
+    pub fn set_cell(&mut self, column: u32, cell: Cell) {
+        if column < self.width && self.height > 0 {
+            let index = self.current_row_start() + column as usize;
+            self.image.set(index, cell == Cell::Alive);
+        }
+    }
+
+    // Starts over from a random first row
+    pub fn randomize_seeded(&mut self, seed: u64, alive_probability: f64) {
+        let mut rng = Pcg32::seed_from_u64(seed);
+        let alive_probability = clamp_chance(alive_probability);
+
+        self.image.clear();
+        self.rows = 1;
+        self.generation = 0;
+        for column in 0..self.width {
+            self.set_cell(column, Cell::from(rng.gen_bool(alive_probability)));
+        }
+    }
+
+    pub fn tick(&mut self) {
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
+        let current = self.current_row_start();
+        let next: Vec<bool> = (0..self.width)
+            .map(|column| {
+                let neighborhood = (self.neighbor(current, column, -1) as u8) << 2
+                    | (self.image.get(current + column as usize) as u8) << 1
+                    | self.neighbor(current, column, 1) as u8;
+                self.rule & (1 << neighborhood) != 0
+            })
+            .collect();
+
+        if self.rows < self.height {
+            self.rows += 1;
+        } else {
+            self.scroll();
+        }
+        let start = self.current_row_start();
+        for (column, alive) in next.into_iter().enumerate() {
+            self.image.set(start + column, alive);
+        }
+        self.generation += 1;
+    }
+
+    pub fn tick_n(&mut self, steps: u32) {
+        for _ in 0..steps {
+            self.tick();
+        }
+    }
+
+    pub fn render(&self) -> String {
+        self.to_string()
+    }
+
+    fn current_row_start(&self) -> usize {
+        ((self.rows - 1) * self.width) as usize
+    }
+
+    fn neighbor(&self, row_start: usize, column: u32, offset: i64) -> bool {
+        let column = i64::from(column) + offset;
+        let width = i64::from(self.width);
+        let column = match self.boundary_mode {
+            BoundaryMode::Clamped if column < 0 || column >= width => return false,
+            BoundaryMode::Clamped => column,
+            BoundaryMode::Toroidal => column.rem_euclid(width),
+        };
+        self.image.get(row_start + column as usize)
+    }
+
+    // Drops the oldest row to make room at the bottom
+    fn scroll(&mut self) {
+        let width = self.width as usize;
+        let mut image = CellBits::new(self.image.len());
+        for index in width..self.image.len() {
+            image.set(index - width, self.image.get(index));
+        }
+        self.image = image;
+    }
+}
+
+impl Display for ElementaryAutomaton {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for row in 0..self.rows {
+            for column in 0..self.width {
+                write!(f, "{}", self.get_cell(row, column))?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn row(automaton: &ElementaryAutomaton, row: u32) -> String {
+        (0..automaton.width())
+            .map(|column| match automaton.get_cell(row, column) {
+                Cell::Alive => '#',
+                Cell::Dead => '.',
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rule_30() {
+        let mut automaton = ElementaryAutomaton::new(7, 4, 30);
+        automaton.tick_n(3);
+
+        assert_eq!("...#...", row(&automaton, 0));
+        assert_eq!("..###..", row(&automaton, 1));
+        assert_eq!(".##..#.", row(&automaton, 2));
+        assert_eq!("##.####", row(&automaton, 3));
+        assert_eq!(4, automaton.rows());
+    }
+
+    #[test]
+    fn test_image_scrolls_when_full() {
+        let mut automaton = ElementaryAutomaton::new(7, 2, 90);
+        automaton.tick_n(2);
+
+        assert_eq!(2, automaton.rows());
+        assert_eq!(2, automaton.generation());
+        assert_eq!("..#.#..", row(&automaton, 0));
+        assert_eq!(".#...#.", row(&automaton, 1));
+    }
+
+    #[test]
+    fn test_toroidal_rule_110() {
+        let mut automaton = ElementaryAutomaton::new(4, 2, 110);
+        automaton.set_cell(2, Cell::Dead);
+        automaton.set_cell(0, Cell::Alive);
+        automaton.set_boundary_mode(BoundaryMode::Toroidal);
+        automaton.tick();
+
+        // the cell on the right edge sees the live cell on the left edge
+        assert_eq!("#..#", row(&automaton, 1));
+    }
+
+    #[test]
+    fn test_randomize_seeded() {
+        let mut automaton = ElementaryAutomaton::new(8, 2, 30);
+        automaton.randomize_seeded(1, 1.0);
+        assert_eq!("########", row(&automaton, 0));
+
+        automaton.randomize_seeded(1, f64::NAN);
+        assert_eq!("........", row(&automaton, 0));
+    }
+}
@@ -0,0 +1,931 @@
+// Gosper's HashLife, the plane is stored as a quadtree where identical nodes are shared
+// and the future of every node is memoized, so repeating and sparse patterns can be
+// advanced enormous numbers of generations cheaply.
+// https://conwaylife.com/wiki/HashLife
+use std::collections::HashMap;
+
+use super::{Engine, UniverseEngine};
+use crate::bits::CellBits;
+use crate::formats::{self, ParseError};
+use crate::memory::vec_bytes;
+use crate::{LogLevel, RuleSet, Universe};
+
+pub(crate) type NodeId = u32;
+
+const DEAD: NodeId = 0;
+const ALIVE: NodeId = 1;
+// Once the arena grows past this many nodes, everything not reachable from the root is dropped
+const MAX_NODES: usize = 1 << 21;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct Quad {
+    nw: NodeId,
+    ne: NodeId,
+    sw: NodeId,
+    se: NodeId,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Node {
+    level: u8,
+    quad: Quad,
+    population: u64,
+}
+
+// An unbounded plane of cells, x is the column and y is the row
+#[derive(Clone, Debug)]
+pub struct HashLife {
+    rule: RuleSet,
+    nodes: Vec<Node>,
+    lookup: HashMap<Quad, NodeId>,
+    successors: HashMap<(NodeId, u8), NodeId>,
+    empty: Vec<NodeId>,
+    root: NodeId,
+    // world coordinates of the top left cell of the root
+    origin_x: i64,
+    origin_y: i64,
+    generation: u64,
+    // the universe's board as the plane last had it, with its top left cell at 0, 0
+    board: Option<Board>,
+}
+
+// A universe jumped forward on the plane, kept so the next jump can carry on from the tree
+// when the board hasn't been edited since
+#[derive(Clone, Debug)]
+struct Board {
+    width: u32,
+    cells: CellBits,
+}
+
+impl Default for HashLife {
+    fn default() -> Self {
+        Self::new(RuleSet::default())
+    }
+}
+
+impl HashLife {
+    pub fn new(rule: RuleSet) -> Self {
+        let leaf = |population| Node {
+            level: 0,
+            quad: Quad {
+                nw: DEAD,
+                ne: DEAD,
+                sw: DEAD,
+                se: DEAD,
+            },
+            population,
+        };
+        let mut hashlife = Self {
+            rule,
+            nodes: vec![leaf(0), leaf(1)],
+            lookup: HashMap::new(),
+            successors: HashMap::new(),
+            empty: vec![DEAD],
+            root: DEAD,
+            origin_x: -4,
+            origin_y: -4,
+            generation: 0,
+            board: None,
+        };
+        hashlife.root = hashlife.empty_node(3);
+
+        hashlife
+    }
+
+    // Golly's macrocell format, which holds the quadtree itself so patterns far too big
+    // for a grid can be loaded
+    pub fn from_macrocell(macrocell: &str) -> Result<HashLife, ParseError> {
+        formats::macrocell::parse(macrocell)
+    }
+
+    pub fn rule(&self) -> RuleSet {
+        self.rule
+    }
+
+    // Memoized futures depend on the rule so they are thrown away when it changes
+    pub fn set_rule(&mut self, rule: RuleSet) {
+        if rule != self.rule {
+            self.rule = rule;
+            self.successors.clear();
+        }
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn population(&self) -> u64 {
+        self.nodes[self.root as usize].population
+    }
+
+    pub fn get_cell(&self, x: i64, y: i64) -> bool {
+        let mut node = self.root;
+        let mut level = self.level(node);
+        let (mut x, mut y) = (x - self.origin_x, y - self.origin_y);
+        if x < 0 || y < 0 || x >= 1 << level || y >= 1 << level {
+            return false;
+        }
+
+        while level > 0 {
+            let half = 1 << (level - 1);
+            let quad = self.quad(node);
+            node = match (y < half, x < half) {
+                (true, true) => quad.nw,
+                (true, false) => quad.ne,
+                (false, true) => quad.sw,
+                (false, false) => quad.se,
+            };
+            x %= half;
+            y %= half;
+            level -= 1;
+        }
+
+        node == ALIVE
+    }
+
+    pub fn set_cell(&mut self, x: i64, y: i64, alive: bool) {
+        loop {
+            let size = 1 << self.level(self.root);
+            if x >= self.origin_x
+                && y >= self.origin_y
+                && x < self.origin_x + size
+                && y < self.origin_y + size
+            {
+                break;
+            }
+            self.expand();
+        }
+
+        let root = self.root;
+        self.root = self.set_in(root, x - self.origin_x, y - self.origin_y, alive);
+    }
+
+    pub fn clear(&mut self) {
+        let level = self.level(self.root);
+        self.root = self.empty_node(level);
+    }
+
+    // Coordinates of every live cell, in no particular order
+    pub fn live_cells(&self) -> Vec<(i64, i64)> {
+        let mut cells = vec![];
+        let level = self.level(self.root);
+        self.for_each_live(
+            self.root,
+            level,
+            (self.origin_x, self.origin_y),
+            &mut |x, y| cells.push((x, y)),
+        );
+
+        cells
+    }
+
+    // Steps forward in the largest power of two jumps the generation count allows. Under
+    // a B0 rule every dead cell of the infinite plane around the pattern would be born,
+    // which no quadtree can hold, so the plane doesn't move.
+    pub fn advance(&mut self, generations: u64) {
+        if self.births_on_empty() {
+            log!(
+                LogLevel::Warn,
+                "HashLife can't advance an unbounded plane under {}",
+                self.rule
+            );
+            return;
+        }
+        let mut remaining = generations;
+        let mut step_log = 0;
+        while remaining > 0 {
+            if remaining & 1 == 1 {
+                self.step(step_log);
+            }
+            remaining >>= 1;
+            step_log += 1;
+        }
+    }
+
+    fn step(&mut self, step_log: u8) {
+        if self.nodes.len() > MAX_NODES {
+            self.collect_garbage();
+        }
+        // the pattern has to sit inside the middle of the root with at least 2^step_log
+        // cells of empty space around it so nothing escapes the center that is returned
+        while self.level(self.root) < step_log + 2 || !self.is_centered(self.root) {
+            self.expand();
+        }
+        self.expand();
+
+        let level = self.level(self.root);
+        let root = self.root;
+        self.root = self.successor(root, step_log);
+        self.origin_x += 1 << (level - 2);
+        self.origin_y += 1 << (level - 2);
+        self.generation += 1 << step_log;
+    }
+
+    // Wraps the root in a node twice its size with the old root in the middle
+    fn expand(&mut self) {
+        let level = self.level(self.root);
+        let root = self.quad(self.root);
+        let empty = self.empty_node(level - 1);
+        let quad = Quad {
+            nw: self.join(empty, empty, empty, root.nw),
+            ne: self.join(empty, empty, root.ne, empty),
+            sw: self.join(empty, root.sw, empty, empty),
+            se: self.join(root.se, empty, empty, empty),
+        };
+        self.root = self.join(quad.nw, quad.ne, quad.sw, quad.se);
+        self.origin_x -= 1 << (level - 1);
+        self.origin_y -= 1 << (level - 1);
+    }
+
+    // True when all live cells are in the middle half of the node
+    fn is_centered(&self, node: NodeId) -> bool {
+        let quad = self.quad(node);
+        let (nw, ne, sw, se) = (
+            self.quad(quad.nw),
+            self.quad(quad.ne),
+            self.quad(quad.sw),
+            self.quad(quad.se),
+        );
+        let inner = self.node_population(nw.se)
+            + self.node_population(ne.sw)
+            + self.node_population(sw.ne)
+            + self.node_population(se.nw);
+
+        inner == self.node_population(node)
+    }
+
+    fn set_in(&mut self, node: NodeId, x: i64, y: i64, alive: bool) -> NodeId {
+        let level = self.level(node);
+        if level == 0 {
+            return if alive { ALIVE } else { DEAD };
+        }
+
+        let half = 1 << (level - 1);
+        let mut quad = self.quad(node);
+        let child = match (y < half, x < half) {
+            (true, true) => &mut quad.nw,
+            (true, false) => &mut quad.ne,
+            (false, true) => &mut quad.sw,
+            (false, false) => &mut quad.se,
+        };
+        *child = self.set_in(*child, x % half, y % half, alive);
+
+        self.join(quad.nw, quad.ne, quad.sw, quad.se)
+    }
+
+    // Builds a node of the given level whose top left cell is at x, y. Only cells
+    // inside of `bounds` (min_x, min_y, max_x, max_y, inclusive) are looked up.
+    fn build(
+        &mut self,
+        level: u8,
+        (x, y): (i64, i64),
+        bounds: (i64, i64, i64, i64),
+        is_alive: &impl Fn(i64, i64) -> bool,
+    ) -> NodeId {
+        let size = 1 << level;
+        let (min_x, min_y, max_x, max_y) = bounds;
+        if x > max_x || y > max_y || x + size <= min_x || y + size <= min_y {
+            return self.empty_node(level);
+        }
+        if level == 0 {
+            return if is_alive(x, y) { ALIVE } else { DEAD };
+        }
+
+        let half = size / 2;
+        let nw = self.build(level - 1, (x, y), bounds, is_alive);
+        let ne = self.build(level - 1, (x + half, y), bounds, is_alive);
+        let sw = self.build(level - 1, (x, y + half), bounds, is_alive);
+        let se = self.build(level - 1, (x + half, y + half), bounds, is_alive);
+
+        self.join(nw, ne, sw, se)
+    }
+
+    // The smallest box holding every live cell as (min_x, min_y, max_x, max_y), None for an
+    // empty plane
+    fn live_bounds(&self) -> Option<(i64, i64, i64, i64)> {
+        let (min_x, min_y, max_x, max_y) = self.node_bounds(self.root, &mut HashMap::new())?;
+        Some((
+            self.origin_x + min_x,
+            self.origin_y + min_y,
+            self.origin_x + max_x,
+            self.origin_y + max_y,
+        ))
+    }
+
+    // live_bounds() relative to the node's top left, shared nodes are only measured once
+    fn node_bounds(
+        &self,
+        node: NodeId,
+        measured: &mut HashMap<NodeId, Option<(i64, i64, i64, i64)>>,
+    ) -> Option<(i64, i64, i64, i64)> {
+        let level = self.level(node);
+        if self.node_population(node) == 0 {
+            return None;
+        }
+        if level == 0 {
+            return Some((0, 0, 0, 0));
+        }
+        if let Some(bounds) = measured.get(&node) {
+            return *bounds;
+        }
+
+        let half = 1 << (level - 1);
+        let quad = self.quad(node);
+        let mut bounds: Option<(i64, i64, i64, i64)> = None;
+        for (child, (x, y)) in [
+            (quad.nw, (0, 0)),
+            (quad.ne, (half, 0)),
+            (quad.sw, (0, half)),
+            (quad.se, (half, half)),
+        ]
+        .iter()
+        {
+            if let Some((min_x, min_y, max_x, max_y)) = self.node_bounds(*child, measured) {
+                let child = (min_x + x, min_y + y, max_x + x, max_y + y);
+                bounds = Some(match bounds {
+                    Some(bounds) => (
+                        bounds.0.min(child.0),
+                        bounds.1.min(child.1),
+                        bounds.2.max(child.2),
+                        bounds.3.max(child.3),
+                    ),
+                    None => child,
+                });
+            }
+        }
+        measured.insert(node, bounds);
+
+        bounds
+    }
+
+    // Takes the root down to its center while the live cells all fit in there. Expanding
+    // and stepping both keep the center of the root in the same place, so once shrunk the
+    // same cells always give the same root.
+    fn shrink(&mut self) {
+        while self.level(self.root) > 3 && self.is_centered(self.root) {
+            let level = self.level(self.root);
+            let quad = self.quad(self.root);
+            let (nw, ne, sw, se) = (
+                self.quad(quad.nw),
+                self.quad(quad.ne),
+                self.quad(quad.sw),
+                self.quad(quad.se),
+            );
+            self.root = self.join(nw.se, ne.sw, sw.ne, se.nw);
+            self.origin_x += 1 << (level - 2);
+            self.origin_y += 1 << (level - 2);
+        }
+    }
+
+    // Puts the universe's cells on the plane with its top left cell at 0, 0
+    fn load_board(&mut self, universe: &Universe) {
+        if self.nodes.len() > MAX_NODES {
+            *self = HashLife::new(self.rule);
+        }
+        let (width, height) = (i64::from(universe.width), i64::from(universe.height));
+        let size_log = 64 - (width.max(height) - 1).leading_zeros() as u8;
+        self.root = self.build(
+            size_log.max(3),
+            (0, 0),
+            (0, 0, width - 1, height - 1),
+            &|x, y| universe.cells.get(universe.get_index(y as u32, x as u32)),
+        );
+        self.origin_x = 0;
+        self.origin_y = 0;
+    }
+
+    fn for_each_live(
+        &self,
+        node: NodeId,
+        level: u8,
+        (x, y): (i64, i64),
+        visit: &mut impl FnMut(i64, i64),
+    ) {
+        if self.node_population(node) == 0 {
+            return;
+        }
+        if level == 0 {
+            visit(x, y);
+            return;
+        }
+
+        let half = 1 << (level - 1);
+        let quad = self.quad(node);
+        self.for_each_live(quad.nw, level - 1, (x, y), visit);
+        self.for_each_live(quad.ne, level - 1, (x + half, y), visit);
+        self.for_each_live(quad.sw, level - 1, (x, y + half), visit);
+        self.for_each_live(quad.se, level - 1, (x + half, y + half), visit);
+    }
+
+    // The center of the node, half its size, 2^step_log generations into the future.
+    // step_log is capped at level - 2, the furthest the center can be known.
+    fn successor(&mut self, node: NodeId, step_log: u8) -> NodeId {
+        let level = self.level(node);
+        // nothing happens in empty space, unless the rule gives birth to cells with no
+        // live neighbors at all
+        if self.node_population(node) == 0 && !self.births_on_empty() {
+            return self.empty_node(level - 1);
+        }
+        let step_log = step_log.min(level - 2);
+        if let Some(result) = self.successors.get(&(node, step_log)) {
+            return *result;
+        }
+
+        let result = if level == 2 {
+            self.successor_4x4(node)
+        } else {
+            let quad = self.quad(node);
+            let (nw, ne, sw, se) = (
+                self.quad(quad.nw),
+                self.quad(quad.ne),
+                self.quad(quad.sw),
+                self.quad(quad.se),
+            );
+            // nine overlapping sub squares, each half the size of the node
+            let squares = [
+                quad.nw,
+                self.join(nw.ne, ne.nw, nw.se, ne.sw),
+                quad.ne,
+                self.join(nw.sw, nw.se, sw.nw, sw.ne),
+                self.join(nw.se, ne.sw, sw.ne, se.nw),
+                self.join(ne.sw, ne.se, se.nw, se.ne),
+                quad.sw,
+                self.join(sw.ne, se.nw, sw.se, se.sw),
+                quad.se,
+            ];
+            let mut c = [DEAD; 9];
+            for (result, square) in c.iter_mut().zip(squares.iter()) {
+                *result = self.successor(*square, step_log);
+            }
+
+            if step_log < level - 2 {
+                let q: Vec<Quad> = c.iter().map(|node| self.quad(*node)).collect();
+                let nw = self.join(q[0].se, q[1].sw, q[3].ne, q[4].nw);
+                let ne = self.join(q[1].se, q[2].sw, q[4].ne, q[5].nw);
+                let sw = self.join(q[3].se, q[4].sw, q[6].ne, q[7].nw);
+                let se = self.join(q[4].se, q[5].sw, q[7].ne, q[8].nw);
+                self.join(nw, ne, sw, se)
+            } else {
+                let nw = self.join(c[0], c[1], c[3], c[4]);
+                let ne = self.join(c[1], c[2], c[4], c[5]);
+                let sw = self.join(c[3], c[4], c[6], c[7]);
+                let se = self.join(c[4], c[5], c[7], c[8]);
+                let nw = self.successor(nw, step_log);
+                let ne = self.successor(ne, step_log);
+                let sw = self.successor(sw, step_log);
+                let se = self.successor(se, step_log);
+                self.join(nw, ne, sw, se)
+            }
+        };
+
+        self.successors.insert((node, step_log), result);
+        result
+    }
+
+    // The base case, runs the rule on the center 2x2 cells of a 4x4 node
+    fn successor_4x4(&mut self, node: NodeId) -> NodeId {
+        let mut cells = [[false; 4]; 4];
+        let quad = self.quad(node);
+        for (child, (x, y)) in [
+            (quad.nw, (0, 0)),
+            (quad.ne, (2, 0)),
+            (quad.sw, (0, 2)),
+            (quad.se, (2, 2)),
+        ]
+        .iter()
+        {
+            let leaves = self.quad(*child);
+            cells[*y][*x] = leaves.nw == ALIVE;
+            cells[*y][x + 1] = leaves.ne == ALIVE;
+            cells[y + 1][*x] = leaves.sw == ALIVE;
+            cells[y + 1][x + 1] = leaves.se == ALIVE;
+        }
+
+        let mut next = [DEAD; 4];
+        for (index, (x, y)) in [(1, 1), (2, 1), (1, 2), (2, 2)].iter().enumerate() {
+            let topology = self.rule.topology();
+            let live_neighbors = (-1..=1)
+                .flat_map(|row_offset| {
+                    (-1..=1).map(move |column_offset| (row_offset, column_offset))
+                })
+                .filter(|(row_offset, column_offset)| {
+                    topology.is_neighbor(*row_offset, *column_offset)
+                        && cells[(*y as i64 + row_offset) as usize]
+                            [(*x as i64 + column_offset) as usize]
+                })
+                .count() as u8;
+            if self.rule.is_alive_next(cells[*y][*x], live_neighbors) {
+                next[index] = ALIVE;
+            }
+        }
+
+        self.join(next[0], next[1], next[2], next[3])
+    }
+
+    // An 8 by 8 block of cells, rows[y][x]
+    pub(crate) fn leaf_block(&mut self, rows: &[[bool; 8]; 8]) -> NodeId {
+        self.build(3, (0, 0), (0, 0, 7, 7), &|x, y| {
+            rows[y as usize][x as usize]
+        })
+    }
+
+    pub(crate) fn cell_node(alive: bool) -> NodeId {
+        if alive {
+            ALIVE
+        } else {
+            DEAD
+        }
+    }
+
+    // Makes node the root with the center of the plane in its middle
+    pub(crate) fn set_root(&mut self, root: NodeId, generation: u64) {
+        let half = 1i64 << (self.level(root) - 1);
+        self.root = root;
+        self.origin_x = -half;
+        self.origin_y = -half;
+        self.generation = generation;
+    }
+
+    pub(crate) fn join(&mut self, nw: NodeId, ne: NodeId, sw: NodeId, se: NodeId) -> NodeId {
+        let quad = Quad { nw, ne, sw, se };
+        if let Some(node) = self.lookup.get(&quad) {
+            return *node;
+        }
+
+        let node = Node {
+            level: self.level(nw) + 1,
+            quad,
+            population: [nw, ne, sw, se]
+                .iter()
+                .map(|child| self.node_population(*child))
+                .fold(0u64, u64::saturating_add),
+        };
+        let id = self.nodes.len() as NodeId;
+        self.nodes.push(node);
+        self.lookup.insert(quad, id);
+
+        id
+    }
+
+    pub(crate) fn empty_node(&mut self, level: u8) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let empty = *self.empty.last().unwrap();
+            let node = self.join(empty, empty, empty, empty);
+            self.empty.push(node);
+        }
+
+        self.empty[level as usize]
+    }
+
+    fn collect_garbage(&mut self) {
+        let mut fresh = HashLife::new(self.rule);
+        let mut copied = HashMap::new();
+        fresh.root = fresh.copy_from(self, self.root, &mut copied);
+        fresh.origin_x = self.origin_x;
+        fresh.origin_y = self.origin_y;
+        fresh.generation = self.generation;
+        fresh.board = self.board.take();
+        *self = fresh;
+    }
+
+    fn copy_from(
+        &mut self,
+        other: &HashLife,
+        node: NodeId,
+        copied: &mut HashMap<NodeId, NodeId>,
+    ) -> NodeId {
+        if node == DEAD || node == ALIVE {
+            return node;
+        }
+        if let Some(copy) = copied.get(&node) {
+            return *copy;
+        }
+
+        let quad = other.quad(node);
+        let nw = self.copy_from(other, quad.nw, copied);
+        let ne = self.copy_from(other, quad.ne, copied);
+        let sw = self.copy_from(other, quad.sw, copied);
+        let se = self.copy_from(other, quad.se, copied);
+        let copy = self.join(nw, ne, sw, se);
+        copied.insert(node, copy);
+
+        copy
+    }
+
+    pub(crate) fn births_on_empty(&self) -> bool {
+        self.rule.masks().0 & 1 != 0
+    }
+
+    pub(crate) fn level(&self, node: NodeId) -> u8 {
+        self.nodes[node as usize].level
+    }
+
+    fn quad(&self, node: NodeId) -> Quad {
+        self.nodes[node as usize].quad
+    }
+
+    fn node_population(&self, node: NodeId) -> u64 {
+        self.nodes[node as usize].population
+    }
+}
+
+impl UniverseEngine for HashLife {
+    fn kind(&self) -> Engine {
+        Engine::HashLife
+    }
+
+    // The board has edges and the plane doesn't, but a cell past an edge can't come to
+    // life before the generation after the live cells reach it. Until then the edges make
+    // no difference, so the plane jumps as many generations as the live cells are away
+    // from the nearest edge, again and again until they get to it.
+    fn advance(&mut self, universe: &Universe, generations: u64, next: &mut CellBits) -> u64 {
+        self.set_rule(universe.rule);
+        if self.births_on_empty() || universe.width == 0 || universe.height == 0 {
+            return 0;
+        }
+        let loaded = self
+            .board
+            .as_ref()
+            .is_some_and(|board| board.width == universe.width && board.cells == universe.cells);
+        if !loaded {
+            self.load_board(universe);
+            self.shrink();
+        }
+
+        let (width, height) = (i64::from(universe.width), i64::from(universe.height));
+        let mut advanced = 0;
+        while advanced < generations {
+            let margin = match self.live_bounds() {
+                Some((min_x, min_y, max_x, max_y)) => min_x
+                    .min(min_y)
+                    .min(width - 1 - max_x)
+                    .min(height - 1 - max_y),
+                // an empty board stays empty
+                None => i64::MAX,
+            };
+            if margin <= 0 {
+                break;
+            }
+            let step = (generations - advanced).min(margin as u64);
+            let before = self.root;
+            HashLife::advance(self, step);
+            self.shrink();
+            advanced += step;
+            if self.root == before {
+                // the cells are back where they were, so they repeat every step generations
+                advanced += (generations - advanced) / step * step;
+            }
+        }
+        if advanced == 0 {
+            return 0;
+        }
+
+        next.clear();
+        for (x, y) in self.live_cells() {
+            next.set(universe.get_index(y as u32, x as u32), true);
+        }
+        self.board = Some(Board {
+            width: universe.width,
+            cells: next.clone(),
+        });
+        advanced
+    }
+
+    // The hash maps are counted by their capacity, not their real allocations
+    fn memory_usage(&self) -> usize {
+        self.board
+            .as_ref()
+            .map_or(0, |board| board.cells.memory_usage())
+            + vec_bytes(&self.nodes)
+            + vec_bytes(&self.empty)
+            + self.lookup.capacity() * std::mem::size_of::<(Quad, NodeId)>()
+            + self.successors.capacity() * std::mem::size_of::<((NodeId, u8), NodeId)>()
+    }
+
+    // The grid is copied into the plane with a one cell border holding either dead
+    // cells or the wrapped around edges, advanced a single generation, and copied back
+    fn tick(&mut self, universe: &Universe, next: &mut CellBits) {
+        let width = i64::from(universe.width);
+        let height = i64::from(universe.height);
+        next.clear();
+        if width == 0 || height == 0 {
+            return;
+        }
+        self.set_rule(universe.rule);
+        if self.nodes.len() > MAX_NODES {
+            *self = HashLife::new(self.rule);
+        }
+
+        let is_alive = |x: i64, y: i64| {
+            universe
+                .edge_topology
+                .map(y, x, universe.width, universe.height)
+                .is_some_and(|(row, column)| universe.cells.get(universe.get_index(row, column)))
+        };
+        // the center half of the root, which is what the successor returns, starts at 0, 0
+        let size_log = 64 - (width.max(height) - 1).leading_zeros() as u8;
+        let level = (size_log + 1).max(3);
+        let quarter = 1 << (level - 2);
+        let root = self.build(
+            level,
+            (-quarter, -quarter),
+            (-1, -1, width, height),
+            &is_alive,
+        );
+        let result = self.successor(root, 0);
+
+        self.for_each_live(result, level - 1, (0, 0), &mut |x, y| {
+            if x < width && y < height {
+                next.set(universe.get_index(y as u32, x as u32), true);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Pattern, Topology};
+
+    fn insert(hashlife: &mut HashLife, pattern: Pattern, x: i64, y: i64) {
+        for (row, column) in pattern.live_cells() {
+            hashlife.set_cell(x + i64::from(column), y + i64::from(row), true);
+        }
+    }
+
+    fn sorted(mut cells: Vec<(i64, i64)>) -> Vec<(i64, i64)> {
+        cells.sort_unstable();
+        cells
+    }
+
+    #[test]
+    fn test_set_and_get_cells() {
+        let mut hashlife = HashLife::default();
+        hashlife.set_cell(0, 0, true);
+        hashlife.set_cell(-100, 250, true);
+        hashlife.set_cell(0, 0, false);
+
+        assert!(!hashlife.get_cell(0, 0));
+        assert!(hashlife.get_cell(-100, 250));
+        assert_eq!(1, hashlife.population());
+        assert_eq!(vec![(-100, 250)], hashlife.live_cells());
+    }
+
+    #[test]
+    fn test_blinker_oscillates() {
+        let mut hashlife = HashLife::default();
+        insert(&mut hashlife, Pattern::Blinker, 0, 0);
+
+        hashlife.advance(1);
+        assert_eq!(vec![(1, -1), (1, 0), (1, 1)], sorted(hashlife.live_cells()));
+        hashlife.advance(1);
+        assert_eq!(vec![(0, 0), (1, 0), (2, 0)], sorted(hashlife.live_cells()));
+        hashlife.advance(1001);
+        assert_eq!(1003, hashlife.generation());
+        assert_eq!(vec![(1, -1), (1, 0), (1, 1)], sorted(hashlife.live_cells()));
+    }
+
+    #[test]
+    fn test_glider_travels_far() {
+        let mut hashlife = HashLife::default();
+        insert(&mut hashlife, Pattern::Glider, 0, 0);
+
+        hashlife.advance(4_000_000);
+
+        let mut expected = HashLife::default();
+        insert(&mut expected, Pattern::Glider, 1_000_000, 1_000_000);
+        assert_eq!(sorted(expected.live_cells()), sorted(hashlife.live_cells()));
+    }
+
+    #[test]
+    fn test_gosper_gun_grows() {
+        let mut hashlife = HashLife::default();
+        insert(&mut hashlife, Pattern::GosperGliderGun, 0, 0);
+
+        // the gun emits a 5 cell glider every 30 generations
+        hashlife.advance(300);
+        assert_eq!(36 + 10 * 5, hashlife.population());
+    }
+
+    #[test]
+    fn test_engine_matches_naive() {
+        // spheres have to be square
+        let cases = [
+            (Topology::Plane, 37, 21),
+            (Topology::Torus, 37, 21),
+            (Topology::KleinBottle, 37, 21),
+            (Topology::CrossSurface, 37, 21),
+            (Topology::Sphere, 24, 24),
+        ];
+        for (topology, width, height) in cases.iter() {
+            let mut naive = Universe::new_with_dimensions(*width, *height);
+            naive.set_edge_topology(*topology);
+            naive.randomize_seeded(7, 0.4);
+            let mut hashlife =
+                Universe::new_with_dimensions(*width, *height).with_engine(Engine::HashLife);
+            hashlife.set_edge_topology(*topology);
+            hashlife.randomize_seeded(7, 0.4);
+
+            for _ in 0..50 {
+                naive.tick();
+                hashlife.tick();
+                assert_eq!(naive.cells(), hashlife.cells());
+            }
+        }
+    }
+
+    #[test]
+    fn test_b0_rules_match_naive() {
+        // empty space gives birth under these, so empty nodes can't be skipped
+        for rule in ["B0/S8", "B012345678/S012345678", "B03/S23"].iter() {
+            for topology in [Topology::Plane, Topology::Torus].iter() {
+                let mut naive = Universe::new_with_dimensions(8, 8);
+                naive.set_edge_topology(*topology);
+                naive.set_rule(rule).unwrap();
+                naive.randomize_seeded(5, 0.2);
+                let mut hashlife =
+                    Universe::new_with_dimensions(8, 8).with_engine(Engine::HashLife);
+                hashlife.set_edge_topology(*topology);
+                hashlife.set_rule(rule).unwrap();
+                hashlife.randomize_seeded(5, 0.2);
+
+                for _ in 0..6 {
+                    naive.tick();
+                    hashlife.tick();
+                    assert_eq!(naive.cells(), hashlife.cells(), "{} {:?}", rule, topology);
+                }
+            }
+        }
+
+        let mut plane = HashLife::new("B0/S8".parse().unwrap());
+        insert(&mut plane, Pattern::Glider, 0, 0);
+        plane.advance(4);
+        assert_eq!(0, plane.generation());
+    }
+
+    #[test]
+    fn test_tick_n_jumps_until_the_edges() {
+        // the gun's gliders reach the edges, where the universe has to tick, and on a
+        // torus come back around through them
+        for topology in [Topology::Plane, Topology::Torus].iter() {
+            let mut naive = Universe::new_with_dimensions(80, 60);
+            naive.set_edge_topology(*topology);
+            naive.insert_pattern(Pattern::GosperGliderGun, 20, 20);
+            let mut hashlife = naive.clone().with_engine(Engine::HashLife);
+
+            for steps in [1, 7, 90, 300, 33].iter() {
+                for _ in 0..*steps {
+                    naive.tick();
+                }
+                assert_eq!(
+                    naive.population(),
+                    hashlife.tick_n(*steps),
+                    "{:?}",
+                    topology
+                );
+                assert_eq!(naive.cells(), hashlife.cells(), "{:?}", topology);
+                assert_eq!(naive.generation(), hashlife.generation());
+            }
+            // an edit between calls is picked up
+            naive.toggle_cell(5, 5);
+            hashlife.toggle_cell(5, 5);
+            naive.tick();
+            hashlife.tick_n(1);
+            assert_eq!(naive.cells(), hashlife.cells(), "{:?}", topology);
+        }
+    }
+
+    #[test]
+    fn test_tick_n_jumps_far() {
+        let mut universe = Universe::new(1024).with_engine(Engine::HashLife);
+        universe.insert_pattern(Pattern::Blinker, 500, 500);
+        universe.set_cell(100, 100, crate::Cell::Alive);
+        universe.set_cell(100, 101, crate::Cell::Alive);
+        universe.set_cell(101, 100, crate::Cell::Alive);
+        universe.set_cell(101, 101, crate::Cell::Alive);
+
+        assert_eq!(7, universe.tick_n(1_000_000_001));
+        let summary = universe.run_until_generation(2_000_000_000);
+        assert_eq!(999_999_999, summary.generations_run);
+        assert!(summary.condition_met);
+        assert_eq!(crate::Cell::Alive, universe.get_cell(500, 502));
+        assert_eq!(crate::Cell::Dead, universe.get_cell(501, 501));
+        assert_eq!(2_000_000_000, universe.age_at(100, 100));
+    }
+
+    #[test]
+    fn test_hex_rule_matches_naive() {
+        let mut naive = Universe::new_with_dimensions(30, 30);
+        naive.set_rule("B2/S34H").unwrap();
+        naive.randomize_seeded(3, 0.3);
+        let mut hashlife = Universe::new_with_dimensions(30, 30).with_engine(Engine::HashLife);
+        hashlife.set_rule("B2/S34H").unwrap();
+        hashlife.randomize_seeded(3, 0.3);
+
+        for _ in 0..20 {
+            naive.tick();
+            hashlife.tick();
+            assert_eq!(naive.cells(), hashlife.cells());
+        }
+    }
+}
@@ -0,0 +1,73 @@
+mod hashlife;
+mod naive;
+mod packed;
+mod parallel;
+
+use std::fmt::Debug;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::bits::CellBits;
+use crate::Universe;
+
+pub use hashlife::HashLife;
+pub(crate) use hashlife::NodeId;
+pub use naive::NaiveEngine;
+pub use packed::PackedEngine;
+pub use parallel::ParallelEngine;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Engine {
+    Naive,
+    HashLife,
+    // 32 cells at a time with bitwise operations on the packed cells
+    Packed,
+    // the packed engine on 128 bit wasm SIMD vectors when built for them
+    Simd,
+    // the naive rules on row bands spread over threads with the "parallel" feature
+    Parallel,
+}
+
+impl Engine {
+    pub const ALL: [Engine; 5] = [
+        Engine::Naive,
+        Engine::HashLife,
+        Engine::Packed,
+        Engine::Simd,
+        Engine::Parallel,
+    ];
+
+    pub fn create(self) -> Box<dyn UniverseEngine> {
+        match self {
+            Engine::Naive => Box::new(NaiveEngine),
+            Engine::HashLife => Box::new(HashLife::default()),
+            Engine::Packed => Box::new(PackedEngine::default()),
+            Engine::Simd => Box::new(PackedEngine::simd()),
+            Engine::Parallel => Box::new(ParallelEngine),
+        }
+    }
+}
+
+// An algorithm for computing the next generation of a universe. Engines only compute
+// cells, the universe takes care of swapping buffers and keeping statistics.
+pub trait UniverseEngine: Debug {
+    fn kind(&self) -> Engine;
+
+    // Writes every cell of the generation after the universe's current one into `next`
+    fn tick(&mut self, universe: &Universe, next: &mut CellBits);
+
+    // Jumps up to `generations` generations at once, writing where the universe ends up
+    // into `next`, and returns how many it went. 0 means the universe has to tick one
+    // generation at a time, which is all most engines can do.
+    fn advance(&mut self, _universe: &Universe, _generations: u64, _next: &mut CellBits) -> u64 {
+        0
+    }
+
+    // Bytes held on to between ticks, most engines keep nothing
+    fn memory_usage(&self) -> usize {
+        0
+    }
+}
@@ -0,0 +1,31 @@
+use super::{Engine, UniverseEngine};
+use crate::bits::CellBits;
+use crate::rule::RuleTable;
+use crate::{Neighborhood, Universe};
+
+// Goes through every cell one at a time. The 8 cells around are looked up in a RuleTable
+// all at once, bigger neighborhoods are counted.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NaiveEngine;
+
+impl UniverseEngine for NaiveEngine {
+    fn kind(&self) -> Engine {
+        Engine::Naive
+    }
+
+    fn tick(&mut self, universe: &Universe, next: &mut CellBits) {
+        // building the table is 512 lookups, less than a tick of any board worth ticking
+        if universe.neighborhood == Neighborhood::Moore && universe.neighborhood_radius == 1 {
+            RuleTable::from(universe.rule).tick(universe, next, None);
+            return;
+        }
+        for row in 0..universe.height {
+            for column in 0..universe.width {
+                let index = universe.get_index(row, column);
+                let alive = universe.cells.get(index);
+                let live_neighbors = universe.live_neighbor_count(row, column);
+                next.set(index, universe.rule.is_alive_next(alive, live_neighbors));
+            }
+        }
+    }
+}
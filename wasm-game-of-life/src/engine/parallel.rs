@@ -0,0 +1,140 @@
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use super::{Engine, UniverseEngine};
+use crate::bits::CellBits;
+use crate::rule::RuleTable;
+use crate::{Topology, Universe};
+
+// Roughly how many cells each band covers, small enough that a large universe is split
+// across every thread and big enough that handing out bands doesn't dominate
+const BAND_CELLS: usize = 1 << 14;
+
+// Splits the universe into bands of whole rows and computes each band on its own, on the
+// rayon thread pool with the "parallel" feature and one band after another without it
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ParallelEngine;
+
+impl UniverseEngine for ParallelEngine {
+    fn kind(&self) -> Engine {
+        Engine::Parallel
+    }
+
+    fn tick(&mut self, universe: &Universe, next: &mut CellBits) {
+        let width = universe.width as usize;
+        let height = universe.height as usize;
+        if width == 0 || height == 0 {
+            return;
+        }
+        let band_rows = (BAND_CELLS / width).max(1);
+        let band_count = height.div_ceil(band_rows);
+        // the tick only comes here for the 8 cells around each cell, see Universe::tick()
+        let board = Board {
+            cells: &universe.cells,
+            width: universe.width,
+            height: universe.height,
+            topology: universe.edge_topology,
+        };
+        let table = RuleTable::from(universe.rule);
+
+        #[cfg(feature = "parallel")]
+        let bands = (0..band_count).into_par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let bands = 0..band_count;
+        let bands: Vec<CellBits> = bands
+            .map(|band| {
+                let first_row = band * band_rows;
+                let rows = band_rows.min(height - first_row);
+                compute_band(&board, &table, first_row as u32, rows as u32)
+            })
+            .collect();
+
+        for (band, cells) in bands.iter().enumerate() {
+            let start = band * band_rows * width;
+            for offset in (0..cells.len()).step_by(32) {
+                let count = (cells.len() - offset).min(32);
+                next.write_bits(start + offset, count, cells.read_bits(offset, count));
+            }
+        }
+    }
+}
+
+// What the bands read, borrowed on its own so the rayon threads share these and not the
+// whole universe
+struct Board<'a> {
+    cells: &'a CellBits,
+    width: u32,
+    height: u32,
+    topology: Topology,
+}
+
+impl Board<'_> {
+    fn is_alive(&self, row: i64, column: i64) -> bool {
+        self.topology
+            .map(row, column, self.width, self.height)
+            .is_some_and(|(row, column)| {
+                self.cells
+                    .get(row as usize * self.width as usize + column as usize)
+            })
+    }
+}
+
+fn compute_band(board: &Board, table: &RuleTable, first_row: u32, rows: u32) -> CellBits {
+    let mut cells = CellBits::new((rows * board.width) as usize);
+    for row in first_row..first_row + rows {
+        for column in 0..board.width {
+            // the 3 by 3 neighborhood in the bit order RuleTable looks it up in
+            let neighborhood = (0..9).fold(0, |neighborhood, bit| {
+                let alive = board.is_alive(
+                    i64::from(row) + i64::from(bit / 3) - 1,
+                    i64::from(column) + i64::from(bit % 3) - 1,
+                );
+                neighborhood | u16::from(alive) << bit
+            });
+            cells.set(
+                ((row - first_row) * board.width + column) as usize,
+                table.is_alive_next(neighborhood),
+            );
+        }
+    }
+
+    cells
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{BoundaryMode, Topology};
+
+    #[test]
+    fn test_engine_matches_naive() {
+        // tall enough for several bands, with a width that doesn't line up with the words
+        let mut naive = Universe::new_with_dimensions(300, 130);
+        naive.set_boundary_mode(BoundaryMode::Toroidal);
+        naive.randomize_seeded(5, 0.35);
+        let mut parallel = Universe::new_with_dimensions(300, 130).with_engine(Engine::Parallel);
+        parallel.set_boundary_mode(BoundaryMode::Toroidal);
+        parallel.randomize_seeded(5, 0.35);
+
+        for _ in 0..10 {
+            naive.tick();
+            parallel.tick();
+            assert_eq!(naive.cells(), parallel.cells());
+        }
+    }
+
+    #[test]
+    fn test_twisted_edges_and_hex_rules() {
+        let mut naive = Universe::new_with_dimensions(40, 30);
+        naive.set_edge_topology(Topology::KleinBottle);
+        naive.set_rule("B2/S34H").unwrap();
+        naive.randomize_seeded(8, 0.3);
+        let mut parallel = naive.clone().with_engine(Engine::Parallel);
+
+        for _ in 0..10 {
+            naive.tick();
+            parallel.tick();
+            assert_eq!(naive.cells(), parallel.cells());
+        }
+    }
+}
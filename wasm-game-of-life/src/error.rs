@@ -0,0 +1,90 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+use crate::formats::ParseError;
+
+// What can go wrong when calling into a Universe. In JavaScript these are thrown as
+// Error objects carrying the Display message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UniverseError {
+    OutOfBounds {
+        row: u32,
+        column: u32,
+        width: u32,
+        height: u32,
+    },
+    InvalidRule {
+        rule: String,
+        error: ParseError,
+    },
+    // settings that can't make a universe, such as a width of 0
+    InvalidConfig(String),
+    // a pattern file that couldn't be read, with the line and column of the problem
+    Parse(ParseError),
+}
+
+impl Display for UniverseError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            UniverseError::OutOfBounds {
+                row,
+                column,
+                width,
+                height,
+            } => write!(
+                f,
+                "row {}, column {} is outside of the {} by {} universe",
+                row, column, width, height
+            ),
+            UniverseError::InvalidRule { rule, error } => {
+                write!(f, "invalid rule \"{}\": {}", rule, error)
+            }
+            UniverseError::InvalidConfig(message) => message.fmt(f),
+            UniverseError::Parse(error) => error.fmt(f),
+        }
+    }
+}
+
+impl Error for UniverseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            UniverseError::InvalidRule { error, .. } | UniverseError::Parse(error) => Some(error),
+            UniverseError::OutOfBounds { .. } | UniverseError::InvalidConfig(_) => None,
+        }
+    }
+}
+
+impl From<ParseError> for UniverseError {
+    fn from(error: ParseError) -> Self {
+        UniverseError::Parse(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_messages() {
+        let error = UniverseError::OutOfBounds {
+            row: 4,
+            column: 9,
+            width: 8,
+            height: 6,
+        };
+        assert_eq!(
+            "row 4, column 9 is outside of the 8 by 6 universe",
+            error.to_string()
+        );
+
+        let error = UniverseError::InvalidRule {
+            rule: "B3/S2x".to_string(),
+            error: ParseError::new(1, 6, "'x' is not a neighbor count between 0 and 8"),
+        };
+        assert_eq!(
+            "invalid rule \"B3/S2x\": line 1, column 6: 'x' is not a neighbor count between 0 and 8",
+            error.to_string()
+        );
+        assert!(error.source().is_some());
+    }
+}
@@ -0,0 +1,62 @@
+use std::fmt::{self, Debug, Formatter};
+
+use serde::Serialize;
+
+// What the callback given to Universe::on_event() receives, a plain object with a `type`
+// of "GenerationCompleted", "GenerationRestored", "UniverseStabilized", "PopulationZero",
+// "PatternDetected" or "WatchedCellChanged" and the fields of that event
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "type")]
+pub enum UniverseEvent {
+    GenerationCompleted {
+        generation: u32,
+        population: u32,
+    },
+    // sent by step_back(), and by step_forward() when it replays the history instead of
+    // ticking, with the generation that is now on the board
+    GenerationRestored {
+        generation: u32,
+        population: u32,
+    },
+    // sent once when the universe settles into a still life (period 1) or an oscillation
+    UniverseStabilized {
+        generation: u32,
+        period: u32,
+    },
+    // sent once when the last live cell dies
+    PopulationZero {
+        generation: u32,
+    },
+    // sent for every spaceship on the board whenever the number of them changes
+    PatternDetected {
+        generation: u32,
+        pattern: String,
+        heading: String,
+        row: u32,
+        column: u32,
+    },
+    // sent for each cell given to Universe::add_watch() that the tick flipped
+    WatchedCellChanged {
+        generation: u32,
+        row: u32,
+        column: u32,
+        alive: bool,
+    },
+}
+
+// What has already been reported, so each event is only sent when something changes
+#[derive(Debug, Default)]
+pub struct EventState {
+    pub callback: Option<EventCallback>,
+    pub stabilized: bool,
+    pub extinct: bool,
+    pub spaceships: usize,
+}
+
+pub struct EventCallback(pub Box<dyn Fn(&UniverseEvent)>);
+
+impl Debug for EventCallback {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("EventCallback")
+    }
+}
@@ -0,0 +1,255 @@
+use std::fmt::{self, Display, Formatter};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::formats::ParseError;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Particle {
+    Empty = 0,
+    Sand = 1,
+    Water = 2,
+    Wall = 3,
+}
+
+impl Particle {
+    // Whether this can fall or flow into a cell holding other, sand sinks through water
+    fn displaces(self, other: Particle) -> bool {
+        matches!(
+            (self, other),
+            (Particle::Sand, Particle::Empty)
+                | (Particle::Sand, Particle::Water)
+                | (Particle::Water, Particle::Empty)
+        )
+    }
+}
+
+impl Display for Particle {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Particle::Empty => write!(f, "."),
+            Particle::Sand => write!(f, "s"),
+            Particle::Water => write!(f, "~"),
+            Particle::Wall => write!(f, "#"),
+        }
+    }
+}
+
+// Sand and water under gravity. Unlike the life-like rules a cell's next state depends on
+// which way things are falling rather than on how many neighbors it has, so cells are
+// moved one at a time from the bottom row up, each particle at most once a generation.
+// Sand falls straight down, or slides down to the side when something is in the way.
+// Water does the same and spreads sideways when it can't fall. Walls stay put and the
+// edges of the board are walls too. Rows are swept left to right and right to left on
+// alternate generations so piles don't lean one way.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug)]
+pub struct FallingSand {
+    width: u32,
+    height: u32,
+    cells: Vec<Particle>,
+    generation: u32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl FallingSand {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Particle::Empty; (width * height) as usize],
+            generation: 0,
+        }
+    }
+
+    // One line per row where . or a space is empty, s is sand, ~ is water and # is a wall,
+    // lines starting with ! are comments
+    pub fn from_text(text: &str) -> Result<FallingSand, ParseError> {
+        let rows: Vec<(usize, &str)> = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim_end()))
+            .filter(|(_, line)| !line.starts_with('!'))
+            .collect();
+        let width = rows
+            .iter()
+            .map(|(_, line)| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut sand = FallingSand::new(width as u32, rows.len() as u32);
+
+        for (row, (line_number, line)) in rows.iter().enumerate() {
+            for (column, character) in line.chars().enumerate() {
+                let particle = match character {
+                    '.' | ' ' => Particle::Empty,
+                    's' => Particle::Sand,
+                    '~' => Particle::Water,
+                    '#' => Particle::Wall,
+                    other => {
+                        return Err(ParseError::new(
+                            *line_number,
+                            column + 1,
+                            format!("unexpected character '{}'", other),
+                        ))
+                    }
+                };
+                sand.set_cell(row as u32, column as u32, particle);
+            }
+        }
+
+        Ok(sand)
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    // One byte per cell with the Particle values, in row major order like Universe
+    pub fn cells_ptr(&self) -> *const Particle {
+        self.cells.as_ptr()
+    }
+
+    pub fn cells_len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn get_cell(&self, row: u32, column: u32) -> Particle {
+        if row < self.height && column < self.width {
+            self.cells[self.get_index(row, column)]
+        } else {
+            Particle::Wall
+        }
+    }
+
+    pub fn set_cell(&mut self, row: u32, column: u32, particle: Particle) {
+        if row < self.height && column < self.width {
+            let index = self.get_index(row, column);
+            self.cells[index] = particle;
+        }
+    }
+
+    pub fn tick(&mut self) {
+        let mut moved = vec![false; self.cells.len()];
+        // which way to look first, and to sweep the rows, this generation
+        let side: i64 = if self.generation.is_multiple_of(2) {
+            1
+        } else {
+            -1
+        };
+        let width = i64::from(self.width);
+
+        for row in (0..i64::from(self.height)).rev() {
+            for step in 0..width {
+                let column = if side > 0 { step } else { width - 1 - step };
+                let index = self.get_index(row as u32, column as u32);
+                let particle = self.cells[index];
+                if moved[index] || !matches!(particle, Particle::Sand | Particle::Water) {
+                    continue;
+                }
+
+                let mut targets = vec![
+                    (row + 1, column),
+                    (row + 1, column + side),
+                    (row + 1, column - side),
+                ];
+                if particle == Particle::Water {
+                    targets.extend_from_slice(&[(row, column + side), (row, column - side)]);
+                }
+                let target = targets
+                    .into_iter()
+                    .find(|(row, column)| particle.displaces(self.get_cell_at(*row, *column)));
+                if let Some((row, column)) = target {
+                    let to = self.get_index(row as u32, column as u32);
+                    self.cells.swap(index, to);
+                    moved[to] = true;
+                    moved[index] = self.cells[index] != Particle::Empty;
+                }
+            }
+        }
+
+        self.generation += 1;
+    }
+
+    pub fn render(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl FallingSand {
+    fn get_index(&self, row: u32, column: u32) -> usize {
+        (row * self.width + column) as usize
+    }
+
+    // get_cell() for coordinates that might be off the board
+    fn get_cell_at(&self, row: i64, column: i64) -> Particle {
+        if row < 0 || column < 0 {
+            Particle::Wall
+        } else {
+            self.get_cell(row as u32, column as u32)
+        }
+    }
+}
+
+impl Display for FallingSand {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for line in self.cells.chunks(self.width.max(1) as usize) {
+            for cell in line {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sand_falls_and_piles_up() {
+        let mut sand = FallingSand::from_text("..s..\n..s..\n.....").unwrap();
+
+        sand.tick();
+        assert_eq!(".....\n..s..\n..s..\n", sand.render());
+        sand.tick();
+        assert_eq!(".....\n.....\n.ss..\n", sand.render());
+        assert_eq!(2, sand.generation());
+        assert_eq!("", FallingSand::new(0, 2).render());
+    }
+
+    #[test]
+    fn test_water_spreads_and_sand_sinks() {
+        // the first drop goes down the hole and the rest flow along after it
+        let mut sand = FallingSand::from_text("~~~\n#.#").unwrap();
+        sand.tick();
+        assert_eq!("~~.\n#~#\n", sand.render());
+
+        let mut sand = FallingSand::from_text("s\n~").unwrap();
+        sand.tick();
+        assert_eq!("~\ns\n", sand.render());
+    }
+
+    #[test]
+    fn test_walls_hold() {
+        let mut sand = FallingSand::from_text("s\n#\n.").unwrap();
+        sand.tick();
+        assert_eq!("s\n#\n.\n", sand.render());
+        assert_eq!(
+            ParseError::new(1, 2, "unexpected character 'x'"),
+            FallingSand::from_text("sx").unwrap_err()
+        );
+    }
+}
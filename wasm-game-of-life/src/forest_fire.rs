@@ -0,0 +1,225 @@
+use std::fmt::{self, Display, Formatter};
+
+use rand::prelude::*;
+use rand_pcg::Pcg32;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::stochastic::clamp_chance;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ForestCell {
+    Empty = 0,
+    Tree = 1,
+    Burning = 2,
+}
+
+impl Display for ForestCell {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ForestCell::Empty => write!(f, "."),
+            ForestCell::Tree => write!(f, "T"),
+            ForestCell::Burning => write!(f, "*"),
+        }
+    }
+}
+
+// Trees grow on empty ground, catch fire from a burning neighbor or a lightning strike,
+// and burn down to empty ground in one generation. Growth is the chance p of an empty cell
+// growing a tree each generation and lightning the chance f of a tree catching fire
+// with no fire next to it. The edges of the board are treated as empty.
+// https://en.wikipedia.org/wiki/Forest-fire_model
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug)]
+pub struct ForestFire {
+    width: u32,
+    height: u32,
+    cells: Vec<ForestCell>,
+    next: Vec<ForestCell>,
+    growth: f64,
+    lightning: f64,
+    rng: Pcg32,
+    generation: u32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ForestFire {
+    // An empty forest, the same seed always grows and burns the same way
+    pub fn new(width: u32, height: u32, growth: f64, lightning: f64, seed: u64) -> Self {
+        let cells = vec![ForestCell::Empty; (width * height) as usize];
+
+        Self {
+            width,
+            height,
+            next: cells.clone(),
+            cells,
+            growth: clamp_chance(growth),
+            lightning: clamp_chance(lightning),
+            rng: Pcg32::seed_from_u64(seed),
+            generation: 0,
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    pub fn growth(&self) -> f64 {
+        self.growth
+    }
+
+    pub fn set_growth(&mut self, growth: f64) {
+        self.growth = clamp_chance(growth);
+    }
+
+    pub fn lightning(&self) -> f64 {
+        self.lightning
+    }
+
+    pub fn set_lightning(&mut self, lightning: f64) {
+        self.lightning = clamp_chance(lightning);
+    }
+
+    // One byte per cell with the ForestCell values, in row major order like Universe
+    pub fn cells_ptr(&self) -> *const ForestCell {
+        self.cells.as_ptr()
+    }
+
+    pub fn cells_len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn get_cell(&self, row: u32, column: u32) -> ForestCell {
+        if row < self.height && column < self.width {
+            self.cells[self.get_index(row, column)]
+        } else {
+            ForestCell::Empty
+        }
+    }
+
+    pub fn set_cell(&mut self, row: u32, column: u32, cell: ForestCell) {
+        if row < self.height && column < self.width {
+            let index = self.get_index(row, column);
+            self.cells[index] = cell;
+        }
+    }
+
+    // How many cells are in the state, for charting trees against fires
+    pub fn count(&self, cell: ForestCell) -> u32 {
+        self.cells.iter().filter(|state| **state == cell).count() as u32
+    }
+
+    pub fn tick(&mut self) {
+        for row in 0..self.height {
+            for column in 0..self.width {
+                let index = self.get_index(row, column);
+                self.next[index] = match self.cells[index] {
+                    ForestCell::Burning => ForestCell::Empty,
+                    ForestCell::Tree
+                        if self.is_near_fire(row, column) || self.rng.gen_bool(self.lightning) =>
+                    {
+                        ForestCell::Burning
+                    }
+                    ForestCell::Tree => ForestCell::Tree,
+                    ForestCell::Empty if self.rng.gen_bool(self.growth) => ForestCell::Tree,
+                    ForestCell::Empty => ForestCell::Empty,
+                };
+            }
+        }
+
+        std::mem::swap(&mut self.cells, &mut self.next);
+        self.generation += 1;
+    }
+
+    pub fn render(&self) -> String {
+        self.to_string()
+    }
+
+    fn get_index(&self, row: u32, column: u32) -> usize {
+        (row * self.width + column) as usize
+    }
+
+    fn is_near_fire(&self, row: u32, column: u32) -> bool {
+        for neighbor_row in row.saturating_sub(1)..=(row + 1).min(self.height - 1) {
+            for neighbor_column in column.saturating_sub(1)..=(column + 1).min(self.width - 1) {
+                if self.cells[self.get_index(neighbor_row, neighbor_column)] == ForestCell::Burning
+                {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+}
+
+impl Display for ForestFire {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for line in self.cells.chunks(self.width.max(1) as usize) {
+            for cell in line {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fire_spreads_and_burns_out() {
+        let mut forest = ForestFire::new(4, 1, 0.0, 0.0, 1);
+        forest.set_cell(0, 0, ForestCell::Burning);
+        for column in 1..4 {
+            forest.set_cell(0, column, ForestCell::Tree);
+        }
+
+        forest.tick();
+        assert_eq!(".*TT\n", forest.render());
+        forest.tick();
+        assert_eq!("..*T\n", forest.render());
+        forest.tick();
+        forest.tick();
+        assert_eq!("....\n", forest.render());
+        assert_eq!(4, forest.generation());
+        assert_eq!("", ForestFire::new(0, 2, 0.0, 0.0, 1).render());
+    }
+
+    #[test]
+    fn test_growth_and_lightning() {
+        let mut forest = ForestFire::new(5, 5, 1.0, 0.0, 1);
+        forest.tick();
+        assert_eq!(25, forest.count(ForestCell::Tree));
+
+        forest.set_lightning(1.0);
+        forest.tick();
+        assert_eq!(25, forest.count(ForestCell::Burning));
+    }
+
+    #[test]
+    fn test_seeded_forests_match() {
+        let mut first = ForestFire::new(16, 16, 0.05, 0.001, 9);
+        let mut second = first.clone();
+        for _ in 0..50 {
+            first.tick();
+            second.tick();
+        }
+
+        assert!(first.count(ForestCell::Tree) > 0);
+        assert_eq!(first.render(), second.render());
+    }
+}
@@ -0,0 +1,93 @@
+// Bytes as text, for the places that only hold strings like localStorage and links. This
+// is the URL and filename safe alphabet from RFC 4648 without the = padding, decoding also
+// takes the standard + and / alphabet and ignores padding so either kind can be pasted in.
+// https://datatracker.ietf.org/doc/html/rfc4648#section-5
+use super::DecodeError;
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+pub fn encode(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity((bytes.len() * 4).div_ceil(3));
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (index, byte)| {
+            group | u32::from(*byte) << (16 - 8 * index)
+        });
+        // 3 bytes are 4 characters, a partial group needs one more character than bytes
+        for position in 0..=chunk.len() {
+            let sextet = (group >> (18 - 6 * position)) & 0x3f;
+            text.push(ALPHABET[sextet as usize] as char);
+        }
+    }
+    text
+}
+
+pub fn decode(text: &str) -> Result<Vec<u8>, DecodeError> {
+    let text = text.trim_end_matches('=');
+    let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+    let mut group = 0u32;
+    let mut bits = 0;
+
+    for (position, character) in text.chars().enumerate() {
+        let sextet = match character {
+            'A'..='Z' => character as u32 - 'A' as u32,
+            'a'..='z' => character as u32 - 'a' as u32 + 26,
+            '0'..='9' => character as u32 - '0' as u32 + 52,
+            '-' | '+' => 62,
+            '_' | '/' => 63,
+            _ => {
+                return Err(DecodeError::new(format!(
+                    "{:?} at {} is not a base64 character",
+                    character, position
+                )))
+            }
+        };
+        group = group << 6 | sextet;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((group >> bits) as u8);
+            group &= (1 << bits) - 1;
+        }
+    }
+    // a single character left over is only 6 bits, not enough for a byte
+    if bits == 6 {
+        return Err(DecodeError::new("the base64 text is cut short"));
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        assert_eq!("", encode(b""));
+        assert_eq!("Zg", encode(b"f"));
+        assert_eq!("Zm8", encode(b"fo"));
+        assert_eq!("Zm9v", encode(b"foo"));
+        assert_eq!("Zm9vYmFy", encode(b"foobar"));
+        assert_eq!("-_8", encode(&[0xfb, 0xff]));
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(b"foobar".to_vec(), decode("Zm9vYmFy").unwrap());
+        assert_eq!(b"fo".to_vec(), decode("Zm8=").unwrap());
+        assert_eq!(vec![0xfb, 0xff], decode("+/8").unwrap());
+        assert_eq!(vec![0xfb, 0xff], decode("-_8").unwrap());
+        assert!(decode("Zm9vY").is_err());
+        assert!(decode("Zm 9v").is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let bytes = (0..=255).collect::<Vec<u8>>();
+        for length in 0..bytes.len() {
+            assert_eq!(
+                &bytes[..length],
+                &decode(&encode(&bytes[..length])).unwrap()[..]
+            );
+        }
+    }
+}
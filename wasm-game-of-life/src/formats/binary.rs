@@ -0,0 +1,283 @@
+// A compact snapshot of a universe meant for posting between a Web Worker and the
+// main thread. Everything is little endian:
+//
+// | bytes | contents                                         |
+// |-------|--------------------------------------------------|
+// | 4     | magic "GOL" followed by version 3                |
+// | 4     | width                                            |
+// | 4     | height                                           |
+// | 1     | topology, see below                              |
+// | 4     | generation                                       |
+// | 2     | birth mask of the rule                           |
+// | 2     | survival mask of the rule                        |
+// | 1     | states of the rule, 2 unless it is Generations   |
+// | 1     | grid, 0 for square and 1 for hex                 |
+// | 4 * n | cells packed 32 to a word                        |
+// | n     | fading states, only for rules with over 2 states |
+//
+// The topology is 0 for a plane, 1 for a torus, 2 for a Klein bottle, 3 for a
+// cross-surface and 4 for a sphere, so 0 and 1 are the clamped and toroidal boundary
+// modes this byte used to hold.
+//
+// Version 1 had no states or grid and stopped after the cells, those snapshots still load
+// with a square life-like rule. Version 2 is the save format in save.rs. Larger than Life
+// and isotropic rules have no masks and can't be written at all, save.rs keeps those.
+use super::{DecodeError, EncodeError};
+use crate::bits::CellBits;
+use crate::{GridTopology, RuleSet, Topology, Universe};
+
+const MAGIC: &[u8; 3] = b"GOL";
+pub(super) const VERSION: u8 = 3;
+const VERSION_1_HEADER_LENGTH: usize = 21;
+const HEADER_LENGTH: usize = 23;
+
+pub fn write(universe: &Universe) -> Result<Vec<u8>, EncodeError> {
+    if universe.ltl.is_some() || universe.isotropic.is_some() {
+        return Err(EncodeError::new(format!(
+            "the rule {} has no birth and survival masks to store",
+            universe.rule()
+        )));
+    }
+    let (birth, survival) = universe.rule.masks();
+    let words = universe.cells.words();
+    let mut bytes = Vec::with_capacity(HEADER_LENGTH + words.len() * 4);
+
+    bytes.extend_from_slice(MAGIC);
+    bytes.push(VERSION);
+    bytes.extend_from_slice(&universe.width.to_le_bytes());
+    bytes.extend_from_slice(&universe.height.to_le_bytes());
+    bytes.push(universe.edge_topology as u8);
+    bytes.extend_from_slice(&universe.generation.to_le_bytes());
+    bytes.extend_from_slice(&birth.to_le_bytes());
+    bytes.extend_from_slice(&survival.to_le_bytes());
+    bytes.push(universe.rule.states());
+    bytes.push(match universe.rule.topology() {
+        GridTopology::Square => 0,
+        GridTopology::Hex => 1,
+    });
+    for word in words {
+        bytes.extend_from_slice(&word.to_le_bytes());
+    }
+    if universe.rule.states() > 2 {
+        bytes.extend_from_slice(&universe.fading);
+    }
+
+    Ok(bytes)
+}
+
+// Reads version 3 snapshots and version 1 ones from before there were states and grids
+pub fn read(bytes: &[u8]) -> Result<Universe, DecodeError> {
+    if bytes.len() < VERSION_1_HEADER_LENGTH || &bytes[0..3] != MAGIC {
+        return Err(DecodeError::new("not a serialized universe"));
+    }
+    let header_length = match bytes[3] {
+        1 => VERSION_1_HEADER_LENGTH,
+        VERSION if bytes.len() >= HEADER_LENGTH => HEADER_LENGTH,
+        VERSION => return Err(DecodeError::new("not a serialized universe")),
+        version => return Err(DecodeError::new(format!("unsupported version {}", version))),
+    };
+
+    let u32_at = |offset: usize| {
+        u32::from_le_bytes([
+            bytes[offset],
+            bytes[offset + 1],
+            bytes[offset + 2],
+            bytes[offset + 3],
+        ])
+    };
+    let u16_at = |offset: usize| u16::from_le_bytes([bytes[offset], bytes[offset + 1]]);
+
+    let width = u32_at(4);
+    let height = u32_at(8);
+    let topology = read_topology(bytes[12])?;
+    let generation = u32_at(13);
+    let mut rule = RuleSet::from_masks(u16_at(17), u16_at(19));
+    if header_length == HEADER_LENGTH {
+        if bytes[21] < 2 {
+            return Err(DecodeError::new(format!(
+                "invalid number of states {}",
+                bytes[21]
+            )));
+        }
+        rule.set_states(bytes[21]);
+        rule.set_topology(match bytes[22] {
+            0 => GridTopology::Square,
+            1 => GridTopology::Hex,
+            other => return Err(DecodeError::new(format!("unknown grid {}", other))),
+        });
+    }
+
+    let cell_count = u64::from(width) * u64::from(height);
+    let word_count = cell_count.div_ceil(32);
+    let fading_length = if rule.states() > 2 { cell_count } else { 0 };
+    if cell_count > u64::from(u32::MAX)
+        || bytes.len() as u64 != header_length as u64 + word_count * 4 + fading_length
+    {
+        return Err(DecodeError::new("cell data does not match the dimensions"));
+    }
+
+    let mut universe = Universe::new_with_dimensions(width, height);
+    universe.edge_topology = topology;
+    universe.generation = generation;
+    universe.rule = rule;
+    let mut cells = CellBits::new(cell_count as usize);
+    for index in 0..cell_count as usize {
+        let word = u32_at(header_length + index / 32 * 4);
+        cells.set(index, word & (1 << (index % 32)) != 0);
+    }
+    universe.cells = cells;
+    if fading_length > 0 {
+        let start = header_length + word_count as usize * 4;
+        universe.fading.copy_from_slice(&bytes[start..]);
+    }
+
+    Ok(universe)
+}
+
+pub(super) fn read_topology(byte: u8) -> Result<Topology, DecodeError> {
+    match byte {
+        0 => Ok(Topology::Plane),
+        1 => Ok(Topology::Torus),
+        2 => Ok(Topology::KleinBottle),
+        3 => Ok(Topology::CrossSurface),
+        4 => Ok(Topology::Sphere),
+        other => Err(DecodeError::new(format!("unknown topology {}", other))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{BoundaryMode, Pattern};
+
+    #[test]
+    fn test_write() {
+        let mut universe = Universe::new_with_dimensions(3, 3);
+        universe.insert_pattern(Pattern::Glider, 0, 0);
+
+        assert_eq!(
+            vec![
+                b'G',
+                b'O',
+                b'L',
+                3,
+                3,
+                0,
+                0,
+                0,
+                3,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                8,
+                0,
+                12,
+                0,
+                2,
+                0,
+                0b1110_0010,
+                0b1,
+                0,
+                0
+            ],
+            write(&universe).unwrap()
+        );
+
+        universe.set_rule("B2-a/S12").unwrap();
+        assert_eq!(
+            EncodeError::new("the rule B2-a/S12 has no birth and survival masks to store"),
+            write(&universe).unwrap_err()
+        );
+        universe.set_rule("R2,C0,M1,S5..9,B4..6,NM").unwrap();
+        assert!(write(&universe).is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut universe = Universe::new_with_dimensions(45, 7);
+        universe.set_boundary_mode(BoundaryMode::Toroidal);
+        universe.set_rule("B36/S23").unwrap();
+        universe.randomize_seeded(3, 0.5);
+        universe.tick_n(4);
+
+        let copy = read(&write(&universe).unwrap()).unwrap();
+
+        assert_eq!(45, copy.width());
+        assert_eq!(7, copy.height());
+        assert_eq!(BoundaryMode::Toroidal, copy.boundary_mode());
+        assert_eq!("B36/S23", copy.rule());
+        assert_eq!(4, copy.generation());
+        assert_eq!(universe.cells(), copy.cells());
+    }
+
+    #[test]
+    fn test_generations_and_hex_round_trip() {
+        let mut universe = Universe::new_with_dimensions(20, 10);
+        universe.set_rule("B2/S34/C5H").unwrap();
+        universe.randomize_seeded(7, 0.4);
+        universe.tick_n(3);
+
+        let mut copy = read(&write(&universe).unwrap()).unwrap();
+        assert_eq!("B2/S34/C5H", copy.rule());
+        assert_eq!(universe.fading, copy.fading);
+
+        universe.tick_n(3);
+        copy.tick_n(3);
+        assert_eq!(universe.cells(), copy.cells());
+        assert_eq!(universe.fading, copy.fading);
+    }
+
+    #[test]
+    fn test_reads_version_1() {
+        // a 3 by 3 glider under B36/S23
+        let bytes = [
+            b'G', b'O', b'L', 1, 3, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0, 0, 72, 0, 12, 0, 226, 1, 0, 0,
+        ];
+        let universe = read(&bytes).unwrap();
+
+        assert_eq!("B36/S23", universe.rule());
+        assert_eq!(5, universe.population());
+    }
+
+    #[test]
+    fn test_read_errors() {
+        let universe = Universe::new(4);
+        let mut bytes = write(&universe).unwrap();
+
+        assert_eq!(
+            DecodeError::new("not a serialized universe"),
+            read(&bytes[0..10]).unwrap_err()
+        );
+
+        bytes.push(0);
+        assert_eq!(
+            DecodeError::new("cell data does not match the dimensions"),
+            read(&bytes).unwrap_err()
+        );
+
+        bytes.pop();
+        bytes[21] = 1;
+        assert_eq!(
+            DecodeError::new("invalid number of states 1"),
+            read(&bytes).unwrap_err()
+        );
+
+        bytes[21] = 2;
+        bytes[22] = 2;
+        assert_eq!(
+            DecodeError::new("unknown grid 2"),
+            read(&bytes).unwrap_err()
+        );
+
+        bytes[3] = 9;
+        assert_eq!(
+            DecodeError::new("unsupported version 9"),
+            read(&bytes).unwrap_err()
+        );
+    }
+}
@@ -0,0 +1,61 @@
+// Any of the binary formats deflated, for blobs that have to fit in localStorage or a
+// link. The header is "GOL" like the others with a Z where the version goes, followed by
+// the deflated blob, which still has its own header and version.
+use std::borrow::Cow;
+
+use miniz_oxide::deflate::compress_to_vec;
+use miniz_oxide::inflate::decompress_to_vec_with_limit;
+
+use super::{DecodeError, MAX_CELLS};
+
+const MAGIC: &[u8; 4] = b"GOLZ";
+const LEVEL: u8 = 6;
+// Past the bits the save format keeps a byte a cell for fading states and another for
+// colors, anything that inflates to more than this is no board the parsers would allow
+const MAX_LENGTH: usize = 4 * MAX_CELLS as usize;
+
+pub fn compress(blob: &[u8]) -> Vec<u8> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend_from_slice(&compress_to_vec(blob, LEVEL));
+    bytes
+}
+
+// The blob inside compressed bytes, anything else is handed back as it is
+pub fn decompress(bytes: &[u8]) -> Result<Cow<'_, [u8]>, DecodeError> {
+    match bytes.strip_prefix(MAGIC) {
+        Some(deflated) => decompress_to_vec_with_limit(deflated, MAX_LENGTH)
+            .map(Cow::Owned)
+            .map_err(|error| DecodeError::new(format!("could not inflate: {}", error))),
+        None => Ok(Cow::Borrowed(bytes)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::formats::save;
+    use crate::{Pattern, Universe};
+
+    #[test]
+    fn test_round_trip() {
+        let mut universe = Universe::new(256);
+        universe.insert_pattern(Pattern::GosperGliderGun, 10, 10);
+        let blob = save::write(&universe);
+        let compressed = compress(&blob);
+        assert!(compressed.len() * 20 < blob.len());
+        assert_eq!(blob, decompress(&compressed).unwrap().into_owned());
+    }
+
+    #[test]
+    fn test_uncompressed_pass_through() {
+        let blob = save::write(&Universe::new(8));
+        assert!(matches!(decompress(&blob), Ok(Cow::Borrowed(_))));
+    }
+
+    #[test]
+    fn test_corrupt() {
+        let mut compressed = compress(&save::write(&Universe::new(8)));
+        compressed.truncate(8);
+        assert!(decompress(&compressed).is_err());
+    }
+}
@@ -0,0 +1,78 @@
+// A board packed small enough to share in a link, for location.hash. The board is
+// written as RLE, deflated and then base64 encoded with the URL safe alphabet, so a
+// glider gun on a 64 by 64 board comes out at just over a hundred characters.
+use miniz_oxide::deflate::compress_to_vec;
+use miniz_oxide::inflate::decompress_to_vec_with_limit;
+
+use super::{base64, rle, DecodeError, MAX_CELLS};
+use crate::Universe;
+
+const LEVEL: u8 = 9;
+
+pub fn write(universe: &Universe) -> String {
+    base64::encode(&compress_to_vec(rle::write(universe).as_bytes(), LEVEL))
+}
+
+// The # that location.hash starts with is skipped
+pub fn read(fragment: &str) -> Result<Universe, DecodeError> {
+    let fragment = fragment.strip_prefix('#').unwrap_or(fragment);
+    let compressed = base64::decode(fragment)?;
+    // RLE takes well under a byte a cell, a bigger board than the parser allows is never
+    // going to fit in this
+    let bytes = decompress_to_vec_with_limit(&compressed, MAX_CELLS as usize)
+        .map_err(|error| DecodeError::new(format!("could not inflate the link: {}", error)))?;
+    let text = String::from_utf8(bytes)
+        .map_err(|_| DecodeError::new("the link does not hold a pattern"))?;
+    rle::parse(&text).map_err(|error| DecodeError::new(error.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Pattern;
+
+    #[test]
+    fn test_round_trip() {
+        let mut universe = Universe::new(64);
+        universe.insert_pattern(Pattern::GosperGliderGun, 4, 4);
+        universe.set_rule("B36/S23").unwrap();
+        let fragment = write(&universe);
+        assert!(fragment.len() < 150, "{}", fragment);
+        assert!(fragment
+            .chars()
+            .all(|character| character.is_ascii_alphanumeric() || "-_".contains(character)));
+
+        let read = read(&format!("#{}", fragment)).unwrap();
+        assert_eq!(universe.cells(), read.cells());
+        assert_eq!("B36/S23", read.rule());
+    }
+
+    #[test]
+    fn test_isotropic_rule_round_trip() {
+        let mut universe = Universe::new(16);
+        universe.insert_pattern(Pattern::Glider, 4, 4);
+        universe.set_rule("B2-a/S12").unwrap();
+
+        let read = read(&write(&universe)).unwrap();
+        assert_eq!("B2-a/S12", read.rule());
+        assert_eq!(universe.cells(), read.cells());
+    }
+
+    #[test]
+    fn test_ltl_rule_round_trip() {
+        let mut universe = Universe::new(16);
+        universe.insert_pattern(Pattern::Glider, 4, 4);
+        universe.set_rule("R2,C0,M1,S5..9,B4..6,NM").unwrap();
+
+        let read = read(&write(&universe)).unwrap();
+        assert_eq!("R2,C0,M1,S5..9,B4..6,NM", read.rule());
+        assert_eq!(universe.cells(), read.cells());
+    }
+
+    #[test]
+    fn test_bad_fragments() {
+        assert!(read("not a link").is_err());
+        assert!(read(&base64::encode(b"not deflated")).is_err());
+        assert!(read(&base64::encode(&compress_to_vec(b"x = oops", LEVEL))).is_err());
+    }
+}
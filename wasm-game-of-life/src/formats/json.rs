@@ -0,0 +1,111 @@
+// The shape of a universe when converted to a JavaScript object, JSON.stringify it
+// to save it in localStorage and JSON.parse it to load it again
+use serde::{Deserialize, Serialize};
+
+use super::DecodeError;
+use crate::bits::CellBits;
+use crate::{BoundaryMode, Topology, Universe};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UniverseData {
+    pub width: u32,
+    pub height: u32,
+    pub rule: String,
+    pub generation: u32,
+    pub toroidal: bool,
+    // objects saved before there were topologies only have toroidal
+    #[serde(default)]
+    pub edge_topology: Option<Topology>,
+    // cells packed 32 to a number, the same layout as bits_ptr()
+    pub cells: Vec<u32>,
+}
+
+impl From<&Universe> for UniverseData {
+    fn from(universe: &Universe) -> Self {
+        Self {
+            width: universe.width,
+            height: universe.height,
+            rule: universe.rule(),
+            generation: universe.generation,
+            toroidal: universe.boundary_mode() == BoundaryMode::Toroidal,
+            edge_topology: Some(universe.edge_topology),
+            cells: universe.cells.words().to_vec(),
+        }
+    }
+}
+
+impl UniverseData {
+    pub fn into_universe(self) -> Result<Universe, DecodeError> {
+        let cell_count = u64::from(self.width) * u64::from(self.height);
+        if cell_count > u64::from(u32::MAX) || self.cells.len() as u64 != cell_count.div_ceil(32) {
+            return Err(DecodeError::new("cell data does not match the dimensions"));
+        }
+
+        let mut universe = Universe::new_with_dimensions(self.width, self.height);
+        universe
+            .set_rule(&self.rule)
+            .map_err(|error| DecodeError::new(error.to_string()))?;
+        universe.generation = self.generation;
+        universe.edge_topology = self.edge_topology.unwrap_or(if self.toroidal {
+            Topology::Torus
+        } else {
+            Topology::Plane
+        });
+        let mut cells = CellBits::new(cell_count as usize);
+        for index in 0..cell_count as usize {
+            cells.set(index, self.cells[index / 32] & (1 << (index % 32)) != 0);
+        }
+        universe.cells = cells;
+
+        Ok(universe)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Pattern;
+
+    #[test]
+    fn test_to_json() {
+        let mut universe = Universe::new(3);
+        universe.insert_pattern(Pattern::Glider, 0, 0);
+
+        assert_eq!(
+            r#"{"width":3,"height":3,"rule":"B3/S23","generation":0,"toroidal":false,"edge_topology":"Plane","cells":[482]}"#,
+            serde_json::to_string(&UniverseData::from(&universe)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut universe = Universe::new_with_dimensions(40, 3);
+        universe.set_rule("B36/S23").unwrap();
+        universe.set_boundary_mode(BoundaryMode::Toroidal);
+        universe.randomize_seeded(1, 0.5);
+        universe.tick();
+
+        let json = serde_json::to_string(&UniverseData::from(&universe)).unwrap();
+        let data: UniverseData = serde_json::from_str(&json).unwrap();
+        let copy = data.into_universe().unwrap();
+
+        assert_eq!(universe.cells(), copy.cells());
+        assert_eq!("B36/S23", copy.rule());
+        assert_eq!(1, copy.generation());
+        assert_eq!(BoundaryMode::Toroidal, copy.boundary_mode());
+    }
+
+    #[test]
+    fn test_invalid_data() {
+        let mut data = UniverseData::from(&Universe::new(3));
+        data.cells.push(0);
+        assert_eq!(
+            DecodeError::new("cell data does not match the dimensions"),
+            data.into_universe().unwrap_err()
+        );
+
+        let mut data = UniverseData::from(&Universe::new(3));
+        data.rule = "B9".to_owned();
+        assert!(data.into_universe().is_err());
+    }
+}
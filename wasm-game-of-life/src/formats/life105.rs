@@ -0,0 +1,253 @@
+// Life 1.05 splits a pattern into blocks, each one starts with a "#P x y" line giving the
+// position of its top left corner and is drawn with * for live cells and . for dead ones.
+// "#N" picks Conway's rule and "#R 23/3" any other in survival/birth notation, "#D" lines
+// are descriptions. The pattern is moved so its top left block lands on the edges.
+// https://conwaylife.com/wiki/Life_1.05
+use super::{EncodeError, ParseError, MAX_CELLS};
+use crate::{GridTopology, PatternMetadata, RuleSet, Universe};
+
+const HEADER: &str = "#Life 1.05";
+// Lines are kept under the 80 characters the format allows
+const MAX_BLOCK_WIDTH: u32 = 70;
+
+pub fn parse(life: &str) -> Result<Universe, ParseError> {
+    let mut lines = life
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim_end()));
+    match lines.next() {
+        Some((_, header)) if header.trim() == HEADER => {}
+        _ => return Err(ParseError::new(1, 1, "missing the #Life 1.05 header")),
+    }
+
+    let mut rule = RuleSet::default();
+    // (row, column) of every live cell
+    let mut cells = vec![];
+    let mut block: Option<(i64, i64)> = None;
+    let mut block_row = 0;
+    let mut metadata = PatternMetadata::default();
+    for (line_number, line) in lines {
+        if let Some(position) = line.strip_prefix("#P") {
+            let numbers: Vec<Option<i64>> = position
+                .split_whitespace()
+                .map(|number| number.parse().ok())
+                .collect();
+            match numbers.as_slice() {
+                [Some(x), Some(y)] => block = Some((*y, *x)),
+                _ => return Err(ParseError::new(line_number, 3, "invalid block position")),
+            }
+            block_row = 0;
+        } else if let Some(value) = line.strip_prefix("#R") {
+            rule = value.parse().map_err(|error: ParseError| {
+                ParseError::new(line_number, 3 + error.column, error.message)
+            })?;
+        } else if line.starts_with("#N") {
+            rule = RuleSet::default();
+        } else if let Some(description) = line.strip_prefix("#D") {
+            metadata.add_comment(description.trim());
+        } else if line.starts_with('#') || line.is_empty() {
+            continue;
+        } else {
+            let (top, left) = block.unwrap_or((0, 0));
+            for (column, character) in line.chars().enumerate() {
+                match character {
+                    '*' => {
+                        let row = top.checked_add(block_row);
+                        let column_number = left.checked_add(column as i64);
+                        match row.zip(column_number) {
+                            Some(cell) => cells.push(cell),
+                            None => {
+                                return Err(ParseError::new(
+                                    line_number,
+                                    column + 1,
+                                    "the pattern is too large",
+                                ))
+                            }
+                        }
+                    }
+                    '.' => {}
+                    other => {
+                        return Err(ParseError::new(
+                            line_number,
+                            column + 1,
+                            format!("unexpected character '{}'", other),
+                        ))
+                    }
+                }
+            }
+            block_row += 1;
+        }
+    }
+
+    let top = cells.iter().map(|(row, _)| *row).min().unwrap_or(0);
+    let left = cells.iter().map(|(_, column)| *column).min().unwrap_or(0);
+    let bottom = cells.iter().map(|(row, _)| *row).max().unwrap_or(-1);
+    let right = cells.iter().map(|(_, column)| *column).max().unwrap_or(-1);
+    // coordinates can be anywhere in an i64, so the size is worked out in an i128
+    let width = i128::from(right) - i128::from(left) + 1;
+    let height = i128::from(bottom) - i128::from(top) + 1;
+    if width
+        .checked_mul(height)
+        .is_none_or(|cells| cells > i128::from(MAX_CELLS))
+    {
+        return Err(ParseError::new(1, 1, "the pattern is too large"));
+    }
+
+    let mut universe = Universe::new_with_dimensions(width as u32, height as u32);
+    universe.rule = rule;
+    universe.metadata = metadata;
+    for (row, column) in cells {
+        let index = universe.get_index((row - top) as u32, (column - left) as u32);
+        universe.cells.set(index, true);
+    }
+
+    Ok(universe)
+}
+
+// Wide universes are written as several blocks side by side. Only the birth and survival
+// counts of the rule fit in the format, Generations and hex rules lose the rest. Only the
+// comments of the metadata are kept, as #D lines.
+// Fails for rules #R can't hold, anything but a life-like rule on the square grid
+pub fn write(universe: &Universe) -> Result<String, EncodeError> {
+    if universe.ltl.is_some()
+        || universe.isotropic.is_some()
+        || universe.rule.states() > 2
+        || universe.rule.topology() != GridTopology::Square
+    {
+        return Err(EncodeError::new(format!(
+            "Life 1.05 files can't hold the rule {}",
+            universe.rule()
+        )));
+    }
+    let mut life = format!("{}\n", HEADER);
+    for comment in universe.metadata.comments() {
+        for line in comment.lines() {
+            life.push_str(&format!("#D {}\n", line));
+        }
+    }
+    if universe.rule == RuleSet::default() {
+        life.push_str("#N\n");
+    } else {
+        let (birth, survival) = universe.rule.masks();
+        life.push_str(&format!("#R {}/{}\n", digits(survival), digits(birth)));
+    }
+
+    for left in (0..universe.width).step_by(MAX_BLOCK_WIDTH as usize) {
+        let right = (left + MAX_BLOCK_WIDTH).min(universe.width);
+        life.push_str(&format!("#P {} 0\n", left));
+        for row in 0..universe.height {
+            let line: String = (left..right)
+                .map(|column| {
+                    if universe.cells.get(universe.get_index(row, column)) {
+                        '*'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            let line = line.trim_end_matches('.');
+            life.push_str(if line.is_empty() { "." } else { line });
+            life.push('\n');
+        }
+    }
+
+    Ok(life)
+}
+
+fn digits(mask: u16) -> String {
+    (0..=8)
+        .filter(|count| mask & (1 << count) != 0)
+        .map(|count| std::char::from_digit(count, 10).unwrap())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Pattern;
+
+    #[test]
+    fn test_parse() {
+        let life = "#Life 1.05\n#D A glider\n#N\n#P -1 -1\n.*\n..*\n***\n";
+        let universe = parse(life).unwrap();
+
+        assert_eq!("B3/S23", universe.rule.to_string());
+        assert_eq!("◻◼◻\n◻◻◼\n◼◼◼\n", universe.render());
+        assert_eq!("A glider", universe.metadata().description());
+    }
+
+    #[test]
+    fn test_parse_blocks_and_rule() {
+        let life = "#Life 1.05\n#R 23/36\n#P 0 0\n**\n#P 4 2\n*\n";
+        let universe = parse(life).unwrap();
+
+        assert_eq!("B36/S23", universe.rule.to_string());
+        assert_eq!(5, universe.width);
+        assert_eq!(3, universe.height);
+        assert_eq!("◼◼◻◻◻\n◻◻◻◻◻\n◻◻◻◻◼\n", universe.render());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            ParseError::new(1, 1, "missing the #Life 1.05 header"),
+            parse("#Life 1.06\n").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(3, 2, "unexpected character 'O'"),
+            parse("#Life 1.05\n#P 0 0\n*O\n").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(2, 3, "invalid block position"),
+            parse("#Life 1.05\n#P 0\n").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(3, 2, "the pattern is too large"),
+            parse("#Life 1.05\n#P 9223372036854775807 0\n**").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(1, 1, "the pattern is too large"),
+            parse(
+                "#Life 1.05\n#P -9223372036854775808 -9223372036854775808\n*\n\
+                 #P 9223372036854775807 9223372036854775807\n*\n"
+            )
+            .unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_write() {
+        let mut universe = Universe::new_with_dimensions(4, 3);
+        universe.insert_pattern(Pattern::Blinker, 1, 0);
+        universe.set_rule("B36/S23").unwrap();
+
+        assert_eq!(
+            "#Life 1.05\n#R 23/36\n#P 0 0\n.\n***\n.\n",
+            write(&universe).unwrap()
+        );
+
+        for rule in [
+            "B2-a/S12",
+            "R2,C0,M1,S5..9,B4..6,NM",
+            "B2/S34/C3",
+            "B2/S34H",
+        ] {
+            universe.set_rule(rule).unwrap();
+            assert_eq!(
+                EncodeError::new(format!("Life 1.05 files can't hold the rule {}", rule)),
+                write(&universe).unwrap_err()
+            );
+        }
+    }
+
+    #[test]
+    fn test_round_trip_wide() {
+        let mut universe = Universe::new_with_dimensions(150, 20);
+        universe.randomize_seeded(4, 0.3);
+        universe.set_cell(0, 0, crate::Cell::Alive);
+        universe.set_cell(19, 149, crate::Cell::Alive);
+
+        let copy = parse(&write(&universe).unwrap()).unwrap();
+        assert_eq!(universe.cells(), copy.cells());
+    }
+}
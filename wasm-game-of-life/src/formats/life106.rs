@@ -0,0 +1,140 @@
+// Life 1.06 lists the x y coordinates of every live cell after a "#Life 1.06" line, x is
+// the column and y the row. Coordinates can be negative, the pattern is moved so its top
+// left live cell lands on the edges of the universe.
+// https://conwaylife.com/wiki/Life_1.06
+use super::{ParseError, MAX_CELLS};
+use crate::Universe;
+
+const HEADER: &str = "#Life 1.06";
+
+pub fn parse(life: &str) -> Result<Universe, ParseError> {
+    let mut lines = life
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()));
+    match lines.next() {
+        Some((_, header)) if header == HEADER => {}
+        _ => return Err(ParseError::new(1, 1, "missing the #Life 1.06 header")),
+    }
+
+    let mut coordinates = vec![];
+    for (line_number, line) in lines {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields = fields(line);
+        let number = |position: usize, name: &str| {
+            let (column, text) = fields.get(position).copied().unwrap_or((line.len(), ""));
+            text.parse::<i64>()
+                .map_err(|_| ParseError::new(line_number, column + 1, format!("invalid {}", name)))
+        };
+        let x = number(0, "x")?;
+        let y = number(1, "y")?;
+        coordinates.push((y, x));
+    }
+
+    let top = coordinates.iter().map(|(y, _)| *y).min().unwrap_or(0);
+    let left = coordinates.iter().map(|(_, x)| *x).min().unwrap_or(0);
+    let bottom = coordinates.iter().map(|(y, _)| *y).max().unwrap_or(-1);
+    let right = coordinates.iter().map(|(_, x)| *x).max().unwrap_or(-1);
+    // coordinates can be anywhere in an i64, so the size is worked out in an i128
+    let width = i128::from(right) - i128::from(left) + 1;
+    let height = i128::from(bottom) - i128::from(top) + 1;
+    if width * height > i128::from(MAX_CELLS) {
+        return Err(ParseError::new(1, 1, "the pattern is too large"));
+    }
+
+    let mut universe = Universe::new_with_dimensions(width as u32, height as u32);
+    for (y, x) in coordinates {
+        let index = universe.get_index((y - top) as u32, (x - left) as u32);
+        universe.cells.set(index, true);
+    }
+
+    Ok(universe)
+}
+
+// The words of a line along with where each one starts
+pub(super) fn fields(line: &str) -> Vec<(usize, &str)> {
+    let mut fields = vec![];
+    let mut start = None;
+    for (index, character) in line
+        .char_indices()
+        .chain(std::iter::once((line.len(), ' ')))
+    {
+        match (start, character.is_whitespace()) {
+            (None, false) => start = Some(index),
+            (Some(field_start), true) => {
+                fields.push((field_start, &line[field_start..index]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    fields
+}
+
+pub fn write(universe: &Universe) -> String {
+    let mut life = format!("{}\n", HEADER);
+    for row in 0..universe.height {
+        for column in 0..universe.width {
+            if universe.cells.get(universe.get_index(row, column)) {
+                life.push_str(&format!("{} {}\n", column, row));
+            }
+        }
+    }
+
+    life
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Pattern;
+
+    #[test]
+    fn test_parse() {
+        let life = "#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n";
+        let universe = parse(life).unwrap();
+
+        assert_eq!(3, universe.width);
+        assert_eq!(3, universe.height);
+        assert_eq!("◻◼◻\n◻◻◼\n◼◼◼\n", universe.render());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            ParseError::new(1, 1, "missing the #Life 1.06 header"),
+            parse("0 0\n").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(3, 3, "invalid y"),
+            parse("#Life 1.06\n0 0\n1 a\n").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(2, 5, "invalid y"),
+            parse("#Life 1.06\n1   1.5\n").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(2, 2, "invalid y"),
+            parse("#Life 1.06\n4\n").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(1, 1, "the pattern is too large"),
+            parse("#Life 1.06\n-9223372036854775808 0\n9223372036854775807 0\n").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut universe = Universe::new(6);
+        universe.insert_pattern(Pattern::Glider, 0, 0);
+
+        assert_eq!("#Life 1.06\n1 0\n2 1\n0 2\n1 2\n2 2\n", write(&universe));
+        assert_eq!(
+            "◻◼◻\n◻◻◼\n◼◼◼\n",
+            parse(&write(&universe)).unwrap().render()
+        );
+    }
+}
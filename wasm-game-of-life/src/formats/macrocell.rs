@@ -0,0 +1,230 @@
+// Golly's macrocell format writes out the HashLife quadtree one node per line, so a
+// pattern is read straight into nodes without ever being drawn on a grid. After the
+// "[M2]" header each line is either an 8 by 8 leaf drawn with . and * and rows ending in
+// $, or "level nw ne sw se" where the children are earlier lines numbered from 1 and 0
+// is an empty node. The last line is the root, its center is the center of the plane.
+// "#R" gives the rule and "#G" the generation, other # lines are comments.
+// https://golly.sourceforge.io/Help/formats.html#mc
+use super::life106::fields;
+use super::ParseError;
+use crate::engine::NodeId;
+use crate::{HashLife, RuleSet};
+
+const HEADER: &str = "[M2]";
+const LEAF_SIZE: usize = 8;
+const LEAF_LEVEL: u8 = 3;
+// Past this the coordinates of the edges no longer fit in an i64
+const MAX_LEVEL: u8 = 60;
+
+pub fn parse(macrocell: &str) -> Result<HashLife, ParseError> {
+    let mut lines = macrocell
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()));
+    match lines.next() {
+        Some((_, header)) if header.starts_with(HEADER) => {}
+        _ => return Err(ParseError::new(1, 1, "missing the [M2] header")),
+    }
+
+    let mut hashlife = HashLife::default();
+    let mut rule = RuleSet::default();
+    let mut generation = 0;
+    let mut nodes: Vec<NodeId> = vec![];
+    for (line_number, line) in lines {
+        if let Some(value) = line.strip_prefix("#R") {
+            rule = value.parse().map_err(|error: ParseError| {
+                ParseError::new(line_number, 3 + error.column, error.message)
+            })?;
+            if rule.masks().0 & 1 != 0 {
+                return Err(ParseError::new(
+                    line_number,
+                    3,
+                    "rules with B0 are not supported",
+                ));
+            }
+        } else if let Some(value) = line.strip_prefix("#G") {
+            generation = value
+                .trim()
+                .parse()
+                .map_err(|_| ParseError::new(line_number, 3, "invalid generation"))?;
+        } else if line.starts_with('#') || line.is_empty() {
+            continue;
+        } else if line.starts_with(['.', '*', '$']) {
+            let node = parse_leaf(&mut hashlife, line_number, line)?;
+            nodes.push(node);
+        } else {
+            let node = parse_node(&mut hashlife, &nodes, line_number, line)?;
+            nodes.push(node);
+        }
+    }
+
+    hashlife.set_rule(rule);
+    if let Some(root) = nodes.last() {
+        // a root smaller than a leaf is grown, HashLife needs room to work
+        let mut root = *root;
+        while hashlife.level(root) < LEAF_LEVEL {
+            let empty = hashlife.empty_node(hashlife.level(root));
+            root = hashlife.join(root, empty, empty, empty);
+        }
+        hashlife.set_root(root, generation);
+    }
+
+    Ok(hashlife)
+}
+
+fn parse_leaf(
+    hashlife: &mut HashLife,
+    line_number: usize,
+    line: &str,
+) -> Result<NodeId, ParseError> {
+    let mut rows = [[false; LEAF_SIZE]; LEAF_SIZE];
+    let (mut row, mut column) = (0, 0);
+    for (index, character) in line.chars().enumerate() {
+        if row == LEAF_SIZE || (column == LEAF_SIZE && character != '$') {
+            return Err(ParseError::new(
+                line_number,
+                index + 1,
+                "a leaf is only 8 cells across",
+            ));
+        }
+        match character {
+            '*' => {
+                rows[row][column] = true;
+                column += 1;
+            }
+            '.' => column += 1,
+            '$' => {
+                row += 1;
+                column = 0;
+            }
+            other => {
+                return Err(ParseError::new(
+                    line_number,
+                    index + 1,
+                    format!("unexpected character '{}'", other),
+                ))
+            }
+        }
+    }
+
+    Ok(hashlife.leaf_block(&rows))
+}
+
+fn parse_node(
+    hashlife: &mut HashLife,
+    nodes: &[NodeId],
+    line_number: usize,
+    line: &str,
+) -> Result<NodeId, ParseError> {
+    let fields = fields(line);
+    if fields.len() != 5 {
+        return Err(ParseError::new(
+            line_number,
+            1,
+            "expected a level and four children",
+        ));
+    }
+    let (column, level) = fields[0];
+    let level = match level.parse::<u8>() {
+        Ok(level) if (1..=MAX_LEVEL).contains(&level) => level,
+        _ => return Err(ParseError::new(line_number, column + 1, "invalid level")),
+    };
+
+    let mut children = [0; 4];
+    for (child, (column, text)) in children.iter_mut().zip(fields[1..].iter()) {
+        let error = |message: &str| ParseError::new(line_number, column + 1, message);
+        let index = text
+            .parse::<usize>()
+            .map_err(|_| error("invalid node number"))?;
+        // the children of a level 1 node are the states of single cells
+        *child = match (level, index) {
+            (1, 0) | (1, 1) => HashLife::cell_node(index == 1),
+            (1, _) => return Err(error("rules with more than 2 states are not supported")),
+            (_, 0) => hashlife.empty_node(level - 1),
+            _ => match nodes.get(index - 1) {
+                Some(node) if hashlife.level(*node) == level - 1 => *node,
+                Some(_) => return Err(error("the child is not one level down")),
+                None => return Err(error("the node has not been defined yet")),
+            },
+        };
+    }
+
+    Ok(hashlife.join(children[0], children[1], children[2], children[3]))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sorted(mut cells: Vec<(i64, i64)>) -> Vec<(i64, i64)> {
+        cells.sort_unstable();
+        cells
+    }
+
+    const GLIDER: &str =
+        "[M2] (golly 4.0)\n#R B3/S23\n#G 12\n$$$$$......*$.......*$.....***$\n4 1 0 0 0\n";
+
+    #[test]
+    fn test_parse_glider() {
+        // the glider is in the bottom right corner of the north west leaf
+        let mut hashlife = parse(GLIDER).unwrap();
+
+        assert_eq!(12, hashlife.generation());
+        assert_eq!(
+            vec![(-3, -1), (-2, -3), (-2, -1), (-1, -2), (-1, -1)],
+            sorted(hashlife.live_cells())
+        );
+
+        hashlife.advance(4);
+        assert_eq!(
+            vec![(-2, 0), (-1, -2), (-1, 0), (0, -1), (0, 0)],
+            sorted(hashlife.live_cells())
+        );
+    }
+
+    #[test]
+    fn test_shared_nodes_stay_small() {
+        // every level repeats the one below four times, 2^30 by 2^30 cells of blocks
+        let mut macrocell = String::from("[M2]\n**$**$\n");
+        for level in 4..=30 {
+            let child = level - 3;
+            macrocell.push_str(&format!(
+                "{} {} {} {} {}\n",
+                level, child, child, child, child
+            ));
+        }
+        let hashlife = parse(&macrocell).unwrap();
+
+        assert_eq!(1 << 56, hashlife.population());
+        assert!(hashlife.get_cell(-(1 << 29), -(1 << 29)));
+        assert!(!hashlife.get_cell(-(1 << 29) + 2, -(1 << 29)));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            ParseError::new(1, 1, "missing the [M2] header"),
+            parse("#R B3/S23").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(2, 9, "a leaf is only 8 cells across"),
+            parse("[M2]\n.........*$").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(3, 5, "the node has not been defined yet"),
+            parse("[M2]\n*$\n4 1 2 0 0").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(3, 3, "the child is not one level down"),
+            parse("[M2]\n*$\n5 1 0 0 0").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(2, 3, "rules with more than 2 states are not supported"),
+            parse("[M2]\n1 2 0 0 0").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(2, 3, "rules with B0 are not supported"),
+            parse("[M2]\n#R B03/S23\n*$").unwrap_err()
+        );
+    }
+}
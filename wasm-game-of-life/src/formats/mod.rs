@@ -0,0 +1,135 @@
+pub mod base64;
+pub mod binary;
+pub mod compressed;
+pub mod fragment;
+#[cfg(feature = "wasm")]
+pub mod json;
+pub mod life105;
+pub mod life106;
+pub mod macrocell;
+pub mod plaintext;
+pub mod rle;
+pub mod save;
+pub mod svg;
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+use crate::Universe;
+
+// The most cells a pattern file can ask for. Each cell takes several bytes between its
+// bit, age, trail and color, so a header claiming billions of them would run the page out
+// of memory before a single cell was read.
+pub const MAX_CELLS: u64 = 1 << 24;
+
+// A pattern file in whichever text format it is in, for files that come without a name
+// to go by. Life 1.05 and 1.06 say so on their first line, RLE has its x = header line
+// after any # comments and everything else is read as plaintext.
+pub fn parse_any(text: &str) -> Result<Universe, ParseError> {
+    let text = text.trim_start();
+    if text.starts_with("#Life 1.06") {
+        return life106::parse(text);
+    }
+    if text.starts_with("#Life 1.05") {
+        return life105::parse(text);
+    }
+    let header = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'));
+    match header {
+        Some(line) if line.starts_with('x') && line.contains('=') => rle::parse(text),
+        _ => plaintext::parse(text),
+    }
+}
+
+// Line and column are 1 based so they match what a text editor shows
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodeError {
+    pub message: String,
+}
+
+impl DecodeError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "could not decode universe: {}", self.message)
+    }
+}
+
+impl Error for DecodeError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EncodeError {
+    pub message: String,
+}
+
+impl EncodeError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for EncodeError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "could not encode: {}", self.message)
+    }
+}
+
+impl Error for EncodeError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_any() {
+        let rle = "#N Glider\n#C https://conwaylife.com/wiki/Glider\nx = 3, y = 3, rule = B3/S23\nbob$2bo$3o!";
+        let plaintext = "!Name: Glider\n.O.\n..O\nOOO";
+        let life106 = "#Life 1.06\n1 0\n2 1\n0 2\n1 2\n2 2";
+        let life105 = "#Life 1.05\n#P 0 0\n.*.\n..*\n***";
+
+        for text in &[rle, plaintext, life106, life105] {
+            let universe = parse_any(text).unwrap();
+            assert_eq!(5, universe.population(), "{}", text);
+        }
+        assert!(parse_any("x = 3, y = oops").is_err());
+    }
+}
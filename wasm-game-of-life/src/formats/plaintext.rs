@@ -0,0 +1,115 @@
+// The LifeWiki plaintext format (.cells files), O is alive, . is dead and lines
+// starting with ! are comments
+// https://conwaylife.com/wiki/Plaintext
+use super::{ParseError, MAX_CELLS};
+use crate::Universe;
+
+pub fn parse(plaintext: &str) -> Result<Universe, ParseError> {
+    let rows: Vec<(usize, &str)> = plaintext
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim_end()))
+        .filter(|(_, line)| !line.starts_with('!'))
+        .collect();
+
+    let width = rows
+        .iter()
+        .map(|(_, line)| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    // one long line and many short ones make a board far bigger than the text
+    if width as u64 * rows.len() as u64 > MAX_CELLS {
+        return Err(ParseError::new(1, 1, "the pattern is too large"));
+    }
+    let mut universe = Universe::new_with_dimensions(width as u32, rows.len() as u32);
+    for line in plaintext.lines().filter(|line| line.starts_with('!')) {
+        universe.metadata.read_plaintext_line(line);
+    }
+
+    for (row, (line_number, line)) in rows.iter().enumerate() {
+        for (column, character) in line.chars().enumerate() {
+            match character {
+                'O' => {
+                    let index = universe.get_index(row as u32, column as u32);
+                    universe.cells.set(index, true);
+                }
+                '.' => {}
+                other => {
+                    return Err(ParseError::new(
+                        *line_number,
+                        column + 1,
+                        format!("unexpected character '{}'", other),
+                    ))
+                }
+            }
+        }
+    }
+
+    Ok(universe)
+}
+
+pub fn write(universe: &Universe) -> String {
+    let mut plaintext = String::new();
+    universe.metadata.write_plaintext(&mut plaintext);
+
+    for row in 0..universe.height {
+        for column in 0..universe.width {
+            if universe.cells.get(universe.get_index(row, column)) {
+                plaintext.push('O');
+            } else {
+                plaintext.push('.');
+            }
+        }
+        plaintext.push('\n');
+    }
+
+    plaintext
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Pattern;
+
+    #[test]
+    fn test_parse() {
+        let plaintext = "!Name: Glider\n!\n.O\n..O\nOOO\n";
+        let universe = parse(plaintext).unwrap();
+
+        assert_eq!(3, universe.width);
+        assert_eq!(3, universe.height);
+        assert_eq!("◻◼◻\n◻◻◼\n◼◼◼\n", universe.render());
+        assert_eq!("Glider", universe.metadata().title());
+        assert!(write(&universe).starts_with("!Name: Glider\n.O.\n"));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            ParseError::new(3, 2, "unexpected character 'x'"),
+            parse("!comment\nOO\nOx\n").unwrap_err()
+        );
+
+        let wide_and_tall = format!("{}\n{}", ".".repeat(5000), "\n".repeat(4000));
+        assert_eq!(
+            ParseError::new(1, 1, "the pattern is too large"),
+            parse(&wide_and_tall).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_write() {
+        let mut universe = Universe::new_with_dimensions(4, 2);
+        universe.insert_pattern(Pattern::Toad, 0, 0);
+
+        assert_eq!(".OOO\nOOO.\n", write(&universe));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut universe = Universe::new(15);
+        universe.insert_pattern(Pattern::Pulsar, 1, 1);
+
+        assert_eq!(universe.cells(), parse(&write(&universe)).unwrap().cells());
+    }
+}
@@ -0,0 +1,353 @@
+// Run Length Encoded patterns as used by Golly and the LifeWiki
+// https://conwaylife.com/wiki/Run_Length_Encoded
+use super::{ParseError, MAX_CELLS};
+use crate::{PatternMetadata, Universe, UniverseError};
+
+const MAX_LINE_LENGTH: usize = 70;
+
+pub fn parse(rle: &str) -> Result<Universe, ParseError> {
+    let mut metadata = PatternMetadata::default();
+    for line in rle.lines().map(str::trim) {
+        metadata.read_rle_line(line);
+    }
+    let mut lines = rle
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+    let (header_line, header) = lines
+        .next()
+        .ok_or_else(|| ParseError::new(1, 1, "missing the x = .., y = .. header"))?;
+    let (width, height, rule) = parse_header(header_line, header)?;
+    if u64::from(width) * u64::from(height) > MAX_CELLS {
+        return Err(ParseError::new(header_line, 1, "the pattern is too large"));
+    }
+    let mut universe = Universe::new_with_dimensions(width, height);
+    // set_rule tells Larger than Life and isotropic rules apart from B/S ones
+    if let Some((rule, rule_column)) = rule {
+        universe.set_rule(rule).map_err(|error| match error {
+            UniverseError::InvalidRule { error, .. } => {
+                ParseError::new(header_line, rule_column + error.column - 1, error.message)
+            }
+            error => ParseError::new(header_line, rule_column, error.to_string()),
+        })?;
+    }
+    universe.metadata = metadata;
+
+    let mut row: u32 = 0;
+    let mut column: u32 = 0;
+    let mut run_count: Option<u32> = None;
+
+    for (line_number, line) in lines {
+        for (index, character) in line.chars().enumerate() {
+            let column_number = index + 1;
+            match character {
+                '0'..='9' => {
+                    let digit = character.to_digit(10).unwrap();
+                    run_count = Some(
+                        run_count
+                            .unwrap_or(0)
+                            .saturating_mul(10)
+                            .saturating_add(digit),
+                    );
+                }
+                '$' => {
+                    row = row.saturating_add(run_count.take().unwrap_or(1));
+                    column = 0;
+                }
+                '!' => return Ok(universe),
+                character if character.is_whitespace() => {}
+                'b' | '.' | 'o' | 'A'..='X' => {
+                    let count = run_count.take().unwrap_or(1);
+                    if row >= height || column.saturating_add(count) > width {
+                        return Err(ParseError::new(
+                            line_number,
+                            column_number,
+                            "pattern is larger than the size in the header",
+                        ));
+                    }
+                    if character != 'b' && character != '.' {
+                        for offset in 0..count {
+                            let index = universe.get_index(row, column + offset);
+                            universe.cells.set(index, true);
+                        }
+                    }
+                    column += count;
+                }
+                other => {
+                    return Err(ParseError::new(
+                        line_number,
+                        column_number,
+                        format!("unexpected character '{}'", other),
+                    ))
+                }
+            }
+        }
+    }
+
+    Ok(universe)
+}
+
+// The rule is everything after "rule =", Larger than Life rules have commas of their own.
+// It comes back with the column it starts at.
+type Header<'a> = (u32, u32, Option<(&'a str, usize)>);
+
+fn parse_header(line_number: usize, header: &str) -> Result<Header<'_>, ParseError> {
+    let mut width = None;
+    let mut height = None;
+    let mut rule = None;
+
+    let mut part_start = 0;
+    for part in header.split(',') {
+        let column_number = part_start + 1;
+        let mut key_value = part.splitn(2, '=');
+        let key = key_value.next().unwrap_or("").trim();
+        let value = key_value.next().map(str::trim);
+        let parse_size = |value: Option<&str>| {
+            value
+                .and_then(|value| value.parse::<u32>().ok())
+                .ok_or_else(|| ParseError::new(line_number, column_number, "invalid size"))
+        };
+
+        match key {
+            "x" => width = Some(parse_size(value)?),
+            "y" => height = Some(parse_size(value)?),
+            "rule" => {
+                let rest = &header[part_start..];
+                let value_start =
+                    part_start + rest.find('=').map_or(rest.len(), |equals| equals + 1);
+                let value = header[value_start..].trim_start();
+                let value_column = header.len() - value.len() + 1;
+                rule = Some((value.trim_end(), value_column));
+                break;
+            }
+            _ => {}
+        }
+        part_start += part.len() + 1;
+    }
+
+    match (width, height) {
+        (Some(width), Some(height)) => Ok((width, height, rule)),
+        _ => Err(ParseError::new(
+            line_number,
+            1,
+            "missing the x = .., y = .. header",
+        )),
+    }
+}
+
+pub fn write(universe: &Universe) -> String {
+    let mut runs: Vec<String> = vec![];
+    let mut pending_rows = 0;
+
+    for row in 0..universe.height {
+        let mut row_runs: Vec<(u32, char)> = vec![];
+        for column in 0..universe.width {
+            let tag = if universe.cells.get(universe.get_index(row, column)) {
+                'o'
+            } else {
+                'b'
+            };
+            match row_runs.last_mut() {
+                Some((count, last_tag)) if *last_tag == tag => *count += 1,
+                _ => row_runs.push((1, tag)),
+            }
+        }
+        if let Some((_, 'b')) = row_runs.last() {
+            row_runs.pop();
+        }
+
+        if row_runs.is_empty() {
+            pending_rows += 1;
+            continue;
+        }
+        // the first row with live cells doesn't end a previous row
+        let rows_to_skip = if runs.is_empty() {
+            pending_rows
+        } else {
+            pending_rows + 1
+        };
+        if rows_to_skip > 0 {
+            runs.push(encode_run(rows_to_skip, '$'));
+        }
+        pending_rows = 0;
+        runs.extend(
+            row_runs
+                .into_iter()
+                .map(|(count, tag)| encode_run(count, tag)),
+        );
+    }
+    runs.push("!".to_owned());
+
+    let mut rle = String::new();
+    universe.metadata.write_rle(&mut rle);
+    rle.push_str(&format!(
+        "x = {}, y = {}, rule = {}\n",
+        universe.width,
+        universe.height,
+        universe.rule()
+    ));
+    let mut line_length = 0;
+    for run in runs {
+        if line_length + run.len() > MAX_LINE_LENGTH {
+            rle.push('\n');
+            line_length = 0;
+        }
+        line_length += run.len();
+        rle.push_str(&run);
+    }
+    rle.push('\n');
+
+    rle
+}
+
+fn encode_run(count: u32, tag: char) -> String {
+    if count == 1 {
+        tag.to_string()
+    } else {
+        format!("{}{}", count, tag)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Pattern;
+
+    #[test]
+    fn test_parse_glider() {
+        let rle = "#N Glider\n#C a comment\nx = 3, y = 3, rule = B3/S23\nbob$2bo$3o!\n";
+        let universe = parse(rle).unwrap();
+
+        assert_eq!("◻◼◻\n◻◻◼\n◼◼◼\n", universe.render());
+    }
+
+    #[test]
+    fn test_metadata_round_trip() {
+        let rle =
+            "#N Glider\n#O Richard K. Guy\n#C a comment\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n";
+        let universe = parse(rle).unwrap();
+
+        assert_eq!(
+            Some("Richard K. Guy".to_string()),
+            universe.metadata().author()
+        );
+        assert_eq!("a comment", universe.metadata().description());
+        assert_eq!(rle, write(&universe));
+    }
+
+    #[test]
+    fn test_parse_multiple_lines_and_row_runs() {
+        let rle = "x = 4, y = 4\no2$\n3bo!";
+        let universe = parse(rle).unwrap();
+
+        assert_eq!("◼◻◻◻\n◻◻◻◻\n◻◻◻◼\n◻◻◻◻\n", universe.render());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            ParseError::new(1, 1, "missing the x = .., y = .. header"),
+            parse("bo$2bo$3o!").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(2, 3, "unexpected character 'z'"),
+            parse("x = 3, y = 3\nbozo!").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(2, 2, "pattern is larger than the size in the header"),
+            parse("x = 3, y = 1\n4o!").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(1, 1, "invalid size"),
+            parse("x = three, y = 1\no!").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(1, 1, "the pattern is too large"),
+            parse("x = 7033333, y = 250\no!").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(2, 23, "pattern is larger than the size in the header"),
+            parse("x = 3, y = 3\n4294967295$4294967295$o!").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_rule() {
+        let universe = parse("x = 1, y = 1, rule = B36/S23\no!").unwrap();
+        assert_eq!("B36/S23", universe.rule());
+
+        assert_eq!(
+            ParseError::new(1, 27, "'9' is not a neighbor count between 0 and 8"),
+            parse("x = 1, y = 1, rule = B3/S29\no!").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_isotropic_rule_round_trip() {
+        let universe = parse("x = 3, y = 2, rule = B2-a/S12\nobo$bo!").unwrap();
+        assert_eq!("B2-a/S12", universe.rule());
+        assert_eq!(
+            ParseError::new(1, 24, "'z' is not a shape of 2 neighbors"),
+            parse("x = 1, y = 1, rule = B2z/S12\no!").unwrap_err()
+        );
+
+        let copy = parse(&write(&universe)).unwrap();
+        assert_eq!("x = 3, y = 2, rule = B2-a/S12\nobo$bo!\n", write(&universe));
+        assert_eq!("B2-a/S12", copy.rule());
+        assert_eq!(universe.cells(), copy.cells());
+    }
+
+    #[test]
+    fn test_ltl_rule_round_trip() {
+        let rle = "x = 4, y = 1, rule = R2,C0,M1,S5..9,B4..6,NM\n4o!\n";
+        let universe = parse(rle).unwrap();
+        assert_eq!("R2,C0,M1,S5..9,B4..6,NM", universe.rule());
+        assert_eq!(rle, write(&universe));
+        assert_eq!(4, parse(&write(&universe)).unwrap().population());
+    }
+
+    #[test]
+    fn test_write_rule() {
+        let mut universe = Universe::new(1);
+        universe.set_rule("B36/S23").unwrap();
+
+        assert_eq!("x = 1, y = 1, rule = B36/S23\n!\n", write(&universe));
+    }
+
+    #[test]
+    fn test_write() {
+        let mut universe = Universe::new_with_dimensions(5, 5);
+        universe.insert_pattern(Pattern::Glider, 0, 0);
+
+        assert_eq!(
+            "x = 5, y = 5, rule = B3/S23\nbo$2bo$3o!\n",
+            write(&universe)
+        );
+    }
+
+    #[test]
+    fn test_write_skips_empty_rows_and_wraps_long_lines() {
+        let mut universe = Universe::new_with_dimensions(3, 4);
+        universe.set_cell(0, 0, crate::Cell::Alive);
+        universe.set_cell(3, 2, crate::Cell::Alive);
+        assert_eq!("x = 3, y = 4, rule = B3/S23\no3$2bo!\n", write(&universe));
+
+        let mut universe = Universe::new_with_dimensions(80, 1);
+        for column in (0..80).step_by(2) {
+            universe.set_cell(0, column, crate::Cell::Alive);
+        }
+        let rle = write(&universe);
+        assert!(rle.lines().all(|line| line.len() <= MAX_LINE_LENGTH));
+        assert_eq!(universe.cells(), parse(&rle).unwrap().cells());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut universe = Universe::new_with_dimensions(40, 12);
+        universe.insert_pattern(Pattern::GosperGliderGun, 1, 2);
+
+        assert_eq!(universe.cells(), parse(&write(&universe)).unwrap().cells());
+    }
+}
@@ -0,0 +1,280 @@
+// Everything needed to pick a simulation up where it was left, for save slots that are
+// kept across visits to the page. This is version 2 of the format in binary.rs, the
+// header is the same and everything after it is little endian:
+//
+// | bytes | contents                                         |
+// |-------|--------------------------------------------------|
+// | 4     | magic "GOL" followed by version 2                |
+// | 4     | width                                            |
+// | 4     | height                                           |
+// | 1     | topology, numbered as in binary.rs               |
+// | 4     | generation                                       |
+// | 1     | length of the rule                               |
+// | n     | the rule as text, such as B2/S34/C3H             |
+// | 1     | engine                                           |
+// | 1     | color variant                                    |
+// | 1     | neighborhood                                     |
+// | 1     | neighborhood radius                              |
+// | 4 * n | cells packed 32 to a word                        |
+// | n     | fading states, only for rules with over 2 states |
+// | n     | colors, only for Immigration and QuadLife        |
+//
+// Version 1 and 3 blobs from serialize() still load, they come in with the default
+// settings.
+use super::{binary, compressed, DecodeError};
+use crate::bits::CellBits;
+use crate::{ColorVariant, Engine, Neighborhood, Universe};
+
+const MAGIC: &[u8; 3] = b"GOL";
+const VERSION: u8 = 2;
+
+pub fn write(universe: &Universe) -> Vec<u8> {
+    let rule = universe.rule();
+    let mut bytes = Vec::new();
+
+    bytes.extend_from_slice(MAGIC);
+    bytes.push(VERSION);
+    bytes.extend_from_slice(&universe.width.to_le_bytes());
+    bytes.extend_from_slice(&universe.height.to_le_bytes());
+    bytes.push(universe.edge_topology as u8);
+    bytes.extend_from_slice(&universe.generation.to_le_bytes());
+    bytes.push(rule.len() as u8);
+    bytes.extend_from_slice(rule.as_bytes());
+    bytes.push(universe.engine() as u8);
+    bytes.push(universe.color_variant as u8);
+    bytes.push(universe.neighborhood as u8);
+    bytes.push(universe.neighborhood_radius as u8);
+    for word in universe.cells.words() {
+        bytes.extend_from_slice(&word.to_le_bytes());
+    }
+    if universe.rule.states() > 2 {
+        bytes.extend_from_slice(&universe.fading);
+    }
+    if universe.color_variant != ColorVariant::Monochrome {
+        bytes.extend_from_slice(&universe.colors);
+    }
+
+    bytes
+}
+
+// Reads version 2 saves and anything older
+// Compressed saves from compressed.rs load too
+pub fn read(bytes: &[u8]) -> Result<Universe, DecodeError> {
+    let bytes = &compressed::decompress(bytes)?;
+    if bytes.len() < 4 || &bytes[0..3] != MAGIC {
+        return Err(DecodeError::new("not a serialized universe"));
+    }
+    match bytes[3] {
+        1 | binary::VERSION => binary::read(bytes),
+        2 => read_version_2(bytes),
+        version => Err(DecodeError::new(format!("unsupported version {}", version))),
+    }
+}
+
+fn read_version_2(bytes: &[u8]) -> Result<Universe, DecodeError> {
+    let mut reader = Reader { bytes, offset: 4 };
+    let width = reader.u32()?;
+    let height = reader.u32()?;
+    let topology = binary::read_topology(reader.u8()?)?;
+    let generation = reader.u32()?;
+    let rule_length = reader.u8()? as usize;
+    let rule = std::str::from_utf8(reader.take(rule_length)?)
+        .map_err(|_| DecodeError::new("the rule is not text"))?;
+    let engine = match reader.u8()? {
+        0 => Engine::Naive,
+        1 => Engine::HashLife,
+        2 => Engine::Packed,
+        3 => Engine::Simd,
+        4 => Engine::Parallel,
+        other => return Err(DecodeError::new(format!("unknown engine {}", other))),
+    };
+    let color_variant = match reader.u8()? {
+        0 => ColorVariant::Monochrome,
+        1 => ColorVariant::Immigration,
+        2 => ColorVariant::QuadLife,
+        other => return Err(DecodeError::new(format!("unknown color variant {}", other))),
+    };
+    let neighborhood = match reader.u8()? {
+        0 => Neighborhood::Moore,
+        1 => Neighborhood::VonNeumann,
+        other => return Err(DecodeError::new(format!("unknown neighborhood {}", other))),
+    };
+    let radius = u32::from(reader.u8()?);
+
+    // the rule says whether fading states follow the cells, a board with no cells is
+    // enough to find out before the real one is made
+    let mut empty = Universe::new_with_dimensions(0, 0);
+    empty
+        .set_rule(rule)
+        .map_err(|error| DecodeError::new(error.to_string()))?;
+    let cell_count = u64::from(width) * u64::from(height);
+    let fading_length = if empty.rule.states() > 2 {
+        cell_count
+    } else {
+        0
+    };
+    let colors_length = if color_variant != ColorVariant::Monochrome {
+        cell_count
+    } else {
+        0
+    };
+    let payload_length = cell_count.div_ceil(32) * 4 + fading_length + colors_length;
+    if cell_count > u64::from(u32::MAX) || (bytes.len() - reader.offset) as u64 != payload_length {
+        return Err(DecodeError::new("cell data does not match the dimensions"));
+    }
+    let cell_count = cell_count as usize;
+    let mut universe = Universe::new_with_dimensions(width, height).with_engine(engine);
+    universe
+        .set_rule(rule)
+        .map_err(|error| DecodeError::new(error.to_string()))?;
+    universe.edge_topology = topology;
+    universe.generation = generation;
+    universe.set_neighborhood(neighborhood, radius);
+    universe.color_variant = color_variant;
+
+    let mut cells = CellBits::new(cell_count);
+    for word in 0..cell_count.div_ceil(32) {
+        let bits = reader.u32()?;
+        let count = (cell_count - word * 32).min(32);
+        cells.write_bits(word * 32, count, bits);
+    }
+    universe.cells = cells;
+    if universe.rule.states() > 2 {
+        universe.fading = reader.take(cell_count)?.to_vec();
+    }
+    if color_variant != ColorVariant::Monochrome {
+        universe.colors = reader.take(cell_count)?.to_vec();
+    }
+
+    Ok(universe)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], DecodeError> {
+        let bytes = self
+            .bytes
+            .get(self.offset..self.offset + count)
+            .ok_or_else(|| DecodeError::new("cell data does not match the dimensions"))?;
+        self.offset += count;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, DecodeError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{BoundaryMode, Topology};
+
+    #[test]
+    fn test_round_trip() {
+        let mut universe = Universe::new_with_dimensions(45, 7).with_engine(Engine::Packed);
+        universe.set_edge_topology(Topology::KleinBottle);
+        universe.set_rule("B2/S345/C4").unwrap();
+        universe.set_color_variant(ColorVariant::QuadLife);
+        universe.set_neighborhood(Neighborhood::VonNeumann, 2);
+        universe.randomize_seeded(3, 0.5);
+        universe.tick_n(4);
+
+        let mut copy = read(&write(&universe)).unwrap();
+
+        assert_eq!(45, copy.width());
+        assert_eq!(7, copy.height());
+        assert_eq!(BoundaryMode::Toroidal, copy.boundary_mode());
+        assert_eq!(Topology::KleinBottle, copy.edge_topology());
+        assert_eq!("B2/S345/C4", copy.rule());
+        assert_eq!(4, copy.generation());
+        assert_eq!(Engine::Packed, copy.engine());
+        assert_eq!(ColorVariant::QuadLife, copy.color_variant());
+        assert_eq!(Neighborhood::VonNeumann, copy.neighborhood());
+        assert_eq!(2, copy.neighborhood_radius());
+        assert_eq!(universe.cells(), copy.cells());
+        assert_eq!(universe.fading, copy.fading);
+        assert_eq!(universe.colors, copy.colors);
+
+        // the copy carries on the same way
+        universe.tick_n(3);
+        copy.tick_n(3);
+        assert_eq!(universe.cells(), copy.cells());
+        assert_eq!(universe.colors, copy.colors);
+    }
+
+    #[test]
+    fn test_isotropic_rule_round_trip() {
+        let mut universe = Universe::new_with_dimensions(12, 12);
+        universe.set_rule("B2-a/S12").unwrap();
+        universe.randomize_seeded(5, 0.3);
+
+        let mut copy = read(&write(&universe)).unwrap();
+        assert_eq!("B2-a/S12", copy.rule());
+
+        universe.tick_n(3);
+        copy.tick_n(3);
+        assert_eq!(universe.cells(), copy.cells());
+    }
+
+    #[test]
+    fn test_ltl_rule_round_trip() {
+        let mut universe = Universe::new_with_dimensions(20, 20);
+        universe.set_rule("R2,C0,M1,S5..9,B4..6,NM").unwrap();
+        universe.randomize_seeded(6, 0.4);
+
+        let mut copy = read(&write(&universe)).unwrap();
+        assert_eq!("R2,C0,M1,S5..9,B4..6,NM", copy.rule());
+
+        universe.tick_n(3);
+        copy.tick_n(3);
+        assert_eq!(universe.cells(), copy.cells());
+    }
+
+    #[test]
+    fn test_reads_version_1() {
+        let mut universe = Universe::new_with_dimensions(10, 6);
+        universe.set_rule("B36/S23").unwrap();
+        universe.randomize_seeded(8, 0.4);
+
+        let copy = read(&binary::write(&universe).unwrap()).unwrap();
+
+        assert_eq!("B36/S23", copy.rule());
+        assert_eq!(universe.cells(), copy.cells());
+    }
+
+    #[test]
+    fn test_read_errors() {
+        let bytes = write(&Universe::new(4));
+
+        assert_eq!(
+            DecodeError::new("cell data does not match the dimensions"),
+            read(&bytes[..bytes.len() - 1]).unwrap_err()
+        );
+        // a header asking for a board far bigger than the cells that follow it
+        let mut bytes = write(&Universe::new_with_dimensions(0, 0));
+        bytes[4..12].copy_from_slice(&[0xff, 0xff, 0, 0, 0xff, 0xff, 0, 0]);
+        assert_eq!(28, bytes.len());
+        assert_eq!(
+            DecodeError::new("cell data does not match the dimensions"),
+            read(&bytes).unwrap_err()
+        );
+        assert_eq!(
+            DecodeError::new("unsupported version 4"),
+            read(b"GOL\x04").unwrap_err()
+        );
+        assert_eq!(
+            DecodeError::new("not a serialized universe"),
+            read(b"PNG").unwrap_err()
+        );
+    }
+}
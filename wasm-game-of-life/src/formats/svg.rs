@@ -0,0 +1,78 @@
+// A vector snapshot of the board, a rectangle of the dead color behind one rectangle per
+// horizontal run of live cells. crispEdges keeps neighbouring runs from showing seams
+// when the image is scaled.
+use std::fmt::Write;
+
+use crate::render::live_runs;
+use crate::Universe;
+
+pub fn write(universe: &Universe, cell_size: u32, alive_color: &str, dead_color: &str) -> String {
+    let width = universe.width * cell_size;
+    let height = universe.height * cell_size;
+    let mut svg = String::new();
+
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}" shape-rendering="crispEdges">"#,
+        width, height
+    );
+    let _ = writeln!(
+        svg,
+        r#"<rect width="{}" height="{}" fill="{}"/>"#,
+        width,
+        height,
+        escape(dead_color)
+    );
+    let _ = writeln!(svg, r#"<g fill="{}">"#, escape(alive_color));
+    for (row, column, length) in live_runs(universe) {
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{}" y="{}" width="{}" height="{}"/>"#,
+            column * cell_size,
+            row * cell_size,
+            length * cell_size,
+            cell_size
+        );
+    }
+    svg.push_str("</g>\n</svg>\n");
+
+    svg
+}
+
+// Colors go inside attributes, so anything that could end one is escaped
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Pattern;
+
+    #[test]
+    fn test_write() {
+        let mut universe = Universe::new_with_dimensions(4, 3);
+        universe.insert_pattern(Pattern::Glider, 0, 0);
+
+        let expected = r##"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="30" viewBox="0 0 40 30" shape-rendering="crispEdges">
+<rect width="40" height="30" fill="#ffffff"/>
+<g fill="#000000">
+<rect x="10" y="0" width="10" height="10"/>
+<rect x="20" y="10" width="10" height="10"/>
+<rect x="0" y="20" width="30" height="10"/>
+</g>
+</svg>
+"##;
+        assert_eq!(expected, write(&universe, 10, "#000000", "#ffffff"));
+    }
+
+    #[test]
+    fn test_colors_are_escaped() {
+        let svg = write(&Universe::new(1), 1, "\"/><script>", "red");
+
+        assert!(svg.contains(r#"<g fill="&quot;/>&lt;script>">"#));
+    }
+}
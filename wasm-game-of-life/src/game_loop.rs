@@ -0,0 +1,585 @@
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::{CanvasRenderer, Cell, Clipboard, KeyAction, PasteMode, Universe, Viewport};
+
+type FrameSlot = RefCell<Option<Closure<dyn FnMut(f64)>>>;
+type FrameClosure = Rc<FrameSlot>;
+
+// The range set_speed() accepts, in ticks per second
+pub const MIN_SPEED: f64 = 0.5;
+pub const MAX_SPEED: f64 = 1000.0;
+// A frame that comes after a longer gap than this, like when the tab was in the
+// background, only catches up this much instead of running thousands of ticks at once
+const MAX_FRAME_TIME: f64 = 250.0;
+
+// Turns the time between animation frames into a number of ticks at a steady speed,
+// whatever the refresh rate of the display. The part of a tick that's left over is
+// carried to the next frame, so at 0.5 ticks a second most frames don't tick at all.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct TickClock {
+    ticks_per_second: f64,
+    last_frame: Option<f64>,
+    owed: f64,
+}
+
+impl TickClock {
+    fn new(ticks_per_second: f64) -> Self {
+        Self {
+            ticks_per_second: ticks_per_second.clamp(MIN_SPEED, MAX_SPEED),
+            last_frame: None,
+            owed: 0.0,
+        }
+    }
+
+    // Forgets the last frame, so the time spent paused isn't made up when resuming
+    fn reset(&mut self) {
+        self.last_frame = None;
+        self.owed = 0.0;
+    }
+
+    // How many ticks are due for a frame at the timestamp requestAnimationFrame gives,
+    // which is in milliseconds
+    fn ticks(&mut self, timestamp: f64) -> u32 {
+        let elapsed = match self.last_frame {
+            Some(last_frame) => (timestamp - last_frame).clamp(0.0, MAX_FRAME_TIME),
+            None => 0.0,
+        };
+        self.last_frame = Some(timestamp);
+        self.owed += elapsed * self.ticks_per_second / 1000.0;
+        let ticks = self.owed.floor();
+        self.owed -= ticks;
+        ticks as u32
+    }
+}
+
+struct LoopState {
+    universe: Universe,
+    ticks_per_frame: u32,
+    // when set the loop runs at a speed instead of ticks_per_frame
+    clock: Option<TickClock>,
+    running: bool,
+    frame_request: Option<i32>,
+    renderer: Option<CanvasRenderer>,
+    viewport: Option<Viewport>,
+    #[cfg(feature = "audio")]
+    sonifier: Option<crate::Sonifier>,
+    on_frame: Option<js_sys::Function>,
+}
+
+impl LoopState {
+    fn render(&self) {
+        if let Some(renderer) = &self.renderer {
+            match &self.viewport {
+                Some(viewport) => renderer.render_viewport(&self.universe, viewport),
+                None => renderer.render_to_canvas(&self.universe),
+            }
+        }
+    }
+
+    // Multiplies the speed, or the ticks per frame when there is no speed, which never
+    // go below 1
+    fn change_speed(&mut self, factor: f64) {
+        match &mut self.clock {
+            Some(clock) => {
+                clock.ticks_per_second =
+                    (clock.ticks_per_second * factor).clamp(MIN_SPEED, MAX_SPEED)
+            }
+            None => {
+                let ticks = (f64::from(self.ticks_per_frame) * factor).round();
+                self.ticks_per_frame = ticks.clamp(1.0, f64::from(u32::MAX)) as u32;
+            }
+        }
+    }
+
+    // The (row, column) under a point on the canvas, in canvas pixels. Without a viewport
+    // the board is drawn from the top left corner at the renderer's cell size.
+    fn cell_at(&self, x: f64, y: f64) -> Option<(i32, i32)> {
+        if let Some(viewport) = &self.viewport {
+            let cell = viewport.screen_to_cell(x, y);
+            return Some((cell[0], cell[1]));
+        }
+        let cell_size = f64::from(self.renderer.as_ref()?.cell_size().max(1));
+        Some((
+            (y / cell_size).floor() as i32,
+            (x / cell_size).floor() as i32,
+        ))
+    }
+}
+
+// Ticks the universe from requestAnimationFrame so JavaScript doesn't need its own loop
+#[wasm_bindgen]
+pub struct GameLoop {
+    state: Rc<RefCell<LoopState>>,
+    // The closure given to requestAnimationFrame needs a handle to itself to schedule
+    // the next frame, it's created on the first start() and dropped with the GameLoop
+    frame: FrameClosure,
+}
+
+#[wasm_bindgen]
+impl GameLoop {
+    pub fn new(universe: Universe) -> Self {
+        Self {
+            state: Rc::new(RefCell::new(LoopState {
+                universe,
+                ticks_per_frame: 1,
+                clock: None,
+                running: false,
+                frame_request: None,
+                renderer: None,
+                viewport: None,
+                #[cfg(feature = "audio")]
+                sonifier: None,
+                on_frame: None,
+            })),
+            frame: Rc::new(RefCell::new(None)),
+        }
+    }
+
+    pub fn start(&mut self) -> Result<(), JsValue> {
+        start(&self.state, &self.frame)
+    }
+
+    pub fn stop(&mut self) {
+        stop(&self.state);
+    }
+
+    pub fn pause(&mut self) {
+        self.stop();
+    }
+
+    pub fn resume(&mut self) -> Result<(), JsValue> {
+        self.start()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.state.borrow().running
+    }
+
+    pub fn ticks_per_frame(&self) -> u32 {
+        self.state.borrow().ticks_per_frame
+    }
+
+    // Goes back to a fixed number of ticks every frame if a speed was set
+    pub fn set_ticks_per_frame(&mut self, ticks_per_frame: u32) {
+        let mut state = self.state.borrow_mut();
+        state.ticks_per_frame = ticks_per_frame;
+        state.clock = None;
+    }
+
+    // Ticks per second, from MIN_SPEED to MAX_SPEED, or undefined when the loop is
+    // ticking a fixed number of times a frame
+    pub fn speed(&self) -> Option<f64> {
+        self.state
+            .borrow()
+            .clock
+            .map(|clock| clock.ticks_per_second)
+    }
+
+    // Runs at ticks_per_second however often the browser asks for a frame, clamped to
+    // MIN_SPEED and MAX_SPEED
+    pub fn set_speed(&mut self, ticks_per_second: f64) {
+        self.state.borrow_mut().clock = Some(TickClock::new(ticks_per_second));
+    }
+
+    // With a renderer the loop draws every frame itself, and redraws after edits
+    pub fn set_renderer(&mut self, renderer: Option<CanvasRenderer>) {
+        let mut state = self.state.borrow_mut();
+        state.renderer = renderer;
+        state.render();
+    }
+
+    // Draw and edit through a viewport instead of the whole board at the renderer's
+    // cell size, None goes back to that
+    pub fn set_viewport(&mut self, viewport: Option<Viewport>) {
+        let mut state = self.state.borrow_mut();
+        state.viewport = viewport;
+        state.render();
+    }
+
+    pub fn viewport(&self) -> Option<Viewport> {
+        self.state.borrow().viewport
+    }
+
+    // Plays every frame that ticked, the last of the frame's ticks when there are several
+    #[cfg(feature = "audio")]
+    pub fn set_sonifier(&mut self, sonifier: Option<crate::Sonifier>) {
+        self.state.borrow_mut().sonifier = sonifier;
+    }
+
+    // Called with no arguments after every frame's ticks, this is where the board gets drawn
+    pub fn set_on_frame(&mut self, on_frame: Option<js_sys::Function>) {
+        self.state.borrow_mut().on_frame = on_frame;
+    }
+
+    // The universe is owned by the loop, these give JavaScript what it needs to draw and edit it
+    pub fn width(&self) -> u32 {
+        self.state.borrow().universe.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.state.borrow().universe.height()
+    }
+
+    pub fn bits_ptr(&self) -> *const u32 {
+        self.state.borrow().universe.bits_ptr()
+    }
+
+    pub fn bits_len(&self) -> usize {
+        self.state.borrow().universe.bits_len()
+    }
+
+    pub fn changed_cells_ptr(&self) -> *const u32 {
+        self.state.borrow().universe.changed_cells_ptr()
+    }
+
+    pub fn changed_cells_len(&self) -> usize {
+        self.state.borrow().universe.changed_cells_len()
+    }
+
+    pub fn population_history_ptr(&self) -> *const u32 {
+        self.state.borrow().universe.population_history_ptr()
+    }
+
+    pub fn population_history_len(&self) -> usize {
+        self.state.borrow().universe.population_history_len()
+    }
+
+    pub fn save_to_local_storage(&self, key: &str) -> Result<(), JsValue> {
+        self.state.borrow().universe.save_to_local_storage(key)
+    }
+
+    // Swaps in the universe saved under key, returns false and keeps the current one when
+    // nothing was saved there
+    pub fn load_from_local_storage(&mut self, key: &str) -> Result<bool, JsValue> {
+        match Universe::load_from_local_storage(key)? {
+            Some(universe) => {
+                let mut state = self.state.borrow_mut();
+                state.universe = universe;
+                state.render();
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    // fetch_pattern() with the live cells added to the board with the pattern's top left
+    // corner at row, column. The promise resolves once they're drawn and rejects with the
+    // Error from fetch_pattern().
+    pub fn load_pattern_from_url(&self, url: String, row: u32, column: u32) -> js_sys::Promise {
+        let state = Rc::downgrade(&self.state);
+        wasm_bindgen_futures::future_to_promise(async move {
+            let pattern = crate::fetch_pattern(url).await?;
+            let clipboard = pattern.copy_region(0, 0, pattern.width(), pattern.height());
+            // the loop may have been dropped while the file was downloading
+            if let Some(state) = state.upgrade() {
+                let mut state = state.borrow_mut();
+                state.universe.paste(&clipboard, row, column, PasteMode::Or);
+                state.render();
+            }
+            Ok(JsValue::UNDEFINED)
+        })
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.state.borrow().universe.generation()
+    }
+
+    // Frame by frame debugging: both pause the loop, then step_forward() or step_back()
+    // the universe and draw the result. Turn on the universe's history with
+    // set_history_capacity() before starting for step_back() to have anything to go back
+    // to. The universe's event callback is told about the new generation either way.
+    pub fn step(&mut self) {
+        step(&self.state);
+    }
+
+    // Returns false when the history doesn't go back any further
+    pub fn step_back(&mut self) -> bool {
+        step_back(&self.state)
+    }
+
+    pub fn toggle_cell(&mut self, row: u32, column: u32) {
+        let mut state = self.state.borrow_mut();
+        state.universe.toggle_cell(row, column);
+        state.render();
+    }
+
+    pub fn randomize(&mut self) {
+        let mut state = self.state.borrow_mut();
+        state.universe.randomize();
+        state.render();
+    }
+
+    pub(crate) fn handle(&self) -> LoopHandle {
+        LoopHandle {
+            state: Rc::downgrade(&self.state),
+            frame: Rc::downgrade(&self.frame),
+        }
+    }
+}
+
+// A GameLoop as seen from an InputController, which keeps working for as long as the
+// loop is around and does nothing after that
+pub(crate) struct LoopHandle {
+    state: Weak<RefCell<LoopState>>,
+    frame: Weak<FrameSlot>,
+}
+
+impl LoopHandle {
+    // Everything but KeyAction::RotateBrush, the brush belongs to the InputController
+    pub(crate) fn perform(&self, action: KeyAction) -> Result<(), JsValue> {
+        let (state, frame) = match (self.state.upgrade(), self.frame.upgrade()) {
+            (Some(state), Some(frame)) => (state, frame),
+            _ => return Ok(()),
+        };
+        match action {
+            KeyAction::Pause => {
+                let running = state.borrow().running;
+                if running {
+                    stop(&state);
+                } else {
+                    start(&state, &frame)?;
+                }
+            }
+            KeyAction::Step => step(&state),
+            KeyAction::StepBack => {
+                step_back(&state);
+            }
+            KeyAction::Randomize | KeyAction::Clear => {
+                let mut state = state.borrow_mut();
+                if action == KeyAction::Randomize {
+                    state.universe.randomize();
+                } else {
+                    state.universe.clear();
+                }
+                state.render();
+            }
+            KeyAction::SpeedUp => state.borrow_mut().change_speed(2.0),
+            KeyAction::SlowDown => state.borrow_mut().change_speed(0.5),
+            KeyAction::RotateBrush => {}
+        }
+        Ok(())
+    }
+
+    pub(crate) fn paste(&self, clipboard: &Clipboard, cell: (i32, i32)) {
+        if let Some(state) = self.state.upgrade() {
+            let mut state = state.borrow_mut();
+            let universe = &state.universe;
+            if cell.0 < 0
+                || cell.1 < 0
+                || cell.0 as u32 >= universe.height()
+                || cell.1 as u32 >= universe.width()
+            {
+                return;
+            }
+            state
+                .universe
+                .paste(clipboard, cell.0 as u32, cell.1 as u32, PasteMode::Or);
+            state.render();
+        }
+    }
+
+    pub(crate) fn cell_at(&self, x: f64, y: f64) -> Option<(i32, i32)> {
+        self.state.upgrade()?.borrow().cell_at(x, y)
+    }
+
+    pub(crate) fn get_cell(&self, row: i32, column: i32) -> Option<Cell> {
+        let state = self.state.upgrade()?;
+        let state = state.borrow();
+        let universe = &state.universe;
+        if row < 0
+            || column < 0
+            || row as u32 >= universe.height()
+            || column as u32 >= universe.width()
+        {
+            return None;
+        }
+        Some(universe.get_cell(row as u32, column as u32))
+    }
+
+    pub(crate) fn draw_line(&self, start: (i32, i32), end: (i32, i32), cell: Cell) {
+        if let Some(state) = self.state.upgrade() {
+            let mut state = state.borrow_mut();
+            state
+                .universe
+                .draw_line(start.0, start.1, end.0, end.1, cell);
+            state.render();
+        }
+    }
+
+    // Does nothing without a viewport, the whole board has a fixed size on the canvas
+    pub(crate) fn zoom_at(&self, x: f64, y: f64, factor: f64) {
+        self.move_viewport(|viewport| viewport.zoom_at(x, y, factor));
+    }
+
+    pub(crate) fn pinch(&self, before: [(f64, f64); 2], after: [(f64, f64); 2]) {
+        self.move_viewport(|viewport| viewport.pinch(before, after));
+    }
+
+    fn move_viewport(&self, change: impl FnOnce(&mut Viewport)) {
+        if let Some(state) = self.state.upgrade() {
+            let mut state = state.borrow_mut();
+            if let Some(viewport) = &mut state.viewport {
+                change(viewport);
+                state.render();
+            }
+        }
+    }
+}
+
+impl GameLoop {
+    fn create_frame_closure(
+        state: Rc<RefCell<LoopState>>,
+        frame: FrameClosure,
+    ) -> Closure<dyn FnMut(f64)> {
+        Closure::wrap(Box::new(move |timestamp: f64| {
+            let on_frame = {
+                let mut state = state.borrow_mut();
+                if !state.running {
+                    return;
+                }
+                let ticks = match &mut state.clock {
+                    Some(clock) => clock.ticks(timestamp),
+                    None => state.ticks_per_frame,
+                };
+                if ticks > 0 {
+                    state.universe.tick_n(ticks);
+                    #[cfg(feature = "audio")]
+                    if let Some(sonifier) = &state.sonifier {
+                        let _ = sonifier.play(&state.universe);
+                    }
+                }
+                state.render();
+                state.on_frame.clone()
+            };
+            // the state isn't borrowed here so the callback is free to call back into the loop
+            if let Some(on_frame) = on_frame {
+                let _ = on_frame.call0(&JsValue::NULL);
+            }
+
+            if state.borrow().running {
+                let frame_request = request_animation_frame(&frame).ok();
+                state.borrow_mut().frame_request = frame_request;
+            }
+        }) as Box<dyn FnMut(f64)>)
+    }
+}
+
+impl Drop for GameLoop {
+    // The frame closure holds handles to itself and the state, taking it breaks that cycle
+    fn drop(&mut self) {
+        self.stop();
+        self.frame.borrow_mut().take();
+    }
+}
+
+fn step(state: &Rc<RefCell<LoopState>>) {
+    stop(state);
+    state.borrow_mut().universe.step_forward();
+    redraw(state);
+}
+
+fn step_back(state: &Rc<RefCell<LoopState>>) -> bool {
+    stop(state);
+    let stepped = state.borrow_mut().universe.step_back();
+    if stepped {
+        redraw(state);
+    }
+    stepped
+}
+
+// What a frame does after its ticks, for the steps taken while paused
+fn redraw(state: &Rc<RefCell<LoopState>>) {
+    let on_frame = {
+        let state = state.borrow();
+        state.render();
+        state.on_frame.clone()
+    };
+    if let Some(on_frame) = on_frame {
+        let _ = on_frame.call0(&JsValue::NULL);
+    }
+}
+
+fn start(state: &Rc<RefCell<LoopState>>, frame: &FrameClosure) -> Result<(), JsValue> {
+    if state.borrow().running {
+        return Ok(());
+    }
+    if frame.borrow().is_none() {
+        let closure = GameLoop::create_frame_closure(state.clone(), frame.clone());
+        *frame.borrow_mut() = Some(closure);
+    }
+
+    let frame_request = request_animation_frame(frame)?;
+    let mut state = state.borrow_mut();
+    if let Some(clock) = &mut state.clock {
+        clock.reset();
+    }
+    state.running = true;
+    state.frame_request = Some(frame_request);
+    Ok(())
+}
+
+fn stop(state: &Rc<RefCell<LoopState>>) {
+    let mut state = state.borrow_mut();
+    state.running = false;
+    if let Some(frame_request) = state.frame_request.take() {
+        if let Some(window) = web_sys::window() {
+            let _ = window.cancel_animation_frame(frame_request);
+        }
+    }
+}
+
+fn request_animation_frame(frame: &FrameClosure) -> Result<i32, JsValue> {
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("no global window"))?;
+    let frame = frame.borrow();
+    let closure = frame
+        .as_ref()
+        .ok_or_else(|| JsValue::from_str("the frame closure was dropped"))?;
+
+    window.request_animation_frame(closure.as_ref().unchecked_ref())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_tick_clock_carries_over_part_ticks() {
+        let mut clock = TickClock::new(0.5);
+        assert_eq!(0, clock.ticks(1000.0));
+        // 60 frames a second for four seconds is two ticks
+        let ticks: u32 = (1..=240)
+            .map(|frame| clock.ticks(1000.0 + f64::from(frame) * 1000.0 / 60.0))
+            .sum();
+        assert_eq!(2, ticks);
+    }
+
+    #[test]
+    fn test_tick_clock_is_independent_of_the_refresh_rate() {
+        for frames_per_second in &[30.0, 60.0, 144.0] {
+            let mut clock = TickClock::new(100.0);
+            let frames = (*frames_per_second * 2.0) as u32;
+            let ticks: u32 = (0..=frames)
+                .map(|frame| clock.ticks(f64::from(frame) * 1000.0 / frames_per_second))
+                .sum();
+            assert!((199..=200).contains(&ticks), "{} ticks", ticks);
+        }
+    }
+
+    #[test]
+    fn test_tick_clock_limits() {
+        assert_eq!(MIN_SPEED, TickClock::new(0.0).ticks_per_second);
+        assert_eq!(MAX_SPEED, TickClock::new(1e9).ticks_per_second);
+
+        let mut clock = TickClock::new(MAX_SPEED);
+        clock.ticks(0.0);
+        assert_eq!(250, clock.ticks(60_000.0));
+
+        clock.reset();
+        assert_eq!(0, clock.ticks(120_000.0));
+        assert_eq!(16, clock.ticks(120_016.0));
+    }
+}
@@ -0,0 +1,281 @@
+use rand::prelude::*;
+use rand_pcg::Pcg32;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+// Feed and kill rates known for patterns worth looking at
+// http://mrob.com/pub/comp/xmorphia/
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GrayScottPreset {
+    // spots that grow and split in two
+    Mitosis,
+    // branching growth that fills the board
+    Coral,
+    // stripes that wind into a maze
+    Maze,
+    // spots that hold still once they have room
+    Solitons,
+}
+
+impl GrayScottPreset {
+    // (feed, kill)
+    fn rates(self) -> (f32, f32) {
+        match self {
+            GrayScottPreset::Mitosis => (0.0367, 0.0649),
+            GrayScottPreset::Coral => (0.0545, 0.062),
+            GrayScottPreset::Maze => (0.029, 0.057),
+            GrayScottPreset::Solitons => (0.03, 0.062),
+        }
+    }
+}
+
+// How fast each chemical spreads, v at half the speed of u
+const DIFFUSION_U: f32 = 1.0;
+const DIFFUSION_V: f32 = 0.5;
+
+// Weights of the 3 by 3 Laplacian, the 4 sides and 4 corners add up to what the center
+// takes away
+const SIDE_WEIGHT: f32 = 0.2;
+const CORNER_WEIGHT: f32 = 0.05;
+
+// Two chemicals on a toroidal board. u is fed in everywhere at the feed rate, v turns u
+// into more v wherever they meet (u + 2v -> 3v) and is removed at the kill rate, and both
+// diffuse. The board starts all u with no v, which stays put until some v is added.
+// https://www.karlsims.com/rd.html
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug)]
+pub struct GrayScott {
+    width: u32,
+    height: u32,
+    u: Vec<f32>,
+    v: Vec<f32>,
+    next_u: Vec<f32>,
+    next_v: Vec<f32>,
+    feed: f32,
+    kill: f32,
+    generation: u32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl GrayScott {
+    // Starts with the Mitosis rates
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(width: u32, height: u32) -> Self {
+        let cell_count = (width * height) as usize;
+        let (feed, kill) = GrayScottPreset::Mitosis.rates();
+
+        Self {
+            width,
+            height,
+            u: vec![1.0; cell_count],
+            v: vec![0.0; cell_count],
+            next_u: vec![1.0; cell_count],
+            next_v: vec![0.0; cell_count],
+            feed,
+            kill,
+            generation: 0,
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    pub fn feed(&self) -> f32 {
+        self.feed
+    }
+
+    pub fn set_feed(&mut self, feed: f32) {
+        self.feed = feed.clamp(0.0, 0.1);
+    }
+
+    pub fn kill(&self) -> f32 {
+        self.kill
+    }
+
+    pub fn set_kill(&mut self, kill: f32) {
+        self.kill = kill.clamp(0.0, 0.1);
+    }
+
+    pub fn set_preset(&mut self, preset: GrayScottPreset) {
+        let (feed, kill) = preset.rates();
+        self.feed = feed;
+        self.kill = kill;
+    }
+
+    // One f32 per cell with the amount of u from 0 to 1, in row major order like
+    // Universe::ages_ptr(). Usually only v is drawn.
+    pub fn u_ptr(&self) -> *const f32 {
+        self.u.as_ptr()
+    }
+
+    pub fn v_ptr(&self) -> *const f32 {
+        self.v.as_ptr()
+    }
+
+    pub fn cells_len(&self) -> usize {
+        self.u.len()
+    }
+
+    pub fn get_v(&self, row: u32, column: u32) -> f32 {
+        if row < self.height && column < self.width {
+            self.v[self.get_index(row, column)]
+        } else {
+            0.0
+        }
+    }
+
+    // Drops v into a square radius cells out from row, column, where the reaction starts.
+    // The square wraps around the edges, once round is enough to cover every cell.
+    pub fn add_v(&mut self, row: u32, column: u32, radius: u32) {
+        if self.u.is_empty() {
+            return;
+        }
+        let radius = i64::from(radius);
+        let rows = (2 * radius + 1).min(i64::from(self.height));
+        let columns = (2 * radius + 1).min(i64::from(self.width));
+        for row_offset in 0..rows {
+            for column_offset in 0..columns {
+                let index = self.wrapped_index(
+                    i64::from(row) - radius + row_offset,
+                    i64::from(column) - radius + column_offset,
+                );
+                self.u[index] = 0.5;
+                self.v[index] = 1.0;
+            }
+        }
+    }
+
+    // Back to all u, then count squares of v dropped at random
+    pub fn randomize_seeded(&mut self, seed: u64, count: u32) {
+        let mut rng = Pcg32::seed_from_u64(seed);
+        self.clear();
+        for _ in 0..count {
+            let row = rng.gen_range(0, self.height.max(1));
+            let column = rng.gen_range(0, self.width.max(1));
+            self.add_v(row, column, 2);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.u.iter_mut().for_each(|u| *u = 1.0);
+        self.v.iter_mut().for_each(|v| *v = 0.0);
+        self.generation = 0;
+    }
+
+    pub fn tick(&mut self) {
+        for row in 0..self.height {
+            for column in 0..self.width {
+                let index = self.get_index(row, column);
+                let (u, v) = (self.u[index], self.v[index]);
+                let reaction = u * v * v;
+                let next_u = u + DIFFUSION_U * self.laplacian(&self.u, row, column) - reaction
+                    + self.feed * (1.0 - u);
+                let next_v = v + DIFFUSION_V * self.laplacian(&self.v, row, column) + reaction
+                    - (self.kill + self.feed) * v;
+                self.next_u[index] = next_u.clamp(0.0, 1.0);
+                self.next_v[index] = next_v.clamp(0.0, 1.0);
+            }
+        }
+
+        std::mem::swap(&mut self.u, &mut self.next_u);
+        std::mem::swap(&mut self.v, &mut self.next_v);
+        self.generation += 1;
+    }
+
+    // Patterns take thousands of ticks to form, so a frame usually runs several
+    pub fn tick_n(&mut self, count: u32) {
+        for _ in 0..count {
+            self.tick();
+        }
+    }
+}
+
+impl GrayScott {
+    fn get_index(&self, row: u32, column: u32) -> usize {
+        (row * self.width + column) as usize
+    }
+
+    fn wrapped_index(&self, row: i64, column: i64) -> usize {
+        let row = row.rem_euclid(i64::from(self.height)) as u32;
+        let column = column.rem_euclid(i64::from(self.width)) as u32;
+        self.get_index(row, column)
+    }
+
+    fn laplacian(&self, field: &[f32], row: u32, column: u32) -> f32 {
+        let (row, column) = (i64::from(row), i64::from(column));
+        let mut sum = -field[self.get_index(row as u32, column as u32)];
+        for row_offset in -1..=1 {
+            for column_offset in -1..=1 {
+                let weight = match (row_offset, column_offset) {
+                    (0, 0) => continue,
+                    (0, _) | (_, 0) => SIDE_WEIGHT,
+                    _ => CORNER_WEIGHT,
+                };
+                sum += weight * field[self.wrapped_index(row + row_offset, column + column_offset)];
+            }
+        }
+        sum
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_empty_board_stays_put() {
+        let mut gray_scott = GrayScott::new(8, 8);
+        gray_scott.tick_n(10);
+
+        assert!(gray_scott.u.iter().all(|u| *u == 1.0));
+        assert!(gray_scott.v.iter().all(|v| *v == 0.0));
+        assert_eq!(10, gray_scott.generation());
+    }
+
+    #[test]
+    fn test_v_spreads() {
+        let mut gray_scott = GrayScott::new(16, 16);
+        gray_scott.set_preset(GrayScottPreset::Coral);
+        assert_eq!((0.0545, 0.062), (gray_scott.feed(), gray_scott.kill()));
+        gray_scott.add_v(0, 0, 1);
+        assert_eq!(1.0, gray_scott.get_v(15, 15));
+        assert_eq!(0.0, gray_scott.get_v(0, 3));
+
+        gray_scott.tick_n(5);
+        assert!(gray_scott.get_v(0, 3) > 0.0);
+    }
+
+    #[test]
+    fn test_seeded_runs_match() {
+        let mut first = GrayScott::new(20, 20);
+        first.randomize_seeded(4, 3);
+        let mut second = first.clone();
+        first.tick_n(20);
+        second.tick_n(20);
+
+        assert_eq!(first.v, second.v);
+        assert!(first.v.iter().any(|v| *v > 0.0));
+    }
+
+    #[test]
+    fn test_add_v_past_the_grid() {
+        let mut gray_scott = GrayScott::new(4, 3);
+        gray_scott.add_v(1, 1, u32::MAX);
+        assert!(gray_scott.v.iter().all(|v| *v == 1.0));
+
+        let mut empty = GrayScott::new(0, 0);
+        empty.add_v(0, 0, 2);
+        empty.randomize_seeded(1, 3);
+        assert_eq!(0, empty.cells_len());
+    }
+}
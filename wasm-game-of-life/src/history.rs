@@ -0,0 +1,172 @@
+use std::collections::VecDeque;
+
+use crate::bits::CellBits;
+use crate::memory::vec_bytes;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    pub cells: CellBits,
+    pub generation: u32,
+    pub births_last_tick: u32,
+    pub deaths_last_tick: u32,
+    // the fading states under a Generations rule, empty for rules with 2 states
+    pub fading: Vec<u8>,
+    // the color of each cell under Immigration and QuadLife, empty for Monochrome
+    pub colors: Vec<u8>,
+}
+
+// Ring buffer of the last `capacity` generations for stepping backwards, plus the
+// generations that were stepped back over so they can be stepped forward again
+#[derive(Clone, Debug, Default)]
+pub struct History {
+    capacity: usize,
+    past: VecDeque<Snapshot>,
+    future: Vec<Snapshot>,
+}
+
+impl History {
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.past.len() > capacity {
+            self.past.pop_front();
+        }
+        // the next generation forward is at the end of future, the furthest ones go first
+        if self.future.len() > capacity {
+            let furthest = self.future.len() - capacity;
+            self.future.drain(..furthest);
+        }
+    }
+
+    // Recording a new generation makes the generations that were stepped back over unreachable
+    pub fn record(&mut self, snapshot: Snapshot) {
+        self.future.clear();
+        if self.capacity == 0 {
+            return;
+        }
+        if self.past.len() == self.capacity {
+            self.past.pop_front();
+        }
+        self.past.push_back(snapshot);
+    }
+
+    pub fn step_back(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let previous = self.past.pop_back()?;
+        self.future.push(current);
+        Some(previous)
+    }
+
+    pub fn step_forward(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let next = self.future.pop()?;
+        self.past.push_back(current);
+        Some(next)
+    }
+
+    pub fn memory_usage(&self) -> usize {
+        let snapshots = self.past.capacity() + self.future.capacity();
+        snapshots * std::mem::size_of::<Snapshot>()
+            + self
+                .past
+                .iter()
+                .chain(self.future.iter())
+                .map(|snapshot| {
+                    snapshot.cells.memory_usage()
+                        + vec_bytes(&snapshot.fading)
+                        + vec_bytes(&snapshot.colors)
+                })
+                .sum::<usize>()
+    }
+
+    pub fn clear(&mut self) {
+        self.past.clear();
+        self.future.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn snapshot(generation: u32) -> Snapshot {
+        Snapshot {
+            cells: CellBits::new(4),
+            generation,
+            births_last_tick: 0,
+            deaths_last_tick: 0,
+            fading: vec![],
+            colors: vec![],
+        }
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut history = History::default();
+        history.set_capacity(2);
+
+        history.record(snapshot(0));
+        history.record(snapshot(1));
+        history.record(snapshot(2));
+
+        assert_eq!(Some(snapshot(2)), history.step_back(snapshot(3)));
+        assert_eq!(Some(snapshot(1)), history.step_back(snapshot(2)));
+        assert_eq!(None, history.step_back(snapshot(1)));
+    }
+
+    #[test]
+    fn test_zero_capacity_records_nothing() {
+        let mut history = History::default();
+        history.record(snapshot(0));
+
+        assert_eq!(None, history.step_back(snapshot(1)));
+    }
+
+    #[test]
+    fn test_step_forward_after_step_back() {
+        let mut history = History::default();
+        history.set_capacity(5);
+        history.record(snapshot(0));
+
+        assert_eq!(Some(snapshot(0)), history.step_back(snapshot(1)));
+        assert_eq!(Some(snapshot(1)), history.step_forward(snapshot(0)));
+        assert_eq!(None, history.step_forward(snapshot(1)));
+
+        history.step_back(snapshot(1));
+        history.record(snapshot(0));
+        assert_eq!(None, history.step_forward(snapshot(1)));
+    }
+
+    #[test]
+    fn test_shrinking_capacity_drops_oldest() {
+        let mut history = History::default();
+        history.set_capacity(3);
+        history.record(snapshot(0));
+        history.record(snapshot(1));
+        history.record(snapshot(2));
+
+        history.set_capacity(1);
+
+        assert_eq!(Some(snapshot(2)), history.step_back(snapshot(3)));
+        assert_eq!(None, history.step_back(snapshot(2)));
+    }
+
+    #[test]
+    fn test_shrinking_capacity_keeps_the_next_generations() {
+        let mut history = History::default();
+        history.set_capacity(3);
+        for generation in 0..3 {
+            history.record(snapshot(generation));
+        }
+        history.step_back(snapshot(3));
+        history.step_back(snapshot(2));
+        history.step_back(snapshot(1));
+
+        history.set_capacity(2);
+
+        assert_eq!(Some(snapshot(1)), history.step_forward(snapshot(0)));
+        assert_eq!(Some(snapshot(2)), history.step_forward(snapshot(1)));
+        assert_eq!(None, history.step_forward(snapshot(2)));
+    }
+}
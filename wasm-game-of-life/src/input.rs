@@ -0,0 +1,299 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{
+    Element, EventTarget, HtmlCanvasElement, KeyboardEvent, MouseEvent, PointerEvent, WheelEvent,
+};
+
+use crate::{Cell, Clipboard, GameLoop, KeyAction, KeyBindings};
+
+// How much one notch of the mouse wheel zooms in or out
+const WHEEL_ZOOM: f64 = 1.1;
+
+// Where a drag is drawing, the cell it's painting with and the last cell it reached
+struct Stroke {
+    cell: Cell,
+    last: (i32, i32),
+}
+
+// What the pointers on the canvas are doing. One pointer draws, a second one turns it
+// into a pinch that pans and zooms, and nothing is drawn until every finger is lifted.
+#[derive(Default)]
+struct Gesture {
+    // the id of each pointer that is down and its last point in canvas pixels, only the
+    // first two are followed
+    pointers: Vec<(i32, (f64, f64))>,
+    stroke: Option<Stroke>,
+    pinching: bool,
+}
+
+type Listener<T> = Closure<dyn FnMut(T)>;
+
+// Edits and drives a GameLoop from the mouse, touch screens and the keyboard, so the page
+// only needs to make one of these for its canvas. Clicking or tapping toggles a cell and
+// dragging keeps drawing in the state the first cell was toggled to, or with a brush set
+// each click stamps the brush. When the loop has a viewport the wheel zooms and two
+// fingers pan and pinch to zoom. The keys do what the KeyBindings say. The listeners come
+// off the page when this is dropped.
+#[wasm_bindgen]
+pub struct InputController {
+    canvas: HtmlCanvasElement,
+    bindings: Rc<RefCell<KeyBindings>>,
+    brush: Rc<RefCell<Option<Clipboard>>>,
+    pointer_down: Listener<PointerEvent>,
+    pointer_move: Listener<PointerEvent>,
+    pointer_up: Listener<PointerEvent>,
+    wheel: Listener<WheelEvent>,
+    key_down: Listener<KeyboardEvent>,
+}
+
+#[wasm_bindgen]
+impl InputController {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        canvas: HtmlCanvasElement,
+        game_loop: &GameLoop,
+    ) -> Result<InputController, JsValue> {
+        let gesture = Rc::new(RefCell::new(Gesture::default()));
+        let bindings = Rc::new(RefCell::new(KeyBindings::default()));
+        let brush: Rc<RefCell<Option<Clipboard>>> = Rc::new(RefCell::new(None));
+
+        let pointer_down = {
+            let handle = game_loop.handle();
+            let canvas = canvas.clone();
+            let gesture = gesture.clone();
+            let brush = brush.clone();
+            Closure::wrap(Box::new(move |event: PointerEvent| {
+                if event.button() != 0 {
+                    return;
+                }
+                let mut gesture = gesture.borrow_mut();
+                if gesture.pointers.len() >= 2 {
+                    return;
+                }
+                // the moves and the lift come to the canvas even once the pointer is off it
+                let _ = canvas.set_pointer_capture(event.pointer_id());
+                let point = canvas_point(&canvas, &event);
+                gesture.pointers.push((event.pointer_id(), point));
+                if gesture.pointers.len() == 2 {
+                    gesture.stroke = None;
+                    gesture.pinching = true;
+                }
+                if gesture.pinching {
+                    return;
+                }
+
+                let cell = match handle.cell_at(point.0, point.1) {
+                    Some(cell) => cell,
+                    None => return,
+                };
+                if let Some(brush) = &*brush.borrow() {
+                    return handle.paste(brush, cell);
+                }
+                let paint = match handle.get_cell(cell.0, cell.1) {
+                    Some(Cell::Alive) => Cell::Dead,
+                    Some(Cell::Dead) => Cell::Alive,
+                    None => return,
+                };
+                handle.draw_line(cell, cell, paint);
+                gesture.stroke = Some(Stroke {
+                    cell: paint,
+                    last: cell,
+                });
+            }) as Box<dyn FnMut(PointerEvent)>)
+        };
+
+        let pointer_move = {
+            let handle = game_loop.handle();
+            let canvas = canvas.clone();
+            let gesture = gesture.clone();
+            Closure::wrap(Box::new(move |event: PointerEvent| {
+                let mut gesture = gesture.borrow_mut();
+                let point = canvas_point(&canvas, &event);
+                let moved = gesture
+                    .pointers
+                    .iter()
+                    .position(|(id, _)| *id == event.pointer_id());
+                let moved = match moved {
+                    Some(moved) => moved,
+                    None => return,
+                };
+                let before = gesture.pointers[moved].1;
+                gesture.pointers[moved].1 = point;
+
+                if gesture.pointers.len() == 2 {
+                    let other = gesture.pointers[1 - moved].1;
+                    handle.pinch([before, other], [point, other]);
+                } else if let Some(stroke) = gesture.stroke.as_mut() {
+                    if let Some(cell) = handle.cell_at(point.0, point.1) {
+                        // a fast drag skips cells between events, the line fills them in
+                        if cell != stroke.last {
+                            handle.draw_line(stroke.last, cell, stroke.cell);
+                            stroke.last = cell;
+                        }
+                    }
+                }
+            }) as Box<dyn FnMut(PointerEvent)>)
+        };
+
+        // also used for pointercancel, when the browser takes the touch over
+        let pointer_up = {
+            let canvas = canvas.clone();
+            let gesture = gesture.clone();
+            Closure::wrap(Box::new(move |event: PointerEvent| {
+                let _ = canvas.release_pointer_capture(event.pointer_id());
+                let mut gesture = gesture.borrow_mut();
+                gesture.pointers.retain(|(id, _)| *id != event.pointer_id());
+                gesture.stroke = None;
+                // a pinch doesn't turn back into drawing when one finger is lifted
+                if gesture.pointers.is_empty() {
+                    gesture.pinching = false;
+                }
+            }) as Box<dyn FnMut(PointerEvent)>)
+        };
+
+        let wheel = {
+            let handle = game_loop.handle();
+            let canvas = canvas.clone();
+            Closure::wrap(Box::new(move |event: WheelEvent| {
+                event.prevent_default();
+                let factor = if event.delta_y() < 0.0 {
+                    WHEEL_ZOOM
+                } else {
+                    1.0 / WHEEL_ZOOM
+                };
+                let point = canvas_point(&canvas, &event);
+                handle.zoom_at(point.0, point.1, factor);
+            }) as Box<dyn FnMut(WheelEvent)>)
+        };
+
+        let key_down = {
+            let handle = game_loop.handle();
+            let bindings = bindings.clone();
+            let brush = brush.clone();
+            Closure::wrap(Box::new(move |event: KeyboardEvent| {
+                if is_typing(&event) {
+                    return;
+                }
+                let action = match bindings.borrow().action(&event.key()) {
+                    Some(action) => action,
+                    None => return,
+                };
+                // space would scroll the page and the arrows move the caret otherwise
+                event.prevent_default();
+                // holding a key down repeats steps but not toggles
+                if event.repeat() && action == KeyAction::Pause {
+                    return;
+                }
+                if action == KeyAction::RotateBrush {
+                    let mut brush = brush.borrow_mut();
+                    if let Some(rotated) = brush.as_ref().map(Clipboard::rotate_cw) {
+                        *brush = Some(rotated);
+                    }
+                    return;
+                }
+                let _ = handle.perform(action);
+            }) as Box<dyn FnMut(KeyboardEvent)>)
+        };
+
+        let controller = Self {
+            canvas,
+            bindings,
+            brush,
+            pointer_down,
+            pointer_move,
+            pointer_up,
+            wheel,
+            key_down,
+        };
+        // stops the browser scrolling and zooming the page under a finger on the canvas
+        controller
+            .canvas
+            .style()
+            .set_property("touch-action", "none")?;
+        controller.listen(true)?;
+        Ok(controller)
+    }
+
+    pub fn key_bindings(&self) -> KeyBindings {
+        self.bindings.borrow().clone()
+    }
+
+    pub fn set_key_bindings(&mut self, bindings: KeyBindings) {
+        *self.bindings.borrow_mut() = bindings;
+    }
+
+    pub fn brush(&self) -> Option<Clipboard> {
+        self.brush.borrow().clone()
+    }
+
+    // With a brush, clicks stamp its live cells with its top left corner on the cell
+    // under the mouse instead of toggling the cell, None goes back to drawing
+    pub fn set_brush(&mut self, brush: Option<Clipboard>) {
+        *self.brush.borrow_mut() = brush;
+    }
+}
+
+impl InputController {
+    fn listeners(&self) -> [(&str, &JsValue); 5] {
+        [
+            ("pointerdown", self.pointer_down.as_ref()),
+            ("pointermove", self.pointer_move.as_ref()),
+            ("pointerup", self.pointer_up.as_ref()),
+            ("pointercancel", self.pointer_up.as_ref()),
+            ("wheel", self.wheel.as_ref()),
+        ]
+    }
+
+    // Keys go to the window, a canvas only gets them while it has focus
+    fn listen(&self, attach: bool) -> Result<(), JsValue> {
+        let window: EventTarget = web_sys::window()
+            .ok_or_else(|| JsValue::from_str("no global window"))?
+            .into();
+        let update = |target: &EventTarget, event: &str, listener: &JsValue| {
+            if attach {
+                target.add_event_listener_with_callback(event, listener.unchecked_ref())
+            } else {
+                target.remove_event_listener_with_callback(event, listener.unchecked_ref())
+            }
+        };
+
+        for (event, listener) in self.listeners().iter() {
+            update(self.canvas.as_ref(), event, listener)?;
+        }
+        update(&window, "keydown", self.key_down.as_ref())
+    }
+}
+
+impl Drop for InputController {
+    fn drop(&mut self) {
+        let _ = self.listen(false);
+    }
+}
+
+// Keys pressed in a text box are for the text box
+fn is_typing(event: &KeyboardEvent) -> bool {
+    event
+        .target()
+        .and_then(|target| target.dyn_into::<Element>().ok())
+        .map(|element| matches!(element.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT"))
+        .unwrap_or(false)
+}
+
+// Mouse events, pointer and wheel events included, are in CSS pixels, which only match the
+// canvas pixels when the canvas isn't being stretched by the page
+fn canvas_point(canvas: &HtmlCanvasElement, event: &MouseEvent) -> (f64, f64) {
+    let scale = |size: u32, client_size: i32| {
+        if client_size > 0 {
+            f64::from(size) / f64::from(client_size)
+        } else {
+            1.0
+        }
+    };
+    (
+        f64::from(event.offset_x()) * scale(canvas.width(), canvas.client_width()),
+        f64::from(event.offset_y()) * scale(canvas.height(), canvas.client_height()),
+    )
+}
@@ -0,0 +1,416 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::formats::ParseError;
+use crate::rule::{parse_states, RuleTable};
+
+// The 3 by 3 neighborhoods are bitmasks in row major order like RuleTable's
+const NEIGHBORS: u16 = 0b1_1110_1111;
+
+// A neighborhood of each letter in Hensel notation for 1 to 4 live neighbors, in
+// alphabetical order the way rules are written. Every rotation and reflection of a
+// neighborhood has the same letter, and 5 to 7 neighbors use the letters of their dead
+// cells, so 5c is everything but a corner the same as 3c is the corners.
+const LETTERS: [&[(char, u16)]; 5] = [
+    &[],
+    &[('c', 1), ('e', 2)],
+    &[
+        ('a', 3),
+        ('c', 5),
+        ('e', 10),
+        ('i', 40),
+        ('k', 33),
+        ('n', 68),
+    ],
+    &[
+        ('a', 11),
+        ('c', 69),
+        ('e', 42),
+        ('i', 7),
+        ('j', 14),
+        ('k', 98),
+        ('n', 13),
+        ('q', 70),
+        ('r', 41),
+        ('y', 97),
+    ],
+    &[
+        ('a', 15),
+        ('c', 325),
+        ('e', 170),
+        ('i', 45),
+        ('j', 106),
+        ('k', 99),
+        ('n', 71),
+        ('q', 102),
+        ('r', 43),
+        ('t', 105),
+        ('w', 78),
+        ('y', 101),
+        ('z', 108),
+    ],
+];
+
+fn letters(count: usize) -> &'static [(char, u16)] {
+    LETTERS[count.min(8 - count)]
+}
+
+// The neighbors of a letter as they are for count live neighbors
+fn letter_neighbors(count: usize, neighbors: u16) -> u16 {
+    match count {
+        0 => 0,
+        8 => NEIGHBORS,
+        5..=7 => NEIGHBORS ^ neighbors,
+        _ => neighbors,
+    }
+}
+
+// The 8 ways of turning and flipping a neighborhood
+fn symmetries(neighbors: u16) -> impl Iterator<Item = u16> {
+    (0..8).map(move |symmetry| {
+        (0..9)
+            .filter(|bit| neighbors & (1 << bit) != 0)
+            .map(|bit| {
+                let (mut row, mut column) = (bit / 3, bit % 3);
+                if symmetry & 4 != 0 {
+                    std::mem::swap(&mut row, &mut column);
+                }
+                if symmetry & 2 != 0 {
+                    row = 2 - row;
+                }
+                if symmetry & 1 != 0 {
+                    column = 2 - column;
+                }
+                1 << (row * 3 + column)
+            })
+            .sum()
+    })
+}
+
+// Where the letter of neighbors is in letters(), 0 for 0 and 8 neighbors
+fn letter_index(neighbors: u16) -> usize {
+    let count = neighbors.count_ones() as usize;
+    letters(count)
+        .iter()
+        .position(|(_, shape)| {
+            symmetries(letter_neighbors(count, *shape)).any(|turned| turned == neighbors)
+        })
+        .unwrap_or(0)
+}
+
+// Rules that look at which neighbors are alive and not only how many, as long as turning
+// or flipping the neighborhood doesn't change the outcome. Written in Hensel notation, a
+// neighbor count on its own is every shape with that many neighbors, letters after it are
+// only those shapes and a - before the letters is every shape but those. tlife is
+// B3/S2-i34q and Snowflakes B2ci3ai4c8/S02ae3eijkq4iz5ar6i7e.
+// https://conwaylife.com/wiki/Isotropic_non-totalistic_rule
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IsotropicRule {
+    table: RuleTable,
+    states: u8,
+}
+
+impl IsotropicRule {
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    pub fn is_alive_next(&self, neighborhood: u16) -> bool {
+        self.table.is_alive_next(neighborhood)
+    }
+
+    pub(crate) fn table(&self) -> &RuleTable {
+        &self.table
+    }
+
+    // Bit n of shapes[count] is set for the nth letter of count neighbors
+    fn shapes(&self, alive: bool) -> [u16; 9] {
+        let mut shapes = [0; 9];
+        for (count, shape_bits) in shapes.iter_mut().enumerate() {
+            let letters = letters(count);
+            let shape_count = letters.len().max(1);
+            for index in 0..shape_count {
+                let neighbors = letter_neighbors(count, letters.get(index).map_or(0, |l| l.1));
+                let cell = if alive { RuleTable::CENTER as u16 } else { 0 };
+                if self.table.is_alive_next(neighbors | cell) {
+                    *shape_bits |= 1 << index;
+                }
+            }
+        }
+        shapes
+    }
+}
+
+impl FromStr for IsotropicRule {
+    type Err = ParseError;
+
+    // Takes "B2-a/S12" in either order and any case, with the number of states as a third
+    // part like "B2n3/S23-q/C3" for a Generations rule
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = rule.trim().split('/').collect();
+        if parts.len() != 2 && parts.len() != 3 {
+            return Err(ParseError::new(1, 1, "expected a rule like B2-a/S12"));
+        }
+
+        let mut birth = None;
+        let mut survival = None;
+        let mut states = None;
+        let mut column = 1;
+        for part in parts.iter() {
+            let kind = part.chars().next().map(|kind| kind.to_ascii_uppercase());
+            let duplicate = match kind {
+                Some('B') => birth
+                    .replace(parse_shapes(&part[1..], column + 1)?)
+                    .is_some(),
+                Some('S') => survival
+                    .replace(parse_shapes(&part[1..], column + 1)?)
+                    .is_some(),
+                Some('C') | Some('G') => states
+                    .replace(parse_states(&part[1..], column + 1)?)
+                    .is_some(),
+                _ => {
+                    return Err(ParseError::new(
+                        1,
+                        column,
+                        "expected B, S or C before each part",
+                    ))
+                }
+            };
+            if duplicate {
+                return Err(ParseError::new(
+                    1,
+                    column,
+                    format!("{} is listed twice", kind.unwrap_or_default()),
+                ));
+            }
+            column += part.len() + 1;
+        }
+
+        let (birth, survival) = match (birth, survival) {
+            (Some(birth), Some(survival)) => (birth, survival),
+            _ => return Err(ParseError::new(1, 1, "expected a rule like B2-a/S12")),
+        };
+        let table = RuleTable::new(|neighborhood| {
+            let neighbors = neighborhood as u16 & NEIGHBORS;
+            let shapes = if neighborhood & RuleTable::CENTER != 0 {
+                survival
+            } else {
+                birth
+            };
+            shapes[neighbors.count_ones() as usize] & (1 << letter_index(neighbors)) != 0
+        });
+
+        Ok(Self {
+            table,
+            states: states.unwrap_or(2),
+        })
+    }
+}
+
+// The shapes of each neighbor count from something like "2-a3ce", see
+// IsotropicRule::shapes()
+fn parse_shapes(text: &str, column: usize) -> Result<[u16; 9], ParseError> {
+    let mut shapes = [0u16; 9];
+    let mut characters = text.char_indices().peekable();
+    while let Some((index, digit)) = characters.next() {
+        let error = |index: usize, message: String| ParseError::new(1, column + index, message);
+        let count = match digit.to_digit(10) {
+            Some(count) if count <= 8 => count as usize,
+            _ => {
+                return Err(error(
+                    index,
+                    format!("'{}' is not a neighbor count between 0 and 8", digit),
+                ))
+            }
+        };
+        let letters = letters(count);
+        let every_shape = (1 << letters.len().max(1)) - 1;
+        let except = characters
+            .next_if(|(_, character)| *character == '-')
+            .is_some();
+        let mut listed = 0;
+        while let Some((index, letter)) =
+            characters.next_if(|(_, character)| character.is_ascii_alphabetic())
+        {
+            let position = letters
+                .iter()
+                .position(|(shape, _)| *shape == letter.to_ascii_lowercase())
+                .ok_or_else(|| {
+                    error(
+                        index,
+                        format!("'{}' is not a shape of {} neighbors", letter, count),
+                    )
+                })?;
+            listed |= 1 << position;
+        }
+        shapes[count] |= match (except, listed) {
+            (false, 0) => every_shape,
+            (true, 0) => return Err(error(index + 1, "expected letters after -".to_owned())),
+            (true, listed) => every_shape & !listed,
+            (false, listed) => listed,
+        };
+    }
+    Ok(shapes)
+}
+
+// Whether a rule is meant to be read as an isotropic rule rather than a plain B/S rule,
+// which it is when its birth or survival part has any letters besides the H of a hex rule
+pub fn is_isotropic(rule: &str) -> bool {
+    let rule = rule.trim();
+    let rule = rule
+        .strip_suffix(|letter| letter == 'H' || letter == 'h')
+        .unwrap_or(rule);
+    rule.split('/').any(|part| {
+        let mut characters = part.chars();
+        matches!(
+            characters.next(),
+            Some('B') | Some('b') | Some('S') | Some('s')
+        ) && characters.any(|character| character.is_ascii_alphabetic() || character == '-')
+    })
+}
+
+impl Display for IsotropicRule {
+    // Lists the letters or, when more than half of them are in, a - and the ones that
+    // aren't, so "B2acike/S" comes back as "B2-n/S"
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for (kind, alive) in [("B", false), ("/S", true)].iter() {
+            write!(f, "{}", kind)?;
+            for (count, shape_bits) in self.shapes(*alive).iter().enumerate() {
+                let letters = letters(count);
+                let listed = shape_bits.count_ones() as usize;
+                if listed == 0 {
+                    continue;
+                }
+                write!(f, "{}", count)?;
+                if listed == letters.len().max(1) {
+                    continue;
+                }
+                let except = listed * 2 > letters.len();
+                if except {
+                    write!(f, "-")?;
+                }
+                for (index, (letter, _)) in letters.iter().enumerate() {
+                    if (shape_bits & (1 << index) != 0) != except {
+                        write!(f, "{}", letter)?;
+                    }
+                }
+            }
+        }
+        if self.states > 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Cell, Topology, Universe};
+
+    // the neighborhood with the given cells alive, as "x" and "." in row major order
+    fn neighborhood(cells: &str) -> u16 {
+        cells
+            .chars()
+            .filter(|cell| *cell != '/')
+            .enumerate()
+            .filter(|(_, cell)| *cell == 'x')
+            .map(|(bit, _)| 1 << bit)
+            .sum()
+    }
+
+    #[test]
+    fn test_letters_cover_every_neighborhood() {
+        // every neighborhood has exactly one letter, and the letters of a count are
+        // different shapes
+        for count in 0..=8 {
+            let letters = letters(count);
+            for (index, (_, shape)) in letters.iter().enumerate() {
+                let neighbors = letter_neighbors(count, *shape);
+                assert_eq!(count as u32, neighbors.count_ones());
+                assert_eq!(index, letter_index(neighbors));
+            }
+        }
+        let shapes: usize = (0..=8).map(|count| letters(count).len().max(1)).sum();
+        assert_eq!(51, shapes);
+        for neighbors in (0..512).map(|neighborhood| neighborhood & NEIGHBORS) {
+            let count = neighbors.count_ones() as usize;
+            assert!(
+                letters(count).is_empty()
+                    || letters(count).iter().any(|(_, shape)| {
+                        symmetries(letter_neighbors(count, *shape))
+                            .any(|turned| turned == neighbors)
+                    })
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let rule: IsotropicRule = "B2-a/S12".parse().unwrap();
+        assert_eq!("B2-a/S12", rule.to_string());
+        assert!(rule.is_alive_next(neighborhood("x.x/.../...")));
+        assert!(!rule.is_alive_next(neighborhood("xx./.../...")));
+        assert!(rule.is_alive_next(neighborhood(".../.xx/...")));
+
+        let snowflakes = "B2ci3ai4c8/S02ae3eijkq4iz5ar6i7e";
+        assert_eq!(
+            snowflakes,
+            snowflakes.parse::<IsotropicRule>().unwrap().to_string()
+        );
+        assert_eq!(
+            "s23-q/b3/c4".parse::<IsotropicRule>().unwrap().to_string(),
+            "B3/S23-q/C4"
+        );
+        assert_eq!(
+            "B2-n/S",
+            "B2acike/S".parse::<IsotropicRule>().unwrap().to_string()
+        );
+
+        assert_eq!(
+            ParseError::new(1, 3, "'z' is not a shape of 2 neighbors"),
+            "B2z/S".parse::<IsotropicRule>().unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(1, 5, "'9' is not a neighbor count between 0 and 8"),
+            "B3/S9a".parse::<IsotropicRule>().unwrap_err()
+        );
+        assert!(is_isotropic("B2-a/S12"));
+        assert!(!is_isotropic("B36/S23/C3"));
+        assert!(!is_isotropic("B2/S34H"));
+    }
+
+    #[test]
+    fn test_tlife() {
+        // tlife keeps cells with two neighbors, except when they are opposite each other
+        let mut universe = Universe::new(7);
+        universe.set_rule("B3/S2-i34q").unwrap();
+        assert_eq!("B3/S2-i34q", universe.rule());
+        for (row, column) in [(1, 1), (2, 1), (3, 1), (1, 4), (2, 4), (2, 5)].iter() {
+            universe.set_cell(*row, *column, Cell::Alive);
+        }
+        universe.tick();
+
+        assert_eq!(Cell::Dead, universe.get_cell(2, 1));
+        assert_eq!(Cell::Alive, universe.get_cell(2, 4));
+    }
+
+    #[test]
+    fn test_every_letter_is_totalistic() {
+        let mut isotropic = Universe::new_with_dimensions(24, 16);
+        isotropic.set_edge_topology(Topology::KleinBottle);
+        isotropic.set_rule("B36-/S2aceikn3").unwrap_err();
+        isotropic.set_rule("B3aceijknqry6ceaikn/S2aceikn3").unwrap();
+        assert_eq!("B36/S23", isotropic.rule());
+        isotropic.randomize_seeded(9, 0.4);
+        let mut totalistic = isotropic.clone();
+        totalistic.set_rule("B36/S23").unwrap();
+
+        for _ in 0..20 {
+            isotropic.tick();
+            totalistic.tick();
+            assert_eq!(totalistic.cells(), isotropic.cells());
+        }
+    }
+}
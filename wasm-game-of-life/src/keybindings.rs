@@ -0,0 +1,156 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+// What a key can do to the game, see InputController
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+pub enum KeyAction {
+    // pauses a running loop and resumes a paused one
+    Pause,
+    // one generation forward while paused
+    Step,
+    // one generation back, with the history turned on
+    StepBack,
+    Randomize,
+    Clear,
+    // twice or half the speed, or ticks per frame when no speed has been set
+    SpeedUp,
+    SlowDown,
+    // turns the brush a quarter turn clockwise
+    RotateBrush,
+}
+
+const DEFAULTS: &[(&str, KeyAction)] = &[
+    (" ", KeyAction::Pause),
+    ("p", KeyAction::Pause),
+    ("n", KeyAction::Step),
+    ("ArrowRight", KeyAction::Step),
+    ("b", KeyAction::StepBack),
+    ("ArrowLeft", KeyAction::StepBack),
+    ("r", KeyAction::Randomize),
+    ("c", KeyAction::Clear),
+    ("+", KeyAction::SpeedUp),
+    ("=", KeyAction::SpeedUp),
+    ("-", KeyAction::SlowDown),
+    ("t", KeyAction::RotateBrush),
+];
+
+// Which key does what, keyed by KeyboardEvent.key. Letters match either case so caps lock
+// and shift don't get in the way.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyBindings {
+    keys: BTreeMap<String, KeyAction>,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl KeyBindings {
+    // Space or p pauses, n or the right arrow steps, b or the left arrow steps back, r
+    // randomizes, c clears, + and - change the speed and t turns the brush
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Nothing bound at all, for pages that want to choose every key themselves
+    pub fn empty() -> Self {
+        Self {
+            keys: BTreeMap::new(),
+        }
+    }
+
+    // A key that already did something else only does this from now on
+    pub fn bind(&mut self, key: &str, action: KeyAction) {
+        self.keys.insert(normalize(key), action);
+    }
+
+    // Returns false when the key wasn't bound
+    pub fn unbind(&mut self, key: &str) -> bool {
+        self.keys.remove(&normalize(key)).is_some()
+    }
+
+    pub fn action(&self, key: &str) -> Option<KeyAction> {
+        self.keys.get(&normalize(key)).copied()
+    }
+
+    // In alphabetical order
+    pub fn keys_for(&self, action: KeyAction) -> Vec<String> {
+        self.keys
+            .iter()
+            .filter(|(_, bound)| **bound == action)
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+}
+
+impl KeyBindings {
+    // Gives each action in the config exactly the keys listed for it, as parsed from
+    // JSON like {"Pause": [" "], "Step": ["n", "ArrowRight"]}. Actions that aren't in
+    // the config keep their keys.
+    pub fn configure(&mut self, config: BTreeMap<KeyAction, Vec<String>>) {
+        self.keys.retain(|_, action| !config.contains_key(action));
+        for (action, keys) in config {
+            for key in keys {
+                self.bind(&key, action);
+            }
+        }
+    }
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let mut bindings = Self::empty();
+        for (key, action) in DEFAULTS {
+            bindings.bind(key, *action);
+        }
+        bindings
+    }
+}
+
+fn normalize(key: &str) -> String {
+    let mut characters = key.chars();
+    match (characters.next(), characters.next()) {
+        (Some(character), None) => character.to_lowercase().collect(),
+        _ => key.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_defaults() {
+        let bindings = KeyBindings::new();
+        assert_eq!(Some(KeyAction::Pause), bindings.action(" "));
+        assert_eq!(Some(KeyAction::Randomize), bindings.action("R"));
+        assert_eq!(Some(KeyAction::Step), bindings.action("ArrowRight"));
+        assert_eq!(None, bindings.action("arrowright"));
+        assert_eq!(vec![" ", "p"], bindings.keys_for(KeyAction::Pause));
+    }
+
+    #[test]
+    fn test_configure() {
+        let config: BTreeMap<KeyAction, Vec<String>> =
+            serde_json::from_str(r#"{"Pause": ["Enter"], "Clear": ["Delete", "Backspace"]}"#)
+                .unwrap();
+        let mut bindings = KeyBindings::new();
+        bindings.configure(config);
+
+        assert_eq!(None, bindings.action(" "));
+        assert_eq!(Some(KeyAction::Pause), bindings.action("Enter"));
+        assert_eq!(None, bindings.action("c"));
+        assert_eq!(
+            vec!["Backspace", "Delete"],
+            bindings.keys_for(KeyAction::Clear)
+        );
+        assert_eq!(Some(KeyAction::Step), bindings.action("n"));
+
+        assert!(
+            serde_json::from_str::<BTreeMap<KeyAction, Vec<String>>>(r#"{"Jump": []}"#).is_err()
+        );
+    }
+}
@@ -0,0 +1,258 @@
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::formats::ParseError;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    fn turned(self, turn: Turn) -> Self {
+        let quarter_turns = match turn {
+            Turn::None => 0,
+            Turn::Right => 1,
+            Turn::Back => 2,
+            Turn::Left => 3,
+        };
+        match (self as u8 + quarter_turns) % 4 {
+            0 => Direction::Up,
+            1 => Direction::Right,
+            2 => Direction::Down,
+            _ => Direction::Left,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Turn {
+    Left,
+    Right,
+    None,
+    Back,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Ant {
+    row: u32,
+    column: u32,
+    direction: Direction,
+    // what to do on a cell in state n is turns[n % turns.len()]
+    turns: Vec<Turn>,
+}
+
+// A grid of colored cells walked by ants, each ant turns according to the color of the cell
+// it is on, moves the cell to the next color and steps forward. The edges wrap around.
+// https://en.wikipedia.org/wiki/Langton%27s_ant
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug)]
+pub struct LangtonsAnt {
+    width: u32,
+    height: u32,
+    // the color index of every cell
+    cells: Vec<u8>,
+    ants: Vec<Ant>,
+    default_turns: Vec<Turn>,
+    generation: u32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl LangtonsAnt {
+    // Rules are a string of L, R, N (no turn) and U (u-turn), one letter per color, like
+    // "RL" for the classic ant or "LLRR"
+    pub fn new(width: u32, height: u32, rule: &str) -> Result<LangtonsAnt, ParseError> {
+        Ok(Self {
+            width,
+            height,
+            cells: vec![0; (width * height) as usize],
+            ants: vec![],
+            default_turns: parse_turns(rule)?,
+            generation: 0,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    // Ants outside of the board are ignored
+    pub fn add_ant(&mut self, row: u32, column: u32, direction: Direction) {
+        let turns = self.default_turns.clone();
+        self.push_ant(row, column, direction, turns);
+    }
+
+    pub fn add_ant_with_rule(
+        &mut self,
+        row: u32,
+        column: u32,
+        direction: Direction,
+        rule: &str,
+    ) -> Result<(), ParseError> {
+        let turns = parse_turns(rule)?;
+        self.push_ant(row, column, direction, turns);
+        Ok(())
+    }
+
+    pub fn ant_count(&self) -> usize {
+        self.ants.len()
+    }
+
+    // Every ant as (row, column, direction) triples flattened into one array
+    pub fn ants(&self) -> Vec<u32> {
+        self.ants
+            .iter()
+            .flat_map(|ant| vec![ant.row, ant.column, ant.direction as u32])
+            .collect()
+    }
+
+    pub fn get_cell(&self, row: u32, column: u32) -> u8 {
+        if row < self.height && column < self.width {
+            self.cells[self.get_index(row, column)]
+        } else {
+            0
+        }
+    }
+
+    // One byte per cell with its color index, in row major order like Universe
+    pub fn cells_ptr(&self) -> *const u8 {
+        self.cells.as_ptr()
+    }
+
+    pub fn cells_len(&self) -> usize {
+        self.cells.len()
+    }
+
+    // Ants move one after the other in the order they were added
+    pub fn tick(&mut self) {
+        for ant in self.ants.iter_mut() {
+            let index = (ant.row * self.width + ant.column) as usize;
+            let color = self.cells[index] as usize;
+            ant.direction = ant.direction.turned(ant.turns[color % ant.turns.len()]);
+            self.cells[index] = ((color + 1) % ant.turns.len()) as u8;
+
+            match ant.direction {
+                Direction::Up => ant.row = (ant.row + self.height - 1) % self.height,
+                Direction::Right => ant.column = (ant.column + 1) % self.width,
+                Direction::Down => ant.row = (ant.row + 1) % self.height,
+                Direction::Left => ant.column = (ant.column + self.width - 1) % self.width,
+            }
+        }
+
+        self.generation += 1;
+    }
+
+    pub fn tick_n(&mut self, steps: u32) {
+        for _ in 0..steps {
+            self.tick();
+        }
+    }
+
+    fn get_index(&self, row: u32, column: u32) -> usize {
+        (row * self.width + column) as usize
+    }
+
+    fn push_ant(&mut self, row: u32, column: u32, direction: Direction, turns: Vec<Turn>) {
+        if row < self.height && column < self.width {
+            self.ants.push(Ant {
+                row,
+                column,
+                direction,
+                turns,
+            });
+        }
+    }
+}
+
+fn parse_turns(rule: &str) -> Result<Vec<Turn>, ParseError> {
+    let turns = rule
+        .trim()
+        .chars()
+        .enumerate()
+        .map(|(index, letter)| match letter.to_ascii_uppercase() {
+            'L' => Ok(Turn::Left),
+            'R' => Ok(Turn::Right),
+            'N' => Ok(Turn::None),
+            'U' => Ok(Turn::Back),
+            other => Err(ParseError::new(
+                1,
+                index + 1,
+                format!("'{}' is not one of L, R, N or U", other),
+            )),
+        })
+        .collect::<Result<Vec<Turn>, ParseError>>()?;
+
+    if turns.is_empty() || turns.len() > 256 {
+        return Err(ParseError::new(
+            1,
+            1,
+            "expected between 1 and 256 turns like RL",
+        ));
+    }
+
+    Ok(turns)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_classic_ant() {
+        let mut ant = LangtonsAnt::new(5, 5, "RL").unwrap();
+        ant.add_ant(2, 2, Direction::Up);
+
+        ant.tick();
+        assert_eq!(1, ant.get_cell(2, 2));
+        assert_eq!(vec![2, 3, Direction::Right as u32], ant.ants());
+
+        ant.tick_n(3);
+        // four right turns on white cells bring it back to the start facing up
+        assert_eq!(vec![2, 2, Direction::Up as u32], ant.ants());
+        assert_eq!(4, ant.generation());
+
+        ant.tick();
+        // the start cell is black now so it turns left
+        assert_eq!(0, ant.get_cell(2, 2));
+        assert_eq!(vec![2, 1, Direction::Left as u32], ant.ants());
+    }
+
+    #[test]
+    fn test_ants_with_different_rules() {
+        let mut ant = LangtonsAnt::new(4, 4, "RL").unwrap();
+        ant.add_ant(0, 3, Direction::Up);
+        ant.add_ant_with_rule(2, 2, Direction::Down, "LLRR")
+            .unwrap();
+        ant.add_ant(9, 9, Direction::Up);
+
+        ant.tick();
+
+        assert_eq!(2, ant.ant_count());
+        // the first ant wraps around the right edge
+        assert_eq!(
+            vec![0, 0, Direction::Right as u32, 2, 3, Direction::Right as u32],
+            ant.ants()
+        );
+    }
+
+    #[test]
+    fn test_rule_errors() {
+        assert_eq!(
+            ParseError::new(1, 2, "'X' is not one of L, R, N or U"),
+            LangtonsAnt::new(4, 4, "RXL").unwrap_err()
+        );
+        assert!(LangtonsAnt::new(4, 4, "").is_err());
+    }
+}
@@ -0,0 +1,287 @@
+use rand::prelude::*;
+use rand_pcg::Pcg32;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+// How the weighted sum of the neighborhood, between 0 and 1, turns into growth between
+// -1 and 1. Each is highest at mu and falls off over about sigma either side.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GrowthFunction {
+    // a bell curve, the smooth one most Lenia creatures are found with
+    Gaussian,
+    // (1 - x²)⁴ cut off at 3 sigma, close to the bell curve and cheaper
+    Polynomial,
+    // 1 within sigma of mu and -1 everywhere else, the Game of Life made continuous
+    Step,
+}
+
+impl GrowthFunction {
+    fn growth(self, potential: f32, mu: f32, sigma: f32) -> f32 {
+        let distance = (potential - mu) / sigma;
+        match self {
+            GrowthFunction::Gaussian => 2.0 * (-distance * distance / 2.0).exp() - 1.0,
+            GrowthFunction::Polynomial => {
+                let x = distance / 3.0;
+                if x.abs() < 1.0 {
+                    2.0 * (1.0 - x * x).powi(4) - 1.0
+                } else {
+                    -1.0
+                }
+            }
+            GrowthFunction::Step if distance.abs() <= 1.0 => 1.0,
+            GrowthFunction::Step => -1.0,
+        }
+    }
+}
+
+// Continuous cellular automata after Bert Chan's Lenia. Every cell holds a number from 0
+// to 1, and each tick it grows or shrinks by the growth of the weighted sum of the cells
+// within radius, where the weights are a smooth ring peaking halfway out. The edges wrap.
+// Orbium, the Lenia glider, lives at radius 13 with mu 0.15 and sigma 0.015.
+// https://chakazul.github.io/lenia.html
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug)]
+pub struct Lenia {
+    width: u32,
+    height: u32,
+    cells: Vec<f32>,
+    next: Vec<f32>,
+    radius: u32,
+    // (row offset, column offset, weight) of every cell the ring covers, weights add up to 1
+    kernel: Vec<(i32, i32, f32)>,
+    growth_function: GrowthFunction,
+    mu: f32,
+    sigma: f32,
+    time_step: f32,
+    generation: u32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Lenia {
+    // An empty board with a Gaussian growth function at mu 0.15 and sigma 0.015 and a
+    // time step of 0.1. Radius is at least 1.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(width: u32, height: u32, radius: u32) -> Self {
+        let radius = radius.max(1);
+        let cell_count = (width * height) as usize;
+
+        Self {
+            width,
+            height,
+            cells: vec![0.0; cell_count],
+            next: vec![0.0; cell_count],
+            radius,
+            kernel: ring_kernel(radius),
+            growth_function: GrowthFunction::Gaussian,
+            mu: 0.15,
+            sigma: 0.015,
+            time_step: 0.1,
+            generation: 0,
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    pub fn radius(&self) -> u32 {
+        self.radius
+    }
+
+    pub fn growth_function(&self) -> GrowthFunction {
+        self.growth_function
+    }
+
+    pub fn mu(&self) -> f32 {
+        self.mu
+    }
+
+    pub fn sigma(&self) -> f32 {
+        self.sigma
+    }
+
+    // Sigma is kept above 0
+    pub fn set_growth(&mut self, growth_function: GrowthFunction, mu: f32, sigma: f32) {
+        self.growth_function = growth_function;
+        self.mu = mu;
+        self.sigma = sigma.max(f32::EPSILON);
+    }
+
+    pub fn time_step(&self) -> f32 {
+        self.time_step
+    }
+
+    // How much of the growth is applied each tick, smaller steps are smoother and slower
+    pub fn set_time_step(&mut self, time_step: f32) {
+        self.time_step = time_step.clamp(0.001, 1.0);
+    }
+
+    // One f32 per cell from 0 to 1 in row major order, ready to upload as a float texture
+    // for a shader to color. Read it again after resizing.
+    pub fn cells_ptr(&self) -> *const f32 {
+        self.cells.as_ptr()
+    }
+
+    pub fn cells_len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn get_cell(&self, row: u32, column: u32) -> f32 {
+        if row < self.height && column < self.width {
+            self.cells[self.get_index(row, column)]
+        } else {
+            0.0
+        }
+    }
+
+    pub fn set_cell(&mut self, row: u32, column: u32, value: f32) {
+        if row < self.height && column < self.width {
+            let index = self.get_index(row, column);
+            self.cells[index] = value.clamp(0.0, 1.0);
+        }
+    }
+
+    // Random values in a size by size square in the middle of an otherwise empty board,
+    // the usual way to look for new creatures
+    pub fn randomize_seeded(&mut self, seed: u64, size: u32) {
+        let mut rng = Pcg32::seed_from_u64(seed);
+        let rows = size.min(self.height);
+        let columns = size.min(self.width);
+        let (top, left) = ((self.height - rows) / 2, (self.width - columns) / 2);
+
+        self.cells.iter_mut().for_each(|cell| *cell = 0.0);
+        for row in top..top + rows {
+            for column in left..left + columns {
+                let index = self.get_index(row, column);
+                self.cells[index] = rng.gen();
+            }
+        }
+    }
+
+    // The sum of every cell, Lenia's stand-in for population
+    pub fn mass(&self) -> f32 {
+        self.cells.iter().sum()
+    }
+
+    pub fn tick(&mut self) {
+        let (width, height) = (self.width as i32, self.height as i32);
+        for row in 0..height {
+            for column in 0..width {
+                let mut potential = 0.0;
+                for (row_offset, column_offset, weight) in self.kernel.iter() {
+                    let neighbor_row = (row + row_offset).rem_euclid(height);
+                    let neighbor_column = (column + column_offset).rem_euclid(width);
+                    potential += weight
+                        * self.cells[self.get_index(neighbor_row as u32, neighbor_column as u32)];
+                }
+
+                let index = self.get_index(row as u32, column as u32);
+                let growth = self.growth_function.growth(potential, self.mu, self.sigma);
+                self.next[index] = (self.cells[index] + self.time_step * growth).clamp(0.0, 1.0);
+            }
+        }
+
+        std::mem::swap(&mut self.cells, &mut self.next);
+        self.generation += 1;
+    }
+}
+
+impl Lenia {
+    fn get_index(&self, row: u32, column: u32) -> usize {
+        (row * self.width + column) as usize
+    }
+}
+
+// A smooth bump that is 0 at the center and at the radius and 1 halfway between
+fn ring_kernel(radius: u32) -> Vec<(i32, i32, f32)> {
+    let radius = radius as i32;
+    let mut kernel = vec![];
+    for row_offset in -radius..=radius {
+        for column_offset in -radius..=radius {
+            let distance = ((row_offset * row_offset + column_offset * column_offset) as f32)
+                .sqrt()
+                / radius as f32;
+            if distance > 0.0 && distance < 1.0 {
+                let weight = (4.0 - 1.0 / (distance * (1.0 - distance))).exp();
+                kernel.push((row_offset, column_offset, weight));
+            }
+        }
+    }
+
+    let total: f32 = kernel.iter().map(|(_, _, weight)| weight).sum();
+    for (_, _, weight) in kernel.iter_mut() {
+        *weight /= total;
+    }
+    kernel
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ring_kernel() {
+        let kernel = ring_kernel(5);
+        let total: f32 = kernel.iter().map(|(_, _, weight)| weight).sum();
+        assert!((total - 1.0).abs() < 1e-5);
+        assert!(!kernel
+            .iter()
+            .any(|(row, column, _)| (*row, *column) == (0, 0)));
+
+        // the ring is heaviest halfway out
+        let weight = |row, column| {
+            kernel
+                .iter()
+                .find(|(r, c, _)| (*r, *c) == (row, column))
+                .map(|(_, _, weight)| *weight)
+        };
+        assert!(weight(0, 3).unwrap() > weight(0, 1).unwrap());
+        assert_eq!(None, weight(0, 5));
+    }
+
+    #[test]
+    fn test_growth_functions() {
+        assert_eq!(1.0, GrowthFunction::Gaussian.growth(0.15, 0.15, 0.015));
+        assert!(GrowthFunction::Gaussian.growth(0.0, 0.15, 0.015) < -0.99);
+        assert_eq!(1.0, GrowthFunction::Polynomial.growth(0.15, 0.15, 0.015));
+        assert_eq!(-1.0, GrowthFunction::Polynomial.growth(0.3, 0.15, 0.015));
+        assert_eq!(1.0, GrowthFunction::Step.growth(0.16, 0.15, 0.015));
+        assert_eq!(-1.0, GrowthFunction::Step.growth(0.2, 0.15, 0.015));
+    }
+
+    #[test]
+    fn test_tick() {
+        // nothing grows from nothing, and a full board is too crowded to last
+        let mut lenia = Lenia::new(16, 16, 3);
+        lenia.tick();
+        assert_eq!(0.0, lenia.mass());
+
+        lenia.cells.iter_mut().for_each(|cell| *cell = 1.0);
+        lenia.tick();
+        assert!((lenia.get_cell(4, 4) - 0.9).abs() < 1e-6);
+        assert_eq!(2, lenia.generation());
+    }
+
+    #[test]
+    fn test_seeded_runs_match() {
+        let mut first = Lenia::new(24, 24, 4);
+        first.randomize_seeded(3, 10);
+        assert_eq!(0.0, first.get_cell(0, 0));
+        let mut second = first.clone();
+        for _ in 0..10 {
+            first.tick();
+            second.tick();
+        }
+
+        assert_eq!(first.cells, second.cells);
+    }
+}
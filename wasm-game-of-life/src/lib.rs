@@ -1,23 +1,135 @@
+#[macro_use]
+mod logging;
+
+mod allocator;
+mod apgcode;
+#[cfg(feature = "audio")]
+mod audio;
+mod bench;
+#[cfg(feature = "wasm")]
+mod bindings;
+mod bits;
+mod build_info;
+mod builder;
+mod census;
+mod clipboard;
+mod cycle;
+mod diff;
+mod drawing;
+mod elementary;
+mod engine;
+mod error;
+mod events;
+mod falling_sand;
+mod forest_fire;
+mod formats;
+#[cfg(feature = "wasm")]
+mod game_loop;
+mod gray_scott;
+mod history;
+#[cfg(feature = "wasm")]
+mod input;
+mod isotropic;
+mod keybindings;
+mod langtons_ant;
+mod lenia;
+mod ltl;
+mod memory;
+mod metadata;
+mod neighborhood;
+mod pattern;
+mod recorder;
+mod render;
+mod replay;
+mod rule;
+mod saves;
+#[cfg(feature = "serde")]
+mod serialization;
+mod soup;
+mod spaceships;
+mod sparse;
+mod stochastic;
+mod tiles;
+mod topology;
 mod utils;
+mod viewport;
+mod wa_tor;
+mod watch;
+mod wireworld;
 
 use std::fmt::{self, Display, Formatter};
+use std::iter::FromIterator;
+use std::ops::Index;
+
+pub use allocator::{allocator_info, AllocatorInfo};
+#[cfg(feature = "audio")]
+pub use audio::{Sonifier, SonifyMode};
+pub use bench::{bench_tick, bench_universe};
+#[cfg(feature = "wasm")]
+pub use bindings::{build_info, fetch_pattern, run_soup_search};
+use bits::CellBits;
+pub use build_info::{BuildInfo, EngineInfo};
+pub use builder::UniverseBuilder;
+pub use clipboard::{Clipboard, PasteMode};
+use cycle::CycleDetector;
+pub use diff::UniverseDiff;
+pub use drawing::BrushShape;
+pub use elementary::ElementaryAutomaton;
+pub use engine::{Engine, HashLife, UniverseEngine};
+pub use error::UniverseError;
+pub use events::UniverseEvent;
+use events::{EventCallback, EventState};
+pub use falling_sand::{FallingSand, Particle};
+pub use forest_fire::{ForestCell, ForestFire};
+pub use formats::{DecodeError, EncodeError, ParseError};
+#[cfg(feature = "wasm")]
+pub use game_loop::GameLoop;
+pub use gray_scott::{GrayScott, GrayScottPreset};
+use history::{History, Snapshot};
+#[cfg(feature = "wasm")]
+pub use input::InputController;
+pub use isotropic::IsotropicRule;
+pub use keybindings::{KeyAction, KeyBindings};
+pub use langtons_ant::{Direction, LangtonsAnt};
+pub use lenia::{GrowthFunction, Lenia};
+pub use logging::{set_log_level, LogLevel};
+pub use ltl::LtlRule;
+use memory::vec_bytes;
+pub use memory::{memory_pages, MemoryUsage, WASM_PAGE_SIZE};
+pub use metadata::PatternMetadata;
+pub use neighborhood::Neighborhood;
+pub use pattern::{Pattern, Transform};
+pub use recorder::{AnimationFormat, Recorder};
+#[cfg(feature = "wasm")]
+pub use render::{CanvasRenderer, WebGlRenderer};
+pub use render::{RenderMode, TextRenderer};
+pub use replay::{ReplayAction, ReplayEntry, ReplayLog};
+use rule::RuleTable;
+pub use rule::{GridTopology, RuleSet};
+pub use saves::SaveManager;
+pub use soup::{SoupConfig, SoupResults, SoupSearch};
+pub use spaceships::{Heading, Spaceship};
+pub use sparse::SparseUniverse;
+use stochastic::clamp_chance;
+pub use stochastic::StochasticRule;
+use tiles::TileActivity;
+pub use topology::Topology;
+pub use viewport::Viewport;
+pub use wa_tor::{WaTor, WaTorCell};
+#[cfg(all(feature = "parallel", target_arch = "wasm32"))]
+pub use wasm_bindgen_rayon::init_thread_pool;
+use watch::Watch;
+pub use wireworld::{WireCell, Wireworld};
 
 use rand::prelude::*;
+use rand_pcg::Pcg32;
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
-#[wasm_bindgen]
-extern "C" {
-    fn alert(string: &str);
-}
-
-#[wasm_bindgen]
-pub fn alert_the_page(string: &str) {
-    alert(string);
-}
-
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Cell {
     Dead = 0,
     Alive = 1,
@@ -32,426 +144,3555 @@ impl Display for Cell {
     }
 }
 
-#[wasm_bindgen]
+impl From<bool> for Cell {
+    fn from(alive: bool) -> Self {
+        if alive {
+            Cell::Alive
+        } else {
+            Cell::Dead
+        }
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn something() -> Cell {
     Cell::Dead
 }
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BoundaryMode {
+    Clamped,
+    Toroidal,
+}
+
+// Colored variants where newborn cells take the majority color of their parents
+// https://conwaylife.com/wiki/Colourised_Life
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ColorVariant {
+    Monochrome,
+    Immigration,
+    QuadLife,
+}
+
+impl ColorVariant {
+    pub fn color_count(self) -> u8 {
+        match self {
+            ColorVariant::Monochrome => 1,
+            ColorVariant::Immigration => 2,
+            ColorVariant::QuadLife => 4,
+        }
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ResizeAnchor {
+    TopLeft,
+    Center,
+}
+
+// What one of the run_until_* calls did, its fields are read only properties in JavaScript
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RunSummary {
+    pub generations_run: u32,
+    pub generation: u32,
+    pub population: u32,
+    // false when the run gave up at its generation limit
+    pub condition_met: bool,
+    // the period the board was repeating with when the run ended, if any
+    pub period: Option<u32>,
+}
+
+// Enough generations for a chart as wide as a large screen
+const DEFAULT_POPULATION_HISTORY: usize = 2048;
+
+// A trail fades to a tenth in about 20 generations
+const DEFAULT_TRAIL_DECAY: f32 = 0.9;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 #[repr(C)]
+#[derive(Debug)]
 pub struct Universe {
     width: u32,
     height: u32,
-    cells: Vec<Cell>,
+    cells: CellBits,
+    // back buffer that tick() writes the next generation into
+    next: CellBits,
+    edge_topology: Topology,
+    rule: RuleSet,
+    // a Larger than Life rule counted instead of rule's neighbors, rule then only gives
+    // the number of states
+    ltl: Option<LtlRule>,
+    // the same for a rule that looks at which neighbors are alive
+    isotropic: Option<IsotropicRule>,
+    // the chances laid over the rule and the generator rolling them, None for a rule
+    // that always does the same thing
+    stochastic: Option<(StochasticRule, Pcg32)>,
+    generation: u32,
+    births_last_tick: u32,
+    deaths_last_tick: u32,
+    history: History,
+    cycles: CycleDetector,
+    // indexes of the cells the last tick flipped
+    changed_cells: Vec<u32>,
+    // the population after each of the last population_history_capacity ticks, oldest first
+    population_history: Vec<u32>,
+    population_history_capacity: usize,
+    // how many ticks each live cell has survived, dead and newborn cells are 0
+    ages: Vec<u32>,
+    // 1.0 for live cells, dropping by trail_decay each tick after a cell dies
+    trails: Vec<f32>,
+    trail_decay: f32,
+    // the state of cells fading out under a Generations rule, 0 for every other cell
+    fading: Vec<u8>,
+    // filled in by states_ptr()
+    states: Vec<u8>,
+    // filled in by density_map(), one byte per block and density_columns blocks a row
+    density: Vec<u8>,
+    density_columns: u32,
+    color_variant: ColorVariant,
+    // the color index of every live cell
+    colors: Vec<u8>,
+    neighborhood: Neighborhood,
+    neighborhood_radius: u32,
+    // (row, column) offsets of the neighbors for the neighborhood and radius
+    neighbor_offsets: Vec<(i32, i32)>,
+    engine: Box<dyn UniverseEngine>,
+    tiles: TileActivity,
+    events: EventState,
+    // (row, column, width, height) of the only cells tick() changes, None for all of them
+    active_region: Option<(u32, u32, u32, u32)>,
+    watches: Vec<Watch>,
+    // the session so far, kept after recording stops so it can still be exported
+    replay: Option<ReplayLog>,
+    recording: bool,
+    // the name, author and comments of the pattern file it was loaded from
+    metadata: PatternMetadata,
 }
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 impl Universe {
+    // new() and new_with_dimensions() are the quick way to an empty board with the
+    // defaults, UniverseBuilder sets everything else up front
     pub fn new(size: u32) -> Self {
-        let mut cells = vec![Cell::Dead; (size * size) as usize];
+        Self::new_with_dimensions(size, size)
+    }
+
+    // Panics for boards of more than u32::MAX cells, UniverseBuilder reports those as an
+    // error instead
+    pub fn new_with_dimensions(width: u32, height: u32) -> Self {
+        utils::set_panic_hook();
+        let cell_count =
+            Self::check_dimensions(width, height).unwrap_or_else(|error| panic!("{}", error));
+        let cells = CellBits::new(cell_count);
 
         Self {
-            width: size,
-            height: size,
+            width,
+            height,
+            next: cells.clone(),
             cells,
+            edge_topology: Topology::Plane,
+            rule: RuleSet::default(),
+            ltl: None,
+            isotropic: None,
+            stochastic: None,
+            generation: 0,
+            births_last_tick: 0,
+            deaths_last_tick: 0,
+            history: History::default(),
+            cycles: CycleDetector::default(),
+            changed_cells: vec![],
+            population_history: vec![],
+            population_history_capacity: DEFAULT_POPULATION_HISTORY,
+            ages: vec![0; cell_count],
+            trails: vec![0.0; cell_count],
+            trail_decay: DEFAULT_TRAIL_DECAY,
+            fading: vec![0; cell_count],
+            states: vec![],
+            density: vec![],
+            density_columns: 0,
+            color_variant: ColorVariant::Monochrome,
+            colors: vec![0; cell_count],
+            neighborhood: Neighborhood::Moore,
+            neighborhood_radius: 1,
+            neighbor_offsets: neighborhood::offsets(Neighborhood::Moore, 1),
+            engine: Engine::Naive.create(),
+            tiles: TileActivity::default(),
+            events: EventState::default(),
+            active_region: None,
+            watches: vec![],
+            replay: None,
+            recording: false,
+            metadata: PatternMetadata::default(),
         }
     }
 
-    pub fn render(&self) -> String {
-        self.to_string()
+    // Universe::new(64).with_engine(Engine.HashLife) from JavaScript
+    pub fn with_engine(mut self, engine: Engine) -> Universe {
+        self.engine = engine.create();
+        self
     }
 
-    pub fn tick(&mut self) {
-        let mut next = self.cells.clone();
+    pub fn engine(&self) -> Engine {
+        self.engine.kind()
+    }
 
-        for row in 0..self.height {
-            for column in 0..self.width {
-                let index = self.get_index(row, column);
-                let cell = self.cells[index];
-                let live_neighbors = self.live_neighbor_count(row, column);
-                if matches!(cell, Cell::Alive) {
-                    if live_neighbors < 2 || live_neighbors > 3 {
-                        next[index] = Cell::Dead;
-                    }
-                } else {
-                    if live_neighbors == 3 {
-                        next[index] = Cell::Alive;
-                    }
-                }
-            }
-        }
+    pub fn from_rle(rle: &str) -> Result<Universe, UniverseError> {
+        formats::rle::parse(rle).map_err(|error| {
+            log!(LogLevel::Warn, "could not parse RLE: {}", error);
+            UniverseError::from(error)
+        })
+    }
 
-        self.cells = next;
+    pub fn from_plaintext(plaintext: &str) -> Result<Universe, UniverseError> {
+        formats::plaintext::parse(plaintext).map_err(|error| {
+            log!(LogLevel::Warn, "could not parse plaintext: {}", error);
+            UniverseError::from(error)
+        })
     }
 
-    pub fn randomize(&mut self) {
-        self.privately_randomize();
+    // The coordinate list format of old pattern archives, "#Life 1.06" and one "x y" per
+    // live cell
+    pub fn from_life106(life: &str) -> Result<Universe, UniverseError> {
+        formats::life106::parse(life).map_err(|error| {
+            log!(LogLevel::Warn, "could not parse Life 1.06: {}", error);
+            UniverseError::from(error)
+        })
     }
 
-    fn privately_randomize(&mut self) {
-        let mut rng = rand::thread_rng();
+    // The "#Life 1.05" format with #P blocks of * and . cells
+    pub fn from_life105(life: &str) -> Result<Universe, UniverseError> {
+        formats::life105::parse(life).map_err(|error| {
+            log!(LogLevel::Warn, "could not parse Life 1.05: {}", error);
+            UniverseError::from(error)
+        })
+    }
 
-        self.cells = self
-            .cells
+    // RLE, plaintext, Life 1.05 or Life 1.06, whichever the text looks like
+    pub fn from_pattern_file(text: &str) -> Result<Universe, UniverseError> {
+        formats::parse_any(text).map_err(|error| {
+            log!(LogLevel::Warn, "could not parse pattern: {}", error);
+            UniverseError::from(error)
+        })
+    }
+
+    // A universe just big enough for the object an apgcode names, like "xq4_153" for
+    // the glider
+    pub fn from_apgcode(apgcode: &str) -> Result<Universe, UniverseError> {
+        let cells = apgcode::decode(apgcode).map_err(|error| {
+            log!(LogLevel::Warn, "could not parse apgcode: {}", error);
+            error
+        })?;
+        let width = cells
             .iter()
-            .map(|cell| {
-                if rng.gen_range(0, 100) > 65 {
-                    Cell::Alive
-                } else {
-                    *cell
-                }
-            })
-            .collect();
+            .map(|(_, column)| column + 1)
+            .max()
+            .unwrap_or(0);
+        let height = cells.iter().map(|(row, _)| row + 1).max().unwrap_or(0);
+        let mut universe = Universe::new_with_dimensions(width, height);
+        for (row, column) in cells {
+            let index = universe.get_index(row, column);
+            universe.cells.set(index, true);
+        }
+
+        Ok(universe)
     }
 
-    fn get_index(&self, row: u32, column: u32) -> usize {
-        (row * self.width + column) as usize
+    // Reads the bytes produced by serialize() or serialize_compressed()
+    pub fn deserialize(bytes: &[u8]) -> Result<Universe, DecodeError> {
+        formats::binary::read(&formats::compressed::decompress(bytes)?)
     }
 
-    fn live_neighbor_count(&self, row: u32, column: u32) -> u8 {
-        let neighbors = vec![
-            self.get_index_above(row, column),
-            self.get_index_above_right(row, column),
-            self.get_index_right(row, column),
-            self.get_index_below_right(row, column),
-            self.get_index_below(row, column),
-            self.get_index_below_left(row, column),
-            self.get_index_left(row, column),
-            self.get_index_above_left(row, column),
-        ];
-
-        neighbors.iter().fold(0, |mut count, next| {
-            count
-                + if let Some(index) = next {
-                    self.cells[*index] as usize
-                } else {
-                    0
-                }
-        }) as u8
+    pub fn width(&self) -> u32 {
+        self.width
     }
 
-    fn get_index_above(&self, row: u32, column: u32) -> Option<usize> {
-        if row == 0 {
-            None
-        } else {
-            Some(self.get_index(row - 1, column))
-        }
+    pub fn height(&self) -> u32 {
+        self.height
     }
 
-    fn get_index_right(&self, row: u32, column: u32) -> Option<usize> {
-        if column >= self.width - 1 {
-            None
-        } else {
-            Some(self.get_index(row, column + 1))
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    pub fn population(&self) -> u32 {
+        self.cells.count_ones()
+    }
+
+    pub fn births_last_tick(&self) -> u32 {
+        self.births_last_tick
+    }
+
+    pub fn deaths_last_tick(&self) -> u32 {
+        self.deaths_last_tick
+    }
+
+    // Any of the twisted topologies count as toroidal, their edges wrap
+    pub fn boundary_mode(&self) -> BoundaryMode {
+        match self.edge_topology {
+            Topology::Plane => BoundaryMode::Clamped,
+            _ => BoundaryMode::Toroidal,
         }
     }
 
-    fn get_index_below(&self, row: u32, column: u32) -> Option<usize> {
-        if row >= self.height - 1 {
-            None
-        } else {
-            Some(self.get_index(row + 1, column))
+    pub fn edge_topology(&self) -> Topology {
+        self.edge_topology
+    }
+
+    // Only cells inside the rectangle change when the universe ticks, the rest of the board
+    // stays as it is and is still seen as neighbors by the cells along the edges of the
+    // region. The rectangle is cut down to fit on the board.
+    pub fn set_active_region(&mut self, row: u32, column: u32, width: u32, height: u32) {
+        let row = row.min(self.height);
+        let column = column.min(self.width);
+        self.active_region = Some((
+            row,
+            column,
+            width.min(self.width - column),
+            height.min(self.height - row),
+        ));
+        self.cycles.clear();
+        self.record_action(ReplayAction::SetActiveRegion {
+            row,
+            column,
+            width,
+            height,
+        });
+    }
+
+    pub fn clear_active_region(&mut self) {
+        self.active_region = None;
+        self.cycles.clear();
+        self.record_action(ReplayAction::ClearActiveRegion);
+    }
+
+    // [row, column, width, height], or nothing when the whole board is active
+    pub fn active_region(&self) -> Vec<u32> {
+        self.active_region
+            .map(|(row, column, width, height)| vec![row, column, width, height])
+            .unwrap_or_default()
+    }
+
+    pub fn set_boundary_mode(&mut self, boundary_mode: BoundaryMode) {
+        self.set_edge_topology(Topology::from(boundary_mode));
+    }
+
+    // Klein bottles and cross-surfaces join the edges with a twist and spheres join the
+    // neighboring edges, see Topology
+    pub fn set_edge_topology(&mut self, topology: Topology) {
+        self.edge_topology = topology;
+        self.cycles.clear();
+        self.record_action(ReplayAction::SetEdgeTopology { topology });
+    }
+
+    pub fn rule(&self) -> String {
+        match (self.ltl, &self.isotropic) {
+            (Some(ltl), _) => ltl.to_string(),
+            (None, Some(isotropic)) => isotropic.to_string(),
+            (None, None) => self.rule.to_string(),
         }
     }
 
-    fn get_index_left(&self, row: u32, column: u32) -> Option<usize> {
-        if column == 0 {
-            None
+    // Takes rules in B/S notation such as B3/S23 for Conway's Game of Life or B36/S23 for
+    // HighLife, and Larger than Life rules such as R5,C0,M1,S34..58,B34..45,NM for Bosco's
+    // rule. Larger than Life rules bring their own neighborhood and radius. Isotropic rules
+    // in Hensel notation such as B3/S2-i34q for tlife always look at the 8 cells around.
+    pub fn set_rule(&mut self, rule: &str) -> Result<(), UniverseError> {
+        let invalid = |error| {
+            log!(LogLevel::Warn, "could not parse rule {}: {}", rule, error);
+            UniverseError::InvalidRule {
+                rule: rule.to_owned(),
+                error,
+            }
+        };
+        if ltl::is_ltl(rule) {
+            let ltl: LtlRule = rule.parse().map_err(invalid)?;
+            self.rule = RuleSet::with_states(ltl.states());
+            self.ltl = Some(ltl);
+            self.isotropic = None;
+        } else if isotropic::is_isotropic(rule) {
+            let isotropic: IsotropicRule = rule.parse().map_err(invalid)?;
+            self.rule = RuleSet::with_states(isotropic.states());
+            self.ltl = None;
+            self.isotropic = Some(isotropic);
         } else {
-            Some(self.get_index(row, column - 1))
+            self.rule = rule.parse().map_err(invalid)?;
+            self.ltl = None;
+            self.isotropic = None;
         }
+        // fading cells from an earlier Generations rule would otherwise linger
+        for state in self.fading.iter_mut() {
+            *state = 0;
+        }
+        self.cycles.clear();
+        self.record_action(ReplayAction::SetRule {
+            rule: rule.to_owned(),
+        });
+        log!(LogLevel::Info, "rule set to {}", self.rule());
+        Ok(())
     }
 
-    fn get_index_above_right(&self, row: u32, column: u32) -> Option<usize> {
-        if row == 0 || column >= self.width - 1 {
-            None
-        } else {
-            Some(self.get_index(row - 1, column + 1))
+    pub fn stochastic_rule(&self) -> Option<StochasticRule> {
+        self.stochastic.as_ref().map(|(rule, _)| *rule)
+    }
+
+    // Lays the chances over the rule from the next tick on, starting the generator over
+    // from the rule's seed. None goes back to the rule on its own.
+    pub fn set_stochastic_rule(&mut self, rule: Option<StochasticRule>) {
+        self.stochastic = rule.map(|rule| (rule, Pcg32::seed_from_u64(rule.seed())));
+        self.cycles.clear();
+        self.record_action(match rule {
+            Some(rule) => ReplayAction::SetStochasticRule {
+                birth: rule.birth(),
+                survival: rule.survival(),
+                spontaneous_birth: rule.spontaneous_birth(),
+                seed: rule.seed(),
+            },
+            None => ReplayAction::ClearStochasticRule,
+        });
+    }
+
+    pub fn neighborhood(&self) -> Neighborhood {
+        self.neighborhood
+    }
+
+    pub fn neighborhood_radius(&self) -> u32 {
+        self.neighborhood_radius
+    }
+
+    // The radius is kept between 1 and 7 so neighbor counts fit in a byte. Rules only
+    // describe 0 to 8 neighbors, cells with more than 8 are born or survive never.
+    pub fn set_neighborhood(&mut self, neighborhood: Neighborhood, radius: u32) {
+        self.neighborhood = neighborhood;
+        self.neighborhood_radius = radius.clamp(1, 7);
+        self.neighbor_offsets = neighborhood::offsets(neighborhood, self.neighborhood_radius);
+        self.cycles.clear();
+        self.record_action(ReplayAction::SetNeighborhood {
+            neighborhood,
+            radius,
+        });
+    }
+
+    pub fn topology(&self) -> GridTopology {
+        self.rule.topology()
+    }
+
+    // The topology is part of the rule, B2/S34H is the hex version of B2/S34
+    pub fn set_topology(&mut self, topology: GridTopology) {
+        self.rule.set_topology(topology);
+        self.cycles.clear();
+        self.record_action(ReplayAction::SetTopology { topology });
+    }
+
+    // Where the center of a hex cell goes on a canvas with hexagons cell_size wide, as
+    // [x, y]. Rows are shifted so the whole board has positive coordinates.
+    pub fn hex_center(&self, row: u32, column: u32, cell_size: f64) -> Vec<f64> {
+        let radius = cell_size / 3f64.sqrt();
+        let shift = (f64::from(self.height) - 1.0 - f64::from(row)) / 2.0;
+        vec![
+            cell_size * (f64::from(column) + shift + 0.5),
+            radius * (1.5 * f64::from(row) + 1.0),
+        ]
+    }
+
+    // JavaScript can wrap this in a Uint32Array over the wasm memory buffer to read
+    // the cells without allocating a string every tick, cell `index` is bit
+    // `index % 32` of word `index / 32`
+    pub fn bits_ptr(&self) -> *const u32 {
+        self.cells.words().as_ptr()
+    }
+
+    // The number of u32 words, not the number of cells
+    pub fn bits_len(&self) -> usize {
+        self.cells.words().len()
+    }
+
+    // Only covers what the last tick changed, edits like set_cell or randomize are not
+    // included so the whole board should be redrawn after them
+    pub fn changed_cells_ptr(&self) -> *const u32 {
+        self.changed_cells.as_ptr()
+    }
+
+    pub fn changed_cells_len(&self) -> usize {
+        self.changed_cells.len()
+    }
+
+    // Appended to on every tick, once it is full the oldest population is dropped from the
+    // front. The pointer can change while the buffer grows so read it again after ticking.
+    pub fn population_history_ptr(&self) -> *const u32 {
+        self.population_history.as_ptr()
+    }
+
+    pub fn population_history_len(&self) -> usize {
+        self.population_history.len()
+    }
+
+    pub fn population_history_capacity(&self) -> usize {
+        self.population_history_capacity
+    }
+
+    pub fn set_population_history_capacity(&mut self, capacity: usize) {
+        self.population_history_capacity = capacity;
+        if self.population_history.len() > capacity {
+            let excess = self.population_history.len() - capacity;
+            self.population_history.drain(..excess);
         }
     }
 
-    fn get_index_below_right(&self, row: u32, column: u32) -> Option<usize> {
-        if row == self.height - 1 || column == self.width - 1 {
-            None
-        } else {
-            Some(self.get_index(row + 1, column + 1))
+    // The tiles the last tick looked up rather than copied because nothing near them
+    // changed, for a debug overlay. One byte per tile in row major order, 1 when awake,
+    // with tile_columns() tiles of tile_size() cells a row. The tiles at the right and
+    // bottom are cut short on boards that aren't a multiple of the tile size.
+    pub fn tile_activity_ptr(&self) -> *const u8 {
+        self.tiles.awake().as_ptr()
+    }
+
+    pub fn tile_size(&self) -> u32 {
+        tiles::TILE_SIZE
+    }
+
+    pub fn tile_columns(&self) -> u32 {
+        self.tiles.columns()
+    }
+
+    pub fn tile_rows(&self) -> u32 {
+        self.tiles.rows()
+    }
+
+    pub fn awake_tile_count(&self) -> u32 {
+        self.tiles.awake_count()
+    }
+
+    pub fn sleeping_tiles(&self) -> bool {
+        self.tiles.is_enabled()
+    }
+
+    // Only the naive engine and isotropic rules skip sleeping tiles, with no stochastic
+    // rule, active region or Generations rule in the way. Turning it off looks up every
+    // cell every tick, which is only worth it to compare the two.
+    pub fn set_sleeping_tiles(&mut self, enabled: bool) {
+        self.tiles.set_enabled(enabled);
+    }
+
+    // What the universe's buffers take up, so a page can warn before making the board or
+    // the history bigger than the browser will allow. memory_pages() has what the whole
+    // module takes.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            cell_buffers: self.cells.memory_usage()
+                + self.next.memory_usage()
+                + self.tiles.memory_usage(),
+            cell_info: vec_bytes(&self.ages)
+                + vec_bytes(&self.trails)
+                + vec_bytes(&self.fading)
+                + vec_bytes(&self.states)
+                + vec_bytes(&self.density)
+                + vec_bytes(&self.colors)
+                + vec_bytes(&self.changed_cells)
+                + vec_bytes(&self.population_history),
+            history: self.history.memory_usage(),
+            engine_cache: self.engine.memory_usage(),
         }
     }
 
-    fn get_index_below_left(&self, row: u32, column: u32) -> Option<usize> {
-        if row == self.height - 1 || column == 0 {
-            None
+    // One u32 per cell in row major order, for coloring cells by how long they have lived
+    pub fn ages_ptr(&self) -> *const u32 {
+        self.ages.as_ptr()
+    }
+
+    pub fn ages_len(&self) -> usize {
+        self.ages.len()
+    }
+
+    // One f32 per cell in row major order for RenderMode::Trails, 1.0 where a cell is
+    // alive and fading towards 0.0 where one has died
+    pub fn trails_ptr(&self) -> *const f32 {
+        self.trails.as_ptr()
+    }
+
+    pub fn trails_len(&self) -> usize {
+        self.trails.len()
+    }
+
+    pub fn trail_at(&self, row: u32, column: u32) -> f32 {
+        if self.is_in_bounds(row, column) {
+            self.trails[self.get_index(row, column)]
         } else {
-            Some(self.get_index(row + 1, column - 1))
+            0.0
         }
     }
 
-    fn get_index_above_left(&self, row: u32, column: u32) -> Option<usize> {
-        if row == 0 || column == 0 {
-            None
+    pub fn trail_decay(&self) -> f32 {
+        self.trail_decay
+    }
+
+    // How much of a trail is left after each tick, 0.0 turns trails off and values
+    // closer to 1.0 make them longer
+    pub fn set_trail_decay(&mut self, decay: f32) {
+        self.trail_decay = decay.clamp(0.0, 1.0);
+    }
+
+    // 0 is dead and 1 is alive, under a Generations rule cells that stop surviving count
+    // up through the states after that before they are dead
+    pub fn state_at(&self, row: u32, column: u32) -> u8 {
+        if !self.is_in_bounds(row, column) {
+            return 0;
+        }
+        let index = self.get_index(row, column);
+        if self.cells.get(index) {
+            1
         } else {
-            Some(self.get_index(row - 1, column - 1))
+            self.fading[index]
         }
     }
-}
 
-impl Display for Universe {
-    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        for line in self.cells.as_slice().chunks(self.width as usize) {
-            for cell in line {
-                write!(f, "{}", cell);
-            }
-            write!(f, "\n");
-        }
+    // One byte per cell with the same values as state_at(), the buffer is refreshed on
+    // every call so take a new view over it after each tick
+    pub fn states_ptr(&mut self) -> *const u8 {
+        self.states.clear();
+        let cells = &self.cells;
+        self.states
+            .extend(
+                self.fading
+                    .iter()
+                    .enumerate()
+                    .map(|(index, state)| if cells.get(index) { 1 } else { *state }),
+            );
+        self.states.as_ptr()
+    }
 
-        Ok(())
+    pub fn states_len(&self) -> usize {
+        self.fading.len()
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
+    // Shrinks the board for a minimap, each block_size by block_size square becomes one
+    // byte from 0 for empty to 255 for full. Blocks cut off by the right and bottom edges
+    // count only the cells they have. Read the result through density_map_ptr() with
+    // density_map_columns() blocks to a row.
+    pub fn density_map(&mut self, block_size: u32) {
+        let block_size = block_size.max(1);
+        let columns = self.width.div_ceil(block_size);
+        let rows = self.height.div_ceil(block_size);
+        let mut counts = vec![0u32; (columns * rows) as usize];
+        for (index, _) in self.cells.iter().enumerate().filter(|(_, alive)| *alive) {
+            let row = index as u32 / self.width;
+            let column = index as u32 % self.width;
+            counts[((row / block_size) * columns + column / block_size) as usize] += 1;
+        }
 
-    #[test]
-    fn test_get_index() {
-        let universe = Universe {
-            width: 5,
-            height: 5,
-            cells: vec![],
-        };
+        self.density_columns = columns;
+        self.density.clear();
+        for (block, count) in counts.into_iter().enumerate() {
+            let block = block as u32;
+            let block_width = block_size.min(self.width - (block % columns) * block_size);
+            let block_height = block_size.min(self.height - (block / columns) * block_size);
+            let area = u64::from(block_width * block_height);
+            // rounded up so a block with any life in it never looks empty
+            self.density
+                .push((u64::from(count) * 255).div_ceil(area) as u8);
+        }
+    }
 
-        let row = 3;
-        let column = 3;
-        let expected_result: usize = 18;
+    pub fn density_map_ptr(&self) -> *const u8 {
+        self.density.as_ptr()
+    }
 
-        assert_eq!(expected_result, universe.get_index(row, column));
+    pub fn density_map_len(&self) -> usize {
+        self.density.len()
     }
 
-    #[test]
-    fn test_get_index_above() {
-        let universe = Universe {
-            width: 5,
-            height: 5,
-            cells: vec![],
-        };
+    pub fn density_map_columns(&self) -> u32 {
+        self.density_columns
+    }
 
-        assert_eq!(13, universe.get_index_above(3, 3).unwrap());
-        assert_eq!(None, universe.get_index_above(0, 3));
+    pub fn color_variant(&self) -> ColorVariant {
+        self.color_variant
     }
 
-    #[test]
-    fn test_get_index_right() {
-        let universe = Universe {
-            width: 5,
-            height: 5,
-            cells: vec![],
-        };
+    // Existing cells keep their color index wrapped into the new number of colors
+    pub fn set_color_variant(&mut self, color_variant: ColorVariant) {
+        self.color_variant = color_variant;
+        let count = color_variant.color_count();
+        for color in self.colors.iter_mut() {
+            *color %= count;
+        }
+        self.record_action(ReplayAction::SetColorVariant { color_variant });
+    }
 
-        assert_eq!(19, universe.get_index_right(3, 3).unwrap());
-        assert_eq!(None, universe.get_index_right(3, 4));
+    // Dead cells and cells outside of the board are color 0
+    pub fn color_at(&self, row: u32, column: u32) -> u8 {
+        if self.is_in_bounds(row, column) && self.cells.get(self.get_index(row, column)) {
+            self.colors[self.get_index(row, column)]
+        } else {
+            0
+        }
     }
 
-    #[test]
-    fn test_get_index_below() {
-        let universe = Universe {
-            width: 5,
-            height: 5,
-            cells: vec![],
-        };
+    // One byte per cell, only meaningful for live cells
+    pub fn colors_ptr(&self) -> *const u8 {
+        self.colors.as_ptr()
+    }
 
-        assert_eq!(23, universe.get_index_below(3, 3).unwrap());
-        assert_eq!(None, universe.get_index_below(4, 3));
+    pub fn colors_len(&self) -> usize {
+        self.colors.len()
     }
 
-    #[test]
-    fn test_get_index_left() {
-        let universe = Universe {
-            width: 5,
-            height: 5,
-            cells: vec![],
-        };
+    // Brings the cell to life in the given color
+    pub fn paint_cell(&mut self, row: u32, column: u32, color: u8) {
+        if self.is_in_bounds(row, column) {
+            let index = self.get_index(row, column);
+            self.cells.set(index, true);
+            self.reset_cell_info_at(index);
+            self.colors[index] = color % self.color_variant.color_count();
+            self.record_action(ReplayAction::PaintCell { row, column, color });
+        }
+    }
+
+    // The number of live cells of each color
+    pub fn census(&self) -> Vec<u32> {
+        let mut census = vec![0; self.color_variant.color_count() as usize];
+        for index in self
+            .cells
+            .iter()
+            .enumerate()
+            .filter(|(_, alive)| *alive)
+            .map(|(index, _)| index)
+        {
+            census[self.colors[index] as usize] += 1;
+        }
 
-        assert_eq!(17, universe.get_index_left(3, 3).unwrap());
-        assert_eq!(None, universe.get_index_left(3, 0));
+        census
     }
 
-    #[test]
-    fn test_get_index_above_right() {
-        let universe = Universe {
-            width: 5,
-            height: 5,
-            cells: vec![],
-        };
+    // The number of ticks the cell has survived, cells outside of the board are 0
+    pub fn age_at(&self, row: u32, column: u32) -> u32 {
+        if self.is_in_bounds(row, column) {
+            self.ages[self.get_index(row, column)]
+        } else {
+            0
+        }
+    }
 
-        assert_eq!(14, universe.get_index_above_right(3, 3).unwrap());
-        assert_eq!(None, universe.get_index_above_right(1, 4));
-        assert_eq!(None, universe.get_index_above_right(0, 2));
+    pub fn render(&self) -> String {
+        let _timer = utils::Timer::new("render");
+        self.to_string()
     }
 
-    #[test]
-    fn test_get_index_below_right() {
-        let universe = Universe {
-            width: 5,
-            height: 5,
-            cells: vec![],
-        };
+    // Just the cells of visible_cells(), one byte each of 1 for alive and 0 for dead, so a
+    // huge board only copies out what is on screen
+    pub fn render_viewport(&self, viewport: &Viewport) -> Vec<u8> {
+        let (top, left, bottom, right) = viewport.visible_range(self.width, self.height);
+        let mut cells = Vec::with_capacity(((bottom - top) * (right - left)) as usize);
+        for row in top..bottom {
+            for column in left..right {
+                cells.push(self.cells.get(self.get_index(row, column)) as u8);
+            }
+        }
 
-        assert_eq!(24, universe.get_index_below_right(3, 3).unwrap());
-        assert_eq!(None, universe.get_index_below_right(3, 4));
-        assert_eq!(None, universe.get_index_below_right(4, 3));
+        cells
     }
 
-    #[test]
-    fn test_get_index_below_left() {
-        let universe = Universe {
-            width: 5,
-            height: 5,
-            cells: vec![],
-        };
+    // The name, author and comments read from the pattern file, which to_rle() and
+    // to_plaintext() write back out. Empty for universes that weren't loaded from one.
+    pub fn metadata(&self) -> PatternMetadata {
+        self.metadata.clone()
+    }
 
-        assert_eq!(22, universe.get_index_below_left(3, 3).unwrap());
-        assert_eq!(None, universe.get_index_below_left(3, 0));
-        assert_eq!(None, universe.get_index_below_left(4, 3));
+    pub fn set_metadata(&mut self, metadata: PatternMetadata) {
+        self.metadata = metadata;
     }
 
-    #[test]
-    fn test_get_index_above_left() {
-        let universe = Universe {
-            width: 5,
-            height: 5,
-            cells: vec![],
-        };
+    pub fn to_rle(&self) -> String {
+        formats::rle::write(self)
+    }
 
-        assert_eq!(12, universe.get_index_above_left(3, 3).unwrap());
-        assert_eq!(None, universe.get_index_above_left(0, 3));
-        assert_eq!(None, universe.get_index_above_left(3, 0));
+    pub fn to_plaintext(&self) -> String {
+        formats::plaintext::write(self)
     }
 
-    #[test]
-    fn test_live_neighbors_count() {
-        // [
-        //     [0, 1, 1],
-        //     [1, 0, 1],
-        //     [0, 0, 1],
-        // ]
-        let cells = vec![
-            Cell::Dead,
-            Cell::Alive,
-            Cell::Alive,
-            Cell::Alive,
-            Cell::Dead,
-            Cell::Alive,
-            Cell::Dead,
-            Cell::Dead,
-            Cell::Alive,
-        ];
-        let universe = Universe {
-            width: 3,
-            height: 3,
-            cells,
-        };
+    // A short string of letters, digits, - and _ with the board and rule in it, for
+    // sharing a board in the location.hash of a link
+    pub fn encode_to_url_fragment(&self) -> String {
+        formats::fragment::write(self)
+    }
 
-        assert_eq!(5, universe.live_neighbor_count(1, 1));
+    // Takes location.hash with or without the #
+    pub fn decode_from_url_fragment(fragment: &str) -> Result<Universe, DecodeError> {
+        formats::fragment::read(fragment)
     }
 
-    #[test]
-    fn test_tick() {
-        // [
-        //     [0, 1, 1],
-        //     [1, 0, 1],
-        //     [0, 0, 1],
-        // ]
-        let initial_cells = vec![
-            Cell::Dead,
-            Cell::Alive,
-            Cell::Alive,
-            Cell::Alive,
-            Cell::Dead,
-            Cell::Alive,
-            Cell::Dead,
-            Cell::Dead,
-            Cell::Alive,
-        ];
-        // [
-        //     [0, 1, 1],
-        //     [0, 0, 1],
-        //     [0, 1, 0],
-        // ]
-        let after_cells = vec![
-            Cell::Dead,
-            Cell::Alive,
-            Cell::Alive,
-            Cell::Dead,
-            Cell::Dead,
-            Cell::Alive,
-            Cell::Dead,
-            Cell::Alive,
-            Cell::Dead,
-        ];
-        let mut universe = Universe {
-            width: 3,
-            height: 3,
-            cells: initial_cells,
-        };
-        universe.tick();
-        assert_eq!(after_cells, universe.cells);
+    pub fn to_life106(&self) -> String {
+        formats::life106::write(self)
     }
 
-    #[test]
-    fn test_render_for_universe() {
-        // [
-        //     [0, 1, 1],
-        //     [0, 0, 1],
-        //     [0, 1, 0],
-        // ]
-        let cells = vec![
-            Cell::Dead,
-            Cell::Alive,
-            Cell::Alive,
-            Cell::Dead,
-            Cell::Dead,
-            Cell::Alive,
-            Cell::Dead,
-            Cell::Alive,
-            Cell::Dead,
-        ];
-        let mut universe = Universe {
-            width: 3,
-            height: 3,
-            cells,
-        };
-        let expected_result = "◻◼◼\n◻◻◼\n◻◼◻\n";
-        assert_eq!(expected_result, universe.render());
+    // Fails for rules the format can't hold, see life105.rs
+    pub fn to_life105(&self) -> Result<String, EncodeError> {
+        formats::life105::write(self)
     }
 
-    #[test]
-    fn test_display_for_cell() {
-        let alive_cell = Cell::Alive;
-        let dead_cell = Cell::Dead;
+    // An SVG document of the board with each cell cell_size pixels across, colors are
+    // anything SVG accepts for fill like "#000000" or "rebeccapurple"
+    pub fn to_svg(&self, cell_size: u32, alive_color: &str, dead_color: &str) -> String {
+        formats::svg::write(self, cell_size, alive_color, dead_color)
+    }
 
-        assert_eq!("◼", alive_cell.to_string());
-        assert_eq!("◻", dead_cell.to_string());
+    // A compact binary snapshot of the universe, this arrives in JavaScript as a
+    // Uint8Array whose buffer can be transferred from a Web Worker with postMessage.
+    // Larger than Life and isotropic rules don't fit in it, save slots keep those.
+    pub fn serialize(&self) -> Result<Vec<u8>, EncodeError> {
+        formats::binary::write(self)
+    }
+
+    // serialize() deflated, which is slower to make but a fraction of the size for
+    // boards that are mostly empty
+    pub fn serialize_compressed(&self) -> Result<Vec<u8>, EncodeError> {
+        Ok(formats::compressed::compress(&formats::binary::write(
+            self,
+        )?))
+    }
+
+    // Every cell of the back buffer is overwritten before the buffers are swapped, so
+    // edits made to the front buffer between ticks never leave the back buffer stale
+    pub fn tick(&mut self) {
+        let _timer = utils::Timer::new("tick");
+        // skip the clone entirely when history is turned off
+        if self.history.capacity() > 0 {
+            self.history.record(self.snapshot());
+        }
+        self.cycles.record(self.generation_hash());
+        let mut next = std::mem::take(&mut self.next);
+        // the engine is moved out while it reads the universe, boxing the zero sized
+        // placeholder doesn't allocate
+        let mut engine = std::mem::replace(&mut self.engine, Box::new(engine::NaiveEngine));
+        let mut tiles = std::mem::take(&mut self.tiles);
+        let table = self.sleepy_rule_table(engine.kind());
+        if let Some(ltl) = self.ltl {
+            ltl.tick(self, &mut next);
+        } else if let Some(table) = &table {
+            tiles.wake(self, table);
+            table.tick(self, &mut next, Some(&tiles));
+        } else if let Some(isotropic) = &self.isotropic {
+            isotropic.table().tick(self, &mut next, None);
+        } else if self.neighborhood == Neighborhood::Moore && self.neighborhood_radius == 1 {
+            engine.tick(self, &mut next);
+        } else {
+            // the other engines only know about the 8 cells around each cell
+            engine::NaiveEngine.tick(self, &mut next);
+        }
+        self.engine = engine;
+        if let Some((rule, rng)) = self.stochastic.as_mut() {
+            rule.apply(rng, &self.cells, &mut next);
+        }
+        if self.active_region.is_some() {
+            let active = self.active_cells();
+            for index in (0..self.cells.len()).filter(|index| !active(*index)) {
+                next.set(index, self.cells.get(index));
+            }
+        }
+        if self.rule.states() > 2 {
+            self.fade_cells(&mut next);
+        }
+        if self.color_variant != ColorVariant::Monochrome {
+            self.color_newborns(&next);
+        }
+        tiles.settle(self, &next, table);
+        self.tiles = tiles;
+        let (births, deaths) = self.cells.count_changes(&next);
+        self.changed_cells.clear();
+        self.changed_cells
+            .extend(self.cells.differences(&next).map(|index| index as u32));
+        let active = self.active_cells();
+        for (index, age) in self.ages.iter_mut().enumerate() {
+            if !active(index) {
+                continue;
+            }
+            *age = if self.cells.get(index) && next.get(index) {
+                age.saturating_add(1)
+            } else {
+                0
+            };
+        }
+
+        for (index, trail) in self.trails.iter_mut().enumerate() {
+            if !active(index) {
+                continue;
+            }
+            *trail = if next.get(index) {
+                1.0
+            } else {
+                *trail * self.trail_decay
+            };
+        }
+
+        self.next = std::mem::replace(&mut self.cells, next);
+        self.generation += 1;
+        self.births_last_tick = births;
+        self.deaths_last_tick = deaths;
+        self.record_population();
+        for watch in self.watches.iter_mut() {
+            let index = (watch.row * self.width + watch.column) as usize;
+            if self.cells.get(index) != self.next.get(index) {
+                watch.record(self.generation, self.cells.get(index));
+            }
+        }
+        if self.events.callback.is_some() {
+            let events = self.collect_events();
+            if let Some(EventCallback(callback)) = &self.events.callback {
+                for event in events.iter() {
+                    callback(event);
+                }
+            }
+        }
+        log!(
+            LogLevel::Debug,
+            "generation {}: {} births, {} deaths",
+            self.generation,
+            births,
+            deaths
+        );
+    }
+
+    // Some(period) once the current generation repeats one of the last max_period
+    // generations, 1 for a still life (an empty board included). Editing the board or
+    // changing the rule starts the search over. Periods past cycle::MAX_PERIOD aren't found.
+    // Under a stochastic rule nothing is sure to repeat, so there is never a cycle.
+    pub fn detect_cycle(&self, max_period: u32) -> Option<u32> {
+        if self.stochastic.is_some() {
+            return None;
+        }
+        self.cycles.period(self.generation_hash(), max_period)
+    }
+
+    // Gliders and spaceships that are out on their own, 6 numbers for each one: the
+    // Spaceship, its Heading, then the row, column, width and height of its bounding box
+    pub fn spaceships(&self) -> Vec<u32> {
+        spaceships::find_spaceships(self)
+            .iter()
+            .flat_map(|sighting| {
+                vec![
+                    sighting.spaceship as u32,
+                    sighting.heading as u32,
+                    sighting.row,
+                    sighting.column,
+                    sighting.width,
+                    sighting.height,
+                ]
+            })
+            .collect()
+    }
+
+    pub fn remove_event_callback(&mut self) {
+        self.events.callback = None;
+    }
+
+    // Keeps track of every tick that flips the cell, for finding out where a machine
+    // built on the board goes wrong. Edits aren't recorded. With a callback from
+    // on_event() a WatchedCellChanged event is also sent for each change.
+    pub fn add_watch(&mut self, row: u32, column: u32) {
+        let watched = self
+            .watches
+            .iter()
+            .any(|watch| (watch.row, watch.column) == (row, column));
+        if self.is_in_bounds(row, column) && !watched {
+            self.watches.push(Watch::new(row, column));
+        }
+    }
+
+    pub fn remove_watch(&mut self, row: u32, column: u32) {
+        self.watches
+            .retain(|watch| (watch.row, watch.column) != (row, column));
+    }
+
+    pub fn clear_watches(&mut self) {
+        self.watches.clear();
+    }
+
+    // Pairs of [row, column] for every watched cell in the order they were added
+    pub fn watches(&self) -> Vec<u32> {
+        self.watches
+            .iter()
+            .flat_map(|watch| vec![watch.row, watch.column])
+            .collect()
+    }
+
+    // Pairs of [generation, state] for the last watch::MAX_WATCH_HISTORY times the cell
+    // changed, oldest first, with 1 for alive and 0 for dead
+    pub fn watch_history(&self, row: u32, column: u32) -> Vec<u32> {
+        self.watches
+            .iter()
+            .find(|watch| (watch.row, watch.column) == (row, column))
+            .map(|watch| {
+                watch
+                    .changes
+                    .iter()
+                    .flat_map(|(generation, alive)| vec![*generation, *alive as u32])
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn history_capacity(&self) -> usize {
+        self.history.capacity()
+    }
+
+    // History is off by default, each generation kept costs one bit per cell
+    pub fn set_history_capacity(&mut self, capacity: usize) {
+        self.history.set_capacity(capacity);
+        self.record_action(ReplayAction::SetHistoryCapacity { capacity });
+    }
+
+    // Returns false when there is no earlier generation in the history
+    pub fn step_back(&mut self) -> bool {
+        match self.history.step_back(self.snapshot()) {
+            Some(snapshot) => {
+                // written down at the generation it was called at, for the replay to tick to
+                self.record_action(ReplayAction::StepBack);
+                self.restore(snapshot);
+                self.population_history.pop();
+                self.send_generation_restored();
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Replays a generation that was stepped back over, or ticks when there isn't one
+    pub fn step_forward(&mut self) {
+        match self.history.step_forward(self.snapshot()) {
+            Some(snapshot) => {
+                self.record_action(ReplayAction::StepForward);
+                self.restore(snapshot);
+                self.record_population();
+                self.send_generation_restored();
+            }
+            None => self.tick(),
+        }
+    }
+
+    // Reuses the existing buffers so a memory view JavaScript has over them stays valid
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.changed_cells.clear();
+        self.population_history.clear();
+        self.reset_cell_info();
+        self.generation = 0;
+        self.births_last_tick = 0;
+        self.deaths_last_tick = 0;
+        self.record_action(ReplayAction::Clear);
+    }
+
+    // Live cells keep their position relative to the anchor, anything that ends up
+    // outside of the new size is dropped. Sizes of more than u32::MAX cells leave the board
+    // as it is.
+    pub fn resize(&mut self, width: u32, height: u32, anchor: ResizeAnchor) {
+        let cell_count = match Self::check_dimensions(width, height) {
+            Ok(cell_count) => cell_count,
+            Err(error) => {
+                log!(LogLevel::Warn, "{}", error);
+                return;
+            }
+        };
+        let (row_offset, column_offset) = match anchor {
+            ResizeAnchor::TopLeft => (0, 0),
+            ResizeAnchor::Center => (
+                (i64::from(height) - i64::from(self.height)) / 2,
+                (i64::from(width) - i64::from(self.width)) / 2,
+            ),
+        };
+        let mut cells = CellBits::new(cell_count);
+
+        for row in 0..self.height {
+            for column in 0..self.width {
+                if !self.cells.get(self.get_index(row, column)) {
+                    continue;
+                }
+                let new_row = i64::from(row) + row_offset;
+                let new_column = i64::from(column) + column_offset;
+                if (0..i64::from(height)).contains(&new_row)
+                    && (0..i64::from(width)).contains(&new_column)
+                {
+                    cells.set((new_row * i64::from(width) + new_column) as usize, true);
+                }
+            }
+        }
+
+        self.width = width;
+        self.height = height;
+        self.active_region = None;
+        // positions move with the anchor, so watches wouldn't be on the same cells
+        self.watches.clear();
+        self.history.clear();
+        // both point at cells of the old board
+        self.changed_cells.clear();
+        self.cycles.clear();
+        self.next = CellBits::new(cells.len());
+        self.ages = vec![0; cells.len()];
+        self.trails = vec![0.0; cells.len()];
+        self.fading = vec![0; cells.len()];
+        self.colors = vec![0; cells.len()];
+        self.cells = cells;
+        self.record_action(ReplayAction::Resize {
+            width,
+            height,
+            anchor,
+        });
+    }
+
+    // An independent copy with the same cells, settings and history, for running side by
+    // side comparisons like the same soup under two rules. Each universe owns its own
+    // buffers, so memory views made over one of them don't see the other.
+    pub fn clone_universe(&self) -> Universe {
+        self.clone()
+    }
+
+    // The cells that are alive in one universe and dead in the other, for checking that
+    // two engines agree or seeing where two rules part ways
+    pub fn diff(&self, other: &Universe) -> UniverseDiff {
+        diff::diff(self, other)
+    }
+
+    // Ticks once and returns how many milliseconds it took, handy for comparing engines
+    pub fn tick_timed(&mut self) -> f64 {
+        let start = utils::now();
+        self.tick();
+        utils::now() - start
+    }
+
+    // Advances many generations in a single call across the wasm boundary and
+    // returns the population of the last one. With the HashLife engine it can jump over
+    // generations instead of ticking through them, see jump().
+    pub fn tick_n(&mut self, steps: u32) -> u32 {
+        self.advance_by(steps);
+
+        self.population()
+    }
+
+    // Ticks until the generation count reaches generation, a generation already behind
+    // the current one runs nothing. Jumps like tick_n().
+    pub fn run_until_generation(&mut self, generation: u32) -> RunSummary {
+        let generations_run = generation.saturating_sub(self.generation);
+        self.advance_by(generations_run);
+
+        RunSummary {
+            generations_run,
+            generation: self.generation,
+            population: self.population(),
+            condition_met: self.generation >= generation,
+            period: self.detect_cycle(cycle::MAX_PERIOD as u32),
+        }
+    }
+
+    // Ticks until fewer than population cells are alive or max_generations have run
+    pub fn run_until_population_below(
+        &mut self,
+        population: u32,
+        max_generations: u32,
+    ) -> RunSummary {
+        self.run_until(max_generations, |universe| {
+            universe.population() < population
+        })
+    }
+
+    // Ticks until the board settles into a still life or an oscillator with a period up
+    // to cycle::MAX_PERIOD, or max_generations have run
+    pub fn run_until_stable(&mut self, max_generations: u32) -> RunSummary {
+        self.run_until(max_generations, |universe| {
+            universe.detect_cycle(cycle::MAX_PERIOD as u32).is_some()
+        })
+    }
+
+    // Cells outside of the board are always dead
+    pub fn get_cell(&self, row: u32, column: u32) -> Cell {
+        if self.is_in_bounds(row, column) {
+            Cell::from(self.cells.get(self.get_index(row, column)))
+        } else {
+            Cell::Dead
+        }
+    }
+
+    // get_cell() for callers that want to know when they've gone past the edge
+    pub fn try_get_cell(&self, row: u32, column: u32) -> Result<Cell, UniverseError> {
+        self.check_bounds(row, column)?;
+        Ok(self.get_cell(row, column))
+    }
+
+    // Clicks from the browser can land outside of the board, those are ignored
+    pub fn toggle_cell(&mut self, row: u32, column: u32) {
+        if self.is_in_bounds(row, column) {
+            let index = self.get_index(row, column);
+            self.cells.toggle(index);
+            self.reset_cell_info_at(index);
+            self.record_action(ReplayAction::ToggleCell { row, column });
+        }
+    }
+
+    pub fn set_cell(&mut self, row: u32, column: u32, cell: Cell) {
+        if self.is_in_bounds(row, column) {
+            let index = self.get_index(row, column);
+            self.cells.set(index, cell == Cell::Alive);
+            self.reset_cell_info_at(index);
+            self.record_action(ReplayAction::SetCell {
+                row,
+                column,
+                alive: cell == Cell::Alive,
+            });
+        }
+    }
+
+    // The same as toggle_cell() and set_cell(), except a cell outside of the board is an
+    // error instead of being ignored
+    pub fn try_toggle_cell(&mut self, row: u32, column: u32) -> Result<(), UniverseError> {
+        self.check_bounds(row, column)?;
+        self.toggle_cell(row, column);
+        Ok(())
+    }
+
+    pub fn try_set_cell(&mut self, row: u32, column: u32, cell: Cell) -> Result<(), UniverseError> {
+        self.check_bounds(row, column)?;
+        self.set_cell(row, column, cell);
+        Ok(())
+    }
+
+    // Stamps the live cells of the pattern with its top left corner at row, column. Cells
+    // that fall off the edge wrap around in toroidal mode and are dropped otherwise.
+    pub fn insert_pattern(&mut self, pattern: Pattern, row: u32, column: u32) {
+        for (row_offset, column_offset) in pattern.live_cells() {
+            if let Some(index) = self.get_offset_index(row, column, row_offset, column_offset) {
+                self.cells.set(index, true);
+                self.reset_cell_info_at(index);
+            }
+        }
+        self.record_action(ReplayAction::InsertPattern {
+            pattern,
+            row,
+            column,
+            transform: Transform::Identity,
+        });
+    }
+
+    // Copies a width by height rectangle starting at row, column. Toroidal universes wrap
+    // around the edges, in clamped ones the rectangle is cut down to the part on the board.
+    // Either way it is at most the size of the board.
+    pub fn copy_region(&self, row: u32, column: u32, width: u32, height: u32) -> Clipboard {
+        let wraps = match self.edge_topology {
+            Topology::Plane => false,
+            Topology::Sphere => self.width == self.height,
+            Topology::Torus | Topology::KleinBottle | Topology::CrossSurface => true,
+        };
+        let (width, height) = if wraps {
+            (width.min(self.width), height.min(self.height))
+        } else {
+            (
+                width.min(self.width.saturating_sub(column)),
+                height.min(self.height.saturating_sub(row)),
+            )
+        };
+        let mut clipboard = Clipboard::new(width, height);
+        for row_offset in 0..height {
+            for column_offset in 0..width {
+                if let Some(index) = self.get_offset_index(row, column, row_offset, column_offset) {
+                    clipboard.set(row_offset, column_offset, self.cells.get(index));
+                }
+            }
+        }
+
+        clipboard
+    }
+
+    // Places the clipboard with its top left corner at row, column, edges are handled the
+    // same way as insert_pattern()
+    pub fn paste(&mut self, clipboard: &Clipboard, row: u32, column: u32, mode: PasteMode) {
+        for row_offset in 0..clipboard.height() {
+            for column_offset in 0..clipboard.width() {
+                let index = match self.get_offset_index(row, column, row_offset, column_offset) {
+                    Some(index) => index,
+                    None => continue,
+                };
+                let alive = clipboard.get_cell(row_offset, column_offset) == Cell::Alive;
+                match mode {
+                    PasteMode::Overwrite => self.cells.set(index, alive),
+                    PasteMode::Or if alive => self.cells.set(index, true),
+                    PasteMode::Xor if alive => self.cells.toggle(index),
+                    PasteMode::Or | PasteMode::Xor => continue,
+                }
+                self.reset_cell_info_at(index);
+            }
+        }
+        self.record_action(ReplayAction::Paste {
+            clipboard: clipboard.clone(),
+            row,
+            column,
+            mode,
+        });
+    }
+
+    // Same as insert_pattern() with the pattern turned or mirrored first
+    pub fn insert_pattern_transformed(
+        &mut self,
+        pattern: Pattern,
+        row: u32,
+        column: u32,
+        transform: Transform,
+    ) {
+        for (row_offset, column_offset) in pattern.transformed_cells(transform) {
+            if let Some(index) = self.get_offset_index(row, column, row_offset, column_offset) {
+                self.cells.set(index, true);
+                self.reset_cell_info_at(index);
+            }
+        }
+        self.record_action(ReplayAction::InsertPattern {
+            pattern,
+            row,
+            column,
+            transform,
+        });
+    }
+
+    // Turns or mirrors the cells of a region in place, keeping its top left corner. A
+    // rotated region that isn't square swaps its width and height, so it clears the old
+    // rectangle and fills in the new one.
+    pub fn transform_region(
+        &mut self,
+        row: u32,
+        column: u32,
+        width: u32,
+        height: u32,
+        transform: Transform,
+    ) {
+        let clipboard = self.copy_region(row, column, width, height);
+        self.paste(
+            &Clipboard::new(clipboard.width(), clipboard.height()),
+            row,
+            column,
+            PasteMode::Overwrite,
+        );
+        self.paste(
+            &clipboard.transformed(transform),
+            row,
+            column,
+            PasteMode::Overwrite,
+        );
+    }
+
+    // The drawing tools set every cell along the shape to `cell`, so the same calls draw
+    // and erase. Parts of a shape past the edge wrap around in toroidal mode and are
+    // dropped otherwise. Lines and circles only wrap round once, the parts of them more
+    // than a board past the edges are dropped too.
+    pub fn draw_line(
+        &mut self,
+        start_row: i32,
+        start_column: i32,
+        end_row: i32,
+        end_column: i32,
+        cell: Cell,
+    ) {
+        let points = drawing::line(
+            (i64::from(start_row), i64::from(start_column)),
+            (i64::from(end_row), i64::from(end_column)),
+            self.drawing_bounds(),
+        );
+        self.paint(&points, cell);
+        self.record_action(ReplayAction::DrawLine {
+            start_row,
+            start_column,
+            end_row,
+            end_column,
+            alive: cell == Cell::Alive,
+        });
+    }
+
+    pub fn draw_rect(&mut self, row: i32, column: i32, width: u32, height: u32, cell: Cell) {
+        let points = drawing::rectangle(
+            i64::from(row),
+            i64::from(column),
+            i64::from(width),
+            i64::from(height),
+            self.drawing_bounds(),
+        );
+        self.paint(&points, cell);
+        self.record_action(ReplayAction::DrawRect {
+            row,
+            column,
+            width,
+            height,
+            alive: cell == Cell::Alive,
+        });
+    }
+
+    pub fn fill_rect(&mut self, row: i32, column: i32, width: u32, height: u32, cell: Cell) {
+        let points = drawing::filled_rectangle(
+            i64::from(row),
+            i64::from(column),
+            i64::from(width),
+            i64::from(height),
+            self.drawing_bounds(),
+        );
+        self.paint(&points, cell);
+        self.record_action(ReplayAction::FillRect {
+            row,
+            column,
+            width,
+            height,
+            alive: cell == Cell::Alive,
+        });
+    }
+
+    pub fn draw_circle(&mut self, center_row: i32, center_column: i32, radius: u32, cell: Cell) {
+        let points = drawing::circle(
+            (i64::from(center_row), i64::from(center_column)),
+            i64::from(radius),
+            self.drawing_bounds(),
+        );
+        self.paint(&points, cell);
+        self.record_action(ReplayAction::DrawCircle {
+            center_row,
+            center_column,
+            radius,
+            alive: cell == Cell::Alive,
+        });
+    }
+
+    // Paints a blob of cells around row, column for freehand drawing, Cell::Dead erases
+    pub fn brush(&mut self, row: i32, column: i32, radius: u32, shape: BrushShape, cell: Cell) {
+        let points = drawing::brush(
+            (i64::from(row), i64::from(column)),
+            i64::from(radius),
+            shape,
+            self.drawing_bounds(),
+        );
+        self.paint(&points, cell);
+        self.record_action(ReplayAction::Brush {
+            row,
+            column,
+            radius,
+            shape,
+            alive: cell == Cell::Alive,
+        });
+    }
+
+    // About a third of the cells come alive. The seed is drawn at random and goes through
+    // randomize_seeded(), so a replay can make the same board again.
+    pub fn randomize(&mut self) {
+        let _timer = utils::Timer::new("randomize");
+        self.randomize_seeded(rand::thread_rng().gen(), 0.34);
+    }
+
+    // Replaces every cell so the same seed and probability always produce the same board
+    pub fn randomize_seeded(&mut self, seed: u64, alive_probability: f64) {
+        let mut rng = Pcg32::seed_from_u64(seed);
+        let alive_probability = clamp_chance(alive_probability);
+
+        for index in 0..self.cells.len() {
+            self.cells.set(index, rng.gen_bool(alive_probability));
+        }
+        self.reset_cell_info();
+        self.randomize_colors(&mut rng);
+        self.record_action(ReplayAction::RandomizeSeeded {
+            seed,
+            alive_probability,
+        });
+    }
+
+    // Writes down every call that changes the cells or how they evolve from now on, edits,
+    // the drawing tools, randomize(), rule and neighborhood changes, resize() and stepping
+    // through history, so the session can be exported with export_replay() and played back
+    // with Universe.replay(). Any earlier recording is thrown away.
+    pub fn start_recording(&mut self) {
+        self.replay = Some(ReplayLog::new(self));
+        self.recording = true;
+    }
+
+    pub fn stop_recording(&mut self) {
+        if let (Some(replay), true) = (&mut self.replay, self.recording) {
+            replay.end_generation = self.generation;
+        }
+        self.recording = false;
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    // Fading cells are part of a Generations pattern, two boards with the same live cells
+    // but different fading ones don't evolve the same way
+    fn generation_hash(&self) -> u64 {
+        if self.rule.states() > 2 {
+            cycle::hash(&(self.cells.words(), &self.fading))
+        } else {
+            cycle::hash(self.cells.words())
+        }
+    }
+
+    // Finding spaceships means looking at every cell, so this only runs when there is a
+    // callback to send the events to
+    fn send_generation_restored(&self) {
+        if let Some(EventCallback(callback)) = &self.events.callback {
+            callback(&UniverseEvent::GenerationRestored {
+                generation: self.generation,
+                population: self.population(),
+            });
+        }
+    }
+
+    fn collect_events(&mut self) -> Vec<UniverseEvent> {
+        let generation = self.generation;
+        let mut events = vec![UniverseEvent::GenerationCompleted {
+            generation,
+            population: self.population(),
+        }];
+
+        let period = self.detect_cycle(cycle::MAX_PERIOD as u32);
+        if let (Some(period), false) = (period, self.events.stabilized) {
+            events.push(UniverseEvent::UniverseStabilized { generation, period });
+        }
+        self.events.stabilized = period.is_some();
+
+        let extinct = self.population() == 0;
+        if extinct && !self.events.extinct {
+            events.push(UniverseEvent::PopulationZero { generation });
+        }
+        self.events.extinct = extinct;
+
+        let sightings = spaceships::find_spaceships(self);
+        if sightings.len() != self.events.spaceships {
+            events.extend(
+                sightings
+                    .iter()
+                    .map(|sighting| UniverseEvent::PatternDetected {
+                        generation,
+                        pattern: format!("{:?}", sighting.spaceship),
+                        heading: format!("{:?}", sighting.heading),
+                        row: sighting.row,
+                        column: sighting.column,
+                    }),
+            );
+        }
+        self.events.spaceships = sightings.len();
+
+        events.extend(
+            self.watches
+                .iter()
+                .filter(|watch| watch.changed_at(generation))
+                .map(|watch| UniverseEvent::WatchedCellChanged {
+                    generation,
+                    row: watch.row,
+                    column: watch.column,
+                    alive: self.cells.get(self.get_index(watch.row, watch.column)),
+                }),
+        );
+
+        events
+    }
+
+    fn record_population(&mut self) {
+        if self.population_history_capacity == 0 {
+            return;
+        }
+        if self.population_history.len() == self.population_history_capacity {
+            self.population_history.remove(0);
+        }
+        self.population_history.push(self.population());
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            cells: self.cells.clone(),
+            generation: self.generation,
+            births_last_tick: self.births_last_tick,
+            deaths_last_tick: self.deaths_last_tick,
+            fading: if self.rule.states() > 2 {
+                self.fading.clone()
+            } else {
+                vec![]
+            },
+            colors: if self.color_variant == ColorVariant::Monochrome {
+                vec![]
+            } else {
+                self.colors.clone()
+            },
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.cells = snapshot.cells;
+        self.generation = snapshot.generation;
+        self.births_last_tick = snapshot.births_last_tick;
+        self.deaths_last_tick = snapshot.deaths_last_tick;
+        // ages aren't kept in the history
+        self.reset_cell_info();
+        // kept only while the rule still has states to fade through
+        if self.rule.states() > 2 && snapshot.fading.len() == self.fading.len() {
+            self.fading.copy_from_slice(&snapshot.fading);
+        }
+        // and while there are colors, wrapped the same way set_color_variant() does
+        if self.color_variant != ColorVariant::Monochrome
+            && snapshot.colors.len() == self.colors.len()
+        {
+            let count = self.color_variant.color_count();
+            for (color, saved) in self.colors.iter_mut().zip(snapshot.colors) {
+                *color = saved % count;
+            }
+        }
+    }
+
+    // Also called for every edit, which breaks the run of generations detect_cycle() looks at
+    fn reset_cell_info(&mut self) {
+        self.cycles.clear();
+        for age in self.ages.iter_mut() {
+            *age = 0;
+        }
+        for state in self.fading.iter_mut() {
+            *state = 0;
+        }
+        for color in self.colors.iter_mut() {
+            *color = 0;
+        }
+    }
+
+    fn reset_cell_info_at(&mut self, index: usize) {
+        self.cycles.clear();
+        self.ages[index] = 0;
+        self.fading[index] = 0;
+        self.colors[index] = 0;
+    }
+
+    // Whether tick() changes the cell at an index, the closure doesn't borrow the universe
+    // so it can be used while its buffers are being updated
+    fn active_cells(&self) -> impl Fn(usize) -> bool {
+        let width = self.width.max(1) as usize;
+        let region = self.active_region;
+        move |index| match region {
+            None => true,
+            Some((row, column, region_width, region_height)) => {
+                let (cell_row, cell_column) = ((index / width) as u32, (index % width) as u32);
+                (row..row + region_height).contains(&cell_row)
+                    && (column..column + region_width).contains(&cell_column)
+            }
+        }
+    }
+
+    // Skipped for monochrome universes so seeded boards don't change
+    fn randomize_colors<R: Rng>(&mut self, rng: &mut R) {
+        let count = self.color_variant.color_count();
+        if count > 1 {
+            for color in self.colors.iter_mut() {
+                *color = rng.gen_range(0, count);
+            }
+        }
+    }
+
+    // Newborns take the most common color among their live neighbors, in QuadLife three
+    // parents that all differ give the fourth color
+    fn color_newborns(&mut self, next: &CellBits) {
+        let count = self.color_variant.color_count() as usize;
+        for row in 0..self.height {
+            for column in 0..self.width {
+                let index = self.get_index(row, column);
+                if self.cells.get(index) || !next.get(index) {
+                    continue;
+                }
+                let mut parents = [0; 4];
+                for neighbor in self.neighbor_indexes(row, column) {
+                    if self.cells.get(neighbor) {
+                        parents[self.colors[neighbor] as usize] += 1;
+                    }
+                }
+                let parents = &parents[..count];
+                let most = parents.iter().copied().max().unwrap_or(0);
+                let color = if self.color_variant == ColorVariant::QuadLife
+                    && most == 1
+                    && parents.iter().sum::<u32>() == 3
+                {
+                    parents.iter().position(|parent| *parent == 0)
+                } else {
+                    parents.iter().position(|parent| *parent == most)
+                };
+                self.colors[index] = color.unwrap_or(0) as u8;
+            }
+        }
+    }
+
+    // Live cells that die start fading, and fading cells can't be born until they are dead
+    fn fade_cells(&mut self, next: &mut CellBits) {
+        let active = self.active_cells();
+        for (index, state) in self.fading.iter_mut().enumerate() {
+            if !active(index) {
+                continue;
+            }
+            if self.cells.get(index) {
+                *state = if next.get(index) {
+                    0
+                } else {
+                    self.rule.fade(1)
+                };
+            } else if *state > 0 {
+                next.set(index, false);
+                *state = self.rule.fade(*state);
+            }
+        }
+    }
+
+    fn drawing_bounds(&self) -> drawing::Bounds {
+        drawing::Bounds::new(self.width, self.height, self.edge_topology)
+    }
+
+    // Shapes are worked out from 0, 0 so points off the top and left are negative
+    fn paint(&mut self, points: &[(i64, i64)], cell: Cell) {
+        for (row, column) in points.iter() {
+            if let Some((row, column)) =
+                self.edge_topology
+                    .map(*row, *column, self.width, self.height)
+            {
+                let index = self.get_index(row, column);
+                self.cells.set(index, cell == Cell::Alive);
+                self.reset_cell_info_at(index);
+            }
+        }
+    }
+
+    fn is_in_bounds(&self, row: u32, column: u32) -> bool {
+        row < self.height && column < self.width
+    }
+
+    fn check_bounds(&self, row: u32, column: u32) -> Result<(), UniverseError> {
+        if self.is_in_bounds(row, column) {
+            Ok(())
+        } else {
+            Err(UniverseError::OutOfBounds {
+                row,
+                column,
+                width: self.width,
+                height: self.height,
+            })
+        }
+    }
+
+    // A corner plus an offset, added up in an i64 so far off corners can't overflow
+    fn get_offset_index(
+        &self,
+        row: u32,
+        column: u32,
+        row_offset: u32,
+        column_offset: u32,
+    ) -> Option<usize> {
+        let (row, column) = self.edge_topology.map(
+            i64::from(row) + i64::from(row_offset),
+            i64::from(column) + i64::from(column_offset),
+            self.width,
+            self.height,
+        )?;
+        Some(self.get_index(row, column))
+    }
+
+    fn get_index(&self, row: u32, column: u32) -> usize {
+        row as usize * self.width as usize + column as usize
+    }
+
+    // The cells of the neighborhood that are on the board, hex grids leave out the
+    // above right and below left cells
+    fn neighbor_indexes(&self, row: u32, column: u32) -> impl Iterator<Item = usize> + '_ {
+        let topology = self.rule.topology();
+        self.neighbor_offsets
+            .iter()
+            .filter(move |(row_offset, column_offset)| {
+                topology.is_neighbor(i64::from(*row_offset), i64::from(*column_offset))
+            })
+            .filter_map(move |(row_offset, column_offset)| {
+                self.get_neighbor_index(row, column, *row_offset, *column_offset)
+            })
+    }
+
+    fn live_neighbor_count(&self, row: u32, column: u32) -> u8 {
+        if !self.is_interior(row, column, self.neighborhood_radius) {
+            return self
+                .neighbor_indexes(row, column)
+                .filter(|index| self.cells.get(*index))
+                .count() as u8;
+        }
+        // nothing to wrap or clip, so each neighbor is a fixed step from the cell's index
+        let topology = self.rule.topology();
+        let index = self.get_index(row, column) as isize;
+        let width = self.width as isize;
+        self.neighbor_offsets
+            .iter()
+            .filter(|(row_offset, column_offset)| {
+                topology == GridTopology::Square
+                    || topology.is_neighbor(i64::from(*row_offset), i64::from(*column_offset))
+            })
+            .filter(|(row_offset, column_offset)| {
+                let step = *row_offset as isize * width + *column_offset as isize;
+                self.cells.get((index + step) as usize)
+            })
+            .count() as u8
+    }
+
+    // Fills row_buffer with a byte a cell of the row, and on each end the cell past that
+    // end the way the edge topology has it. Rows past the top or bottom are whatever the
+    // topology maps them to, and dead on a plane.
+    fn read_padded_row(&self, row: i64, row_buffer: &mut Vec<u8>) {
+        let width = self.width as usize;
+        row_buffer.clear();
+        row_buffer.resize(width + 2, 0);
+        let is_alive = |row: i64, column: i64| {
+            self.edge_topology
+                .map(row, column, self.width, self.height)
+                .is_some_and(|(row, column)| self.cells.get(self.get_index(row, column)))
+                as u8
+        };
+        if row < 0 || row >= i64::from(self.height) {
+            for (column, cell) in row_buffer.iter_mut().enumerate() {
+                *cell = is_alive(row, column as i64 - 1);
+            }
+            return;
+        }
+
+        let start = row as usize * width;
+        for (chunk, cells) in row_buffer[1..=width].chunks_mut(32).enumerate() {
+            let bits = self.cells.read_bits(start + chunk * 32, cells.len());
+            for (bit, cell) in cells.iter_mut().enumerate() {
+                *cell = (bits >> bit & 1) as u8;
+            }
+        }
+        row_buffer[0] = is_alive(row, -1);
+        row_buffer[width + 1] = is_alive(row, width as i64);
+    }
+
+    // The rule table for the tick when it can leave the tiles that are asleep alone, which
+    // takes a 3 by 3 neighborhood deciding each cell with nothing else laid over the rule
+    fn sleepy_rule_table(&self, engine: Engine) -> Option<RuleTable> {
+        let moore = self.neighborhood == Neighborhood::Moore && self.neighborhood_radius == 1;
+        if !self.tiles.is_enabled()
+            || self.ltl.is_some()
+            || self.stochastic.is_some()
+            || self.active_region.is_some()
+            || self.rule.states() > 2
+        {
+            return None;
+        }
+        match &self.isotropic {
+            Some(isotropic) => Some(isotropic.table().clone()),
+            None if moore && engine == Engine::Naive => Some(RuleTable::from(self.rule)),
+            None => None,
+        }
+    }
+
+    // Whether every cell within radius of the cell is on the board, so the edge topology
+    // has no say in where its neighbors are
+    fn is_interior(&self, row: u32, column: u32, radius: u32) -> bool {
+        row >= radius
+            && column >= radius
+            && row + radius < self.height
+            && column + radius < self.width
+    }
+
+    fn get_neighbor_index(
+        &self,
+        row: u32,
+        column: u32,
+        row_offset: i32,
+        column_offset: i32,
+    ) -> Option<usize> {
+        // the topology decides where a neighbor past the edge is, if anywhere
+        let (row, column) = self.edge_topology.map(
+            i64::from(row) + i64::from(row_offset),
+            i64::from(column) + i64::from(column_offset),
+            self.width,
+            self.height,
+        )?;
+        Some(self.get_index(row, column))
+    }
+}
+
+impl Universe {
+    // Every cell index has to fit in a u32, for get_index() and for JavaScript
+    pub(crate) fn check_dimensions(width: u32, height: u32) -> Result<usize, UniverseError> {
+        let cell_count = u64::from(width) * u64::from(height);
+        if cell_count > u64::from(u32::MAX) {
+            return Err(UniverseError::InvalidConfig(format!(
+                "a {} by {} universe can't be made",
+                width, height
+            )));
+        }
+        Ok(cell_count as usize)
+    }
+
+    pub fn cells(&self) -> Vec<Cell> {
+        self.cells.iter().map(Cell::from).collect()
+    }
+
+    // Calls handler with every UniverseEvent after each tick, see on_event() for the
+    // JavaScript version. A new handler replaces the old one.
+    pub fn set_event_handler<F>(&mut self, handler: F)
+    where
+        F: Fn(&UniverseEvent) + 'static,
+    {
+        self.events = EventState {
+            callback: Some(EventCallback(Box::new(handler))),
+            ..EventState::default()
+        };
+    }
+
+    // A small board drawn as text, one string per row with # or O for live cells and _ or .
+    // for dead ones. The ◼ and ◻ from render() work too, so its output can be pasted back.
+    //
+    //     let universe = Universe::from_rows(&["_##", "#_#", "__#"])?;
+    pub fn from_rows(rows: &[&str]) -> Result<Universe, UniverseError> {
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        let mut cells = Vec::with_capacity(width * rows.len());
+        for (line, row) in rows.iter().enumerate() {
+            let length = row.chars().count();
+            if length != width {
+                return Err(ParseError::new(
+                    line + 1,
+                    1,
+                    format!("the row is {} cells long instead of {}", length, width),
+                )
+                .into());
+            }
+            for (column, character) in row.chars().enumerate() {
+                cells.push(match character {
+                    '#' | 'O' | '◼' => Cell::Alive,
+                    '_' | '.' | '◻' => Cell::Dead,
+                    _ => {
+                        return Err(ParseError::new(
+                            line + 1,
+                            column + 1,
+                            format!("unexpected character '{}'", character),
+                        )
+                        .into())
+                    }
+                });
+            }
+        }
+
+        Universe::from_cells(width as u32, rows.len() as u32, cells)
+    }
+
+    // The cells in row order, there have to be exactly width * height of them
+    pub fn from_cells<I>(width: u32, height: u32, cells: I) -> Result<Universe, UniverseError>
+    where
+        I: IntoIterator<Item = Cell>,
+    {
+        let cells: CellBits = cells.into_iter().collect();
+        if cells.len() as u64 != u64::from(width) * u64::from(height) {
+            return Err(UniverseError::InvalidConfig(format!(
+                "{} cells don't fill a {} by {} universe",
+                cells.len(),
+                width,
+                height
+            )));
+        }
+        let mut universe = Universe::new_with_dimensions(width, height);
+        universe.cells = cells;
+        Ok(universe)
+    }
+
+    // Every cell as (row, column, cell), a row at a time from the top left
+    pub fn iter_cells(&self) -> impl Iterator<Item = (u32, u32, Cell)> + '_ {
+        let width = self.width.max(1);
+        self.cells.iter().enumerate().map(move |(index, alive)| {
+            (
+                index as u32 / width,
+                index as u32 % width,
+                Cell::from(alive),
+            )
+        })
+    }
+
+    // The (row, column) of each live cell in the same order as iter_cells()
+    pub fn iter_live_cells(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.iter_cells()
+            .filter(|(_, _, cell)| *cell == Cell::Alive)
+            .map(|(row, column, _)| (row, column))
+    }
+
+    // One iterator of cells for each row, top to bottom
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = Cell> + '_> + '_ {
+        (0..self.height).map(move |row| {
+            (0..self.width)
+                .map(move |column| Cell::from(self.cells.get(self.get_index(row, column))))
+        })
+    }
+
+    // The recording so far, or the last one if recording has stopped
+    pub fn replay_log(&self) -> Option<ReplayLog> {
+        let mut replay = self.replay.clone()?;
+        if self.recording {
+            replay.end_generation = self.generation;
+        }
+        Some(replay)
+    }
+
+    fn record_action(&mut self, action: ReplayAction) {
+        if let (Some(replay), true) = (&mut self.replay, self.recording) {
+            replay.record(self.generation, action);
+        }
+    }
+
+    // Runs generations forward, jumping when it can and ticking when it can't. A jump
+    // that fails costs a pass over the board, so after one the next few generations are
+    // ticked before trying again, more of them each time it fails.
+    fn advance_by(&mut self, generations: u32) {
+        let mut remaining = generations;
+        let mut backoff = 0u32;
+        let mut ticks_before_jump = 0u32;
+        while remaining > 0 {
+            if ticks_before_jump == 0 {
+                let jumped = self.jump(remaining);
+                if jumped > 0 {
+                    remaining -= jumped;
+                    backoff = 0;
+                    continue;
+                }
+                backoff = (backoff * 2).clamp(1, 64);
+                ticks_before_jump = backoff;
+            }
+            self.tick();
+            remaining -= 1;
+            ticks_before_jump -= 1;
+        }
+    }
+
+    // Skips up to generations ahead at once when the engine can (only HashLife can) and
+    // nothing needs to see the generations in between: no history, watches or event
+    // callback, and a plain two state rule over the 8 neighbors with one color. Returns
+    // how many generations it skipped. What the skipped generations did is summed up:
+    // births, deaths and changed_cells compare both ends of the jump, ages grow by the
+    // length of the jump for cells alive at both ends, trails fade as if the cell had
+    // been dead throughout, the population history gets one entry and cycle detection
+    // starts over.
+    fn jump(&mut self, generations: u32) -> u32 {
+        let can_jump = self.ltl.is_none()
+            && self.isotropic.is_none()
+            && self.stochastic.is_none()
+            && self.active_region.is_none()
+            && self.neighborhood == Neighborhood::Moore
+            && self.neighborhood_radius == 1
+            && self.rule.states() <= 2
+            && self.color_variant == ColorVariant::Monochrome
+            && self.history.capacity() == 0
+            && self.watches.is_empty()
+            && self.events.callback.is_none();
+        if !can_jump {
+            return 0;
+        }
+
+        let mut next = std::mem::take(&mut self.next);
+        let mut engine = std::mem::replace(&mut self.engine, Box::new(engine::NaiveEngine));
+        let jumped = engine.advance(self, u64::from(generations), &mut next) as u32;
+        self.engine = engine;
+        if jumped == 0 {
+            self.next = next;
+            return 0;
+        }
+
+        let mut tiles = std::mem::take(&mut self.tiles);
+        tiles.settle(self, &next, None);
+        self.tiles = tiles;
+        let (births, deaths) = self.cells.count_changes(&next);
+        self.changed_cells.clear();
+        self.changed_cells
+            .extend(self.cells.differences(&next).map(|index| index as u32));
+        for (index, age) in self.ages.iter_mut().enumerate() {
+            *age = if self.cells.get(index) && next.get(index) {
+                age.saturating_add(jumped)
+            } else {
+                0
+            };
+        }
+        let fade = self.trail_decay.powi(jumped.min(i32::MAX as u32) as i32);
+        for (index, trail) in self.trails.iter_mut().enumerate() {
+            *trail = if next.get(index) { 1.0 } else { *trail * fade };
+        }
+
+        self.next = std::mem::replace(&mut self.cells, next);
+        self.generation = self.generation.saturating_add(jumped);
+        self.births_last_tick = births;
+        self.deaths_last_tick = deaths;
+        self.record_population();
+        self.cycles.clear();
+        log!(
+            LogLevel::Debug,
+            "jumped {} generations to {}",
+            jumped,
+            self.generation
+        );
+
+        jumped
+    }
+
+    // Ticks until condition holds, which is checked before every tick, or until
+    // max_generations have run
+    pub fn run_until<F>(&mut self, max_generations: u32, condition: F) -> RunSummary
+    where
+        F: Fn(&Universe) -> bool,
+    {
+        let mut generations_run = 0;
+        let mut condition_met = condition(self);
+        while !condition_met && generations_run < max_generations {
+            self.tick();
+            generations_run += 1;
+            condition_met = condition(self);
+        }
+
+        RunSummary {
+            generations_run,
+            generation: self.generation,
+            population: self.population(),
+            condition_met,
+            period: self.detect_cycle(cycle::MAX_PERIOD as u32),
+        }
+    }
+}
+
+// Engines only hold caches, so the copy starts with a fresh one of the same kind. An
+// event callback belongs to the universe it was given to and isn't copied.
+impl Clone for Universe {
+    fn clone(&self) -> Self {
+        Self {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.clone(),
+            next: self.next.clone(),
+            edge_topology: self.edge_topology,
+            rule: self.rule,
+            ltl: self.ltl,
+            isotropic: self.isotropic.clone(),
+            stochastic: self.stochastic.clone(),
+            generation: self.generation,
+            births_last_tick: self.births_last_tick,
+            deaths_last_tick: self.deaths_last_tick,
+            history: self.history.clone(),
+            cycles: self.cycles.clone(),
+            changed_cells: self.changed_cells.clone(),
+            population_history: self.population_history.clone(),
+            population_history_capacity: self.population_history_capacity,
+            ages: self.ages.clone(),
+            trails: self.trails.clone(),
+            trail_decay: self.trail_decay,
+            fading: self.fading.clone(),
+            states: self.states.clone(),
+            density: self.density.clone(),
+            density_columns: self.density_columns,
+            color_variant: self.color_variant,
+            colors: self.colors.clone(),
+            neighborhood: self.neighborhood,
+            neighborhood_radius: self.neighborhood_radius,
+            neighbor_offsets: self.neighbor_offsets.clone(),
+            engine: self.engine.kind().create(),
+            tiles: self.tiles.clone(),
+            events: EventState::default(),
+            active_region: self.active_region,
+            watches: self.watches.clone(),
+            replay: self.replay.clone(),
+            recording: self.recording,
+            metadata: self.metadata.clone(),
+        }
+    }
+}
+
+// For tests that would rather panic than unwrap, see from_rows()
+impl From<&[&str]> for Universe {
+    fn from(rows: &[&str]) -> Self {
+        Universe::from_rows(rows).unwrap_or_else(|error| panic!("{}", error))
+    }
+}
+
+// Collects rows of cells, such as the ones rows() gives back. Every row has to be as long
+// as the first one.
+impl<R> FromIterator<R> for Universe
+where
+    R: IntoIterator<Item = Cell>,
+{
+    fn from_iter<I: IntoIterator<Item = R>>(rows: I) -> Self {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = vec![];
+        for row in rows {
+            let start = cells.len();
+            cells.extend(row);
+            let length = cells.len() - start;
+            assert_eq!(
+                *width.get_or_insert(length),
+                length,
+                "row {} is a different length than the ones before it",
+                height
+            );
+            height += 1;
+        }
+
+        Universe::from_cells(width.unwrap_or(0) as u32, height, cells)
+            .expect("the rows fill the universe")
+    }
+}
+
+// universe[(row, column)] panics outside of the board like slices do, get_cell() is the
+// forgiving version
+impl Index<(u32, u32)> for Universe {
+    type Output = Cell;
+
+    fn index(&self, (row, column): (u32, u32)) -> &Cell {
+        assert!(
+            self.is_in_bounds(row, column),
+            "row {}, column {} is outside of the {} by {} universe",
+            row,
+            column,
+            self.width,
+            self.height
+        );
+        if self.cells.get(self.get_index(row, column)) {
+            &Cell::Alive
+        } else {
+            &Cell::Dead
+        }
+    }
+}
+
+impl Display for Universe {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        // boards 0 cells wide have no cells to split into rows
+        for line in self.cells().chunks(self.width.max(1) as usize) {
+            for cell in line {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_get_index() {
+        let universe = Universe::new_with_dimensions(5, 5);
+
+        let row = 3;
+        let column = 3;
+        let expected_result: usize = 18;
+
+        assert_eq!(expected_result, universe.get_index(row, column));
+    }
+
+    #[test]
+    fn test_get_index_above() {
+        let universe = Universe::new_with_dimensions(5, 5);
+
+        assert_eq!(13, universe.get_neighbor_index(3, 3, -1, 0).unwrap());
+        assert_eq!(None, universe.get_neighbor_index(0, 3, -1, 0));
+    }
+
+    #[test]
+    fn test_get_index_right() {
+        let universe = Universe::new_with_dimensions(5, 5);
+
+        assert_eq!(19, universe.get_neighbor_index(3, 3, 0, 1).unwrap());
+        assert_eq!(None, universe.get_neighbor_index(3, 4, 0, 1));
+    }
+
+    #[test]
+    fn test_get_index_below() {
+        let universe = Universe::new_with_dimensions(5, 5);
+
+        assert_eq!(23, universe.get_neighbor_index(3, 3, 1, 0).unwrap());
+        assert_eq!(None, universe.get_neighbor_index(4, 3, 1, 0));
+    }
+
+    #[test]
+    fn test_get_index_left() {
+        let universe = Universe::new_with_dimensions(5, 5);
+
+        assert_eq!(17, universe.get_neighbor_index(3, 3, 0, -1).unwrap());
+        assert_eq!(None, universe.get_neighbor_index(3, 0, 0, -1));
+    }
+
+    #[test]
+    fn test_get_index_above_right() {
+        let universe = Universe::new_with_dimensions(5, 5);
+
+        assert_eq!(14, universe.get_neighbor_index(3, 3, -1, 1).unwrap());
+        assert_eq!(None, universe.get_neighbor_index(1, 4, -1, 1));
+        assert_eq!(None, universe.get_neighbor_index(0, 2, -1, 1));
+    }
+
+    #[test]
+    fn test_get_index_below_right() {
+        let universe = Universe::new_with_dimensions(5, 5);
+
+        assert_eq!(24, universe.get_neighbor_index(3, 3, 1, 1).unwrap());
+        assert_eq!(None, universe.get_neighbor_index(3, 4, 1, 1));
+        assert_eq!(None, universe.get_neighbor_index(4, 3, 1, 1));
+    }
+
+    #[test]
+    fn test_get_index_below_left() {
+        let universe = Universe::new_with_dimensions(5, 5);
+
+        assert_eq!(22, universe.get_neighbor_index(3, 3, 1, -1).unwrap());
+        assert_eq!(None, universe.get_neighbor_index(3, 0, 1, -1));
+        assert_eq!(None, universe.get_neighbor_index(4, 3, 1, -1));
+    }
+
+    #[test]
+    fn test_get_index_above_left() {
+        let universe = Universe::new_with_dimensions(5, 5);
+
+        assert_eq!(12, universe.get_neighbor_index(3, 3, -1, -1).unwrap());
+        assert_eq!(None, universe.get_neighbor_index(0, 3, -1, -1));
+        assert_eq!(None, universe.get_neighbor_index(3, 0, -1, -1));
+    }
+
+    #[test]
+    fn test_live_neighbors_count() {
+        let universe = Universe::from(&["_##", "#_#", "__#"][..]);
+
+        assert_eq!(5, universe.live_neighbor_count(1, 1));
+    }
+
+    #[test]
+    fn test_tick() {
+        let mut universe = Universe::from_rows(&["_##", "#_#", "__#"]).unwrap();
+        let after = Universe::from_rows(&["_##", "__#", "_#_"]).unwrap();
+        universe.tick();
+        assert_eq!(after.cells(), universe.cells());
+    }
+
+    #[test]
+    fn test_render_for_universe() {
+        let universe = Universe::from_rows(&["_##", "__#", "_#_"]).unwrap();
+        let expected_result = "◻◼◼\n◻◻◼\n◻◼◻\n";
+        assert_eq!(expected_result, universe.render());
+    }
+
+    #[test]
+    fn test_display_for_cell() {
+        let alive_cell = Cell::Alive;
+        let dead_cell = Cell::Dead;
+
+        assert_eq!("◼", alive_cell.to_string());
+        assert_eq!("◻", dead_cell.to_string());
     }
 
     #[test]
     fn test_new_universe() {
         let universe = Universe::new(3);
 
-        assert_eq!(3, universe.width);
-        assert_eq!(3, universe.height);
+        assert_eq!(3, universe.width);
+        assert_eq!(3, universe.height);
+        assert_eq!(
+            vec![
+                Cell::Dead,
+                Cell::Dead,
+                Cell::Dead,
+                Cell::Dead,
+                Cell::Dead,
+                Cell::Dead,
+                Cell::Dead,
+                Cell::Dead,
+                Cell::Dead,
+            ],
+            universe.cells()
+        );
+    }
+
+    #[test]
+    fn test_bits_ptr_and_len() {
+        let mut universe = Universe::new_with_dimensions(8, 5);
+        universe.set_cell(0, 4, Cell::Alive);
+        universe.set_cell(4, 1, Cell::Alive);
+
+        let words = unsafe { std::slice::from_raw_parts(universe.bits_ptr(), universe.bits_len()) };
+
+        assert_eq!(2, universe.bits_len());
+        assert_eq!(&[1 << 4, 1 << 1], words);
+    }
+
+    #[test]
+    fn test_new_universe_with_dimensions() {
+        let universe = Universe::new_with_dimensions(4, 2);
+
+        assert_eq!(4, universe.width);
+        assert_eq!(2, universe.height);
+        assert_eq!(vec![Cell::Dead; 8], universe.cells());
+        assert_eq!("◻◻◻◻\n◻◻◻◻\n", universe.render());
+        assert_eq!("", Universe::new_with_dimensions(0, 3).render());
+    }
+
+    #[test]
+    #[should_panic(expected = "a 65536 by 65537 universe can't be made")]
+    fn test_new_universe_with_too_many_cells() {
+        Universe::new_with_dimensions(65536, 65537);
+    }
+
+    #[test]
+    fn test_toroidal_neighbor_indexes() {
+        let mut universe = Universe::new_with_dimensions(5, 4);
+        universe.set_boundary_mode(BoundaryMode::Toroidal);
+
+        assert_eq!(Some(15), universe.get_neighbor_index(0, 0, -1, 0));
+        assert_eq!(Some(5), universe.get_neighbor_index(1, 4, 0, 1));
+        assert_eq!(Some(2), universe.get_neighbor_index(3, 2, 1, 0));
+        assert_eq!(Some(9), universe.get_neighbor_index(1, 0, 0, -1));
+        assert_eq!(Some(15), universe.get_neighbor_index(0, 4, -1, 1));
+        assert_eq!(Some(0), universe.get_neighbor_index(3, 4, 1, 1));
+        assert_eq!(Some(4), universe.get_neighbor_index(3, 0, 1, -1));
+        assert_eq!(Some(19), universe.get_neighbor_index(0, 0, -1, -1));
+    }
+
+    #[test]
+    fn test_klein_bottle_neighbor_indexes() {
+        let mut universe = Universe::new_with_dimensions(5, 4);
+        universe.set_edge_topology(Topology::KleinBottle);
+
+        // the row above row 0 is row 3 mirrored, the sides wrap the same as a torus
+        assert_eq!(Some(19), universe.get_neighbor_index(0, 0, -1, 0));
+        assert_eq!(Some(16), universe.get_neighbor_index(0, 2, -1, 1));
+        assert_eq!(Some(5), universe.get_neighbor_index(1, 4, 0, 1));
+        assert_eq!(BoundaryMode::Toroidal, universe.boundary_mode());
+
+        universe.set_boundary_mode(BoundaryMode::Clamped);
+        assert_eq!(Topology::Plane, universe.edge_topology());
+    }
+
+    #[test]
+    fn test_interior_counts_match_the_edges() {
+        // cells away from the edges skip the topology, which has to come out the same
+        let mut universe = Universe::new_with_dimensions(12, 9);
+        universe.set_edge_topology(Topology::Torus);
+        universe.randomize_seeded(6, 0.5);
+        for (neighborhood, radius) in
+            [(Neighborhood::Moore, 1), (Neighborhood::VonNeumann, 3)].iter()
+        {
+            universe.set_neighborhood(*neighborhood, *radius);
+            for row in 0..universe.height() {
+                for column in 0..universe.width() {
+                    let counted = universe
+                        .neighbor_indexes(row, column)
+                        .filter(|index| universe.cells.get(*index))
+                        .count() as u8;
+                    assert_eq!(counted, universe.live_neighbor_count(row, column));
+                }
+            }
+        }
+
+        // the row above the top of a torus is the bottom row, with its ends wrapped too
+        let mut row_buffer = vec![];
+        universe.read_padded_row(-1, &mut row_buffer);
+        let bottom = universe.cells()[8 * 12..].to_vec();
+        assert_eq!(14, row_buffer.len());
+        assert_eq!(bottom[11] as u8, row_buffer[0]);
+        assert_eq!(bottom[0] as u8, row_buffer[1]);
+        assert_eq!(bottom[0] as u8, row_buffer[13]);
+    }
+
+    #[test]
+    fn test_memory_usage() {
+        let mut universe = Universe::new(64).with_engine(Engine::HashLife);
+        let usage = universe.memory_usage();
+        // 128 words of cells in each buffer
+        assert!(usage.cell_buffers >= 2 * 512);
+        assert_eq!(0, usage.history);
+        let fresh_cache = usage.engine_cache;
+
+        universe.set_history_capacity(4);
+        universe.randomize_seeded(1, 0.3);
+        universe.tick_n(6);
+        let usage = universe.memory_usage();
+        assert!(usage.history >= 4 * 512);
+        assert!(usage.engine_cache > fresh_cache);
+        assert_eq!(
+            usage.cell_buffers + usage.cell_info + usage.history + usage.engine_cache,
+            usage.total()
+        );
+        assert_eq!(0, memory_pages());
+    }
+
+    #[test]
+    fn test_toroidal_glider_wraps_around() {
+        let mut universe = Universe::new(6);
+        universe.set_boundary_mode(BoundaryMode::Toroidal);
+        // glider heading down and to the right
+        for (row, column) in [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)].iter() {
+            let index = universe.get_index(*row, *column);
+            universe.cells.set(index, true);
+        }
+        let initial_cells = universe.cells();
+
+        // a glider moves one cell diagonally every 4 generations, so after
+        // 4 * 6 generations it has crossed the whole board back to the start
+        for _ in 0..24 {
+            universe.tick();
+        }
+
+        assert_eq!(initial_cells, universe.cells());
+    }
+
+    #[test]
+    fn test_toggle_cell() {
+        let mut universe = Universe::new_with_dimensions(3, 2);
+
+        universe.toggle_cell(1, 2);
+        assert_eq!(Cell::Alive, universe.get_cell(1, 2));
+        universe.toggle_cell(1, 2);
+        assert_eq!(Cell::Dead, universe.get_cell(1, 2));
+
+        universe.toggle_cell(2, 0);
+        universe.toggle_cell(0, 3);
+        assert_eq!(vec![Cell::Dead; 6], universe.cells());
+    }
+
+    #[test]
+    fn test_set_cell() {
+        let mut universe = Universe::new_with_dimensions(3, 2);
+
+        universe.set_cell(0, 1, Cell::Alive);
+        universe.set_cell(0, 1, Cell::Alive);
+        assert_eq!(Cell::Alive, universe.get_cell(0, 1));
+        universe.set_cell(0, 1, Cell::Dead);
+        assert_eq!(Cell::Dead, universe.get_cell(0, 1));
+
+        universe.set_cell(5, 5, Cell::Alive);
+        assert_eq!(vec![Cell::Dead; 6], universe.cells());
+    }
+
+    #[test]
+    fn test_tick_after_editing_between_ticks() {
+        let mut universe = Universe::new(4);
+        // blinker
+        universe.set_cell(1, 0, Cell::Alive);
+        universe.set_cell(1, 1, Cell::Alive);
+        universe.set_cell(1, 2, Cell::Alive);
+        universe.tick();
+
+        // turn the vertical blinker into a 2x2 block which is a still life
+        universe.set_cell(2, 1, Cell::Dead);
+        universe.set_cell(0, 2, Cell::Alive);
+        universe.set_cell(1, 2, Cell::Alive);
+        let block = universe.cells();
+        universe.tick();
+        assert_eq!(block, universe.cells());
+        universe.tick();
+        assert_eq!(block, universe.cells());
+    }
+
+    #[test]
+    fn test_insert_pattern() {
+        let mut universe = Universe::new(4);
+        universe.insert_pattern(Pattern::Glider, 1, 1);
+
+        assert_eq!("◻◻◻◻\n◻◻◼◻\n◻◻◻◼\n◻◼◼◼\n", universe.render());
+    }
+
+    #[test]
+    fn test_insert_pattern_respects_boundaries() {
+        let mut universe = Universe::new(3);
+        universe.insert_pattern(Pattern::Glider, 1, 1);
+
+        assert_eq!("◻◻◻\n◻◻◼\n◻◻◻\n", universe.render());
+
+        let mut universe = Universe::new(3);
+        universe.set_boundary_mode(BoundaryMode::Toroidal);
+        universe.insert_pattern(Pattern::Glider, 1, 1);
+
+        assert_eq!("◼◼◼\n◻◻◼\n◼◻◻\n", universe.render());
+    }
+
+    #[test]
+    fn test_copy_region() {
+        let mut universe = Universe::new(5);
+        universe.insert_pattern(Pattern::Glider, 1, 1);
+
+        let clipboard = universe.copy_region(1, 1, 3, 3);
+        assert_eq!("◻◼◻\n◻◻◼\n◼◼◼\n", clipboard.render());
+        assert_eq!(5, clipboard.population());
+
+        // a clamped edge cuts the copy short
+        let clipboard = universe.copy_region(3, 3, 3, 3);
+        assert_eq!("◼◻\n◻◻\n", clipboard.render());
+        let clipboard = universe.copy_region(u32::MAX, 2, u32::MAX, u32::MAX);
+        assert_eq!((3, 0), (clipboard.width(), clipboard.height()));
+
+        universe.set_boundary_mode(BoundaryMode::Toroidal);
+        let clipboard = universe.copy_region(u32::MAX, u32::MAX, u32::MAX, u32::MAX);
+        assert_eq!(
+            (5, 5, 5),
+            (
+                clipboard.width(),
+                clipboard.height(),
+                clipboard.population()
+            )
+        );
+    }
+
+    #[test]
+    fn test_paste_modes() {
+        let mut source = Universe::new(3);
+        source.insert_pattern(Pattern::Blinker, 1, 0);
+        let clipboard = source.copy_region(0, 0, 3, 3);
+
+        let pasted = |mode| {
+            let mut universe = Universe::new(3);
+            universe.set_cell(0, 0, Cell::Alive);
+            universe.set_cell(1, 1, Cell::Alive);
+            universe.paste(&clipboard, 0, 0, mode);
+            universe.render()
+        };
+
+        assert_eq!("◻◻◻\n◼◼◼\n◻◻◻\n", pasted(PasteMode::Overwrite));
+        assert_eq!("◼◻◻\n◼◼◼\n◻◻◻\n", pasted(PasteMode::Or));
+        assert_eq!("◼◻◻\n◼◻◼\n◻◻◻\n", pasted(PasteMode::Xor));
+    }
+
+    #[test]
+    fn test_paste_wraps_in_toroidal_mode() {
+        let mut source = Universe::new(3);
+        source.insert_pattern(Pattern::Blinker, 0, 0);
+        let clipboard = source.copy_region(0, 0, 3, 1);
+
+        let mut universe = Universe::new(3);
+        universe.set_boundary_mode(BoundaryMode::Toroidal);
+        universe.paste(&clipboard, 2, 2, PasteMode::Or);
+
+        assert_eq!("◻◻◻\n◻◻◻\n◼◼◼\n", universe.render());
+
+        // far off corners wrap around instead of overflowing
+        let mut universe = Universe::new(3);
+        universe.set_boundary_mode(BoundaryMode::Toroidal);
+        universe.paste(&clipboard, u32::MAX, u32::MAX, PasteMode::Or);
+        universe.insert_pattern(Pattern::Blinker, u32::MAX, u32::MAX);
+        assert_eq!(3, universe.population());
+    }
+
+    #[test]
+    fn test_insert_pattern_transformed() {
+        let mut universe = Universe::new(5);
+        universe.insert_pattern_transformed(Pattern::Blinker, 1, 2, Transform::RotateClockwise);
+
+        assert_eq!("◻◻◻◻◻\n◻◻◼◻◻\n◻◻◼◻◻\n◻◻◼◻◻\n◻◻◻◻◻\n", universe.render());
+    }
+
+    #[test]
+    fn test_transform_region() {
+        let mut universe = Universe::new(4);
+        universe.insert_pattern(Pattern::Blinker, 0, 0);
+        universe.set_cell(3, 3, Cell::Alive);
+        universe.transform_region(0, 0, 3, 1, Transform::RotateCounterClockwise);
+
+        // the cell outside of the region is left alone
+        assert_eq!("◼◻◻◻\n◼◻◻◻\n◼◻◻◻\n◻◻◻◼\n", universe.render());
+    }
+
+    #[test]
+    fn test_detect_cycle() {
+        let mut universe = Universe::new(6);
+        universe.insert_pattern(Pattern::Blinker, 2, 1);
+        assert_eq!(None, universe.detect_cycle(10));
+
+        universe.tick();
+        assert_eq!(None, universe.detect_cycle(10));
+        universe.tick();
+        assert_eq!(Some(2), universe.detect_cycle(10));
+        assert_eq!(None, universe.detect_cycle(1));
+
+        universe.toggle_cell(0, 0);
+        assert_eq!(None, universe.detect_cycle(10));
+    }
+
+    #[test]
+    fn test_detect_still_life() {
+        let mut universe = Universe::new(6);
+        universe.insert_pattern(Pattern::Blinker, 0, 0);
+        universe.tick_n(3);
+
+        // a blinker against the edge turns into a domino and dies off
+        assert_eq!(0, universe.population());
+        assert_eq!(Some(1), universe.detect_cycle(100));
+    }
+
+    #[test]
+    fn test_spaceships() {
+        let mut universe = Universe::new(8);
+        universe.insert_pattern(Pattern::Glider, 0, 0);
+
+        assert_eq!(
+            vec![
+                Spaceship::Glider as u32,
+                Heading::SouthEast as u32,
+                0,
+                0,
+                3,
+                3
+            ],
+            universe.spaceships()
+        );
+        universe.clear();
+        assert!(universe.spaceships().is_empty());
+    }
+
+    #[test]
+    fn test_population_history() {
+        let mut universe = Universe::new(6);
+        universe.insert_pattern(Pattern::Blinker, 0, 1);
+        universe.set_population_history_capacity(3);
+        universe.set_history_capacity(5);
+        assert_eq!(0, universe.population_history_len());
+
+        // a blinker against the edge turns into a domino and dies off
+        universe.tick_n(2);
+        assert_eq!(vec![2, 0], universe.population_history);
+        universe.tick_n(2);
+        assert_eq!(vec![0, 0, 0], universe.population_history);
+        assert_eq!(3, universe.population_history_len());
+
+        universe.step_back();
+        universe.step_back();
+        universe.step_back();
+        assert!(universe.population_history.is_empty());
+        // back to generation 0 and forward to generation 1
+        universe.step_back();
+        universe.step_forward();
+        assert_eq!(vec![2], universe.population_history);
+
+        universe.tick();
+        universe.set_population_history_capacity(1);
+        assert_eq!(vec![0], universe.population_history);
+        universe.clear();
+        assert_eq!(0, universe.population_history_len());
+    }
+
+    #[test]
+    fn test_collect_events() {
+        let mut universe = Universe::new(12);
+        universe.insert_pattern(Pattern::Glider, 0, 0);
+        // turns into a domino against the edge
+        universe.insert_pattern(Pattern::Blinker, 0, 9);
+        universe.tick();
+
+        let events = universe.collect_events();
+        assert_eq!(
+            UniverseEvent::GenerationCompleted {
+                generation: 1,
+                population: 7
+            },
+            events[0]
+        );
+        assert_eq!(
+            UniverseEvent::PatternDetected {
+                generation: 1,
+                pattern: "Glider".to_string(),
+                heading: "SouthEast".to_string(),
+                row: 1,
+                column: 0
+            },
+            events[1]
+        );
+        assert_eq!(2, events.len());
+
+        // the spaceship count hasn't changed so only the generation is reported
+        universe.tick();
+        assert_eq!(1, universe.collect_events().len());
+    }
+
+    #[test]
+    fn test_serialize_compressed() {
+        let mut universe = Universe::new(128);
+        universe.insert_pattern(Pattern::Glider, 64, 64);
+        let compressed = universe.serialize_compressed().unwrap();
+        assert!(compressed.len() < universe.serialize().unwrap().len() / 10);
+
+        let copy = Universe::deserialize(&compressed).unwrap();
+        assert_eq!(universe.cells(), copy.cells());
+        let copy = Universe::deserialize(&universe.serialize().unwrap()).unwrap();
+        assert_eq!(universe.cells(), copy.cells());
+    }
+
+    #[test]
+    fn test_step_events() {
+        let events = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let mut universe = Universe::new(6);
+        universe.set_history_capacity(4);
+        universe.insert_pattern(Pattern::Blinker, 2, 1);
+        let sent = events.clone();
+        universe.set_event_handler(move |event| sent.lock().unwrap().push(event.clone()));
+
+        universe.step_forward();
+        universe.step_back();
+        universe.step_forward();
+        let events = events.lock().unwrap();
+        let generations = events
+            .iter()
+            .filter_map(|event| match event {
+                UniverseEvent::GenerationCompleted { generation, .. } => {
+                    Some(("ticked", *generation))
+                }
+                UniverseEvent::GenerationRestored { generation, .. } => {
+                    Some(("restored", *generation))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![("ticked", 1), ("restored", 0), ("restored", 1)],
+            generations
+        );
+    }
+
+    #[test]
+    fn test_collect_events_once_stable() {
+        let mut universe = Universe::new(6);
+        universe.insert_pattern(Pattern::Blinker, 0, 1);
+        universe.tick_n(3);
+
         assert_eq!(
             vec![
-                Cell::Dead,
-                Cell::Dead,
-                Cell::Dead,
-                Cell::Dead,
-                Cell::Dead,
-                Cell::Dead,
-                Cell::Dead,
-                Cell::Dead,
-                Cell::Dead,
+                UniverseEvent::GenerationCompleted {
+                    generation: 3,
+                    population: 0
+                },
+                UniverseEvent::UniverseStabilized {
+                    generation: 3,
+                    period: 1
+                },
+                UniverseEvent::PopulationZero { generation: 3 },
             ],
-            universe.cells
+            universe.collect_events()
+        );
+        universe.tick();
+        assert_eq!(1, universe.collect_events().len());
+    }
+
+    #[test]
+    fn test_collect_events_for_watches() {
+        let mut universe = Universe::new(6);
+        universe.insert_pattern(Pattern::Blinker, 2, 1);
+        universe.add_watch(1, 2);
+        universe.add_watch(2, 2);
+        universe.tick();
+
+        let events = universe.collect_events();
+        assert_eq!(
+            Some(&UniverseEvent::WatchedCellChanged {
+                generation: 1,
+                row: 1,
+                column: 2,
+                alive: true
+            }),
+            events.last()
+        );
+        assert_eq!(2, events.len());
+    }
+
+    #[test]
+    fn test_drawing_tools() {
+        let mut universe = Universe::new(5);
+        universe.draw_line(0, 0, 4, 4, Cell::Alive);
+        assert_eq!("◼◻◻◻◻\n◻◼◻◻◻\n◻◻◼◻◻\n◻◻◻◼◻\n◻◻◻◻◼\n", universe.render());
+
+        universe.clear();
+        universe.draw_rect(1, 1, 3, 3, Cell::Alive);
+        assert_eq!("◻◻◻◻◻\n◻◼◼◼◻\n◻◼◻◼◻\n◻◼◼◼◻\n◻◻◻◻◻\n", universe.render());
+        universe.fill_rect(0, 0, 2, 2, Cell::Dead);
+        assert_eq!("◻◻◻◻◻\n◻◻◼◼◻\n◻◼◻◼◻\n◻◼◼◼◻\n◻◻◻◻◻\n", universe.render());
+
+        universe.clear();
+        universe.draw_circle(2, 2, 2, Cell::Alive);
+        assert_eq!("◻◼◼◼◻\n◼◻◻◻◼\n◼◻◻◻◼\n◼◻◻◻◼\n◻◼◼◼◻\n", universe.render());
+    }
+
+    #[test]
+    fn test_brush() {
+        let mut universe = Universe::new(5);
+        universe.brush(2, 2, 2, BrushShape::Circle, Cell::Alive);
+        assert_eq!("◻◼◼◼◻\n◼◼◼◼◼\n◼◼◼◼◼\n◼◼◼◼◼\n◻◼◼◼◻\n", universe.render());
+
+        universe.brush(0, 0, 1, BrushShape::Square, Cell::Dead);
+        assert_eq!("◻◻◼◼◻\n◻◻◼◼◼\n◼◼◼◼◼\n◼◼◼◼◼\n◻◼◼◼◻\n", universe.render());
+
+        universe.clear();
+        universe.set_boundary_mode(BoundaryMode::Toroidal);
+        universe.brush(0, 0, 1, BrushShape::Circle, Cell::Alive);
+        assert_eq!("◼◼◻◻◼\n◼◼◻◻◼\n◻◻◻◻◻\n◻◻◻◻◻\n◼◼◻◻◼\n", universe.render());
+        universe.brush(
+            i32::MIN,
+            i32::MIN,
+            u32::MAX,
+            BrushShape::Circle,
+            Cell::Alive,
+        );
+        assert_eq!(25, universe.population());
+    }
+
+    #[test]
+    fn test_drawing_past_the_edges() {
+        let mut universe = Universe::new(3);
+        universe.draw_line(-1, 1, 1, 1, Cell::Alive);
+        assert_eq!("◻◼◻\n◻◼◻\n◻◻◻\n", universe.render());
+
+        universe.clear();
+        universe.set_boundary_mode(BoundaryMode::Toroidal);
+        universe.draw_line(-1, 1, 1, 1, Cell::Alive);
+        assert_eq!("◻◼◻\n◻◼◻\n◻◼◻\n", universe.render());
+
+        // shapes far bigger than the board only paint what lands on it
+        universe.clear();
+        universe.fill_rect(i32::MIN, i32::MIN, u32::MAX, u32::MAX, Cell::Alive);
+        assert_eq!(9, universe.population());
+        universe.set_boundary_mode(BoundaryMode::Clamped);
+        universe.draw_rect(0, i32::MIN, u32::MAX, 1, Cell::Dead);
+        universe.draw_line(2, i32::MIN, 2, i32::MAX, Cell::Dead);
+        assert_eq!("◻◻◻\n◼◼◼\n◻◻◻\n", universe.render());
+        universe.draw_circle(i32::MIN, 1, 1 << 31, Cell::Alive);
+        assert_eq!("◼◼◼\n◼◼◼\n◻◻◻\n", universe.render());
+    }
+
+    #[test]
+    fn test_set_rule() {
+        let mut universe = Universe::new(5);
+        assert_eq!("B3/S23", universe.rule());
+
+        universe.set_rule("B36/S23").unwrap();
+        assert_eq!("B36/S23", universe.rule());
+
+        assert!(universe.set_rule("B3/S2x").is_err());
+        assert_eq!("B36/S23", universe.rule());
+    }
+
+    #[test]
+    fn test_density_map() {
+        let mut universe = Universe::new_with_dimensions(10, 5);
+        universe.insert_pattern(Pattern::Glider, 0, 0);
+        universe.set_cell(4, 9, Cell::Alive);
+        universe.density_map(4);
+
+        let density = unsafe {
+            std::slice::from_raw_parts(universe.density_map_ptr(), universe.density_map_len())
+        };
+        assert_eq!(3, universe.density_map_columns());
+        // 5 of 16, nothing, nothing, and 1 of the 2 cells in the bottom right corner
+        assert_eq!(&[80, 0, 0, 0, 0, 128], density);
+    }
+
+    #[test]
+    fn test_iterators() {
+        let mut universe = Universe::new_with_dimensions(3, 2);
+        universe.set_cell(0, 1, Cell::Alive);
+        universe.set_cell(1, 2, Cell::Alive);
+
+        assert_eq!(Cell::Alive, universe[(1, 2)]);
+        assert_eq!(Cell::Dead, universe[(1, 1)]);
+        assert_eq!(
+            vec![(0, 0, Cell::Dead), (0, 1, Cell::Alive)],
+            universe.iter_cells().take(2).collect::<Vec<_>>()
+        );
+        assert_eq!(6, universe.iter_cells().count());
+        assert_eq!(
+            vec![(0, 1), (1, 2)],
+            universe.iter_live_cells().collect::<Vec<_>>()
+        );
+        let rows: Vec<String> = universe
+            .rows()
+            .map(|row| row.map(|cell| cell.to_string()).collect())
+            .collect();
+        assert_eq!(vec!["◻◼◻", "◻◻◼"], rows);
+    }
+
+    #[test]
+    fn test_from_rows() {
+        let universe = Universe::from_rows(&["◻◼", "O.", "_#"]).unwrap();
+        assert_eq!((2, 3), (universe.width(), universe.height()));
+        assert_eq!("◻◼\n◼◻\n◻◼\n", universe.render());
+
+        let copy: Universe = universe.rows().collect();
+        assert_eq!(universe.render(), copy.render());
+
+        assert_eq!(
+            UniverseError::Parse(ParseError::new(2, 2, "unexpected character 'x'")),
+            Universe::from_rows(&["__", "_x"]).unwrap_err()
+        );
+        assert_eq!(
+            UniverseError::Parse(ParseError::new(
+                2,
+                1,
+                "the row is 3 cells long instead of 2"
+            )),
+            Universe::from_rows(&["__", "___"]).unwrap_err()
+        );
+        assert!(Universe::from_cells(2, 2, vec![Cell::Alive; 3]).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "row 2, column 0 is outside of the 3 by 2 universe")]
+    fn test_index_out_of_bounds() {
+        let universe = Universe::new_with_dimensions(3, 2);
+        let _ = universe[(2, 0)];
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut universe = Universe::new_with_dimensions(4, 3);
+        assert_eq!(Ok(()), universe.try_set_cell(2, 3, Cell::Alive));
+        assert_eq!(Ok(Cell::Alive), universe.try_get_cell(2, 3));
+        assert_eq!(
+            Err(UniverseError::OutOfBounds {
+                row: 3,
+                column: 0,
+                width: 4,
+                height: 3,
+            }),
+            universe.try_toggle_cell(3, 0)
+        );
+        assert!(universe.try_get_cell(0, 4).is_err());
+
+        assert_eq!(
+            Err(UniverseError::InvalidRule {
+                rule: "B3".to_string(),
+                error: ParseError::new(1, 1, "expected a rule like B3/S23"),
+            }),
+            universe.set_rule("B3")
         );
+        assert_eq!(
+            UniverseError::Parse(ParseError::new(1, 1, "missing the x = .., y = .. header")),
+            Universe::from_rle("").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_tick_with_rule() {
+        // under Seeds every live cell dies and dead cells with exactly 2 neighbors are born
+        let mut universe = Universe::new(4);
+        universe.set_rule("B2/S").unwrap();
+        universe.set_cell(1, 1, Cell::Alive);
+        universe.set_cell(1, 2, Cell::Alive);
+        universe.tick();
+
+        assert_eq!("◻◼◼◻\n◻◻◻◻\n◻◼◼◻\n◻◻◻◻\n", universe.render());
+    }
+
+    #[test]
+    fn test_statistics() {
+        let mut universe = Universe::new(5);
+        assert_eq!(0, universe.generation());
+        assert_eq!(0, universe.population());
+
+        universe.insert_pattern(Pattern::Blinker, 2, 1);
+        assert_eq!(3, universe.population());
+
+        universe.tick();
+        assert_eq!(1, universe.generation());
+        assert_eq!(3, universe.population());
+        assert_eq!(2, universe.births_last_tick());
+        assert_eq!(2, universe.deaths_last_tick());
+
+        universe.set_cell(1, 2, Cell::Dead);
+        universe.tick();
+        assert_eq!(2, universe.generation());
+        assert_eq!(0, universe.population());
+        assert_eq!(0, universe.births_last_tick());
+        assert_eq!(2, universe.deaths_last_tick());
+    }
+
+    #[test]
+    fn test_tick_n() {
+        let mut universe = Universe::new(8);
+        universe.insert_pattern(Pattern::Glider, 0, 0);
+
+        assert_eq!(5, universe.tick_n(8));
+        assert_eq!(8, universe.generation());
+        // the glider has moved two cells down and to the right
+        let mut expected = Universe::new(8);
+        expected.insert_pattern(Pattern::Glider, 2, 2);
+        assert_eq!(expected.cells(), universe.cells());
+
+        assert_eq!(5, universe.tick_n(0));
+        assert_eq!(8, universe.generation());
+    }
+
+    #[test]
+    fn test_resize_top_left() {
+        let mut universe = Universe::new(3);
+        universe.insert_pattern(Pattern::Glider, 0, 0);
+
+        universe.resize(4, 2, ResizeAnchor::TopLeft);
+        assert_eq!(4, universe.width());
+        assert_eq!(2, universe.height());
+        assert_eq!("◻◼◻◻\n◻◻◼◻\n", universe.render());
+
+        universe.tick();
+        assert_eq!("◻◻◻◻\n◻◻◻◻\n", universe.render());
+    }
+
+    #[test]
+    fn test_resize_centered() {
+        let mut universe = Universe::new(3);
+        universe.insert_pattern(Pattern::Glider, 0, 0);
+
+        universe.resize(5, 5, ResizeAnchor::Center);
+        assert_eq!("◻◻◻◻◻\n◻◻◼◻◻\n◻◻◻◼◻\n◻◼◼◼◻\n◻◻◻◻◻\n", universe.render());
+
+        universe.resize(3, 3, ResizeAnchor::Center);
+        assert_eq!("◻◼◻\n◻◻◼\n◼◼◼\n", universe.render());
+
+        universe.resize(65536, 65537, ResizeAnchor::Center);
+        assert_eq!((3, 3), (universe.width(), universe.height()));
+
+        universe.tick();
+        assert!(universe.changed_cells_len() > 0);
+        universe.resize(2, 2, ResizeAnchor::TopLeft);
+        assert_eq!(0, universe.changed_cells_len());
+    }
+
+    #[test]
+    fn test_stochastic_rule() {
+        let mut universe = Universe::new(6);
+        universe.insert_pattern(Pattern::Blinker, 2, 1);
+        let mut copy = universe.clone();
+
+        // certain chances are the same as the rule on its own
+        universe.set_stochastic_rule(Some(StochasticRule::new(1.0, 1.0, 0.0, 3)));
+        universe.tick();
+        copy.tick();
+        assert_eq!(copy.cells(), universe.cells());
+        assert_eq!(None, universe.detect_cycle(2));
+
+        let mut first = Universe::new(20);
+        first.set_stochastic_rule(Some(StochasticRule::new(0.5, 0.5, 0.1, 7)));
+        let mut second = first.clone();
+        first.tick_n(5);
+        second.tick_n(5);
+        assert!(first.population() > 0);
+        assert_eq!(first.cells(), second.cells());
+
+        second.set_stochastic_rule(Some(StochasticRule::new(0.5, 0.5, 0.1, 8)));
+        second.clear();
+        second.tick_n(5);
+        assert_ne!(first.cells(), second.cells());
+
+        second.set_stochastic_rule(None);
+        assert_eq!(None, second.stochastic_rule());
+    }
+
+    #[test]
+    fn test_randomize_seeded() {
+        let mut first = Universe::new(16);
+        let mut second = Universe::new(16);
+        second.set_cell(0, 0, Cell::Alive);
+
+        first.randomize_seeded(42, 0.5);
+        second.randomize_seeded(42, 0.5);
+        assert_eq!(first.cells(), second.cells());
+        assert!(first.population() > 64 && first.population() < 192);
+
+        second.randomize_seeded(43, 0.5);
+        assert_ne!(first.cells(), second.cells());
+
+        first.randomize_seeded(42, 0.0);
+        assert_eq!(0, first.population());
+        first.randomize_seeded(42, 2.0);
+        assert_eq!(256, first.population());
+        for probability in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            first.randomize_seeded(42, probability);
+            assert_eq!(0, first.population());
+        }
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut universe = Universe::new(6);
+        universe.insert_pattern(Pattern::Glider, 0, 0);
+        universe.tick_n(3);
+        let bits_ptr = universe.bits_ptr();
+
+        universe.clear();
+
+        assert_eq!(0, universe.population());
+        assert_eq!(0, universe.generation());
+        assert_eq!(0, universe.births_last_tick());
+        assert_eq!(0, universe.deaths_last_tick());
+        assert_eq!(bits_ptr, universe.bits_ptr());
+    }
+
+    #[test]
+    fn test_step_back_and_forward() {
+        let mut universe = Universe::new(6);
+        universe.set_history_capacity(2);
+        universe.insert_pattern(Pattern::Glider, 0, 0);
+        let generations: Vec<Vec<Cell>> = (0..3)
+            .map(|_| {
+                let cells = universe.cells();
+                universe.tick();
+                cells
+            })
+            .collect();
+
+        assert!(universe.step_back());
+        assert_eq!(generations[2], universe.cells());
+        assert_eq!(2, universe.generation());
+        assert!(universe.step_back());
+        assert_eq!(generations[1], universe.cells());
+        assert!(!universe.step_back());
+
+        universe.step_forward();
+        assert_eq!(generations[2], universe.cells());
+        universe.step_forward();
+        universe.step_forward();
+        assert_eq!(4, universe.generation());
+    }
+
+    #[test]
+    fn test_step_back_keeps_fading_cells() {
+        let mut universe = Universe::new(12);
+        universe.set_rule("B2/S/C3").unwrap();
+        universe.set_history_capacity(2);
+        universe.randomize_seeded(9, 0.3);
+        universe.tick();
+        let fading = universe.fading.clone();
+        universe.tick();
+        let cells = universe.cells();
+
+        assert!(universe.step_back());
+        assert_eq!(fading, universe.fading);
+        universe.tick();
+        assert_eq!(cells, universe.cells());
+    }
+
+    #[test]
+    fn test_step_back_keeps_colors() {
+        let mut universe = Universe::new(12);
+        universe.set_color_variant(ColorVariant::QuadLife);
+        universe.set_history_capacity(2);
+        universe.randomize_seeded(4, 0.4);
+        universe.tick();
+        let colors = universe.colors.clone();
+        universe.tick();
+        let census = universe.census();
+
+        assert!(universe.step_back());
+        assert_eq!(colors, universe.colors);
+        universe.tick();
+        assert_eq!(census, universe.census());
+    }
+
+    #[test]
+    fn test_history_is_off_by_default() {
+        let mut universe = Universe::new(4);
+        universe.tick();
+
+        assert_eq!(0, universe.history_capacity());
+        assert!(!universe.step_back());
+    }
+
+    #[test]
+    fn test_changed_cells() {
+        let mut universe = Universe::new(5);
+        universe.insert_pattern(Pattern::Blinker, 2, 1);
+        assert_eq!(0, universe.changed_cells_len());
+
+        universe.tick();
+        let changed = unsafe {
+            std::slice::from_raw_parts(universe.changed_cells_ptr(), universe.changed_cells_len())
+        };
+        assert_eq!(&[7, 11, 13, 17], changed);
+
+        universe.clear();
+        assert_eq!(0, universe.changed_cells_len());
+    }
+
+    #[test]
+    fn test_tick_timed() {
+        let mut universe = Universe::new(8);
+        universe.insert_pattern(Pattern::Blinker, 3, 3);
+
+        assert!(universe.tick_timed() >= 0.0);
+        assert_eq!(1, universe.generation());
+    }
+
+    #[test]
+    fn test_run_until_generation() {
+        let mut universe = Universe::new(8);
+        universe.insert_pattern(Pattern::Blinker, 3, 2);
+        universe.tick();
+
+        let summary = universe.run_until_generation(10);
+        assert_eq!(9, summary.generations_run);
+        assert_eq!(10, summary.generation);
+        assert!(summary.condition_met);
+        assert_eq!(Some(2), summary.period);
+
+        assert_eq!(0, universe.run_until_generation(4).generations_run);
+    }
+
+    #[test]
+    fn test_run_until_population_below() {
+        // a glider runs into the corner and settles into a block after dropping to 3 cells
+        let mut universe = Universe::new(8);
+        universe.insert_pattern(Pattern::Glider, 0, 0);
+
+        let summary = universe.run_until_population_below(5, 100);
+        assert!(summary.condition_met);
+        assert_eq!(4, summary.population);
+        assert_eq!(summary.generation, summary.generations_run);
+
+        let summary = universe.run_until_population_below(4, 10);
+        assert_eq!((1, 3), (summary.generations_run, summary.population));
+
+        let summary = universe.run_until_population_below(3, 10);
+        assert!(!summary.condition_met);
+        assert_eq!(10, summary.generations_run);
+        assert_eq!(Some(1), summary.period);
+    }
+
+    #[test]
+    fn test_run_until_stable() {
+        let mut universe = Universe::new(8);
+        universe.insert_pattern(Pattern::Blinker, 3, 2);
+
+        let summary = universe.run_until_stable(100);
+        assert!(summary.condition_met);
+        assert_eq!(Some(2), summary.period);
+        assert_eq!(2, summary.generations_run);
+    }
+
+    #[test]
+    fn test_clone_universe() {
+        let mut universe = Universe::new(12).with_engine(Engine::Packed);
+        universe.set_color_variant(ColorVariant::Immigration);
+        universe.set_history_capacity(2);
+        universe.randomize_seeded(5, 0.4);
+        universe.tick_n(3);
+
+        let mut copy = universe.clone_universe();
+        assert_eq!(Engine::Packed, copy.engine());
+        universe.tick_n(5);
+        copy.tick_n(5);
+        assert_eq!(universe.cells(), copy.cells());
+        assert_eq!(universe.colors, copy.colors);
+        assert!(copy.step_back());
+
+        copy.set_rule("B36/S23").unwrap();
+        copy.clear();
+        assert_eq!("B3/S23", universe.rule());
+        assert!(universe.population() > 0);
+    }
+
+    #[test]
+    fn test_watches() {
+        let mut universe = Universe::new(6);
+        universe.insert_pattern(Pattern::Blinker, 2, 1);
+        universe.add_watch(2, 1);
+        universe.add_watch(2, 2);
+        universe.add_watch(2, 1);
+        universe.add_watch(9, 9);
+        assert_eq!(vec![2, 1, 2, 2], universe.watches());
+
+        universe.tick_n(3);
+        assert_eq!(vec![1, 0, 2, 1, 3, 0], universe.watch_history(2, 1));
+        // the middle of the blinker never changes
+        assert!(universe.watch_history(2, 2).is_empty());
+
+        universe.remove_watch(2, 1);
+        assert!(universe.watch_history(2, 1).is_empty());
+        universe.clear_watches();
+        assert!(universe.watches().is_empty());
+    }
+
+    #[test]
+    fn test_active_region() {
+        let mut universe = Universe::new(10);
+        universe.insert_pattern(Pattern::Blinker, 1, 1);
+        universe.insert_pattern(Pattern::Blinker, 6, 6);
+        universe.set_active_region(4, 4, 20, 20);
+        assert_eq!(vec![4, 4, 6, 6], universe.active_region());
+
+        universe.tick();
+        assert_eq!(Cell::Alive, universe.get_cell(1, 1));
+        assert_eq!(Cell::Dead, universe.get_cell(0, 2));
+        assert_eq!(Cell::Alive, universe.get_cell(5, 7));
+        assert_eq!(Cell::Dead, universe.get_cell(6, 6));
+
+        universe.clear_active_region();
+        assert!(universe.active_region().is_empty());
+        universe.tick();
+        assert_eq!(Cell::Alive, universe.get_cell(0, 2));
+        assert_eq!(Cell::Alive, universe.get_cell(6, 6));
+    }
+
+    #[test]
+    fn test_active_region_edges_see_frozen_cells() {
+        // the blinker is cut in half by the region, the frozen half still feeds births
+        let mut universe = Universe::new(6);
+        universe.insert_pattern(Pattern::Blinker, 2, 1);
+        universe.set_active_region(0, 2, 4, 6);
+
+        universe.tick();
+        assert_eq!(Cell::Alive, universe.get_cell(2, 1));
+        assert_eq!(Cell::Alive, universe.get_cell(1, 2));
+        assert_eq!(Cell::Alive, universe.get_cell(3, 2));
+        assert_eq!(Cell::Dead, universe.get_cell(2, 3));
+    }
+
+    #[test]
+    fn test_trails() {
+        let mut universe = Universe::new(6);
+        universe.set_trail_decay(0.5);
+        universe.insert_pattern(Pattern::Blinker, 2, 1);
+
+        universe.tick();
+        assert_eq!(0.0, universe.trail_at(2, 1));
+        assert_eq!(1.0, universe.trail_at(1, 2));
+        universe.tick();
+        assert_eq!(1.0, universe.trail_at(2, 1));
+        assert_eq!(0.5, universe.trail_at(1, 2));
+        universe.tick();
+        assert_eq!(0.5, universe.trail_at(2, 1));
+        assert_eq!(1.0, universe.trail_at(1, 2));
+        assert_eq!(1.0, universe.trail_at(2, 2));
+        assert_eq!(36, universe.trails_len());
+
+        universe.set_trail_decay(2.0);
+        assert_eq!(1.0, universe.trail_decay());
+    }
+
+    #[test]
+    fn test_ages() {
+        let mut universe = Universe::new(8);
+        universe.set_cell(1, 1, Cell::Alive);
+        universe.set_cell(1, 2, Cell::Alive);
+        universe.set_cell(2, 1, Cell::Alive);
+        universe.set_cell(2, 2, Cell::Alive);
+        universe.insert_pattern(Pattern::Blinker, 5, 4);
+
+        universe.tick_n(3);
+
+        // the block never changes while the blinker is reborn every tick
+        assert_eq!(3, universe.age_at(1, 1));
+        assert_eq!(3, universe.age_at(2, 2));
+        assert_eq!(0, universe.age_at(4, 5));
+        assert_eq!(3, universe.age_at(5, 5));
+        assert_eq!(0, universe.age_at(0, 0));
+        assert_eq!(0, universe.age_at(10, 10));
+        assert_eq!(64, universe.ages_len());
+
+        universe.toggle_cell(1, 1);
+        assert_eq!(0, universe.age_at(1, 1));
+    }
+
+    #[test]
+    fn test_generations_rule() {
+        let mut universe = Universe::new(6);
+        universe.set_rule("/2/3").unwrap();
+        universe.set_cell(2, 2, Cell::Alive);
+        universe.set_cell(2, 3, Cell::Alive);
+
+        universe.tick();
+        // Brian's Brain, every live cell starts fading and the cells touching both are born
+        assert_eq!(2, universe.state_at(2, 2));
+        assert_eq!(2, universe.state_at(2, 3));
+        assert_eq!(1, universe.state_at(1, 2));
+        assert_eq!(1, universe.state_at(3, 3));
+        assert_eq!(0, universe.state_at(0, 0));
+
+        universe.tick();
+        // the fading cells are dead now and couldn't be born while they were fading
+        assert_eq!(0, universe.state_at(2, 2));
+        assert_eq!(0, universe.state_at(2, 3));
+        let states =
+            unsafe { std::slice::from_raw_parts(universe.states_ptr(), universe.states_len()) };
+        assert_eq!(2, states[universe.get_index(1, 2)]);
+        assert_eq!(36, states.len());
+    }
+
+    #[test]
+    fn test_immigration_majority_color() {
+        let mut universe = Universe::new(5);
+        universe.set_color_variant(ColorVariant::Immigration);
+        universe.paint_cell(1, 1, 1);
+        universe.paint_cell(1, 2, 1);
+        universe.paint_cell(1, 3, 0);
+
+        universe.tick();
+
+        // the blinker turns, the new cells above and below have two color 1 parents
+        assert_eq!(Cell::Alive, universe.get_cell(0, 2));
+        assert_eq!(1, universe.color_at(0, 2));
+        assert_eq!(1, universe.color_at(2, 2));
+        assert_eq!(1, universe.color_at(1, 2));
+        assert_eq!(vec![0, 3], universe.census());
+    }
+
+    #[test]
+    fn test_quadlife_fourth_color() {
+        let mut universe = Universe::new(5);
+        universe.set_color_variant(ColorVariant::QuadLife);
+        universe.paint_cell(1, 1, 0);
+        universe.paint_cell(1, 2, 2);
+        universe.paint_cell(1, 3, 3);
+
+        universe.tick();
+
+        assert_eq!(1, universe.color_at(0, 2));
+        assert_eq!(1, universe.color_at(2, 2));
+        assert_eq!(2, universe.color_at(1, 2));
+        assert_eq!(vec![0, 2, 1, 0], universe.census());
+    }
+
+    #[test]
+    fn test_hex_topology() {
+        let mut universe = Universe::new(5);
+        universe.set_rule("B2/S34H").unwrap();
+        universe.set_cell(1, 3, Cell::Alive);
+        universe.set_cell(3, 1, Cell::Alive);
+
+        assert_eq!(GridTopology::Hex, universe.topology());
+        // the two cells only touch (2, 2) through the corners a hex cell doesn't have
+        assert_eq!(0, universe.live_neighbor_count(2, 2));
+
+        universe.set_topology(GridTopology::Square);
+        assert_eq!(2, universe.live_neighbor_count(2, 2));
+        assert_eq!("B2/S34", universe.rule());
+    }
+
+    #[test]
+    fn test_hex_center() {
+        let universe = Universe::new_with_dimensions(3, 3);
+        let radius = 10.0 / 3f64.sqrt();
+
+        assert_eq!(vec![15.0, radius], universe.hex_center(0, 0, 10.0));
+        assert_eq!(vec![5.0, radius * 4.0], universe.hex_center(2, 0, 10.0));
+    }
+
+    #[test]
+    fn test_von_neumann_neighborhood() {
+        let mut universe = Universe::new(5);
+        universe.set_cell(1, 1, Cell::Alive);
+        universe.set_cell(1, 2, Cell::Alive);
+        universe.set_cell(0, 2, Cell::Alive);
+        universe.set_neighborhood(Neighborhood::VonNeumann, 1);
+
+        // (1, 1) is diagonal to (0, 2) so only (1, 2) counts
+        assert_eq!(1, universe.live_neighbor_count(1, 1));
+        assert_eq!(2, universe.live_neighbor_count(1, 2));
+    }
+
+    #[test]
+    fn test_moore_radius_two() {
+        let mut universe = Universe::new(7);
+        universe.set_cell(1, 1, Cell::Alive);
+        universe.set_cell(5, 5, Cell::Alive);
+        universe.set_cell(3, 3, Cell::Alive);
+        universe.set_neighborhood(Neighborhood::Moore, 2);
+
+        assert_eq!(2, universe.live_neighbor_count(3, 3));
+        assert_eq!(1, universe.live_neighbor_count(0, 0));
+        assert_eq!(2, universe.neighborhood_radius());
+
+        universe.set_neighborhood(Neighborhood::Moore, 20);
+        assert_eq!(7, universe.neighborhood_radius());
     }
 }
@@ -0,0 +1,96 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Off = 0,
+    Error = 1,
+    Warn = 2,
+    Info = 3,
+    Debug = 4,
+}
+
+// Warnings go to the devtools console in the browser. Anywhere else the crate is a
+// library and stderr belongs to whoever is using it, so nothing is written until
+// set_log_level() asks for it.
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+const DEFAULT_LOG_LEVEL: LogLevel = LogLevel::Warn;
+#[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+const DEFAULT_LOG_LEVEL: LogLevel = LogLevel::Off;
+
+static LOG_LEVEL: AtomicU8 = AtomicU8::new(DEFAULT_LOG_LEVEL as u8);
+
+// Messages less severe than the level are skipped before they are even formatted
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn set_log_level(level: LogLevel) {
+    LOG_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn log_level() -> LogLevel {
+    match LOG_LEVEL.load(Ordering::Relaxed) {
+        0 => LogLevel::Off,
+        1 => LogLevel::Error,
+        2 => LogLevel::Warn,
+        3 => LogLevel::Info,
+        _ => LogLevel::Debug,
+    }
+}
+
+pub fn is_enabled(level: LogLevel) -> bool {
+    level != LogLevel::Off && level <= log_level()
+}
+
+// Goes to the devtools console in the browser and stderr everywhere else, including wasm
+// builds without the "wasm" feature
+pub fn write(level: LogLevel, message: &str) {
+    #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+    {
+        let message = JsValue::from_str(message);
+        match level {
+            LogLevel::Off => {}
+            LogLevel::Error => web_sys::console::error_1(&message),
+            LogLevel::Warn => web_sys::console::warn_1(&message),
+            LogLevel::Info => web_sys::console::info_1(&message),
+            LogLevel::Debug => web_sys::console::debug_1(&message),
+        }
+    }
+    #[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+    eprintln!("[{:?}] {}", level, message);
+}
+
+// log!(LogLevel::Debug, "generation {}", generation)
+macro_rules! log {
+    ($level:expr, $($arguments:tt)*) => {
+        if $crate::logging::is_enabled($level) {
+            $crate::logging::write($level, &format!($($arguments)*));
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_is_enabled() {
+        assert_eq!(LogLevel::Off, log_level());
+        assert!(!is_enabled(LogLevel::Error));
+
+        set_log_level(LogLevel::Info);
+        assert_eq!(LogLevel::Info, log_level());
+        assert!(is_enabled(LogLevel::Error));
+        assert!(is_enabled(LogLevel::Info));
+        assert!(!is_enabled(LogLevel::Debug));
+        assert!(!is_enabled(LogLevel::Off));
+
+        set_log_level(LogLevel::Off);
+        assert!(!is_enabled(LogLevel::Error));
+
+        set_log_level(DEFAULT_LOG_LEVEL);
+    }
+}
@@ -0,0 +1,342 @@
+use std::fmt::{self, Display, Formatter};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use crate::bits::CellBits;
+use crate::formats::ParseError;
+use crate::{Neighborhood, Universe};
+
+// Golly goes up to 500, past that the tables get too big for a browser tab
+pub const MAX_RADIUS: u32 = 500;
+
+// Larger than Life, life-like rules over a neighborhood radius cells out with ranges of
+// neighbor counts instead of lists, written like R5,C0,M1,S34..58,B34..45,NM for Bosco's
+// rule. M1 counts the cell itself as one of its neighbors, C is the number of states the
+// same as in Generations rules with C0 and C2 both meaning 2, and NM or NN picks the Moore
+// or von Neumann neighborhood. C, M and N can be left out, they default to C0, M0 and NM.
+// https://golly.sourceforge.io/Help/Algorithms/Larger_than_Life.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LtlRule {
+    radius: u32,
+    states: u8,
+    middle: bool,
+    survival: (u32, u32),
+    birth: (u32, u32),
+    neighborhood: Neighborhood,
+}
+
+impl LtlRule {
+    pub fn radius(&self) -> u32 {
+        self.radius
+    }
+
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    pub fn middle(&self) -> bool {
+        self.middle
+    }
+
+    pub fn survival(&self) -> RangeInclusive<u32> {
+        self.survival.0..=self.survival.1
+    }
+
+    pub fn birth(&self) -> RangeInclusive<u32> {
+        self.birth.0..=self.birth.1
+    }
+
+    pub fn neighborhood(&self) -> Neighborhood {
+        self.neighborhood
+    }
+
+    // Counting each neighborhood cell by cell would be (2R + 1)² reads per cell, 121 at
+    // radius 5. Instead the board is padded by the radius on each side, with the padding
+    // filled in by the edge topology, and summed up once so that every count is a few
+    // lookups: a summed-area table gives the Moore square in 4, and running sums along
+    // each row give the von Neumann diamond in 2 per row.
+    pub(crate) fn tick(&self, universe: &Universe, next: &mut CellBits) {
+        let (width, height) = (universe.width, universe.height);
+        if width == 0 || height == 0 {
+            return;
+        }
+        let radius = self.radius as usize;
+        let padded_width = width as usize + 2 * radius;
+        let padded_height = height as usize + 2 * radius;
+        let is_alive = |padded_row: usize, padded_column: usize| {
+            universe
+                .edge_topology
+                .map(
+                    padded_row as i64 - radius as i64,
+                    padded_column as i64 - radius as i64,
+                    width,
+                    height,
+                )
+                .is_some_and(|(row, column)| universe.cells.get(universe.get_index(row, column)))
+        };
+
+        // row_sums[row][column] is the live cells of the padded row before column, and
+        // area_sums[row][column] is the live cells above and to the left of row, column
+        let stride = padded_width + 1;
+        let mut row_sums = vec![0u32; padded_height * stride];
+        for row in 0..padded_height {
+            for column in 0..padded_width {
+                row_sums[row * stride + column + 1] =
+                    row_sums[row * stride + column] + is_alive(row, column) as u32;
+            }
+        }
+        let row_span = |row: usize, first: usize, last: usize| {
+            row_sums[row * stride + last + 1] - row_sums[row * stride + first]
+        };
+        let area_sums = match self.neighborhood {
+            Neighborhood::Moore => {
+                let mut sums = vec![0u32; (padded_height + 1) * stride];
+                for row in 0..padded_height {
+                    for column in 0..=padded_width {
+                        sums[(row + 1) * stride + column] =
+                            sums[row * stride + column] + row_sums[row * stride + column];
+                    }
+                }
+                sums
+            }
+            Neighborhood::VonNeumann => vec![],
+        };
+
+        for row in 0..height as usize {
+            for column in 0..width as usize {
+                // the cell is at row + radius, column + radius of the padded board
+                let count = match self.neighborhood {
+                    Neighborhood::Moore => {
+                        let (bottom, right) = (row + 2 * radius + 1, column + 2 * radius + 1);
+                        area_sums[bottom * stride + right] + area_sums[row * stride + column]
+                            - area_sums[row * stride + right]
+                            - area_sums[bottom * stride + column]
+                    }
+                    Neighborhood::VonNeumann => (0..=2 * radius)
+                        .map(|row_offset| {
+                            let reach =
+                                radius - (row_offset as isize - radius as isize).unsigned_abs();
+                            row_span(
+                                row + row_offset,
+                                column + radius - reach,
+                                column + radius + reach,
+                            )
+                        })
+                        .sum(),
+                };
+                let index = universe.get_index(row as u32, column as u32);
+                let alive = universe.cells.get(index);
+                let count = if alive && !self.middle {
+                    count - 1
+                } else {
+                    count
+                };
+                next.set(index, self.is_alive_next(alive, count));
+            }
+        }
+    }
+
+    fn is_alive_next(&self, alive: bool, count: u32) -> bool {
+        if alive {
+            self.survival().contains(&count)
+        } else {
+            self.birth().contains(&count)
+        }
+    }
+}
+
+impl FromStr for LtlRule {
+    type Err = ParseError;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let mut radius = None;
+        let mut states = 2;
+        let mut middle = false;
+        let mut survival = None;
+        let mut birth = None;
+        let mut neighborhood = Neighborhood::Moore;
+
+        let mut column = 1;
+        for part in rule.trim().split(',') {
+            let mut characters = part.chars();
+            let key = characters.next().map(|key| key.to_ascii_uppercase());
+            let value = characters.as_str();
+            let error = |message: &str| ParseError::new(1, column, message);
+            let number = |value: &str| {
+                value
+                    .parse::<u32>()
+                    .map_err(|_| error(&format!("'{}' is not a number", value)))
+            };
+            match key {
+                Some('R') => match number(value)? {
+                    radius_value @ 1..=MAX_RADIUS => radius = Some(radius_value),
+                    _ => {
+                        return Err(error(&format!(
+                            "the radius has to be between 1 and {}",
+                            MAX_RADIUS
+                        )))
+                    }
+                },
+                Some('C') => match number(value)? {
+                    0..=2 => states = 2,
+                    count @ 3..=255 => states = count as u8,
+                    _ => return Err(error("the number of states has to be at most 255")),
+                },
+                Some('M') => match value {
+                    "0" => middle = false,
+                    "1" => middle = true,
+                    _ => return Err(error("M has to be 0 or 1")),
+                },
+                Some('S') | Some('B') => {
+                    let mut bounds = value.splitn(2, "..");
+                    let low = number(bounds.next().unwrap_or(""))?;
+                    let high = match bounds.next() {
+                        Some(high) => number(high)?,
+                        None => low,
+                    };
+                    if low > high {
+                        return Err(error(&format!("{}..{} is an empty range", low, high)));
+                    }
+                    if key == Some('S') {
+                        survival = Some((low, high));
+                    } else {
+                        birth = Some((low, high));
+                    }
+                }
+                Some('N') => match value {
+                    "M" | "m" => neighborhood = Neighborhood::Moore,
+                    "N" | "n" => neighborhood = Neighborhood::VonNeumann,
+                    _ => return Err(error("the neighborhood has to be NM or NN")),
+                },
+                _ => return Err(error("expected a rule like R5,C0,M1,S34..58,B34..45,NM")),
+            }
+            column += part.len() + 1;
+        }
+
+        match (radius, survival, birth) {
+            (Some(radius), Some(survival), Some(birth)) => Ok(Self {
+                radius,
+                states,
+                middle,
+                survival,
+                birth,
+                neighborhood,
+            }),
+            _ => Err(ParseError::new(
+                1,
+                1,
+                "a Larger than Life rule needs R, S and B",
+            )),
+        }
+    }
+}
+
+impl Display for LtlRule {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "R{},C{},M{},S{}..{},B{}..{},N{}",
+            self.radius,
+            if self.states > 2 { self.states } else { 0 },
+            self.middle as u8,
+            self.survival.0,
+            self.survival.1,
+            self.birth.0,
+            self.birth.1,
+            match self.neighborhood {
+                Neighborhood::Moore => "M",
+                Neighborhood::VonNeumann => "N",
+            }
+        )
+    }
+}
+
+// Whether a rule is meant to be read as Larger than Life rather than B/S notation
+pub fn is_ltl(rule: &str) -> bool {
+    let mut characters = rule.trim_start().chars();
+    matches!(characters.next(), Some('R') | Some('r'))
+        && characters
+            .next()
+            .is_some_and(|digit| digit.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Topology;
+
+    #[test]
+    fn test_parse() {
+        let bosco: LtlRule = "R5,C0,M1,S34..58,B34..45,NM".parse().unwrap();
+        assert_eq!(5, bosco.radius());
+        assert!(bosco.middle());
+        assert_eq!(34..=58, bosco.survival());
+        assert_eq!(34..=45, bosco.birth());
+        assert_eq!("R5,C0,M1,S34..58,B34..45,NM", bosco.to_string());
+
+        let short: LtlRule = "r2,s3,b3..4,nn,c4".parse().unwrap();
+        assert_eq!("R2,C4,M0,S3..3,B3..4,NN", short.to_string());
+
+        assert_eq!(
+            ParseError::new(1, 4, "the radius has to be between 1 and 500"),
+            "S1,R0".parse::<LtlRule>().unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(1, 4, "5..4 is an empty range"),
+            "R1,B5..4,S1".parse::<LtlRule>().unwrap_err()
+        );
+        assert!("R1,B3".parse::<LtlRule>().is_err());
+        assert!(is_ltl("R5,C0"));
+        assert!(!is_ltl("B3/S23"));
+    }
+
+    #[test]
+    fn test_matches_neighbor_counting() {
+        // radius 1 without the middle is Conway's Game of Life, and the von Neumann
+        // diamond at radius 2 matches the naive count over the same 12 neighbors
+        let cases = [
+            ("R1,M0,S2..3,B3..3", Neighborhood::Moore, 1, Topology::Torus),
+            ("R1,M0,S2..3,B3..3", Neighborhood::Moore, 1, Topology::Plane),
+            (
+                "R2,M0,S2..3,B3..3,NN",
+                Neighborhood::VonNeumann,
+                2,
+                Topology::KleinBottle,
+            ),
+        ];
+        for (rule, neighborhood, radius, topology) in cases.iter() {
+            let mut ltl = Universe::new_with_dimensions(30, 20);
+            ltl.set_edge_topology(*topology);
+            ltl.set_rule(rule).unwrap();
+            ltl.randomize_seeded(2, 0.4);
+            let mut naive = Universe::new_with_dimensions(30, 20);
+            naive.set_edge_topology(*topology);
+            naive.set_neighborhood(*neighborhood, *radius);
+            naive.randomize_seeded(2, 0.4);
+
+            for _ in 0..20 {
+                ltl.tick();
+                naive.tick();
+                assert_eq!(naive.cells(), ltl.cells(), "{}", rule);
+            }
+        }
+    }
+
+    #[test]
+    fn test_middle_and_wide_counts() {
+        // a solid 3 by 3 block at radius 2 has 9 cells around its center, or 8 without it
+        let mut universe = Universe::new(9);
+        universe.set_rule("R2,M1,S9..9,B100..100").unwrap();
+        for row in 3..6 {
+            for column in 3..6 {
+                universe.set_cell(row, column, crate::Cell::Alive);
+            }
+        }
+        universe.tick();
+        assert_eq!(9, universe.population());
+
+        universe.set_rule("R2,M0,S9..9,B100..100").unwrap();
+        universe.tick();
+        assert_eq!(0, universe.population());
+    }
+}
@@ -0,0 +1,49 @@
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+// WebAssembly memory grows in pages of 64 KiB
+pub const WASM_PAGE_SIZE: usize = 65536;
+
+// Roughly how many bytes a universe is holding on to, from Universe::memory_usage(). The
+// fields are read only properties in JavaScript. Only the buffers are counted, and a
+// buffer's spare capacity counts as used.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryUsage {
+    // the cells and the back buffer tick() writes into, plus the copies kept for skipping
+    // sleeping tiles
+    pub cell_buffers: usize,
+    // ages, trails, colors, fading states and the other per cell extras, and the
+    // population history
+    pub cell_info: usize,
+    // the generations kept for stepping back and forward
+    pub history: usize,
+    // whatever the engine caches between ticks, HashLife's nodes and memoized results
+    pub engine_cache: usize,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl MemoryUsage {
+    pub fn total(&self) -> usize {
+        self.cell_buffers + self.cell_info + self.history + self.engine_cache
+    }
+}
+
+// How many pages of linear memory the module has, which only ever grows. Multiply by
+// WASM_PAGE_SIZE for bytes. Native builds have no linear memory and get 0.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn memory_pages() -> u32 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        core::arch::wasm32::memory_size(0) as u32
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        0
+    }
+}
+
+// The bytes held by a Vec, counting its spare capacity
+pub(crate) fn vec_bytes<T>(vec: &Vec<T>) -> usize {
+    vec.capacity() * std::mem::size_of::<T>()
+}
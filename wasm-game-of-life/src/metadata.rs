@@ -0,0 +1,190 @@
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+// What a pattern file says about its pattern besides the cells: the #N name, #O author and
+// #C comments of RLE, the !Name: and !Author: lines and other ! comments of plaintext, and
+// the #D descriptions of Life 1.05. LifeWiki files put the pattern's discoverer in the
+// author line and a link to its wiki page in the last comment.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PatternMetadata {
+    name: Option<String>,
+    author: Option<String>,
+    comments: Vec<String>,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl PatternMetadata {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
+    pub fn set_name(&mut self, name: Option<String>) {
+        self.name = name.filter(|name| !name.trim().is_empty());
+    }
+
+    pub fn author(&self) -> Option<String> {
+        self.author.clone()
+    }
+
+    pub fn set_author(&mut self, author: Option<String>) {
+        self.author = author.filter(|author| !author.trim().is_empty());
+    }
+
+    pub fn comments(&self) -> Vec<String> {
+        self.comments.clone()
+    }
+
+    pub fn add_comment(&mut self, comment: &str) {
+        self.comments.push(comment.to_string());
+    }
+
+    pub fn clear_comments(&mut self) {
+        self.comments.clear();
+    }
+
+    // Taken from a comment like "Discovered by Charles Corderman in 1971." when there is
+    // one, files that don't say otherwise credit the discoverer as the author
+    pub fn discovered_by(&self) -> Option<String> {
+        self.comments.iter().find_map(|comment| {
+            let start =
+                comment.to_ascii_lowercase().find("discovered by ")? + "discovered by ".len();
+            let discoverer = comment[start..].split([',', ';']).next()?;
+            let discoverer = match discoverer.rfind(" in ") {
+                Some(year) => &discoverer[..year],
+                None => discoverer,
+            };
+            let discoverer = discoverer.trim().trim_end_matches('.');
+            if discoverer.is_empty() {
+                None
+            } else {
+                Some(discoverer.to_string())
+            }
+        })
+    }
+
+    // The name to show for the pattern, "Untitled" for files without one
+    pub fn title(&self) -> String {
+        self.name.clone().unwrap_or_else(|| "Untitled".to_string())
+    }
+
+    // The comments as one block of text, a line each
+    pub fn description(&self) -> String {
+        self.comments.join("\n")
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.author.is_none() && self.comments.is_empty()
+    }
+}
+
+impl PatternMetadata {
+    // Reads a # line of an RLE file, any other # lines are left alone
+    pub(crate) fn read_rle_line(&mut self, line: &str) {
+        let mut characters = line.chars();
+        if characters.next() != Some('#') {
+            return;
+        }
+        let tag = characters.next();
+        let value = characters.as_str().trim();
+        match tag {
+            Some('N') => self.set_name(Some(value.to_string())),
+            Some('O') => self.set_author(Some(value.to_string())),
+            Some('C') | Some('c') => self.add_comment(value),
+            _ => {}
+        }
+    }
+
+    pub(crate) fn write_rle(&self, rle: &mut String) {
+        self.write_lines(rle, "#N ", "#O ", "#C ");
+    }
+
+    // Reads a ! line of a plaintext file
+    pub(crate) fn read_plaintext_line(&mut self, line: &str) {
+        let value = match line.strip_prefix('!') {
+            Some(value) => value.trim(),
+            None => return,
+        };
+        if let Some(name) = value.strip_prefix("Name:") {
+            self.set_name(Some(name.trim().to_string()));
+        } else if let Some(author) = value.strip_prefix("Author:") {
+            self.set_author(Some(author.trim().to_string()));
+        } else if !value.is_empty() {
+            self.add_comment(value);
+        }
+    }
+
+    pub(crate) fn write_plaintext(&self, plaintext: &mut String) {
+        self.write_lines(plaintext, "!Name: ", "!Author: ", "!");
+    }
+
+    fn write_lines(&self, text: &mut String, name_tag: &str, author_tag: &str, comment_tag: &str) {
+        let lines = self
+            .name
+            .iter()
+            .map(|name| (name_tag, name))
+            .chain(self.author.iter().map(|author| (author_tag, author)))
+            .chain(self.comments.iter().map(|comment| (comment_tag, comment)));
+        for (tag, value) in lines {
+            // a line break in a value would end the comment and start a row of cells
+            for line in value.lines() {
+                text.push_str(tag);
+                text.push_str(line);
+                text.push('\n');
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rle_lines() {
+        let mut metadata = PatternMetadata::new();
+        for line in [
+            "#N Glider",
+            "#O Richard K. Guy",
+            "#C The smallest, most common, and first discovered spaceship.",
+            "#c Discovered by Richard K. Guy in 1969, while tracking the R-pentomino.",
+            "#r 23/3",
+        ] {
+            metadata.read_rle_line(line);
+        }
+
+        assert_eq!("Glider", metadata.title());
+        assert_eq!(Some("Richard K. Guy".to_string()), metadata.author());
+        assert_eq!(Some("Richard K. Guy".to_string()), metadata.discovered_by());
+        assert_eq!(2, metadata.comments().len());
+
+        let mut rle = String::new();
+        metadata.write_rle(&mut rle);
+        assert_eq!(
+            "#N Glider\n#O Richard K. Guy\n\
+             #C The smallest, most common, and first discovered spaceship.\n\
+             #C Discovered by Richard K. Guy in 1969, while tracking the R-pentomino.\n",
+            rle
+        );
+    }
+
+    #[test]
+    fn test_plaintext_lines() {
+        let mut metadata = PatternMetadata::new();
+        for line in ["!Name: Blinker", "!Author: John Conway", "!", "!Period 2."] {
+            metadata.read_plaintext_line(line);
+        }
+
+        assert_eq!(Some("Blinker".to_string()), metadata.name());
+        assert_eq!("Period 2.", metadata.description());
+        assert_eq!(None, metadata.discovered_by());
+        assert_eq!("Untitled", PatternMetadata::new().title());
+        assert!(PatternMetadata::new().is_empty());
+    }
+}
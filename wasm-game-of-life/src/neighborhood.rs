@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+// Which cells around a cell count as its neighbors, within `radius` cells of it
+// https://conwaylife.com/wiki/Neighbourhood
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Neighborhood {
+    // the square around the cell, 8 neighbors at radius 1
+    Moore,
+    // cells at most radius steps away without going diagonally, 4 neighbors at radius 1
+    VonNeumann,
+}
+
+// The (row, column) offsets of every neighbor, the cell itself is left out
+pub fn offsets(neighborhood: Neighborhood, radius: u32) -> Vec<(i32, i32)> {
+    let radius = radius as i32;
+    let mut offsets = vec![];
+    for row_offset in -radius..=radius {
+        for column_offset in -radius..=radius {
+            let included = match neighborhood {
+                Neighborhood::Moore => true,
+                Neighborhood::VonNeumann => row_offset.abs() + column_offset.abs() <= radius,
+            };
+            if included && (row_offset, column_offset) != (0, 0) {
+                offsets.push((row_offset, column_offset));
+            }
+        }
+    }
+
+    offsets
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_offsets() {
+        assert_eq!(8, offsets(Neighborhood::Moore, 1).len());
+        assert_eq!(24, offsets(Neighborhood::Moore, 2).len());
+        assert_eq!(
+            vec![(-1, 0), (0, -1), (0, 1), (1, 0)],
+            offsets(Neighborhood::VonNeumann, 1)
+        );
+        assert_eq!(12, offsets(Neighborhood::VonNeumann, 2).len());
+        assert!(offsets(Neighborhood::Moore, 0).is_empty());
+    }
+}
@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Pattern {
+    Glider,
+    Blinker,
+    Toad,
+    Beacon,
+    Pulsar,
+    LightweightSpaceship,
+    GosperGliderGun,
+}
+
+// Ways to orient a pattern or region before it is placed, rotations are a quarter turn
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Transform {
+    Identity,
+    RotateClockwise,
+    RotateCounterClockwise,
+    // mirrors left and right
+    FlipHorizontal,
+    // mirrors top and bottom
+    FlipVertical,
+}
+
+impl Transform {
+    // The width and height of a width by height rectangle once it is transformed
+    pub fn dimensions(self, width: u32, height: u32) -> (u32, u32) {
+        match self {
+            Transform::RotateClockwise | Transform::RotateCounterClockwise => (height, width),
+            _ => (width, height),
+        }
+    }
+
+    // Where row, column of a width by height rectangle ends up
+    pub fn apply(self, row: u32, column: u32, width: u32, height: u32) -> (u32, u32) {
+        match self {
+            Transform::Identity => (row, column),
+            Transform::RotateClockwise => (column, height - 1 - row),
+            Transform::RotateCounterClockwise => (width - 1 - column, row),
+            Transform::FlipHorizontal => (row, width - 1 - column),
+            Transform::FlipVertical => (height - 1 - row, column),
+        }
+    }
+
+    // Where a step of row_offset, column_offset points after the transform
+    pub fn apply_to_offset(self, row_offset: i32, column_offset: i32) -> (i32, i32) {
+        match self {
+            Transform::Identity => (row_offset, column_offset),
+            Transform::RotateClockwise => (column_offset, -row_offset),
+            Transform::RotateCounterClockwise => (-column_offset, row_offset),
+            Transform::FlipHorizontal => (row_offset, -column_offset),
+            Transform::FlipVertical => (-row_offset, column_offset),
+        }
+    }
+}
+
+impl Pattern {
+    // Drawn the same way as the LifeWiki plaintext format, O is alive and . is dead
+    fn rows(&self) -> &'static [&'static str] {
+        match self {
+            Pattern::Glider => &[".O.", "..O", "OOO"],
+            Pattern::Blinker => &["OOO"],
+            Pattern::Toad => &[".OOO", "OOO."],
+            Pattern::Beacon => &["OO..", "OO..", "..OO", "..OO"],
+            Pattern::Pulsar => &[
+                "..OOO...OOO..",
+                ".............",
+                "O....O.O....O",
+                "O....O.O....O",
+                "O....O.O....O",
+                "..OOO...OOO..",
+                ".............",
+                "..OOO...OOO..",
+                "O....O.O....O",
+                "O....O.O....O",
+                "O....O.O....O",
+                ".............",
+                "..OOO...OOO..",
+            ],
+            Pattern::LightweightSpaceship => &[".O..O", "O....", "O...O", "OOOO."],
+            Pattern::GosperGliderGun => &[
+                "........................O...........",
+                "......................O.O...........",
+                "............OO......OO............OO",
+                "...........O...O....OO............OO",
+                "OO........O.....O...OO..............",
+                "OO........O...O.OO....O.O...........",
+                "..........O.....O.......O...........",
+                "...........O...O....................",
+                "............OO......................",
+            ],
+        }
+    }
+
+    // Offsets of the live cells as (row, column) from the top left of the pattern
+    pub fn live_cells(&self) -> Vec<(u32, u32)> {
+        self.rows()
+            .iter()
+            .enumerate()
+            .flat_map(|(row, line)| {
+                line.chars()
+                    .enumerate()
+                    .filter(|(_, character)| *character == 'O')
+                    .map(move |(column, _)| (row as u32, column as u32))
+            })
+            .collect()
+    }
+
+    // The same cells as live_cells() after the transform, still measured from the top left
+    pub fn transformed_cells(&self, transform: Transform) -> Vec<(u32, u32)> {
+        let rows = self.rows();
+        let height = rows.len() as u32;
+        let width = rows.iter().map(|line| line.len()).max().unwrap_or(0) as u32;
+        self.live_cells()
+            .into_iter()
+            .map(|(row, column)| transform.apply(row, column, width, height))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_glider_live_cells() {
+        assert_eq!(
+            vec![(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)],
+            Pattern::Glider.live_cells()
+        );
+    }
+
+    #[test]
+    fn test_transformed_glider() {
+        // .O.    O..
+        // ..O -> O.O
+        // OOO    OO.
+        let mut cells = Pattern::Glider.transformed_cells(Transform::RotateClockwise);
+        cells.sort_unstable();
+        assert_eq!(vec![(0, 0), (1, 0), (1, 2), (2, 0), (2, 1)], cells);
+
+        let mut cells = Pattern::Glider.transformed_cells(Transform::FlipHorizontal);
+        cells.sort_unstable();
+        assert_eq!(vec![(0, 1), (1, 0), (2, 0), (2, 1), (2, 2)], cells);
+
+        let mut cells = Pattern::Glider.transformed_cells(Transform::FlipVertical);
+        cells.sort_unstable();
+        assert_eq!(vec![(0, 0), (0, 1), (0, 2), (1, 2), (2, 1)], cells);
+    }
+
+    #[test]
+    fn test_transform_dimensions() {
+        assert_eq!((1, 3), Transform::RotateClockwise.dimensions(3, 1));
+        assert_eq!((3, 1), Transform::FlipVertical.dimensions(3, 1));
+        assert_eq!((2, 0), Transform::RotateCounterClockwise.apply(0, 0, 3, 1));
+    }
+
+    #[test]
+    fn test_pattern_populations() {
+        assert_eq!(3, Pattern::Blinker.live_cells().len());
+        assert_eq!(6, Pattern::Toad.live_cells().len());
+        assert_eq!(8, Pattern::Beacon.live_cells().len());
+        assert_eq!(48, Pattern::Pulsar.live_cells().len());
+        assert_eq!(9, Pattern::LightweightSpaceship.live_cells().len());
+        assert_eq!(36, Pattern::GosperGliderGun.live_cells().len());
+    }
+}
@@ -0,0 +1,285 @@
+use std::borrow::Cow;
+use std::convert::TryFrom;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::render::hex_rgb;
+use crate::{EncodeError, Universe};
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnimationFormat {
+    Gif,
+    Apng,
+}
+
+// Captures the board once per generation and turns the frames into an animated GIF or
+// APNG, the bytes arrive in JavaScript as a Uint8Array ready to be put in a Blob for
+// download:
+//
+//     const recorder = new Recorder(4, 100);
+//     recorder.record(universe, 60);
+//     const gif = recorder.encode(AnimationFormat.Gif);
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug)]
+pub struct Recorder {
+    width: u32,
+    height: u32,
+    // 1 for live cells and 0 for dead ones, which are also the palette indexes
+    frames: Vec<Vec<u8>>,
+    // pixels per cell
+    scale: u32,
+    frame_delay: u32,
+    alive_color: [u8; 3],
+    dead_color: [u8; 3],
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Recorder {
+    // frame_delay is in milliseconds, GIF only keeps hundredths of a second so it is
+    // rounded down to those
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(scale: u32, frame_delay: u32) -> Self {
+        Self {
+            width: 0,
+            height: 0,
+            frames: vec![],
+            scale: scale.max(1),
+            frame_delay,
+            alive_color: [0, 0, 0],
+            dead_color: [255, 255, 255],
+        }
+    }
+
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    pub fn set_scale(&mut self, scale: u32) {
+        self.scale = scale.max(1);
+    }
+
+    pub fn frame_delay(&self) -> u32 {
+        self.frame_delay
+    }
+
+    pub fn set_frame_delay(&mut self, frame_delay: u32) {
+        self.frame_delay = frame_delay;
+    }
+
+    // Colors are "#rrggbb", ones that can't be read are left as they were
+    pub fn set_colors(&mut self, alive_color: &str, dead_color: &str) {
+        self.alive_color = hex_rgb(alive_color).unwrap_or(self.alive_color);
+        self.dead_color = hex_rgb(dead_color).unwrap_or(self.dead_color);
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    // Adds the board as it is now. Every frame has to be the same size, so a universe
+    // with different dimensions than the frames so far starts a new recording.
+    pub fn capture(&mut self, universe: &Universe) {
+        if (universe.width(), universe.height()) != (self.width, self.height) {
+            self.frames.clear();
+            self.width = universe.width();
+            self.height = universe.height();
+        }
+        self.frames.push(
+            (0..universe.width() * universe.height())
+                .map(|index| universe.cells.get(index as usize) as u8)
+                .collect(),
+        );
+    }
+
+    // Captures the current generation and the next `generations` after it
+    pub fn record(&mut self, universe: &mut Universe, generations: u32) {
+        self.capture(universe);
+        for _ in 0..generations {
+            universe.tick();
+            self.capture(universe);
+        }
+    }
+
+    // Both formats loop forever
+    pub fn encode(&self, format: AnimationFormat) -> Result<Vec<u8>, EncodeError> {
+        if self.frames.is_empty() {
+            return Err(EncodeError::new("there are no frames to encode"));
+        }
+        match format {
+            AnimationFormat::Gif => self.encode_gif(),
+            AnimationFormat::Apng => self.encode_apng(),
+        }
+    }
+}
+
+impl Recorder {
+    fn palette(&self) -> Vec<u8> {
+        self.dead_color
+            .iter()
+            .chain(self.alive_color.iter())
+            .copied()
+            .collect()
+    }
+
+    // The frame with every cell grown into a scale by scale square of pixels
+    fn scaled(&self, frame: &[u8]) -> Vec<u8> {
+        let scale = self.scale as usize;
+        let mut pixels = Vec::with_capacity(frame.len() * scale * scale);
+        for row in frame.chunks(self.width.max(1) as usize) {
+            let line: Vec<u8> = row
+                .iter()
+                .flat_map(|cell| std::iter::repeat_n(*cell, scale))
+                .collect();
+            for _ in 0..scale {
+                pixels.extend_from_slice(&line);
+            }
+        }
+
+        pixels
+    }
+
+    fn encode_gif(&self) -> Result<Vec<u8>, EncodeError> {
+        let too_large = || EncodeError::new("GIFs can be at most 65535 pixels across");
+        let width = u16::try_from(self.width * self.scale).map_err(|_| too_large())?;
+        let height = u16::try_from(self.height * self.scale).map_err(|_| too_large())?;
+        let delay = u16::try_from(self.frame_delay / 10).unwrap_or(u16::MAX);
+
+        let mut bytes = vec![];
+        {
+            let error = |error: gif::EncodingError| EncodeError::new(error.to_string());
+            let mut encoder =
+                gif::Encoder::new(&mut bytes, width, height, &self.palette()).map_err(error)?;
+            encoder.set_repeat(gif::Repeat::Infinite).map_err(error)?;
+            for frame in self.frames.iter() {
+                let frame = gif::Frame {
+                    width,
+                    height,
+                    delay,
+                    buffer: Cow::Owned(self.scaled(frame)),
+                    ..gif::Frame::default()
+                };
+                encoder.write_frame(&frame).map_err(error)?;
+            }
+        }
+
+        Ok(bytes)
+    }
+
+    fn encode_apng(&self) -> Result<Vec<u8>, EncodeError> {
+        let error = |error: png::EncodingError| EncodeError::new(error.to_string());
+        let delay = u16::try_from(self.frame_delay).unwrap_or(u16::MAX);
+
+        let mut bytes = vec![];
+        {
+            let mut encoder = png::Encoder::new(
+                &mut bytes,
+                self.width * self.scale,
+                self.height * self.scale,
+            );
+            encoder.set_color(png::ColorType::Indexed);
+            encoder.set_depth(png::BitDepth::Eight);
+            encoder.set_palette(self.palette());
+            encoder
+                .set_animated(self.frames.len() as u32, 0)
+                .map_err(error)?;
+            encoder.set_frame_delay(delay, 1000).map_err(error)?;
+            let mut writer = encoder.write_header().map_err(error)?;
+            for frame in self.frames.iter() {
+                writer
+                    .write_image_data(&self.scaled(frame))
+                    .map_err(error)?;
+            }
+            writer.finish().map_err(error)?;
+        }
+
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Pattern;
+
+    fn recording() -> Recorder {
+        let mut universe = Universe::new_with_dimensions(5, 4);
+        universe.insert_pattern(Pattern::Blinker, 1, 1);
+        let mut recorder = Recorder::new(3, 250);
+        recorder.set_colors("#ff0000", "#000000");
+        recorder.record(&mut universe, 2);
+        recorder
+    }
+
+    #[test]
+    fn test_record() {
+        let recorder = recording();
+
+        assert_eq!(3, recorder.frame_count());
+        assert_eq!(recorder.frames[0], recorder.frames[2]);
+        assert_ne!(recorder.frames[0], recorder.frames[1]);
+        assert_eq!(15 * 12, recorder.scaled(&recorder.frames[0]).len());
+    }
+
+    #[test]
+    fn test_capture_restarts_on_a_new_size() {
+        let mut recorder = recording();
+        recorder.capture(&Universe::new(3));
+
+        assert_eq!(1, recorder.frame_count());
+    }
+
+    #[test]
+    fn test_encode_gif() {
+        let recorder = recording();
+        let bytes = recorder.encode(AnimationFormat::Gif).unwrap();
+
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::Indexed);
+        let mut decoder = options.read_info(bytes.as_slice()).unwrap();
+        assert_eq!((15, 12), (decoder.width(), decoder.height()));
+        assert_eq!(Some(&[0, 0, 0, 255, 0, 0][..]), decoder.global_palette());
+        let mut frames = 0;
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!(25, frame.delay);
+            assert_eq!(
+                &recorder.scaled(&recorder.frames[frames])[..],
+                &frame.buffer[..]
+            );
+            frames += 1;
+        }
+        assert_eq!(3, frames);
+    }
+
+    #[test]
+    fn test_encode_apng() {
+        let bytes = recording().encode(AnimationFormat::Apng).unwrap();
+
+        let reader = png::Decoder::new(bytes.as_slice()).read_info().unwrap();
+        let info = reader.info();
+        assert_eq!((15, 12), (info.width, info.height));
+        assert_eq!(3, info.animation_control.unwrap().num_frames);
+    }
+
+    #[test]
+    fn test_encode_errors() {
+        assert_eq!(
+            EncodeError::new("there are no frames to encode"),
+            Recorder::new(1, 100)
+                .encode(AnimationFormat::Gif)
+                .unwrap_err()
+        );
+
+        let mut recorder = Recorder::new(100, 100);
+        recorder.capture(&Universe::new(1000));
+        assert_eq!(
+            EncodeError::new("GIFs can be at most 65535 pixels across"),
+            recorder.encode(AnimationFormat::Gif).unwrap_err()
+        );
+    }
+}
@@ -0,0 +1,373 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+
+use super::{
+    heat_level, live_runs, RenderMode, COLONY_COLORS, FALLING_SAND_COLORS, FOREST_FIRE_COLORS,
+    HEATMAP_COLORS, WA_TOR_COLORS, WIREWORLD_COLORS,
+};
+use crate::{
+    FallingSand, ForestCell, ForestFire, GridTopology, LangtonsAnt, Particle, Universe, Viewport,
+    WaTor, WaTorCell, WireCell, Wireworld,
+};
+
+const MIN_TRAIL: f32 = 0.05;
+
+// Draws a universe onto a <canvas> with the 2D context
+#[wasm_bindgen]
+pub struct CanvasRenderer {
+    canvas: HtmlCanvasElement,
+    context: CanvasRenderingContext2d,
+    cell_size: u32,
+    alive_color: String,
+    dead_color: String,
+    mode: RenderMode,
+}
+
+#[wasm_bindgen]
+impl CanvasRenderer {
+    // Colors are anything the canvas fillStyle accepts, like "#000000" or "rebeccapurple"
+    pub fn new(
+        canvas_id: &str,
+        cell_size: u32,
+        alive_color: &str,
+        dead_color: &str,
+    ) -> Result<CanvasRenderer, JsValue> {
+        let document = web_sys::window()
+            .and_then(|window| window.document())
+            .ok_or_else(|| JsValue::from_str("no document to find the canvas in"))?;
+        let canvas: HtmlCanvasElement = document
+            .get_element_by_id(canvas_id)
+            .ok_or_else(|| JsValue::from_str(&format!("no element with the id {}", canvas_id)))?
+            .dyn_into()?;
+        let context: CanvasRenderingContext2d = canvas
+            .get_context("2d")?
+            .ok_or_else(|| JsValue::from_str("the canvas has no 2d context"))?
+            .dyn_into()?;
+
+        Ok(Self {
+            canvas,
+            context,
+            cell_size,
+            alive_color: alive_color.to_owned(),
+            dead_color: dead_color.to_owned(),
+            mode: RenderMode::Solid,
+        })
+    }
+
+    pub fn cell_size(&self) -> u32 {
+        self.cell_size
+    }
+
+    pub fn mode(&self) -> RenderMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: RenderMode) {
+        self.mode = mode;
+    }
+
+    // Clears to the dead color and then draws the live cells in the current mode, the
+    // canvas is resized to fit the universe when needed
+    pub fn render_to_canvas(&self, universe: &Universe) {
+        if universe.topology() == GridTopology::Hex {
+            return self.render_hex(universe);
+        }
+        self.clear_cells(universe.width(), universe.height());
+
+        match self.mode {
+            RenderMode::Solid => self.render_solid(universe),
+            RenderMode::Heatmap => {
+                self.render_by_level(universe, &HEATMAP_COLORS, |row, column| {
+                    heat_level(universe.age_at(row, column))
+                })
+            }
+            RenderMode::Colonies => {
+                self.render_by_level(universe, &COLONY_COLORS, |row, column| {
+                    universe.color_at(row, column) as usize
+                })
+            }
+            RenderMode::Trails => self.render_trails(universe),
+        }
+    }
+
+    // Draws only what is inside the viewport at its zoom, with the canvas sized to the
+    // viewport instead of the board. Live cells are always in the alive color.
+    pub fn render_viewport(&self, universe: &Universe, viewport: &Viewport) {
+        self.clear(viewport.width(), viewport.height());
+        let (top, left, bottom, right) =
+            viewport.visible_range(universe.width(), universe.height());
+        let zoom = viewport.zoom();
+
+        self.context.set_fill_style_str(&self.alive_color);
+        for row in top..bottom {
+            let mut run_start = None;
+            for column in left..=right {
+                let alive = column < right && universe.cells.get(universe.get_index(row, column));
+                match (alive, run_start) {
+                    (true, None) => run_start = Some(column),
+                    (false, Some(start)) => {
+                        let corner = viewport.cell_to_screen(row as i32, start as i32);
+                        self.context.fill_rect(
+                            corner[0],
+                            corner[1],
+                            f64::from(column - start) * zoom,
+                            zoom,
+                        );
+                        run_start = None;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    // Wireworld shares the canvas, the alive color isn't used and the mode is ignored
+    pub fn render_wireworld(&self, wireworld: &Wireworld) {
+        self.clear_cells(wireworld.width(), wireworld.height());
+
+        let cell_size = f64::from(self.cell_size);
+        for (color, state) in
+            WIREWORLD_COLORS
+                .iter()
+                .zip(&[WireCell::Conductor, WireCell::Head, WireCell::Tail])
+        {
+            self.context.set_fill_style_str(color);
+            for row in 0..wireworld.height() {
+                for column in 0..wireworld.width() {
+                    if wireworld.get_cell(row, column) == *state {
+                        self.context.fill_rect(
+                            f64::from(column) * cell_size,
+                            f64::from(row) * cell_size,
+                            cell_size,
+                            cell_size,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    // Drawn the same way as Wireworld
+    pub fn render_forest_fire(&self, forest: &ForestFire) {
+        self.clear_cells(forest.width(), forest.height());
+
+        let cell_size = f64::from(self.cell_size);
+        for (color, state) in FOREST_FIRE_COLORS
+            .iter()
+            .zip(&[ForestCell::Tree, ForestCell::Burning])
+        {
+            self.context.set_fill_style_str(color);
+            for row in 0..forest.height() {
+                for column in 0..forest.width() {
+                    if forest.get_cell(row, column) == *state {
+                        self.context.fill_rect(
+                            f64::from(column) * cell_size,
+                            f64::from(row) * cell_size,
+                            cell_size,
+                            cell_size,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn render_wa_tor(&self, wa_tor: &WaTor) {
+        self.clear_cells(wa_tor.width(), wa_tor.height());
+
+        let cell_size = f64::from(self.cell_size);
+        for (color, state) in WA_TOR_COLORS
+            .iter()
+            .zip(&[WaTorCell::Fish, WaTorCell::Shark])
+        {
+            self.context.set_fill_style_str(color);
+            for row in 0..wa_tor.height() {
+                for column in 0..wa_tor.width() {
+                    if wa_tor.get_cell(row, column) == *state {
+                        self.context.fill_rect(
+                            f64::from(column) * cell_size,
+                            f64::from(row) * cell_size,
+                            cell_size,
+                            cell_size,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn render_falling_sand(&self, sand: &FallingSand) {
+        self.clear_cells(sand.width(), sand.height());
+
+        let cell_size = f64::from(self.cell_size);
+        for (color, particle) in
+            FALLING_SAND_COLORS
+                .iter()
+                .zip(&[Particle::Sand, Particle::Water, Particle::Wall])
+        {
+            self.context.set_fill_style_str(color);
+            for row in 0..sand.height() {
+                for column in 0..sand.width() {
+                    if sand.get_cell(row, column) == *particle {
+                        self.context.fill_rect(
+                            f64::from(column) * cell_size,
+                            f64::from(row) * cell_size,
+                            cell_size,
+                            cell_size,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    // Colored cells use the heatmap colors in order and the ants are drawn in the alive color
+    pub fn render_langtons_ant(&self, langtons_ant: &LangtonsAnt) {
+        self.clear_cells(langtons_ant.width(), langtons_ant.height());
+
+        let cell_size = f64::from(self.cell_size);
+        for row in 0..langtons_ant.height() {
+            for column in 0..langtons_ant.width() {
+                let color = langtons_ant.get_cell(row, column) as usize;
+                if color > 0 {
+                    self.context
+                        .set_fill_style_str(HEATMAP_COLORS[(color - 1) % HEATMAP_COLORS.len()]);
+                    self.context.fill_rect(
+                        f64::from(column) * cell_size,
+                        f64::from(row) * cell_size,
+                        cell_size,
+                        cell_size,
+                    );
+                }
+            }
+        }
+
+        self.context.set_fill_style_str(&self.alive_color);
+        for ant in langtons_ant.ants().chunks(3) {
+            self.context.fill_rect(
+                f64::from(ant[1]) * cell_size,
+                f64::from(ant[0]) * cell_size,
+                cell_size,
+                cell_size,
+            );
+        }
+    }
+}
+
+impl CanvasRenderer {
+    // Resizes the canvas to fit a board of the given size and fills it with the dead color
+    fn clear_cells(&self, columns: u32, rows: u32) {
+        self.clear(columns * self.cell_size, rows * self.cell_size);
+    }
+
+    fn clear(&self, width: u32, height: u32) {
+        if self.canvas.width() != width || self.canvas.height() != height {
+            self.canvas.set_width(width);
+            self.canvas.set_height(height);
+        }
+
+        self.context.set_fill_style_str(&self.dead_color);
+        self.context
+            .fill_rect(0.0, 0.0, f64::from(width), f64::from(height));
+    }
+
+    // Live cells are drawn as hexagons cell_size wide in the alive color, render modes
+    // only apply to square grids
+    fn render_hex(&self, universe: &Universe) {
+        let cell_size = f64::from(self.cell_size);
+        let radius = cell_size / 3f64.sqrt();
+        let rows = f64::from(universe.height());
+        let width = cell_size * (f64::from(universe.width()) + (rows - 1.0).max(0.0) / 2.0);
+        let height = radius * (1.5 * rows + 0.5);
+        self.clear(width.ceil() as u32, height.ceil() as u32);
+
+        self.context.set_fill_style_str(&self.alive_color);
+        self.context.begin_path();
+        for row in 0..universe.height() {
+            for column in 0..universe.width() {
+                if universe.get_cell(row, column) != crate::Cell::Alive {
+                    continue;
+                }
+                let center = universe.hex_center(row, column, cell_size);
+                for corner in 0..6 {
+                    // pointy topped, starting from the top corner
+                    let angle = std::f64::consts::PI / 3.0 * f64::from(corner);
+                    let x = center[0] + radius * angle.sin();
+                    let y = center[1] - radius * angle.cos();
+                    if corner == 0 {
+                        self.context.move_to(x, y);
+                    } else {
+                        self.context.line_to(x, y);
+                    }
+                }
+                self.context.close_path();
+            }
+        }
+        self.context.fill();
+    }
+
+    // Each run of live cells is drawn as one rectangle
+    fn render_solid(&self, universe: &Universe) {
+        let cell_size = f64::from(self.cell_size);
+        self.context.set_fill_style_str(&self.alive_color);
+        for (row, column, length) in live_runs(universe) {
+            self.context.fill_rect(
+                f64::from(column) * cell_size,
+                f64::from(row) * cell_size,
+                f64::from(length) * cell_size,
+                cell_size,
+            );
+        }
+    }
+
+    // Trails too faint to see are skipped, live cells are drawn last at full strength
+    fn render_trails(&self, universe: &Universe) {
+        let cell_size = f64::from(self.cell_size);
+        self.context.set_fill_style_str(&self.alive_color);
+        for row in 0..universe.height() {
+            for column in 0..universe.width() {
+                let trail = universe.trail_at(row, column);
+                if trail >= MIN_TRAIL && universe.get_cell(row, column) != crate::Cell::Alive {
+                    self.context.set_global_alpha(f64::from(trail));
+                    self.context.fill_rect(
+                        f64::from(column) * cell_size,
+                        f64::from(row) * cell_size,
+                        cell_size,
+                        cell_size,
+                    );
+                }
+            }
+        }
+        self.context.set_global_alpha(1.0);
+        self.render_solid(universe);
+    }
+
+    // Live cells are grouped by color first so the fill style only changes once per color
+    fn render_by_level<F>(&self, universe: &Universe, colors: &[&str], level: F)
+    where
+        F: Fn(u32, u32) -> usize,
+    {
+        let cell_size = f64::from(self.cell_size);
+        let mut levels = vec![vec![]; colors.len()];
+        for row in 0..universe.height() {
+            for column in 0..universe.width() {
+                if universe.get_cell(row, column) == crate::Cell::Alive {
+                    levels[level(row, column).min(colors.len() - 1)].push((row, column));
+                }
+            }
+        }
+
+        for (color, cells) in colors.iter().zip(levels) {
+            self.context.set_fill_style_str(color);
+            for (row, column) in cells {
+                self.context.fill_rect(
+                    f64::from(column) * cell_size,
+                    f64::from(row) * cell_size,
+                    cell_size,
+                    cell_size,
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+// canvas and webgl draw in the browser, text works anywhere
+#[cfg(feature = "wasm")]
+mod canvas;
+mod text;
+#[cfg(feature = "wasm")]
+mod webgl;
+
+#[cfg(feature = "wasm")]
+pub use canvas::CanvasRenderer;
+pub use text::TextRenderer;
+#[cfg(feature = "wasm")]
+pub use webgl::WebGlRenderer;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::Universe;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenderMode {
+    // every live cell in the alive color
+    Solid,
+    // live cells colored by age, from pale yellow for newborns to dark red for old ones
+    Heatmap,
+    // live cells in the color of their colony under Immigration or QuadLife
+    Colonies,
+    // the alive color faded out over the cells that have died recently, see
+    // Universe::set_trail_decay()
+    Trails,
+}
+
+pub const HEATMAP_COLORS: [&str; 8] = [
+    "#ffffb2", "#fed976", "#feb24c", "#fd8d3c", "#fc4e2a", "#e31a1c", "#bd0026", "#800026",
+];
+
+pub const COLONY_COLORS: [&str; 4] = ["#d62728", "#1f77b4", "#2ca02c", "#ffbf00"];
+
+// Conductor, electron head and electron tail, empty cells are left in the dead color
+#[cfg_attr(not(feature = "wasm"), allow(dead_code))]
+pub const WIREWORLD_COLORS: [&str; 3] = ["#ffbf00", "#1f77b4", "#d62728"];
+
+// Trees and fires, empty ground is left in the dead color
+#[cfg_attr(not(feature = "wasm"), allow(dead_code))]
+pub const FOREST_FIRE_COLORS: [&str; 2] = ["#2ca02c", "#ff7f0e"];
+
+// Fish and sharks, the water is left in the dead color
+#[cfg_attr(not(feature = "wasm"), allow(dead_code))]
+pub const WA_TOR_COLORS: [&str; 2] = ["#ffbf00", "#7f7f7f"];
+
+// Sand, water and walls
+#[cfg_attr(not(feature = "wasm"), allow(dead_code))]
+pub const FALLING_SAND_COLORS: [&str; 3] = ["#e7c27d", "#1f77b4", "#7f7f7f"];
+
+// Ages go up a color each time they double so still lifes end up in the darkest one
+pub fn heat_level(age: u32) -> usize {
+    ((32 - age.leading_zeros()) as usize).min(HEATMAP_COLORS.len() - 1)
+}
+
+// The red, green and blue of a "#rrggbb" color
+pub fn hex_rgb(color: &str) -> Option<[u8; 3]> {
+    let hex = color.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |range: std::ops::Range<usize>| u8::from_str_radix(hex.get(range)?, 16).ok();
+
+    Some([channel(0..2)?, channel(2..4)?, channel(4..6)?])
+}
+
+// Horizontal runs of live cells as (row, column, length), drawing one rectangle per run
+// instead of one per cell cuts down on draw calls for dense boards
+pub fn live_runs(universe: &Universe) -> Vec<(u32, u32, u32)> {
+    let mut runs = vec![];
+
+    for row in 0..universe.height {
+        let mut run_start = None;
+        for column in 0..=universe.width {
+            let alive =
+                column < universe.width && universe.cells.get(universe.get_index(row, column));
+            match (alive, run_start) {
+                (true, None) => run_start = Some(column),
+                (false, Some(start)) => {
+                    runs.push((row, start, column - start));
+                    run_start = None;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    runs
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Pattern;
+
+    #[test]
+    fn test_live_runs() {
+        let mut universe = Universe::new(4);
+        universe.insert_pattern(Pattern::Glider, 0, 1);
+
+        assert_eq!(vec![(0, 2, 1), (1, 3, 1), (2, 1, 3)], live_runs(&universe));
+    }
+
+    #[test]
+    fn test_live_runs_of_an_empty_universe() {
+        assert!(live_runs(&Universe::new(4)).is_empty());
+    }
+
+    #[test]
+    fn test_heat_level() {
+        assert_eq!(0, heat_level(0));
+        assert_eq!(1, heat_level(1));
+        assert_eq!(2, heat_level(2));
+        assert_eq!(2, heat_level(3));
+        assert_eq!(3, heat_level(4));
+        assert_eq!(7, heat_level(64));
+        assert_eq!(7, heat_level(u32::MAX));
+    }
+}
@@ -0,0 +1,241 @@
+use std::fmt::Write;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use super::{heat_level, hex_rgb, RenderMode, COLONY_COLORS, HEATMAP_COLORS};
+use crate::Universe;
+
+const RESET: &str = "\x1b[0m";
+
+// Draws a universe as lines of text for terminals and native examples. By default it
+// matches the Display impl of Universe, one ◼ or ◻ per cell.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextRenderer {
+    alive_glyph: String,
+    dead_glyph: String,
+    // live cells are wrapped in 24 bit ANSI color escapes when this is set
+    ansi_color: bool,
+    alive_color: String,
+    // two rows to a line with the ▀, ▄ and █ blocks, the glyphs aren't used
+    half_blocks: bool,
+    mode: RenderMode,
+}
+
+impl Default for TextRenderer {
+    fn default() -> Self {
+        Self {
+            alive_glyph: "◼".to_string(),
+            dead_glyph: "◻".to_string(),
+            ansi_color: false,
+            alive_color: "#ffffff".to_string(),
+            half_blocks: false,
+            mode: RenderMode::Solid,
+        }
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl TextRenderer {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn alive_glyph(&self) -> String {
+        self.alive_glyph.clone()
+    }
+
+    pub fn dead_glyph(&self) -> String {
+        self.dead_glyph.clone()
+    }
+
+    // A glyph can be more than one character, "[]" and "  " make square cells in most
+    // terminal fonts
+    pub fn set_glyphs(&mut self, alive: &str, dead: &str) {
+        self.alive_glyph = alive.to_owned();
+        self.dead_glyph = dead.to_owned();
+    }
+
+    pub fn ansi_color(&self) -> bool {
+        self.ansi_color
+    }
+
+    pub fn set_ansi_color(&mut self, ansi_color: bool) {
+        self.ansi_color = ansi_color;
+    }
+
+    // The color of live cells in RenderMode::Solid, as "#rrggbb"
+    pub fn set_alive_color(&mut self, color: &str) {
+        self.alive_color = color.to_owned();
+    }
+
+    pub fn half_blocks(&self) -> bool {
+        self.half_blocks
+    }
+
+    pub fn set_half_blocks(&mut self, half_blocks: bool) {
+        self.half_blocks = half_blocks;
+    }
+
+    pub fn mode(&self) -> RenderMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: RenderMode) {
+        self.mode = mode;
+    }
+
+    pub fn render(&self, universe: &Universe) -> String {
+        let mut text = String::new();
+        if self.half_blocks {
+            self.render_half_blocks(universe, &mut text);
+        } else {
+            self.render_glyphs(universe, &mut text);
+        }
+
+        text
+    }
+}
+
+impl TextRenderer {
+    fn render_glyphs(&self, universe: &Universe, text: &mut String) {
+        for row in 0..universe.height() {
+            for column in 0..universe.width() {
+                match self.cell_color(universe, row, column) {
+                    None => text.push_str(&self.dead_glyph),
+                    Some(None) => text.push_str(&self.alive_glyph),
+                    Some(Some([red, green, blue])) => {
+                        let _ = write!(
+                            text,
+                            "\x1b[38;2;{};{};{}m{}{}",
+                            red, green, blue, self.alive_glyph, RESET
+                        );
+                    }
+                }
+            }
+            text.push('\n');
+        }
+    }
+
+    // The top cell of each pair is drawn in the foreground of ▀ and the bottom one in the
+    // background, so both keep their own color
+    fn render_half_blocks(&self, universe: &Universe, text: &mut String) {
+        for row in (0..universe.height()).step_by(2) {
+            for column in 0..universe.width() {
+                let top = self.cell_color(universe, row, column);
+                let bottom = if row + 1 < universe.height() {
+                    self.cell_color(universe, row + 1, column)
+                } else {
+                    None
+                };
+                match (top, bottom) {
+                    (None, None) => text.push(' '),
+                    (Some(None), Some(None)) => text.push('█'),
+                    (Some(None), None) => text.push('▀'),
+                    (None, Some(None)) => text.push('▄'),
+                    (Some(Some(top)), Some(Some(bottom))) => {
+                        let _ = write!(
+                            text,
+                            "\x1b[38;2;{};{};{};48;2;{};{};{}m▀{}",
+                            top[0], top[1], top[2], bottom[0], bottom[1], bottom[2], RESET
+                        );
+                    }
+                    // colors are either on for every live cell or off for all of them,
+                    // so the other cell here is dead
+                    (Some(Some(top)), _) => {
+                        let _ = write!(
+                            text,
+                            "\x1b[38;2;{};{};{}m▀{}",
+                            top[0], top[1], top[2], RESET
+                        );
+                    }
+                    (_, Some(Some(bottom))) => {
+                        let _ = write!(
+                            text,
+                            "\x1b[38;2;{};{};{}m▄{}",
+                            bottom[0], bottom[1], bottom[2], RESET
+                        );
+                    }
+                }
+            }
+            text.push('\n');
+        }
+    }
+
+    // None for a dead cell, otherwise the color of the live cell when colors are on
+    fn cell_color(&self, universe: &Universe, row: u32, column: u32) -> Option<Option<[u8; 3]>> {
+        if universe.get_cell(row, column) != crate::Cell::Alive {
+            return None;
+        }
+        if !self.ansi_color {
+            return Some(None);
+        }
+
+        let color = match self.mode {
+            // text has no transparency, so there are no trails to draw
+            RenderMode::Solid | RenderMode::Trails => self.alive_color.as_str(),
+            RenderMode::Heatmap => HEATMAP_COLORS[heat_level(universe.age_at(row, column))],
+            RenderMode::Colonies => COLONY_COLORS[universe.color_at(row, column) as usize],
+        };
+        // a color that can't be read falls back to white so the cell isn't lost
+        Some(Some(hex_rgb(color).unwrap_or([255, 255, 255])))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Pattern;
+
+    fn glider() -> Universe {
+        let mut universe = Universe::new_with_dimensions(4, 3);
+        universe.insert_pattern(Pattern::Glider, 0, 0);
+        universe
+    }
+
+    #[test]
+    fn test_default_matches_display() {
+        let universe = glider();
+
+        assert_eq!(universe.to_string(), TextRenderer::new().render(&universe));
+    }
+
+    #[test]
+    fn test_glyphs() {
+        let mut renderer = TextRenderer::new();
+        renderer.set_glyphs("#", ".");
+
+        assert_eq!(".#..\n..#.\n###.\n", renderer.render(&glider()));
+    }
+
+    #[test]
+    fn test_half_blocks() {
+        let mut renderer = TextRenderer::new();
+        renderer.set_half_blocks(true);
+
+        assert_eq!(" ▀▄ \n▀▀▀ \n", renderer.render(&glider()));
+    }
+
+    #[test]
+    fn test_ansi_color() {
+        let mut renderer = TextRenderer::new();
+        renderer.set_glyphs("#", ".");
+        renderer.set_ansi_color(true);
+        renderer.set_alive_color("#ff8000");
+        let mut universe = Universe::new_with_dimensions(2, 1);
+        universe.set_cell(0, 1, crate::Cell::Alive);
+
+        assert_eq!(
+            ".\x1b[38;2;255;128;0m#\x1b[0m\n",
+            renderer.render(&universe)
+        );
+
+        renderer.set_half_blocks(true);
+        assert_eq!(
+            " \x1b[38;2;255;128;0m▀\x1b[0m\n",
+            renderer.render(&universe)
+        );
+    }
+}
@@ -0,0 +1,289 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{
+    HtmlCanvasElement, WebGl2RenderingContext as Gl, WebGlProgram, WebGlShader, WebGlTexture,
+    WebGlUniformLocation,
+};
+
+use super::hex_rgb;
+use crate::Universe;
+
+// WebGL2 textures are limited to a few thousand texels per side, so the packed cells
+// are wrapped into rows of this many words
+const MAX_TEXTURE_WIDTH: usize = 4096;
+
+const VERTEX_SHADER: &str = r#"#version 300 es
+in vec2 a_position;
+out vec2 v_position;
+
+void main() {
+    // 0,0 is the top left of the universe and 1,1 the bottom right
+    v_position = vec2(a_position.x * 0.5 + 0.5, 0.5 - a_position.y * 0.5);
+    gl_Position = vec4(a_position, 0.0, 1.0);
+}
+"#;
+
+const FRAGMENT_SHADER: &str = r#"#version 300 es
+precision highp float;
+precision highp int;
+precision highp usampler2D;
+
+uniform usampler2D u_cells;
+uniform uvec2 u_size;
+uniform uint u_texture_width;
+uniform vec4 u_alive_color;
+uniform vec4 u_dead_color;
+
+in vec2 v_position;
+out vec4 color;
+
+void main() {
+    uint column = min(uint(v_position.x * float(u_size.x)), u_size.x - 1u);
+    uint row = min(uint(v_position.y * float(u_size.y)), u_size.y - 1u);
+    uint index = row * u_size.x + column;
+    uint word_index = index / 32u;
+    ivec2 texel = ivec2(int(word_index % u_texture_width), int(word_index / u_texture_width));
+    uint word = texelFetch(u_cells, texel, 0).r;
+
+    color = ((word >> (index % 32u)) & 1u) == 1u ? u_alive_color : u_dead_color;
+}
+"#;
+
+// Draws a universe with a fragment shader that reads the packed cells straight out of a
+// texture, so the cost per frame is one upload no matter how many cells are alive
+#[wasm_bindgen]
+pub struct WebGlRenderer {
+    canvas: HtmlCanvasElement,
+    gl: Gl,
+    program: WebGlProgram,
+    texture: WebGlTexture,
+    size_location: Option<WebGlUniformLocation>,
+    texture_width_location: Option<WebGlUniformLocation>,
+    // reused between frames so uploading doesn't allocate
+    upload: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl WebGlRenderer {
+    // Colors are given as #rrggbb
+    pub fn init(
+        canvas_id: &str,
+        alive_color: &str,
+        dead_color: &str,
+    ) -> Result<WebGlRenderer, JsValue> {
+        let alive_color = parse_hex_color(alive_color)
+            .ok_or_else(|| JsValue::from_str(&format!("{} is not a #rrggbb color", alive_color)))?;
+        let dead_color = parse_hex_color(dead_color)
+            .ok_or_else(|| JsValue::from_str(&format!("{} is not a #rrggbb color", dead_color)))?;
+        let canvas: HtmlCanvasElement = web_sys::window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.get_element_by_id(canvas_id))
+            .ok_or_else(|| JsValue::from_str(&format!("no element with the id {}", canvas_id)))?
+            .dyn_into()?;
+        let gl: Gl = canvas
+            .get_context("webgl2")?
+            .ok_or_else(|| JsValue::from_str("WebGL2 is not supported"))?
+            .dyn_into()?;
+
+        let vertex_shader = compile_shader(&gl, Gl::VERTEX_SHADER, VERTEX_SHADER)?;
+        let fragment_shader = compile_shader(&gl, Gl::FRAGMENT_SHADER, FRAGMENT_SHADER)?;
+        let program = link_program(&gl, &vertex_shader, &fragment_shader)?;
+        gl.use_program(Some(&program));
+
+        // two triangles covering the whole canvas
+        let vertices: [f32; 12] = [
+            -1.0, -1.0, 1.0, -1.0, -1.0, 1.0, -1.0, 1.0, 1.0, -1.0, 1.0, 1.0,
+        ];
+        let vertex_bytes: Vec<u8> = vertices
+            .iter()
+            .flat_map(|vertex| vertex.to_le_bytes().to_vec())
+            .collect();
+        let vertex_array = gl
+            .create_vertex_array()
+            .ok_or_else(|| JsValue::from_str("could not create a vertex array"))?;
+        gl.bind_vertex_array(Some(&vertex_array));
+        let buffer = gl
+            .create_buffer()
+            .ok_or_else(|| JsValue::from_str("could not create a buffer"))?;
+        gl.bind_buffer(Gl::ARRAY_BUFFER, Some(&buffer));
+        gl.buffer_data_with_u8_array(Gl::ARRAY_BUFFER, &vertex_bytes, Gl::STATIC_DRAW);
+        let position = gl.get_attrib_location(&program, "a_position") as u32;
+        gl.enable_vertex_attrib_array(position);
+        gl.vertex_attrib_pointer_with_i32(position, 2, Gl::FLOAT, false, 0, 0);
+
+        let texture = gl
+            .create_texture()
+            .ok_or_else(|| JsValue::from_str("could not create a texture"))?;
+        gl.active_texture(Gl::TEXTURE0);
+        gl.bind_texture(Gl::TEXTURE_2D, Some(&texture));
+        // integer textures can't be filtered
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_MIN_FILTER, Gl::NEAREST as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_MAG_FILTER, Gl::NEAREST as i32);
+        gl.pixel_storei(Gl::UNPACK_ALIGNMENT, 4);
+        gl.uniform1i(gl.get_uniform_location(&program, "u_cells").as_ref(), 0);
+        // the colors never change so they're only set once
+        let [red, green, blue, alpha] = alive_color;
+        gl.uniform4f(
+            gl.get_uniform_location(&program, "u_alive_color").as_ref(),
+            red,
+            green,
+            blue,
+            alpha,
+        );
+        let [red, green, blue, alpha] = dead_color;
+        gl.uniform4f(
+            gl.get_uniform_location(&program, "u_dead_color").as_ref(),
+            red,
+            green,
+            blue,
+            alpha,
+        );
+
+        Ok(Self {
+            size_location: gl.get_uniform_location(&program, "u_size"),
+            texture_width_location: gl.get_uniform_location(&program, "u_texture_width"),
+            canvas,
+            gl,
+            program,
+            texture,
+            upload: vec![],
+        })
+    }
+
+    pub fn draw(&mut self, universe: &Universe) -> Result<(), JsValue> {
+        let words = universe.cells.words();
+        if words.is_empty() {
+            return Ok(());
+        }
+        let (texture_width, texture_height) = texture_layout(words.len());
+        fill_upload(&mut self.upload, words, texture_width * texture_height);
+
+        let gl = &self.gl;
+        gl.use_program(Some(&self.program));
+        gl.viewport(
+            0,
+            0,
+            self.canvas.width() as i32,
+            self.canvas.height() as i32,
+        );
+        gl.bind_texture(Gl::TEXTURE_2D, Some(&self.texture));
+        gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+            Gl::TEXTURE_2D,
+            0,
+            Gl::R32UI as i32,
+            texture_width as i32,
+            texture_height as i32,
+            0,
+            Gl::RED_INTEGER,
+            Gl::UNSIGNED_INT,
+            Some(&self.upload),
+        )?;
+
+        gl.uniform2ui(
+            self.size_location.as_ref(),
+            universe.width(),
+            universe.height(),
+        );
+        gl.uniform1ui(self.texture_width_location.as_ref(), texture_width as u32);
+        gl.draw_arrays(Gl::TRIANGLES, 0, 6);
+
+        Ok(())
+    }
+}
+
+// The texture width and height in words needed to hold `word_count` words
+fn texture_layout(word_count: usize) -> (usize, usize) {
+    let width = word_count.clamp(1, MAX_TEXTURE_WIDTH);
+    (width, word_count.div_ceil(width))
+}
+
+// Copies the words into the upload buffer as little endian bytes, padded with zeros to
+// fill the whole texture
+fn fill_upload(upload: &mut Vec<u8>, words: &[u32], texel_count: usize) {
+    upload.clear();
+    upload.extend(words.iter().flat_map(|word| word.to_le_bytes().to_vec()));
+    upload.resize(texel_count * 4, 0);
+}
+
+fn parse_hex_color(color: &str) -> Option<[f32; 4]> {
+    let [red, green, blue] = hex_rgb(color)?;
+    let channel = |value: u8| f32::from(value) / 255.0;
+
+    Some([channel(red), channel(green), channel(blue), 1.0])
+}
+
+fn compile_shader(gl: &Gl, kind: u32, source: &str) -> Result<WebGlShader, JsValue> {
+    let shader = gl
+        .create_shader(kind)
+        .ok_or_else(|| JsValue::from_str("could not create a shader"))?;
+    gl.shader_source(&shader, source);
+    gl.compile_shader(&shader);
+
+    if gl
+        .get_shader_parameter(&shader, Gl::COMPILE_STATUS)
+        .as_bool()
+        .unwrap_or(false)
+    {
+        Ok(shader)
+    } else {
+        Err(JsValue::from_str(
+            &gl.get_shader_info_log(&shader)
+                .unwrap_or_else(|| "could not compile the shader".to_owned()),
+        ))
+    }
+}
+
+fn link_program(
+    gl: &Gl,
+    vertex_shader: &WebGlShader,
+    fragment_shader: &WebGlShader,
+) -> Result<WebGlProgram, JsValue> {
+    let program = gl
+        .create_program()
+        .ok_or_else(|| JsValue::from_str("could not create a program"))?;
+    gl.attach_shader(&program, vertex_shader);
+    gl.attach_shader(&program, fragment_shader);
+    gl.link_program(&program);
+
+    if gl
+        .get_program_parameter(&program, Gl::LINK_STATUS)
+        .as_bool()
+        .unwrap_or(false)
+    {
+        Ok(program)
+    } else {
+        Err(JsValue::from_str(
+            &gl.get_program_info_log(&program)
+                .unwrap_or_else(|| "could not link the program".to_owned()),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_texture_layout() {
+        assert_eq!((1, 1), texture_layout(1));
+        assert_eq!((4096, 1), texture_layout(4096));
+        // a 2000x2000 universe
+        assert_eq!((4096, 31), texture_layout(125_000));
+    }
+
+    #[test]
+    fn test_fill_upload() {
+        let mut upload = vec![9; 100];
+        fill_upload(&mut upload, &[0x0403_0201, 5], 3);
+
+        assert_eq!(vec![1, 2, 3, 4, 5, 0, 0, 0, 0, 0, 0, 0], upload);
+    }
+
+    #[test]
+    fn test_parse_hex_color() {
+        assert_eq!(Some([1.0, 0.0, 0.2, 1.0]), parse_hex_color("#ff0033"));
+        assert_eq!(None, parse_hex_color("ff0033"));
+        assert_eq!(None, parse_hex_color("#fff"));
+        assert_eq!(None, parse_hex_color("#gg0000"));
+    }
+}
@@ -0,0 +1,410 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::formats::{save, DecodeError};
+use crate::{
+    BrushShape, Cell, Clipboard, ColorVariant, GridTopology, Neighborhood, PasteMode, Pattern,
+    ResizeAnchor, StochasticRule, Topology, Transform, Universe,
+};
+
+// The calls Universe writes down while it is recording, along with the generation they
+// were made at. Ticks aren't listed, a replay ticks its way from one action to the next.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ReplayAction {
+    ToggleCell {
+        row: u32,
+        column: u32,
+    },
+    SetCell {
+        row: u32,
+        column: u32,
+        alive: bool,
+    },
+    InsertPattern {
+        pattern: Pattern,
+        row: u32,
+        column: u32,
+        transform: Transform,
+    },
+    RandomizeSeeded {
+        // kept as text, JavaScript numbers can't hold every u64
+        #[serde(
+            serialize_with = "seed_to_string",
+            deserialize_with = "seed_from_string"
+        )]
+        seed: u64,
+        alive_probability: f64,
+    },
+    SetRule {
+        rule: String,
+    },
+    Clear,
+    StepBack,
+    StepForward,
+    Paste {
+        clipboard: Clipboard,
+        row: u32,
+        column: u32,
+        mode: PasteMode,
+    },
+    PaintCell {
+        row: u32,
+        column: u32,
+        color: u8,
+    },
+    DrawLine {
+        start_row: i32,
+        start_column: i32,
+        end_row: i32,
+        end_column: i32,
+        alive: bool,
+    },
+    DrawRect {
+        row: i32,
+        column: i32,
+        width: u32,
+        height: u32,
+        alive: bool,
+    },
+    FillRect {
+        row: i32,
+        column: i32,
+        width: u32,
+        height: u32,
+        alive: bool,
+    },
+    DrawCircle {
+        center_row: i32,
+        center_column: i32,
+        radius: u32,
+        alive: bool,
+    },
+    Brush {
+        row: i32,
+        column: i32,
+        radius: u32,
+        shape: BrushShape,
+        alive: bool,
+    },
+    Resize {
+        width: u32,
+        height: u32,
+        anchor: ResizeAnchor,
+    },
+    SetEdgeTopology {
+        topology: Topology,
+    },
+    SetTopology {
+        topology: GridTopology,
+    },
+    SetNeighborhood {
+        neighborhood: Neighborhood,
+        radius: u32,
+    },
+    SetStochasticRule {
+        birth: f64,
+        survival: f64,
+        spontaneous_birth: f64,
+        #[serde(
+            serialize_with = "seed_to_string",
+            deserialize_with = "seed_from_string"
+        )]
+        seed: u64,
+    },
+    ClearStochasticRule,
+    SetActiveRegion {
+        row: u32,
+        column: u32,
+        width: u32,
+        height: u32,
+    },
+    ClearActiveRegion,
+    SetColorVariant {
+        color_variant: ColorVariant,
+    },
+    SetHistoryCapacity {
+        capacity: usize,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReplayEntry {
+    pub generation: u32,
+    #[serde(flatten)]
+    pub action: ReplayAction,
+}
+
+// A recording of an interactive session: the board when recording started in the save
+// format, then every action. Playing it back gives the same board every time.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReplayLog {
+    pub start: Vec<u8>,
+    // step_back() depends on how many generations are kept
+    pub history_capacity: usize,
+    pub entries: Vec<ReplayEntry>,
+    // the generation the recording ended at, after the last action
+    pub end_generation: u32,
+}
+
+impl ReplayLog {
+    pub fn new(universe: &Universe) -> Self {
+        Self {
+            start: save::write(universe),
+            history_capacity: universe.history_capacity(),
+            entries: vec![],
+            end_generation: universe.generation(),
+        }
+    }
+
+    pub fn record(&mut self, generation: u32, action: ReplayAction) {
+        self.entries.push(ReplayEntry { generation, action });
+    }
+
+    pub fn play(&self) -> Result<Universe, DecodeError> {
+        let mut universe = save::read(&self.start)?;
+        universe.set_history_capacity(self.history_capacity);
+        for entry in self.entries.iter() {
+            tick_to(&mut universe, entry.generation)?;
+            match &entry.action {
+                ReplayAction::ToggleCell { row, column } => universe.toggle_cell(*row, *column),
+                ReplayAction::SetCell { row, column, alive } => {
+                    universe.set_cell(*row, *column, Cell::from(*alive))
+                }
+                ReplayAction::InsertPattern {
+                    pattern,
+                    row,
+                    column,
+                    transform,
+                } => universe.insert_pattern_transformed(*pattern, *row, *column, *transform),
+                ReplayAction::RandomizeSeeded {
+                    seed,
+                    alive_probability,
+                } => universe.randomize_seeded(*seed, *alive_probability),
+                ReplayAction::SetRule { rule } => universe
+                    .set_rule(rule)
+                    .map_err(|error| DecodeError::new(error.to_string()))?,
+                ReplayAction::Clear => universe.clear(),
+                ReplayAction::StepBack => {
+                    universe.step_back();
+                }
+                ReplayAction::StepForward => universe.step_forward(),
+                ReplayAction::Paste {
+                    clipboard,
+                    row,
+                    column,
+                    mode,
+                } => universe.paste(clipboard, *row, *column, *mode),
+                ReplayAction::PaintCell { row, column, color } => {
+                    universe.paint_cell(*row, *column, *color)
+                }
+                ReplayAction::DrawLine {
+                    start_row,
+                    start_column,
+                    end_row,
+                    end_column,
+                    alive,
+                } => universe.draw_line(
+                    *start_row,
+                    *start_column,
+                    *end_row,
+                    *end_column,
+                    Cell::from(*alive),
+                ),
+                ReplayAction::DrawRect {
+                    row,
+                    column,
+                    width,
+                    height,
+                    alive,
+                } => universe.draw_rect(*row, *column, *width, *height, Cell::from(*alive)),
+                ReplayAction::FillRect {
+                    row,
+                    column,
+                    width,
+                    height,
+                    alive,
+                } => universe.fill_rect(*row, *column, *width, *height, Cell::from(*alive)),
+                ReplayAction::DrawCircle {
+                    center_row,
+                    center_column,
+                    radius,
+                    alive,
+                } => universe.draw_circle(*center_row, *center_column, *radius, Cell::from(*alive)),
+                ReplayAction::Brush {
+                    row,
+                    column,
+                    radius,
+                    shape,
+                    alive,
+                } => universe.brush(*row, *column, *radius, *shape, Cell::from(*alive)),
+                ReplayAction::Resize {
+                    width,
+                    height,
+                    anchor,
+                } => universe.resize(*width, *height, *anchor),
+                ReplayAction::SetEdgeTopology { topology } => universe.set_edge_topology(*topology),
+                ReplayAction::SetTopology { topology } => universe.set_topology(*topology),
+                ReplayAction::SetNeighborhood {
+                    neighborhood,
+                    radius,
+                } => universe.set_neighborhood(*neighborhood, *radius),
+                ReplayAction::SetStochasticRule {
+                    birth,
+                    survival,
+                    spontaneous_birth,
+                    seed,
+                } => universe.set_stochastic_rule(Some(StochasticRule::new(
+                    *birth,
+                    *survival,
+                    *spontaneous_birth,
+                    *seed,
+                ))),
+                ReplayAction::ClearStochasticRule => universe.set_stochastic_rule(None),
+                ReplayAction::SetActiveRegion {
+                    row,
+                    column,
+                    width,
+                    height,
+                } => universe.set_active_region(*row, *column, *width, *height),
+                ReplayAction::ClearActiveRegion => universe.clear_active_region(),
+                ReplayAction::SetColorVariant { color_variant } => {
+                    universe.set_color_variant(*color_variant)
+                }
+                ReplayAction::SetHistoryCapacity { capacity } => {
+                    universe.set_history_capacity(*capacity)
+                }
+            }
+        }
+        tick_to(&mut universe, self.end_generation)?;
+
+        Ok(universe)
+    }
+}
+
+// Generations only go backwards through an action, so a log that needs them to is broken
+fn tick_to(universe: &mut Universe, generation: u32) -> Result<(), DecodeError> {
+    if generation < universe.generation() {
+        return Err(DecodeError::new(format!(
+            "the replay goes back to generation {} without stepping back",
+            generation
+        )));
+    }
+    universe.run_until_generation(generation);
+    Ok(())
+}
+
+fn seed_to_string<S: Serializer>(seed: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&seed.to_string())
+}
+
+fn seed_from_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_play() {
+        let mut universe = Universe::new(16);
+        universe.set_history_capacity(4);
+        universe.start_recording();
+        universe.insert_pattern(Pattern::Glider, 0, 0);
+        universe.tick_n(3);
+        universe.toggle_cell(10, 10);
+        universe.set_rule("B36/S23").unwrap();
+        universe.tick_n(2);
+        universe.step_back();
+        universe.randomize_seeded(u64::MAX, 0.3);
+        universe.tick_n(4);
+        universe.set_cell(0, 0, Cell::Alive);
+        universe.tick();
+
+        let log = universe.replay_log().unwrap();
+        assert_eq!(6, log.entries.len());
+        assert_eq!(9, log.end_generation);
+
+        let copy = log.play().unwrap();
+        assert_eq!(universe.cells(), copy.cells());
+        assert_eq!(universe.generation(), copy.generation());
+        assert_eq!("B36/S23", copy.rule());
+    }
+
+    #[test]
+    fn test_play_every_action() {
+        let mut universe = Universe::new(24);
+        universe.start_recording();
+        universe.randomize();
+        universe.tick_n(2);
+        universe.set_history_capacity(3);
+        universe.set_color_variant(ColorVariant::Immigration);
+        universe.paint_cell(3, 3, 1);
+        universe.paste(
+            &Clipboard::from_pattern(Pattern::Glider),
+            5,
+            5,
+            PasteMode::Xor,
+        );
+        universe.draw_line(0, 0, 20, 9, Cell::Alive);
+        universe.draw_rect(-2, 4, 6, 5, Cell::Alive);
+        universe.fill_rect(10, 10, 4, 3, Cell::Dead);
+        universe.draw_circle(12, 12, 5, Cell::Alive);
+        universe.brush(20, 3, 2, BrushShape::Square, Cell::Dead);
+        universe.tick();
+        universe.set_edge_topology(Topology::Torus);
+        universe.set_neighborhood(Neighborhood::VonNeumann, 2);
+        universe.tick_n(2);
+        universe.set_stochastic_rule(Some(StochasticRule::new(0.9, 0.8, 0.01, u64::MAX)));
+        universe.set_active_region(2, 2, 16, 16);
+        universe.tick_n(3);
+        universe.step_back();
+        universe.set_stochastic_rule(None);
+        universe.clear_active_region();
+        universe.set_topology(GridTopology::Hex);
+        universe.resize(30, 20, ResizeAnchor::Center);
+        universe.tick_n(2);
+
+        let log = universe.replay_log().unwrap();
+        // the log goes through JSON the way export_replay() hands it to JavaScript
+        let log: ReplayLog = serde_json::from_str(&serde_json::to_string(&log).unwrap()).unwrap();
+        let copy = log.play().unwrap();
+        assert_eq!(universe.cells(), copy.cells());
+        assert_eq!(universe.colors, copy.colors);
+        assert_eq!(universe.generation(), copy.generation());
+        assert_eq!(30, copy.width());
+    }
+
+    #[test]
+    fn test_serialized_entries() {
+        let entry = ReplayEntry {
+            generation: 3,
+            action: ReplayAction::RandomizeSeeded {
+                seed: u64::MAX,
+                alive_probability: 0.5,
+            },
+        };
+        let json = serde_json::to_string(&entry).unwrap();
+
+        assert_eq!(
+            r#"{"generation":3,"type":"RandomizeSeeded","seed":"18446744073709551615","alive_probability":0.5}"#,
+            json
+        );
+        assert_eq!(entry, serde_json::from_str(&json).unwrap());
+    }
+
+    #[test]
+    fn test_play_rejects_going_back_in_time() {
+        let mut universe = Universe::new(4);
+        universe.tick_n(5);
+        let mut log = ReplayLog::new(&universe);
+        log.record(2, ReplayAction::Clear);
+
+        assert_eq!(
+            DecodeError::new("the replay goes back to generation 2 without stepping back"),
+            log.play().unwrap_err()
+        );
+    }
+}
@@ -0,0 +1,443 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::bits::CellBits;
+use crate::formats::ParseError;
+use crate::tiles::TileActivity;
+use crate::Universe;
+
+// Hex grids are stored on the square grid with each row shifted half a cell to the left of
+// the one below it, so the above right and below left cells aren't neighbors and every
+// cell has 6 of them
+// https://conwaylife.com/wiki/Hexagonal_neighbourhood
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GridTopology {
+    Square,
+    Hex,
+}
+
+impl GridTopology {
+    // Whether the cell at the offset, each between -1 and 1, is a neighbor
+    pub fn is_neighbor(self, row_offset: i64, column_offset: i64) -> bool {
+        !matches!(
+            (self, row_offset, column_offset),
+            (_, 0, 0) | (GridTopology::Hex, -1, 1) | (GridTopology::Hex, 1, -1)
+        )
+    }
+}
+
+// Life-like rules in B/S notation, bit n of birth is set when a dead cell with n live
+// neighbors is born and bit n of survival when a live cell with n live neighbors survives
+// https://conwaylife.com/wiki/Rulestring
+//
+// Generations rules add a number of states, a live cell that doesn't survive fades through
+// states 2 up to states - 1 before it is dead, and fading cells can't be born or count as
+// neighbors. Life-like rules have 2 states.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RuleSet {
+    birth: u16,
+    survival: u16,
+    states: u8,
+    topology: GridTopology,
+}
+
+impl RuleSet {
+    pub fn conway() -> Self {
+        Self {
+            birth: 1 << 3,
+            survival: (1 << 2) | (1 << 3),
+            states: 2,
+            topology: GridTopology::Square,
+        }
+    }
+
+    // The birth and survival masks, bit n is set when n live neighbors give birth or survive
+    pub fn masks(&self) -> (u16, u16) {
+        (self.birth, self.survival)
+    }
+
+    pub fn from_masks(birth: u16, survival: u16) -> Self {
+        Self {
+            birth: birth & 0x1ff,
+            survival: survival & 0x1ff,
+            states: 2,
+            topology: GridTopology::Square,
+        }
+    }
+
+    // A rule where nothing is born or survives, standing in for rules counted some other
+    // way so that cells still fade through the states
+    pub(crate) fn with_states(states: u8) -> Self {
+        Self {
+            states: states.max(2),
+            ..Self::from_masks(0, 0)
+        }
+    }
+
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    pub fn topology(&self) -> GridTopology {
+        self.topology
+    }
+
+    pub fn set_topology(&mut self, topology: GridTopology) {
+        self.topology = topology;
+    }
+
+    pub(crate) fn set_states(&mut self, states: u8) {
+        self.states = states.max(2);
+    }
+
+    // The state a cell in `state` fades to when it doesn't survive, 0 is dead
+    pub fn fade(&self, state: u8) -> u8 {
+        if state + 1 < self.states {
+            state + 1
+        } else {
+            0
+        }
+    }
+
+    pub fn is_alive_next(&self, alive: bool, live_neighbors: u8) -> bool {
+        let table = if alive { self.survival } else { self.birth };
+        live_neighbors <= 8 && table & (1 << live_neighbors) != 0
+    }
+}
+
+// The next state of a cell for each of the 512 ways its 3 by 3 neighborhood can be, as
+// a bitmask in row major order with the cell itself at bit 4. Looking the whole
+// neighborhood up at once works the same for B/S rules and for rules that care which
+// neighbors are alive.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct RuleTable([bool; 512]);
+
+impl RuleTable {
+    pub(crate) const CENTER: usize = 1 << 4;
+
+    pub(crate) fn new(is_alive_next: impl Fn(usize) -> bool) -> Self {
+        let mut table = [false; 512];
+        for (neighborhood, next) in table.iter_mut().enumerate() {
+            *next = is_alive_next(neighborhood);
+        }
+        Self(table)
+    }
+
+    pub(crate) fn is_alive_next(&self, neighborhood: u16) -> bool {
+        self.0[usize::from(neighborhood) & 0x1ff]
+    }
+
+    // A row at a time, with the rows above, at and below it unpacked to a byte a cell so
+    // that each neighborhood is read from three slices walked side by side. The edges are
+    // all taken care of in the padding read_padded_row() puts on the ends. Cells in tiles
+    // that are asleep are copied as they are.
+    pub(crate) fn tick(
+        &self,
+        universe: &Universe,
+        next: &mut CellBits,
+        tiles: Option<&TileActivity>,
+    ) {
+        let width = universe.width as usize;
+        let (mut above, mut current, mut below) = (vec![], vec![], vec![]);
+        // the row in current, rows in sleeping tiles aren't read at all
+        let mut current_row = None;
+        let mut next_row = vec![false; width];
+
+        for row in 0..i64::from(universe.height) {
+            let start = row as usize * width;
+            if !tiles.is_none_or(|tiles| tiles.is_row_awake(row as u32)) {
+                for offset in (0..width).step_by(32) {
+                    let count = (width - offset).min(32);
+                    next.write_bits(
+                        start + offset,
+                        count,
+                        universe.cells.read_bits(start + offset, count),
+                    );
+                }
+                continue;
+            }
+            if current_row != Some(row) {
+                universe.read_padded_row(row - 1, &mut above);
+                universe.read_padded_row(row, &mut current);
+            }
+            universe.read_padded_row(row + 1, &mut below);
+
+            let neighborhoods = above
+                .windows(3)
+                .zip(current.windows(3))
+                .zip(below.windows(3));
+            for (column, (cell, ((above, current), below))) in
+                next_row.iter_mut().zip(neighborhoods).enumerate()
+            {
+                if !tiles.is_none_or(|tiles| tiles.is_awake(row as u32, column as u32)) {
+                    *cell = current[1] != 0;
+                    continue;
+                }
+                let neighborhood = u16::from(above[0])
+                    | u16::from(above[1]) << 1
+                    | u16::from(above[2]) << 2
+                    | u16::from(current[0]) << 3
+                    | u16::from(current[1]) << 4
+                    | u16::from(current[2]) << 5
+                    | u16::from(below[0]) << 6
+                    | u16::from(below[1]) << 7
+                    | u16::from(below[2]) << 8;
+                *cell = self.is_alive_next(neighborhood);
+            }
+
+            for (chunk, cells) in next_row.chunks(32).enumerate() {
+                let bits = cells
+                    .iter()
+                    .enumerate()
+                    .fold(0, |bits, (bit, alive)| bits | (*alive as u32) << bit);
+                next.write_bits(start + chunk * 32, cells.len(), bits);
+            }
+            std::mem::swap(&mut above, &mut current);
+            std::mem::swap(&mut current, &mut below);
+            current_row = Some(row + 1);
+        }
+    }
+}
+
+// Hex rules leave the above right and below left cells out of the count
+impl From<RuleSet> for RuleTable {
+    fn from(rule: RuleSet) -> Self {
+        RuleTable::new(|neighborhood| {
+            let live_neighbors = (0..9)
+                .filter(|bit| neighborhood & (1 << bit) != 0)
+                .filter(|bit| rule.topology.is_neighbor(bit / 3 - 1, bit % 3 - 1))
+                .count();
+            rule.is_alive_next(neighborhood & RuleTable::CENTER != 0, live_neighbors as u8)
+        })
+    }
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        Self::conway()
+    }
+}
+
+impl FromStr for RuleSet {
+    type Err = ParseError;
+
+    // Accepts "B3/S23" in either order and any case, as well as the older "23/3"
+    // survival/birth notation. Generations rules have a third part with the number of
+    // states, like "B2/S345/C4" or the older "345/2/4". A trailing H picks the hex
+    // topology, like "B2/S34H".
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let rule = rule.trim();
+        let (rule, topology) = match rule.strip_suffix(|letter| letter == 'H' || letter == 'h') {
+            Some(rule) => (rule, GridTopology::Hex),
+            None => (rule, GridTopology::Square),
+        };
+        let parts: Vec<&str> = rule.split('/').collect();
+        if parts.len() != 2 && parts.len() != 3 {
+            return Err(ParseError::new(1, 1, "expected a rule like B3/S23"));
+        }
+
+        let mut birth = None;
+        let mut survival = None;
+        let mut states = None;
+        let mut column = 1;
+        for (position, part) in parts.iter().enumerate() {
+            let (kind, digits) = match part.chars().next() {
+                Some('B') | Some('b') => ('B', &part[1..]),
+                Some('S') | Some('s') => ('S', &part[1..]),
+                Some('C') | Some('c') | Some('G') | Some('g') => ('C', &part[1..]),
+                _ if position == 0 => ('S', *part),
+                _ if position == 1 => ('B', *part),
+                _ => ('C', *part),
+            };
+            let digits_column = column + part.len() - digits.len();
+            let duplicate = match kind {
+                'B' => birth
+                    .replace(parse_neighbor_counts(digits, digits_column)?)
+                    .is_some(),
+                'S' => survival
+                    .replace(parse_neighbor_counts(digits, digits_column)?)
+                    .is_some(),
+                _ => states
+                    .replace(parse_states(digits, digits_column)?)
+                    .is_some(),
+            };
+            if duplicate {
+                return Err(ParseError::new(
+                    1,
+                    column,
+                    format!("{} is listed twice", kind),
+                ));
+            }
+            column += part.len() + 1;
+        }
+
+        match (birth, survival) {
+            (Some(birth), Some(survival)) => Ok(Self {
+                birth,
+                survival,
+                states: states.unwrap_or(2),
+                topology,
+            }),
+            _ => Err(ParseError::new(1, 1, "expected a rule like B3/S23")),
+        }
+    }
+}
+
+fn parse_neighbor_counts(digits: &str, column: usize) -> Result<u16, ParseError> {
+    digits
+        .chars()
+        .enumerate()
+        .try_fold(0, |counts, (index, digit)| match digit.to_digit(10) {
+            Some(count) if count <= 8 => Ok(counts | (1 << count)),
+            _ => Err(ParseError::new(
+                1,
+                column + index,
+                format!("'{}' is not a neighbor count between 0 and 8", digit),
+            )),
+        })
+}
+
+pub(crate) fn parse_states(digits: &str, column: usize) -> Result<u8, ParseError> {
+    match digits.parse() {
+        Ok(states) if states >= 2 => Ok(states),
+        _ => Err(ParseError::new(
+            1,
+            column,
+            format!("'{}' is not a number of states between 2 and 255", digits),
+        )),
+    }
+}
+
+impl Display for RuleSet {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "B")?;
+        for count in (0..=8).filter(|count| self.birth & (1 << count) != 0) {
+            write!(f, "{}", count)?;
+        }
+        write!(f, "/S")?;
+        for count in (0..=8).filter(|count| self.survival & (1 << count) != 0) {
+            write!(f, "{}", count)?;
+        }
+        if self.states > 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+        if self.topology == GridTopology::Hex {
+            write!(f, "H")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_conway_rules() {
+        let rule = RuleSet::conway();
+
+        assert!(rule.is_alive_next(false, 3));
+        assert!(!rule.is_alive_next(false, 2));
+        assert!(rule.is_alive_next(true, 2));
+        assert!(rule.is_alive_next(true, 3));
+        assert!(!rule.is_alive_next(true, 1));
+        assert!(!rule.is_alive_next(true, 4));
+    }
+
+    #[test]
+    fn test_rule_table() {
+        // bit 0 is the top left cell, so the bottom row is written first
+        let table = RuleTable::from(RuleSet::conway());
+        assert!(!table.is_alive_next(0b000_011_000));
+        assert!(table.is_alive_next(0b010_001_001));
+        assert!(table.is_alive_next(0b001_011_001));
+        assert!(!table.is_alive_next(0b111_111_111));
+
+        let mut hex = RuleSet::from_masks(1 << 2, 0);
+        hex.set_topology(GridTopology::Hex);
+        let table = RuleTable::from(hex);
+        assert!(table.is_alive_next(0b000_000_011));
+        assert!(!table.is_alive_next(0b000_000_101));
+    }
+
+    #[test]
+    fn test_parse_rules() {
+        assert_eq!(RuleSet::conway(), "B3/S23".parse().unwrap());
+        assert_eq!(RuleSet::conway(), "s23/b3".parse().unwrap());
+        assert_eq!(RuleSet::conway(), "23/3".parse().unwrap());
+
+        let high_life: RuleSet = "B36/S23".parse().unwrap();
+        assert!(high_life.is_alive_next(false, 6));
+
+        let seeds: RuleSet = "B2/S".parse().unwrap();
+        assert!(!seeds.is_alive_next(true, 2));
+        assert!(seeds.is_alive_next(false, 2));
+    }
+
+    #[test]
+    fn test_parse_rule_errors() {
+        assert_eq!(
+            ParseError::new(1, 1, "expected a rule like B3/S23"),
+            "B3".parse::<RuleSet>().unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(1, 6, "'9' is not a neighbor count between 0 and 8"),
+            "B3/S29".parse::<RuleSet>().unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(1, 4, "B is listed twice"),
+            "B3/B2".parse::<RuleSet>().unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!("B3/S23", RuleSet::conway().to_string());
+        assert_eq!(
+            "B3678/S34678",
+            "B3678/S34678".parse::<RuleSet>().unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn test_parse_generations_rules() {
+        let star_wars: RuleSet = "345/2/4".parse().unwrap();
+        assert_eq!(star_wars, "B2/S345/C4".parse().unwrap());
+        assert_eq!(star_wars, "b2/g4/s345".parse().unwrap());
+        assert_eq!(4, star_wars.states());
+        assert_eq!("B2/S345/C4", star_wars.to_string());
+        assert_eq!(2, star_wars.fade(1));
+        assert_eq!(3, star_wars.fade(2));
+        assert_eq!(0, star_wars.fade(3));
+
+        let brians_brain: RuleSet = "/2/3".parse().unwrap();
+        assert!(!brians_brain.is_alive_next(true, 2));
+        assert!(brians_brain.is_alive_next(false, 2));
+
+        assert_eq!(2, RuleSet::conway().states());
+        assert_eq!(0, RuleSet::conway().fade(1));
+        assert_eq!(
+            ParseError::new(1, 9, "'1' is not a number of states between 2 and 255"),
+            "B2/S34/C1".parse::<RuleSet>().unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_hex_rules() {
+        let rule: RuleSet = "B2/S34H".parse().unwrap();
+
+        assert_eq!(GridTopology::Hex, rule.topology());
+        assert!(rule.is_alive_next(false, 2));
+        assert_eq!("B2/S34H", rule.to_string());
+        assert_eq!(GridTopology::Square, RuleSet::conway().topology());
+        assert!(!GridTopology::Hex.is_neighbor(-1, 1));
+        assert!(GridTopology::Hex.is_neighbor(-1, -1));
+        assert!(GridTopology::Square.is_neighbor(1, -1));
+    }
+}
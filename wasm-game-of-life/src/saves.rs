@@ -0,0 +1,107 @@
+use std::collections::BTreeMap;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::formats::{compressed, save, DecodeError};
+use crate::Universe;
+
+// Named save slots holding the whole simulation, see formats/save.rs for what is kept.
+// The slots only live as long as the manager, export() and import() move a slot in and
+// out as bytes for keeping it in IndexedDB or a file.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug, Default)]
+pub struct SaveManager {
+    slots: BTreeMap<String, Vec<u8>>,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl SaveManager {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Saving to a name that is already taken replaces that save. Saves are kept
+    // compressed, which is also how export() hands them out.
+    pub fn save(&mut self, name: &str, universe: &Universe) {
+        let bytes = compressed::compress(&save::write(universe));
+        self.slots.insert(name.to_string(), bytes);
+    }
+
+    pub fn load(&self, name: &str) -> Result<Universe, DecodeError> {
+        let bytes = self
+            .slots
+            .get(name)
+            .ok_or_else(|| DecodeError::new(format!("there is no save named {}", name)))?;
+        save::read(bytes)
+    }
+
+    // Returns false when there was no save with the name
+    pub fn delete(&mut self, name: &str) -> bool {
+        self.slots.remove(name).is_some()
+    }
+
+    // In alphabetical order
+    pub fn names(&self) -> Vec<String> {
+        self.slots.keys().cloned().collect()
+    }
+
+    pub fn export(&self, name: &str) -> Option<Vec<u8>> {
+        self.slots.get(name).cloned()
+    }
+
+    // Checks that the bytes load before keeping them, saves from older versions are
+    // kept as they are and upgraded each time they load
+    pub fn import(&mut self, name: &str, bytes: &[u8]) -> Result<(), DecodeError> {
+        save::read(bytes)?;
+        self.slots.insert(name.to_string(), bytes.to_vec());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Pattern;
+
+    #[test]
+    fn test_slots() {
+        let mut universe = Universe::new(8);
+        universe.insert_pattern(Pattern::Glider, 0, 0);
+        let mut saves = SaveManager::new();
+        saves.save("start", &universe);
+        universe.tick_n(4);
+        saves.save("later", &universe);
+
+        assert_eq!(vec!["later", "start"], saves.names());
+        assert_eq!(0, saves.load("start").unwrap().generation());
+        assert_eq!(universe.cells(), saves.load("later").unwrap().cells());
+
+        assert!(saves.delete("start"));
+        assert!(!saves.delete("start"));
+        assert_eq!(
+            DecodeError::new("there is no save named start"),
+            saves.load("start").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_export_and_import() {
+        let mut saves = SaveManager::new();
+        saves.save("board", &Universe::new(4));
+        let bytes = saves.export("board").unwrap();
+
+        let mut other = SaveManager::new();
+        other.import("copy", &bytes).unwrap();
+        assert_eq!(4, other.load("copy").unwrap().width());
+        // the version 1 snapshots from serialize() can be imported too
+        other
+            .import("old", &Universe::new(3).serialize().unwrap())
+            .unwrap();
+        assert_eq!(3, other.load("old").unwrap().width());
+        assert!(other.import("broken", b"GOL").is_err());
+        assert!(bytes.starts_with(b"GOLZ"));
+        assert_eq!(vec!["copy", "old"], other.names());
+    }
+}
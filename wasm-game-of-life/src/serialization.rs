@@ -0,0 +1,184 @@
+// serde support for native programs with the "serde" feature, so a simulation can be kept
+// in whatever format they already use. A universe holds the same things as a save from
+// formats::save, with the cells packed 32 to a number like bits_ptr():
+//
+//     let json = serde_json::to_string(&universe)?;
+//     let universe: Universe = serde_json::from_str(&json)?;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::bits::CellBits;
+use crate::{ColorVariant, Engine, Neighborhood, PatternMetadata, RuleSet, Topology, Universe};
+
+// Rules are kept as text such as "B3/S23", the same as rule() gives back
+impl Serialize for RuleSet {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for RuleSet {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct UniverseState {
+    width: u32,
+    height: u32,
+    // universes serialized before there were topologies have a boundary_mode of Clamped
+    // or Toroidal, which Topology reads as Plane and Torus
+    #[serde(alias = "boundary_mode")]
+    edge_topology: Topology,
+    generation: u32,
+    // whatever rule() gives back, B/S or Larger than Life
+    rule: String,
+    engine: Engine,
+    color_variant: ColorVariant,
+    neighborhood: Neighborhood,
+    neighborhood_radius: u32,
+    cells: Vec<u32>,
+    // only rules with more than 2 states have fading cells
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    fading: Vec<u8>,
+    // only Immigration and QuadLife have colors
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    colors: Vec<u8>,
+    #[serde(default, skip_serializing_if = "PatternMetadata::is_empty")]
+    metadata: PatternMetadata,
+}
+
+impl Serialize for Universe {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        UniverseState {
+            width: self.width,
+            height: self.height,
+            edge_topology: self.edge_topology,
+            generation: self.generation,
+            rule: self.rule(),
+            engine: self.engine(),
+            color_variant: self.color_variant,
+            neighborhood: self.neighborhood,
+            neighborhood_radius: self.neighborhood_radius,
+            cells: self.cells.words().to_vec(),
+            fading: if self.rule.states() > 2 {
+                self.fading.clone()
+            } else {
+                vec![]
+            },
+            colors: if self.color_variant == ColorVariant::Monochrome {
+                vec![]
+            } else {
+                self.colors.clone()
+            },
+            metadata: self.metadata.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Universe {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let state = UniverseState::deserialize(deserializer)?;
+        let cell_count = u64::from(state.width) * u64::from(state.height);
+        let mismatch = || D::Error::custom("cell data does not match the dimensions");
+        if cell_count > u64::from(u32::MAX) || state.cells.len() as u64 != cell_count.div_ceil(32) {
+            return Err(mismatch());
+        }
+        let cell_count = cell_count as usize;
+        for extra in [&state.fading, &state.colors].iter() {
+            if !extra.is_empty() && extra.len() != cell_count {
+                return Err(mismatch());
+            }
+        }
+
+        let mut universe =
+            Universe::new_with_dimensions(state.width, state.height).with_engine(state.engine);
+        universe.set_rule(&state.rule).map_err(D::Error::custom)?;
+        universe.edge_topology = state.edge_topology;
+        universe.generation = state.generation;
+        universe.set_neighborhood(state.neighborhood, state.neighborhood_radius);
+        universe.color_variant = state.color_variant;
+        let mut cells = CellBits::new(cell_count);
+        for (word, bits) in state.cells.iter().enumerate() {
+            cells.write_bits(word * 32, (cell_count - word * 32).min(32), *bits);
+        }
+        universe.cells = cells;
+        if !state.fading.is_empty() {
+            universe.fading = state.fading;
+        }
+        if !state.colors.is_empty() {
+            universe.colors = state.colors;
+        }
+        universe.metadata = state.metadata;
+
+        Ok(universe)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{BoundaryMode, Cell, Pattern};
+
+    #[test]
+    fn test_round_trip() {
+        let mut universe = Universe::new_with_dimensions(40, 9).with_engine(Engine::Packed);
+        universe.set_boundary_mode(BoundaryMode::Toroidal);
+        universe.set_rule("B2/S345/C4").unwrap();
+        universe.set_color_variant(ColorVariant::Immigration);
+        universe.randomize_seeded(5, 0.4);
+        universe.tick_n(3);
+
+        let json = serde_json::to_string(&universe).unwrap();
+        let mut copy: Universe = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(universe.cells(), copy.cells());
+        assert_eq!("B2/S345/C4", copy.rule());
+        assert_eq!(3, copy.generation());
+        assert_eq!(Engine::Packed, copy.engine());
+        assert_eq!(universe.fading, copy.fading);
+        assert_eq!(universe.colors, copy.colors);
+        universe.tick();
+        copy.tick();
+        assert_eq!(universe.cells(), copy.cells());
+
+        universe.set_rule("R2,C3,M1,S5..9,B4..6,NN").unwrap();
+        let copy: Universe =
+            serde_json::from_str(&serde_json::to_string(&universe).unwrap()).unwrap();
+        assert_eq!("R2,C3,M1,S5..9,B4..6,NN", copy.rule());
+
+        let mut metadata = PatternMetadata::new();
+        metadata.set_name(Some("Soup".to_string()));
+        universe.set_metadata(metadata);
+        let copy: Universe =
+            serde_json::from_str(&serde_json::to_string(&universe).unwrap()).unwrap();
+        assert_eq!("Soup", copy.metadata().title());
+    }
+
+    #[test]
+    fn test_small_types() {
+        assert_eq!("\"Alive\"", serde_json::to_string(&Cell::Alive).unwrap());
+        assert_eq!(
+            "\"B36/S23\"",
+            serde_json::to_string(&"B36/S23".parse::<RuleSet>().unwrap()).unwrap()
+        );
+        assert!(serde_json::from_str::<RuleSet>("\"B3/S2x\"").is_err());
+        assert_eq!(Pattern::Glider, serde_json::from_str("\"Glider\"").unwrap());
+        assert_eq!(
+            Topology::Torus,
+            serde_json::from_str("\"Toroidal\"").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_mismatched_cells() {
+        let mut value = serde_json::to_value(Universe::new(8)).unwrap();
+        value["cells"] = serde_json::json!([0]);
+
+        assert!(serde_json::from_value::<Universe>(value).is_err());
+    }
+}
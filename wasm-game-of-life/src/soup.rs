@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{apgcode, census, PasteMode, Universe, UniverseError};
+
+// Oscillators in common ash have periods of 1, 2, 3 and 15, waiting for period 30 to
+// repeat covers all of them
+const MAX_ASH_PERIOD: u32 = 30;
+
+// Every field can be left out of the object passed to run_soup_search()
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SoupConfig {
+    pub soups: u32,
+    // soup n is filled from seed + n, so any soup of a search can be run again on its own
+    pub seed: u64,
+    // the random square in the middle of the board
+    pub soup_size: u32,
+    // the clamped board the soup is run on, big enough that most soups settle before
+    // reaching the edge
+    pub board_size: u32,
+    pub density: f64,
+    pub max_generations: u32,
+    pub rule: String,
+}
+
+impl Default for SoupConfig {
+    fn default() -> Self {
+        Self {
+            soups: 100,
+            seed: 0,
+            soup_size: 16,
+            board_size: 64,
+            density: 0.5,
+            max_generations: 5000,
+            rule: "B3/S23".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SoupResults {
+    pub soups: u32,
+    // soups that settled into still lifes and oscillators before max_generations
+    pub stabilized: u32,
+    // generations run across all soups
+    pub generations: u64,
+    // how many of each object the soups left behind, see census::count_objects()
+    pub census: BTreeMap<String, u32>,
+    // the same objects keyed by apgcode, the names Catagolue uses
+    pub apgcodes: BTreeMap<String, u32>,
+}
+
+// Runs one random soup after another on a headless universe and adds up what is left of
+// them. Nothing here touches the browser, so batch runs can use it natively:
+//
+//     let mut search = SoupSearch::new(SoupConfig { soups: 10_000, ..SoupConfig::default() });
+//     let results = search.run()?;
+#[derive(Clone, Debug, Default)]
+pub struct SoupSearch {
+    config: SoupConfig,
+    results: SoupResults,
+}
+
+impl SoupSearch {
+    pub fn new(config: SoupConfig) -> Self {
+        Self {
+            config,
+            results: SoupResults::default(),
+        }
+    }
+
+    pub fn results(&self) -> &SoupResults {
+        &self.results
+    }
+
+    pub fn run(&mut self) -> Result<&SoupResults, UniverseError> {
+        for soup in 0..self.config.soups {
+            self.run_soup(soup)?;
+        }
+
+        Ok(&self.results)
+    }
+
+    // Runs soup number `soup` and adds it to the results, the universe it settled into
+    // is returned for a closer look. Fails when the rule in the config can't be parsed.
+    pub fn run_soup(&mut self, soup: u32) -> Result<Universe, UniverseError> {
+        let config = &self.config;
+        let mut universe = Universe::new(config.board_size);
+        universe.set_rule(&config.rule)?;
+
+        let mut filler = Universe::new(config.soup_size);
+        filler.randomize_seeded(config.seed.wrapping_add(u64::from(soup)), config.density);
+        let clipboard = filler.copy_region(0, 0, config.soup_size, config.soup_size);
+        let corner = config.board_size.saturating_sub(config.soup_size) / 2;
+        universe.paste(&clipboard, corner, corner, PasteMode::Overwrite);
+
+        let mut stabilized = false;
+        while universe.generation() < config.max_generations {
+            universe.tick();
+            if universe.detect_cycle(MAX_ASH_PERIOD).is_some() {
+                stabilized = true;
+                break;
+            }
+        }
+
+        self.results.soups += 1;
+        self.results.stabilized += stabilized as u32;
+        self.results.generations += u64::from(universe.generation());
+        for (name, count) in census::count_objects(&universe) {
+            *self.results.census.entry(name).or_insert(0) += count;
+        }
+        for (apgcode, count) in apgcode::count_apgcodes(&universe) {
+            *self.results.apgcodes.entry(apgcode).or_insert(0) += count;
+        }
+
+        Ok(universe)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_soup_search() {
+        let config = SoupConfig {
+            soups: 4,
+            seed: 7,
+            max_generations: 2000,
+            ..SoupConfig::default()
+        };
+        let mut search = SoupSearch::new(config.clone());
+        let results = search.run().unwrap().clone();
+
+        assert_eq!(4, results.soups);
+        assert!(results.generations > 0);
+        assert!(results.census.values().sum::<u32>() > 0);
+        // the same seed gives the same results
+        assert_eq!(&results, SoupSearch::new(config).run().unwrap());
+    }
+
+    #[test]
+    fn test_run_soup_stops_when_stable() {
+        let mut search = SoupSearch::new(SoupConfig {
+            soup_size: 2,
+            density: 1.0,
+            ..SoupConfig::default()
+        });
+        let universe = search.run_soup(0).unwrap();
+
+        // a full 2 by 2 soup is already a block
+        assert_eq!(1, universe.generation());
+        assert_eq!(1, search.results().stabilized);
+        assert_eq!(Some(&1), search.results().census.get("block"));
+        assert_eq!(Some(&1), search.results().apgcodes.get("xs4_33"));
+    }
+}
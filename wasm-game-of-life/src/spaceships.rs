@@ -0,0 +1,196 @@
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::census::{self, CensusObject};
+use crate::Universe;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Spaceship {
+    Glider,
+    LightweightSpaceship,
+    MiddleweightSpaceship,
+    HeavyweightSpaceship,
+}
+
+// The way a spaceship is travelling across the board, north is up
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Heading {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Heading {
+    fn from_offset(row_offset: i32, column_offset: i32) -> Self {
+        match (row_offset.signum(), column_offset.signum()) {
+            (-1, 0) => Heading::North,
+            (-1, 1) => Heading::NorthEast,
+            (0, 1) => Heading::East,
+            (1, 1) => Heading::SouthEast,
+            (1, 0) => Heading::South,
+            (1, -1) => Heading::SouthWest,
+            (0, -1) => Heading::West,
+            _ => Heading::NorthWest,
+        }
+    }
+}
+
+// Every phase of each spaceship that isn't a mirror image of another phase, along with
+// the way that phase as drawn is travelling
+const SPACESHIP_PHASES: &[(Spaceship, (i32, i32), &[&str])] = &[
+    (Spaceship::Glider, (1, 1), &[".O.", "..O", "OOO"]),
+    (Spaceship::Glider, (1, 1), &["O.O", ".OO", ".O."]),
+    (Spaceship::Glider, (1, 1), &["..O", "O.O", ".OO"]),
+    (Spaceship::Glider, (1, 1), &["O..", ".OO", "OO."]),
+    (
+        Spaceship::LightweightSpaceship,
+        (0, 1),
+        &["O..O.", "....O", "O...O", ".OOOO"],
+    ),
+    (
+        Spaceship::LightweightSpaceship,
+        (0, 1),
+        &["..OO.", "OO.OO", "OOOO.", ".OO.."],
+    ),
+    (
+        Spaceship::MiddleweightSpaceship,
+        (0, 1),
+        &["..O...", "O...O.", ".....O", "O....O", ".OOOOO"],
+    ),
+    (
+        Spaceship::MiddleweightSpaceship,
+        (0, 1),
+        &["...OO.", "OOO.OO", "OOOOO.", ".OOO.."],
+    ),
+    (
+        Spaceship::HeavyweightSpaceship,
+        (0, 1),
+        &["..OO...", "O....O.", "......O", "O.....O", ".OOOOOO"],
+    ),
+    (
+        Spaceship::HeavyweightSpaceship,
+        (0, 1),
+        &["....OO.", "OOOO.OO", "OOOOOO.", ".OOOO.."],
+    ),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sighting {
+    pub spaceship: Spaceship,
+    pub heading: Heading,
+    // the bounding box of the spaceship in its current phase
+    pub row: u32,
+    pub column: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+// Spaceships that are on their own, one that is touching other cells is part of a bigger
+// object and isn't recognized until it gets away from them
+pub fn find_spaceships(universe: &Universe) -> Vec<Sighting> {
+    census::find_objects(universe)
+        .iter()
+        .filter_map(identify)
+        .collect()
+}
+
+fn identify(object: &CensusObject) -> Option<Sighting> {
+    for (spaceship, (row_offset, column_offset), rows) in SPACESHIP_PHASES.iter() {
+        let phase = census::live_cells(rows);
+        if phase.len() != object.cells.len() {
+            continue;
+        }
+        for symmetry in census::symmetries() {
+            if census::orient(&phase, &symmetry) != object.cells {
+                continue;
+            }
+            let (row_offset, column_offset) = symmetry
+                .iter()
+                .fold((*row_offset, *column_offset), |(row, column), transform| {
+                    transform.apply_to_offset(row, column)
+                });
+            return Some(Sighting {
+                spaceship: *spaceship,
+                heading: Heading::from_offset(row_offset, column_offset),
+                row: object.row,
+                column: object.column,
+                width: object.width,
+                height: object.height,
+            });
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Pattern, Transform};
+
+    #[test]
+    fn test_glider_headings() {
+        let transforms = [
+            (Transform::Identity, Heading::SouthEast),
+            (Transform::FlipHorizontal, Heading::SouthWest),
+            (Transform::FlipVertical, Heading::NorthEast),
+            (Transform::RotateClockwise, Heading::SouthWest),
+            (Transform::RotateCounterClockwise, Heading::NorthEast),
+        ];
+        for (transform, heading) in transforms.iter() {
+            let mut universe = Universe::new(10);
+            universe.insert_pattern_transformed(Pattern::Glider, 3, 3, *transform);
+            for _ in 0..4 {
+                let sightings = find_spaceships(&universe);
+                assert_eq!(1, sightings.len(), "{:?}", transform);
+                assert_eq!(Spaceship::Glider, sightings[0].spaceship);
+                assert_eq!(*heading, sightings[0].heading, "{:?}", transform);
+                universe.tick();
+            }
+        }
+    }
+
+    #[test]
+    fn test_lightweight_spaceship() {
+        let mut universe = Universe::new_with_dimensions(20, 10);
+        universe.insert_pattern(Pattern::LightweightSpaceship, 3, 12);
+        universe.insert_pattern(Pattern::Blinker, 8, 0);
+
+        for _ in 0..4 {
+            let sightings = find_spaceships(&universe);
+            assert_eq!(1, sightings.len());
+            assert_eq!(Spaceship::LightweightSpaceship, sightings[0].spaceship);
+            assert_eq!(Heading::West, sightings[0].heading);
+            universe.tick();
+        }
+        assert_eq!(10, find_spaceships(&universe)[0].column);
+    }
+
+    #[test]
+    fn test_heavier_spaceships() {
+        let ships = [
+            (Spaceship::MiddleweightSpaceship, SPACESHIP_PHASES[6].2),
+            (Spaceship::HeavyweightSpaceship, SPACESHIP_PHASES[8].2),
+        ];
+        for (spaceship, rows) in ships.iter() {
+            let mut universe = Universe::new_with_dimensions(20, 13);
+            for (row, column) in census::live_cells(rows) {
+                universe.set_cell(row + 4, column + 2, crate::Cell::Alive);
+            }
+            for _ in 0..4 {
+                let sightings = find_spaceships(&universe);
+                assert_eq!(1, sightings.len());
+                assert_eq!(*spaceship, sightings[0].spaceship);
+                assert_eq!(Heading::East, sightings[0].heading);
+                universe.tick();
+            }
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use std::collections::{HashMap, HashSet};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::{Cell, Pattern, RuleSet, UniverseError};
+
+// An effectively infinite plane that only stores the coordinates of live cells, so
+// memory and tick time grow with the population instead of the area. Rules that give
+// birth to cells with zero neighbors (B0) can't be simulated on an infinite plane and
+// behave as if the 0 was left out.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug, Default)]
+pub struct SparseUniverse {
+    // (row, column) of every live cell
+    live_cells: HashSet<(i64, i64)>,
+    rule: RuleSet,
+    generation: u32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl SparseUniverse {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    pub fn population(&self) -> u32 {
+        self.live_cells.len() as u32
+    }
+
+    pub fn rule(&self) -> String {
+        self.rule.to_string()
+    }
+
+    pub fn set_rule(&mut self, rule: &str) -> Result<(), UniverseError> {
+        self.rule = rule.parse().map_err(|error| UniverseError::InvalidRule {
+            rule: rule.to_owned(),
+            error,
+        })?;
+        Ok(())
+    }
+
+    pub fn get_cell(&self, row: i32, column: i32) -> Cell {
+        Cell::from(self.is_alive(i64::from(row), i64::from(column)))
+    }
+
+    pub fn set_cell(&mut self, row: i32, column: i32, cell: Cell) {
+        self.set_alive(i64::from(row), i64::from(column), cell == Cell::Alive);
+    }
+
+    pub fn toggle_cell(&mut self, row: i32, column: i32) {
+        let position = (i64::from(row), i64::from(column));
+        if !self.live_cells.remove(&position) {
+            self.live_cells.insert(position);
+        }
+    }
+
+    pub fn insert_pattern(&mut self, pattern: Pattern, row: i32, column: i32) {
+        for (row_offset, column_offset) in pattern.live_cells() {
+            self.set_alive(
+                i64::from(row) + i64::from(row_offset),
+                i64::from(column) + i64::from(column_offset),
+                true,
+            );
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.live_cells.clear();
+        self.generation = 0;
+    }
+
+    pub fn tick(&mut self) {
+        let topology = self.rule.topology();
+        let mut neighbor_counts: HashMap<(i64, i64), u8> = HashMap::new();
+        for (row, column) in self.live_cells.iter() {
+            for row_offset in -1..=1 {
+                for column_offset in -1..=1 {
+                    if topology.is_neighbor(row_offset, column_offset) {
+                        *neighbor_counts
+                            .entry((row + row_offset, column + column_offset))
+                            .or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+
+        // live cells without any neighbors aren't in the counts but may survive with S0
+        let lonely_survivors: Vec<(i64, i64)> = if self.rule.is_alive_next(true, 0) {
+            self.live_cells
+                .iter()
+                .filter(|position| !neighbor_counts.contains_key(position))
+                .copied()
+                .collect()
+        } else {
+            vec![]
+        };
+
+        self.live_cells = neighbor_counts
+            .into_iter()
+            .filter(|(position, count)| {
+                self.rule
+                    .is_alive_next(self.live_cells.contains(position), *count)
+            })
+            .map(|(position, _)| position)
+            .chain(lonely_survivors)
+            .collect();
+        self.generation += 1;
+    }
+
+    // Live cells inside the rectangle as flattened (row, column) pairs relative to
+    // its top left corner, so the canvas can draw whatever part of the plane is visible
+    pub fn live_cells_in_rect(&self, row: i32, column: i32, width: u32, height: u32) -> Vec<u32> {
+        let (row, column) = (i64::from(row), i64::from(column));
+        let mut cells: Vec<(u32, u32)> = self
+            .live_cells
+            .iter()
+            .filter(|(cell_row, cell_column)| {
+                (row..row + i64::from(height)).contains(cell_row)
+                    && (column..column + i64::from(width)).contains(cell_column)
+            })
+            .map(|(cell_row, cell_column)| ((cell_row - row) as u32, (cell_column - column) as u32))
+            .collect();
+        cells.sort_unstable();
+
+        cells
+            .into_iter()
+            .flat_map(|(row, column)| vec![row, column])
+            .collect()
+    }
+}
+
+impl SparseUniverse {
+    pub fn is_alive(&self, row: i64, column: i64) -> bool {
+        self.live_cells.contains(&(row, column))
+    }
+
+    pub fn set_alive(&mut self, row: i64, column: i64, alive: bool) {
+        if alive {
+            self.live_cells.insert((row, column));
+        } else {
+            self.live_cells.remove(&(row, column));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_set_and_toggle_cells() {
+        let mut universe = SparseUniverse::new();
+        universe.set_cell(-5, 3, Cell::Alive);
+        universe.toggle_cell(1_000_000, -1_000_000);
+        universe.toggle_cell(2, 2);
+        universe.toggle_cell(2, 2);
+
+        assert_eq!(Cell::Alive, universe.get_cell(-5, 3));
+        assert_eq!(Cell::Alive, universe.get_cell(1_000_000, -1_000_000));
+        assert_eq!(Cell::Dead, universe.get_cell(2, 2));
+        assert_eq!(2, universe.population());
+    }
+
+    #[test]
+    fn test_glider_moves_without_boundaries() {
+        let mut universe = SparseUniverse::new();
+        universe.insert_pattern(Pattern::Glider, -2, -2);
+
+        for _ in 0..400 {
+            universe.tick();
+        }
+
+        let mut expected = SparseUniverse::new();
+        expected.insert_pattern(Pattern::Glider, 98, 98);
+        assert_eq!(expected.live_cells, universe.live_cells);
+        assert_eq!(400, universe.generation());
+    }
+
+    #[test]
+    fn test_lonely_cells_survive_with_s0() {
+        let mut universe = SparseUniverse::new();
+        universe.set_rule("B3/S0").unwrap();
+        universe.set_cell(0, 0, Cell::Alive);
+        universe.tick();
+
+        assert_eq!(Cell::Alive, universe.get_cell(0, 0));
+    }
+
+    #[test]
+    fn test_live_cells_in_rect() {
+        let mut universe = SparseUniverse::new();
+        universe.insert_pattern(Pattern::Glider, -1, -1);
+
+        assert_eq!(vec![0, 0, 1, 1], universe.live_cells_in_rect(-1, 0, 2, 2));
+        assert_eq!(
+            vec![0, 1, 1, 2, 2, 0, 2, 1, 2, 2],
+            universe.live_cells_in_rect(-1, -1, 3, 3)
+        );
+        assert!(universe.live_cells_in_rect(10, 10, 5, 5).is_empty());
+    }
+}
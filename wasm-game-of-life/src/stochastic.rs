@@ -0,0 +1,125 @@
+use rand::Rng;
+use rand_pcg::Pcg32;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::bits::CellBits;
+
+// Chances laid over the life-like rule, so a cell the rule says is born is only born some
+// of the time and so on. With a spontaneous birth chance above 0, empty cells fill in on
+// their own too, which with a low survival chance gives the growth and burning down of
+// the forest-fire model. The same seed always plays out the same way.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StochasticRule {
+    birth: f64,
+    survival: f64,
+    spontaneous_birth: f64,
+    seed: u64,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl StochasticRule {
+    // Chances are between 0 and 1, anything outside is moved to the nearest end
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(birth: f64, survival: f64, spontaneous_birth: f64, seed: u64) -> Self {
+        Self {
+            birth: clamp_chance(birth),
+            survival: clamp_chance(survival),
+            spontaneous_birth: clamp_chance(spontaneous_birth),
+            seed,
+        }
+    }
+
+    // Of a cell with the neighbors to be born being born
+    pub fn birth(&self) -> f64 {
+        self.birth
+    }
+
+    // Of a live cell with the neighbors to survive surviving
+    pub fn survival(&self) -> f64 {
+        self.survival
+    }
+
+    // Of a dead cell the rule leaves dead coming alive anyway
+    pub fn spontaneous_birth(&self) -> f64 {
+        self.spontaneous_birth
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl StochasticRule {
+    // Rolls for every cell that next has alive or that is dead in both, so the rolls are
+    // the same each time for the same two generations
+    pub(crate) fn apply(&self, rng: &mut Pcg32, current: &CellBits, next: &mut CellBits) {
+        for index in 0..next.len() {
+            let chance = match (current.get(index), next.get(index)) {
+                (false, true) => self.birth,
+                (true, true) => self.survival,
+                (false, false) => 1.0 - self.spontaneous_birth,
+                (true, false) => continue,
+            };
+            // the rule's own outcome stands unless the roll goes against it
+            if !rng.gen_bool(chance) {
+                next.toggle(index);
+            }
+        }
+    }
+}
+
+// NaN and infinities count as never
+pub(crate) fn clamp_chance(chance: f64) -> f64 {
+    if !chance.is_finite() {
+        0.0
+    } else {
+        chance.clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_new() {
+        let rule = StochasticRule::new(1.5, -1.0, f64::NAN, 9);
+        assert_eq!(1.0, rule.birth());
+        assert_eq!(0.0, rule.survival());
+        assert_eq!(0.0, rule.spontaneous_birth());
+        assert_eq!(9, rule.seed());
+    }
+
+    #[test]
+    fn test_apply() {
+        // born, survives, stays dead, dies
+        let mut current = CellBits::new(4);
+        current.set(1, true);
+        current.set(3, true);
+        let mut next = CellBits::new(4);
+        next.set(0, true);
+        next.set(1, true);
+
+        let mut certain = next.clone();
+        StochasticRule::new(1.0, 1.0, 0.0, 0).apply(
+            &mut Pcg32::seed_from_u64(0),
+            &current,
+            &mut certain,
+        );
+        assert_eq!(next, certain);
+
+        let mut reversed = next.clone();
+        StochasticRule::new(0.0, 0.0, 1.0, 0).apply(
+            &mut Pcg32::seed_from_u64(0),
+            &current,
+            &mut reversed,
+        );
+        assert_eq!(
+            vec![false, false, true, false],
+            reversed.iter().collect::<Vec<_>>()
+        );
+    }
+}
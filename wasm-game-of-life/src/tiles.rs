@@ -0,0 +1,230 @@
+use crate::bits::CellBits;
+use crate::rule::RuleTable;
+use crate::{Topology, Universe};
+
+pub const TILE_SIZE: u32 = 32;
+
+// The board split into TILE_SIZE by TILE_SIZE tiles, the last row and column of tiles can
+// be smaller. A cell can only change if something in its neighborhood changed the tick
+// before, so a tile where nothing changed near it is asleep and the rule table copies it
+// over instead of looking up every cell. Anything else that moves cells, like editing
+// them, is caught by comparing the cells with what the last tick left.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TileActivity {
+    disabled: bool,
+    columns: u32,
+    rows: u32,
+    // 1 for each tile the last tick looked up, 0 for the ones it copied, in row major order
+    awake: Vec<u8>,
+    last_tick: Option<LastTick>,
+}
+
+// What the last tick went from and to, and what it ran, when it only depended on the cells
+#[derive(Clone, Debug)]
+struct LastTick {
+    before: CellBits,
+    after: CellBits,
+    table: RuleTable,
+    topology: Topology,
+    width: u32,
+}
+
+impl TileActivity {
+    pub(crate) fn is_enabled(&self) -> bool {
+        !self.disabled
+    }
+
+    pub(crate) fn set_enabled(&mut self, enabled: bool) {
+        self.disabled = !enabled;
+        self.last_tick = None;
+    }
+
+    pub(crate) fn columns(&self) -> u32 {
+        self.columns
+    }
+
+    pub(crate) fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    pub(crate) fn awake(&self) -> &[u8] {
+        &self.awake
+    }
+
+    pub(crate) fn awake_count(&self) -> u32 {
+        self.awake.iter().map(|awake| u32::from(*awake)).sum()
+    }
+
+    pub(crate) fn memory_usage(&self) -> usize {
+        crate::memory::vec_bytes(&self.awake)
+            + self.last_tick.as_ref().map_or(0, |last_tick| {
+                last_tick.before.memory_usage() + last_tick.after.memory_usage()
+            })
+    }
+
+    pub(crate) fn is_awake(&self, row: u32, column: u32) -> bool {
+        self.awake[((row / TILE_SIZE) * self.columns + column / TILE_SIZE) as usize] != 0
+    }
+
+    pub(crate) fn is_row_awake(&self, row: u32) -> bool {
+        let first = ((row / TILE_SIZE) * self.columns) as usize;
+        self.awake[first..first + self.columns as usize]
+            .iter()
+            .any(|awake| *awake != 0)
+    }
+
+    // Works out which tiles the coming tick with table has to look up. Everything is awake
+    // when the last tick ran something else or couldn't be skipped through.
+    pub(crate) fn wake(&mut self, universe: &Universe, table: &RuleTable) {
+        self.columns = universe.width.div_ceil(TILE_SIZE);
+        self.rows = universe.height.div_ceil(TILE_SIZE);
+        let tile_count = (self.columns * self.rows) as usize;
+        let last_tick = self.last_tick.as_ref().filter(|last_tick| {
+            last_tick.table == *table
+                && last_tick.topology == universe.edge_topology
+                && last_tick.width == universe.width
+                && last_tick.after.len() == universe.cells.len()
+        });
+        self.awake.clear();
+        let last_tick = match last_tick {
+            Some(last_tick) => last_tick,
+            None => {
+                self.awake.resize(tile_count, 1);
+                return;
+            }
+        };
+
+        self.awake.resize(tile_count, 0);
+        let changed = last_tick
+            .before
+            .differences(&last_tick.after)
+            .chain(last_tick.after.differences(&universe.cells));
+        let (width, height) = (universe.width, universe.height);
+        // twisted edges are joined in ways that aren't always the same both ways around
+        // the corners, so a change on any edge wakes every tile along the edges
+        let twisted = !universe.edge_topology.is_plane_or_torus();
+        let mut edges_awake = false;
+        for index in changed {
+            let (row, column) = (index as u32 / width, index as u32 % width);
+            for row_offset in -1..=1 {
+                for column_offset in -1..=1 {
+                    let neighbor = universe.edge_topology.map(
+                        i64::from(row) + row_offset,
+                        i64::from(column) + column_offset,
+                        width,
+                        height,
+                    );
+                    if let Some((row, column)) = neighbor {
+                        let tile = (row / TILE_SIZE) * self.columns + column / TILE_SIZE;
+                        self.awake[tile as usize] = 1;
+                    }
+                }
+            }
+            edges_awake |=
+                twisted && (row == 0 || column == 0 || row + 1 == height || column + 1 == width);
+        }
+        if edges_awake {
+            for tile_row in 0..self.rows {
+                for tile_column in 0..self.columns {
+                    if tile_row == 0
+                        || tile_column == 0
+                        || tile_row + 1 == self.rows
+                        || tile_column + 1 == self.columns
+                    {
+                        self.awake[(tile_row * self.columns + tile_column) as usize] = 1;
+                    }
+                }
+            }
+        }
+    }
+
+    // Remembers the tick that took the universe's cells to next, when table alone decided
+    // it. Ticks that didn't go through wake() looked up every tile.
+    pub(crate) fn settle(
+        &mut self,
+        universe: &Universe,
+        next: &CellBits,
+        table: Option<RuleTable>,
+    ) {
+        let table = match table {
+            Some(table) if !self.disabled => table,
+            _ => {
+                self.columns = universe.width.div_ceil(TILE_SIZE);
+                self.rows = universe.height.div_ceil(TILE_SIZE);
+                self.awake.clear();
+                self.awake.resize((self.columns * self.rows) as usize, 1);
+                self.last_tick = None;
+                return;
+            }
+        };
+        match self.last_tick.as_mut() {
+            Some(last_tick) => {
+                last_tick.before.clone_from(&universe.cells);
+                last_tick.after.clone_from(next);
+                last_tick.table = table;
+                last_tick.topology = universe.edge_topology;
+                last_tick.width = universe.width;
+            }
+            None => {
+                self.last_tick = Some(LastTick {
+                    before: universe.cells.clone(),
+                    after: next.clone(),
+                    table,
+                    topology: universe.edge_topology,
+                    width: universe.width,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Cell, Pattern, Topology, Universe};
+
+    #[test]
+    fn test_still_lifes_sleep() {
+        // a block settles right away, and a blinker keeps its own tile awake
+        let mut universe = Universe::new_with_dimensions(96, 64);
+        universe.insert_pattern(Pattern::Blinker, 10, 10);
+        universe.set_cell(40, 70, Cell::Alive);
+        universe.set_cell(40, 71, Cell::Alive);
+        universe.set_cell(41, 70, Cell::Alive);
+        universe.set_cell(41, 71, Cell::Alive);
+        universe.tick();
+        assert_eq!(6, universe.awake_tile_count());
+        universe.tick();
+        universe.tick();
+        assert_eq!((3, 2), (universe.tile_columns(), universe.tile_rows()));
+        assert_eq!(1, universe.awake_tile_count());
+
+        // an edit wakes the tiles around it up again
+        universe.set_cell(31, 63, Cell::Alive);
+        universe.tick();
+        assert_eq!(5, universe.awake_tile_count());
+    }
+
+    #[test]
+    fn test_matches_ticking_every_tile() {
+        for topology in [Topology::Plane, Topology::Torus, Topology::Sphere].iter() {
+            let mut sleeping = Universe::new_with_dimensions(70, 70);
+            sleeping.set_edge_topology(*topology);
+            sleeping.randomize_seeded(11, 0.3);
+            let mut awake = sleeping.clone();
+            awake.set_sleeping_tiles(false);
+
+            for generation in 0..60 {
+                if generation == 30 {
+                    for universe in [&mut sleeping, &mut awake] {
+                        universe.toggle_cell(0, 69);
+                        universe.toggle_cell(33, 35);
+                    }
+                }
+                sleeping.tick();
+                awake.tick();
+                assert_eq!(awake.cells(), sleeping.cells(), "{:?}", topology);
+            }
+            assert_eq!(9, awake.awake_tile_count());
+        }
+    }
+}
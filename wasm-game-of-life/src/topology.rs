@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::BoundaryMode;
+
+// How the edges of the board are joined up, the bounded grids Golly supports. This is
+// about the edges, GridTopology is about the shape of the cells.
+// https://golly.sourceforge.io/Help/bounded.html
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Topology {
+    // nothing past the edges, BoundaryMode::Clamped
+    #[serde(alias = "Clamped")]
+    Plane,
+    // the left and right edges are joined and so are the top and bottom,
+    // BoundaryMode::Toroidal
+    #[serde(alias = "Toroidal")]
+    Torus,
+    // a torus where the top and bottom edges are joined with a twist, so something that
+    // goes off the bottom comes back at the top mirrored left to right
+    KleinBottle,
+    // both pairs of edges joined with a twist
+    CrossSurface,
+    // the top edge is joined to the left edge and the bottom edge to the right one, only
+    // for square boards, on others it's the same as a plane
+    Sphere,
+}
+
+impl Topology {
+    // Where a cell that is off the board by row or column ends up, None when it falls off
+    // the board. This is the one place the edges are worked out for every engine that
+    // doesn't have its own way of doing it.
+    pub fn map(self, row: i64, column: i64, width: u32, height: u32) -> Option<(u32, u32)> {
+        let (width, height) = (i64::from(width), i64::from(height));
+        let inside =
+            |row: i64, column: i64| (0..height).contains(&row) && (0..width).contains(&column);
+        if inside(row, column) {
+            return Some((row as u32, column as u32));
+        }
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        let (row, column) = match self {
+            Topology::Plane => return None,
+            Topology::Torus => (row.rem_euclid(height), column.rem_euclid(width)),
+            Topology::KleinBottle | Topology::CrossSurface => {
+                let mut wrapped_row = row.rem_euclid(height);
+                let mut wrapped_column = column.rem_euclid(width);
+                // each trip across a twisted edge mirrors the other coordinate
+                if row.div_euclid(height) % 2 != 0 {
+                    wrapped_column = width - 1 - wrapped_column;
+                }
+                if self == Topology::CrossSurface && column.div_euclid(width) % 2 != 0 {
+                    wrapped_row = height - 1 - wrapped_row;
+                }
+                (wrapped_row, wrapped_column)
+            }
+            Topology::Sphere if width != height => return None,
+            Topology::Sphere => {
+                // off the top is the column along the left edge turned on its side and
+                // so on for the other edges. A corner is off two edges, that's two turns.
+                let (mut row, mut column) = (row, column);
+                for _ in 0..2 {
+                    if row < 0 {
+                        (row, column) = (column, -1 - row);
+                    } else if row >= height {
+                        (row, column) = (column, 2 * height - 1 - row);
+                    } else if column < 0 {
+                        (row, column) = (-1 - column, row);
+                    } else if column >= width {
+                        (row, column) = (2 * width - 1 - column, row);
+                    }
+                }
+                (row, column)
+            }
+        };
+
+        if inside(row, column) {
+            Some((row as u32, column as u32))
+        } else {
+            None
+        }
+    }
+
+    // The edges the packed engine knows how to join up, the rest tick with the naive engine
+    pub(crate) fn is_plane_or_torus(self) -> bool {
+        matches!(self, Topology::Plane | Topology::Torus)
+    }
+}
+
+impl From<BoundaryMode> for Topology {
+    fn from(boundary_mode: BoundaryMode) -> Self {
+        match boundary_mode {
+            BoundaryMode::Clamped => Topology::Plane,
+            BoundaryMode::Toroidal => Topology::Torus,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_plane_and_torus() {
+        assert_eq!(Some((2, 3)), Topology::Plane.map(2, 3, 4, 4));
+        assert_eq!(None, Topology::Plane.map(-1, 3, 4, 4));
+        assert_eq!(Some((3, 0)), Topology::Torus.map(-1, 4, 4, 4));
+    }
+
+    #[test]
+    fn test_klein_bottle() {
+        // off the top comes in at the bottom mirrored, off the sides wraps as it is
+        assert_eq!(Some((2, 4)), Topology::KleinBottle.map(-1, 0, 5, 3));
+        assert_eq!(Some((0, 3)), Topology::KleinBottle.map(3, 1, 5, 3));
+        assert_eq!(Some((1, 0)), Topology::KleinBottle.map(1, 5, 5, 3));
+        assert_eq!(Some((2, 0)), Topology::KleinBottle.map(-1, -1, 5, 3));
+    }
+
+    #[test]
+    fn test_cross_surface() {
+        assert_eq!(Some((2, 4)), Topology::CrossSurface.map(-1, 0, 5, 3));
+        assert_eq!(Some((1, 0)), Topology::CrossSurface.map(1, 5, 5, 3));
+        assert_eq!(Some((2, 4)), Topology::CrossSurface.map(0, -1, 5, 3));
+        assert_eq!(Some((0, 0)), Topology::CrossSurface.map(-1, -1, 5, 3));
+    }
+
+    #[test]
+    fn test_sphere() {
+        // the cell above column 2 is the cell left of row 2, and the other way around
+        assert_eq!(Some((2, 0)), Topology::Sphere.map(-1, 2, 4, 4));
+        assert_eq!(Some((0, 2)), Topology::Sphere.map(2, -1, 4, 4));
+        assert_eq!(Some((1, 3)), Topology::Sphere.map(4, 1, 4, 4));
+        assert_eq!(Some((3, 1)), Topology::Sphere.map(1, 4, 4, 4));
+        assert_eq!(Some((0, 0)), Topology::Sphere.map(-1, -1, 4, 4));
+        assert_eq!(None, Topology::Sphere.map(-1, 2, 4, 5));
+    }
+
+    #[test]
+    fn test_every_neighbor_is_on_the_board() {
+        for topology in [
+            Topology::Torus,
+            Topology::KleinBottle,
+            Topology::CrossSurface,
+            Topology::Sphere,
+        ]
+        .iter()
+        {
+            for row in -1..=4 {
+                for column in -1..=4 {
+                    assert!(
+                        topology.map(row, column, 4, 4).is_some(),
+                        "{:?} {} {}",
+                        topology,
+                        row,
+                        column
+                    );
+                }
+            }
+        }
+    }
+}
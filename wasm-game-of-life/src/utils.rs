@@ -8,3 +8,48 @@ pub fn set_panic_hook() {
     #[cfg(feature = "console_error_panic_hook")]
     console_error_panic_hook::set_once();
 }
+
+// Shows how long it lived as a console.time entry when the `profiling` feature is enabled,
+// and does nothing otherwise
+pub struct Timer<'a> {
+    #[cfg_attr(
+        not(all(feature = "profiling", target_arch = "wasm32")),
+        allow(dead_code)
+    )]
+    label: &'a str,
+}
+
+impl<'a> Timer<'a> {
+    pub fn new(label: &'a str) -> Self {
+        #[cfg(all(feature = "profiling", target_arch = "wasm32"))]
+        web_sys::console::time_with_label(label);
+
+        Self { label }
+    }
+}
+
+impl<'a> Drop for Timer<'a> {
+    fn drop(&mut self) {
+        #[cfg(all(feature = "profiling", target_arch = "wasm32"))]
+        web_sys::console::time_end_with_label(self.label);
+    }
+}
+
+// Milliseconds from an arbitrary starting point, only useful for measuring durations
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub fn now() -> f64 {
+    web_sys::window()
+        .and_then(|window| window.performance())
+        .map(|performance| performance.now())
+        .unwrap_or(0.0)
+}
+
+#[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+pub fn now() -> f64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs_f64() * 1000.0)
+        .unwrap_or(0.0)
+}
@@ -0,0 +1,228 @@
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::Universe;
+
+// How far in and out zoom_at() goes, in pixels per cell
+pub const MIN_ZOOM: f64 = 0.25;
+pub const MAX_ZOOM: f64 = 64.0;
+
+// The part of a universe that is on screen, for boards too big to draw whole. Screen
+// coordinates are pixels from the top left of the canvas and the origin is the cell
+// position at that corner, which is fractional while panning and zooming.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewport {
+    row: f64,
+    column: f64,
+    // pixels per cell
+    zoom: f64,
+    width: u32,
+    height: u32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Viewport {
+    // A width by height pixel screen showing the top left of the board
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(width: u32, height: u32, zoom: f64) -> Self {
+        Self {
+            row: 0.0,
+            column: 0.0,
+            zoom: zoom.clamp(MIN_ZOOM, MAX_ZOOM),
+            width,
+            height,
+        }
+    }
+
+    pub fn zoom(&self) -> f64 {
+        self.zoom
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    // For when the canvas changes size, the origin stays put
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.width = width;
+        self.height = height;
+    }
+
+    // [row, column] of the top left corner
+    pub fn origin(&self) -> Vec<f64> {
+        vec![self.row, self.column]
+    }
+
+    pub fn set_origin(&mut self, row: f64, column: f64) {
+        self.row = row;
+        self.column = column;
+    }
+
+    // Moves the board by dx, dy pixels, the way it follows a drag of the mouse
+    pub fn pan(&mut self, dx: f64, dy: f64) {
+        self.column -= dx / self.zoom;
+        self.row -= dy / self.zoom;
+    }
+
+    // Zooms in for factors over 1 and out for ones under, keeping the cell under x, y
+    // where it is so the board grows from the mouse pointer
+    pub fn zoom_at(&mut self, x: f64, y: f64, factor: f64) {
+        let zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.column += x / self.zoom - x / zoom;
+        self.row += y / self.zoom - y / zoom;
+        self.zoom = zoom;
+    }
+
+    // [row, column] of the cell under a point on the screen, which can be off the board
+    pub fn screen_to_cell(&self, x: f64, y: f64) -> Vec<i32> {
+        vec![
+            (self.row + y / self.zoom).floor() as i32,
+            (self.column + x / self.zoom).floor() as i32,
+        ]
+    }
+
+    // [x, y] of the top left corner of a cell on the screen
+    pub fn cell_to_screen(&self, row: i32, column: i32) -> Vec<f64> {
+        vec![
+            (f64::from(column) - self.column) * self.zoom,
+            (f64::from(row) - self.row) * self.zoom,
+        ]
+    }
+
+    // [row, column, width, height] of the cells of the universe that are at least partly
+    // on screen, all 0 when none of the board is
+    pub fn visible_cells(&self, universe: &Universe) -> Vec<u32> {
+        let (top, left, bottom, right) = self.visible_range(universe.width(), universe.height());
+        if top >= bottom || left >= right {
+            return vec![0, 0, 0, 0];
+        }
+        vec![top, left, right - left, bottom - top]
+    }
+}
+
+impl Viewport {
+    // Follows two fingers that moved from the points before to the points after: the
+    // spot between them moves with them and the board scales with the distance between
+    // them, so the cells under the fingers stay under them
+    pub fn pinch(&mut self, before: [(f64, f64); 2], after: [(f64, f64); 2]) {
+        let middle = |points: [(f64, f64); 2]| {
+            (
+                (points[0].0 + points[1].0) / 2.0,
+                (points[0].1 + points[1].1) / 2.0,
+            )
+        };
+        let distance =
+            |points: [(f64, f64); 2]| (points[0].0 - points[1].0).hypot(points[0].1 - points[1].1);
+        let (from, to) = (middle(before), middle(after));
+        if distance(before) > 0.0 {
+            self.zoom_at(from.0, from.1, distance(after) / distance(before));
+        }
+        self.pan(to.0 - from.0, to.1 - from.1);
+    }
+
+    // (top, left, bottom, right) with the bottom and right left out
+    pub(crate) fn visible_range(&self, width: u32, height: u32) -> (u32, u32, u32, u32) {
+        let clamp = |value: f64, limit: u32| value.max(0.0).min(f64::from(limit)) as u32;
+        let top = clamp(self.row.floor(), height);
+        let left = clamp(self.column.floor(), width);
+        let bottom = clamp(
+            (self.row + f64::from(self.height) / self.zoom).ceil(),
+            height,
+        );
+        let right = clamp(
+            (self.column + f64::from(self.width) / self.zoom).ceil(),
+            width,
+        );
+        (top, left, bottom, right)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pan_and_convert() {
+        let mut viewport = Viewport::new(100, 50, 10.0);
+        assert_eq!(vec![2, 3], viewport.screen_to_cell(35.0, 25.0));
+
+        viewport.pan(-20.0, -10.0);
+        assert_eq!(vec![1.0, 2.0], viewport.origin());
+        assert_eq!(vec![3, 5], viewport.screen_to_cell(35.0, 25.0));
+        assert_eq!(vec![30.0, 20.0], viewport.cell_to_screen(3, 5));
+        assert_eq!(vec![-1, -1], viewport.screen_to_cell(-25.0, -15.0));
+    }
+
+    #[test]
+    fn test_zoom_at_keeps_the_cell_under_the_pointer() {
+        let mut viewport = Viewport::new(200, 200, 4.0);
+        viewport.set_origin(10.0, 10.0);
+        let before = viewport.screen_to_cell(120.0, 60.0);
+
+        viewport.zoom_at(120.0, 60.0, 2.0);
+        assert_eq!(8.0, viewport.zoom());
+        assert_eq!(before, viewport.screen_to_cell(120.0, 60.0));
+
+        viewport.zoom_at(0.0, 0.0, 1000.0);
+        assert_eq!(MAX_ZOOM, viewport.zoom());
+    }
+
+    #[test]
+    fn test_pinch() {
+        let mut viewport = Viewport::new(200, 200, 4.0);
+        let under = |viewport: &Viewport, x: f64, y: f64| {
+            let origin = viewport.origin();
+            (
+                origin[0] + y / viewport.zoom(),
+                origin[1] + x / viewport.zoom(),
+            )
+        };
+        let left = under(&viewport, 50.0, 100.0);
+        let right = under(&viewport, 100.0, 100.0);
+
+        // the fingers spread apart and move down at the same time
+        viewport.pinch(
+            [(50.0, 100.0), (100.0, 100.0)],
+            [(25.0, 120.0), (125.0, 120.0)],
+        );
+        assert_eq!(8.0, viewport.zoom());
+        assert_eq!(left, under(&viewport, 25.0, 120.0));
+        assert_eq!(right, under(&viewport, 125.0, 120.0));
+
+        // two fingers that move together only pan
+        viewport.pinch([(0.0, 0.0), (10.0, 0.0)], [(16.0, 8.0), (26.0, 8.0)]);
+        assert_eq!(8.0, viewport.zoom());
+        assert_eq!(left, under(&viewport, 41.0, 128.0));
+    }
+
+    #[test]
+    fn test_visible_cells() {
+        let universe = Universe::new_with_dimensions(40, 30);
+        let mut viewport = Viewport::new(100, 50, 10.0);
+        viewport.set_origin(2.5, 35.0);
+        assert_eq!(vec![2, 35, 5, 6], viewport.visible_cells(&universe));
+
+        viewport.set_origin(-100.0, 0.0);
+        assert_eq!(vec![0, 0, 0, 0], viewport.visible_cells(&universe));
+    }
+
+    #[test]
+    fn test_render_viewport() {
+        let mut universe = Universe::new(100);
+        universe.insert_pattern(crate::Pattern::Glider, 50, 60);
+        let mut viewport = Viewport::new(30, 30, 10.0);
+        viewport.set_origin(50.0, 60.0);
+
+        assert_eq!(
+            vec![0, 1, 0, 0, 0, 1, 1, 1, 1],
+            universe.render_viewport(&viewport)
+        );
+        viewport.set_origin(200.0, 200.0);
+        assert!(universe.render_viewport(&viewport).is_empty());
+    }
+}
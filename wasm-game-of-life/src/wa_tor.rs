@@ -0,0 +1,355 @@
+use std::fmt::{self, Display, Formatter};
+
+use rand::prelude::*;
+use rand_pcg::Pcg32;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::stochastic::clamp_chance;
+use crate::{Topology, DEFAULT_POPULATION_HISTORY};
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WaTorCell {
+    Water = 0,
+    Fish = 1,
+    Shark = 2,
+}
+
+impl Display for WaTorCell {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            WaTorCell::Water => write!(f, "."),
+            WaTorCell::Fish => write!(f, "f"),
+            WaTorCell::Shark => write!(f, "S"),
+        }
+    }
+}
+
+// up, right, down and left
+const DIRECTIONS: [(i64, i64); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+// Fish and sharks on a toroidal ocean. Each generation every fish swims to a random empty
+// cell next to it and every shark eats a fish next to it or else swims like a fish. Once
+// an animal has lived breed generations since it last bred, it leaves a young one behind
+// when it moves. A shark that goes starve generations without eating dies.
+// https://en.wikipedia.org/wiki/Wa-Tor
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug)]
+pub struct WaTor {
+    width: u32,
+    height: u32,
+    cells: Vec<WaTorCell>,
+    // generations since each animal was born or last bred
+    breed_timers: Vec<u32>,
+    // generations since each shark last ate
+    hunger: Vec<u32>,
+    fish_breed: u32,
+    shark_breed: u32,
+    shark_starve: u32,
+    rng: Pcg32,
+    generation: u32,
+    // the count of each after every tick, oldest first and at most
+    // DEFAULT_POPULATION_HISTORY long
+    fish_history: Vec<u32>,
+    shark_history: Vec<u32>,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl WaTor {
+    // An empty ocean, the same seed always plays out the same way
+    pub fn new(
+        width: u32,
+        height: u32,
+        fish_breed: u32,
+        shark_breed: u32,
+        shark_starve: u32,
+        seed: u64,
+    ) -> Self {
+        let cell_count = (width * height) as usize;
+
+        Self {
+            width,
+            height,
+            cells: vec![WaTorCell::Water; cell_count],
+            breed_timers: vec![0; cell_count],
+            hunger: vec![0; cell_count],
+            fish_breed: fish_breed.max(1),
+            shark_breed: shark_breed.max(1),
+            shark_starve: shark_starve.max(1),
+            rng: Pcg32::seed_from_u64(seed),
+            generation: 0,
+            fish_history: vec![],
+            shark_history: vec![],
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    // Fills each cell with a fish or a shark by the given chances, water otherwise. NaN
+    // and infinite chances count as 0.
+    pub fn randomize(&mut self, fish_probability: f64, shark_probability: f64) {
+        let fish_probability = clamp_chance(fish_probability);
+        let shark_probability = clamp_chance(shark_probability).min(1.0 - fish_probability);
+        for index in 0..self.cells.len() {
+            let roll: f64 = self.rng.gen();
+            let cell = if roll < fish_probability {
+                WaTorCell::Fish
+            } else if roll < fish_probability + shark_probability {
+                WaTorCell::Shark
+            } else {
+                WaTorCell::Water
+            };
+            self.place(index, cell);
+        }
+    }
+
+    // One byte per cell with the WaTorCell values, in row major order like Universe
+    pub fn cells_ptr(&self) -> *const WaTorCell {
+        self.cells.as_ptr()
+    }
+
+    pub fn cells_len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn get_cell(&self, row: u32, column: u32) -> WaTorCell {
+        if row < self.height && column < self.width {
+            self.cells[self.get_index(row, column)]
+        } else {
+            WaTorCell::Water
+        }
+    }
+
+    // A new fish or shark, with its timers at 0
+    pub fn set_cell(&mut self, row: u32, column: u32, cell: WaTorCell) {
+        if row < self.height && column < self.width {
+            let index = self.get_index(row, column);
+            self.place(index, cell);
+        }
+    }
+
+    pub fn fish(&self) -> u32 {
+        self.count(WaTorCell::Fish)
+    }
+
+    pub fn sharks(&self) -> u32 {
+        self.count(WaTorCell::Shark)
+    }
+
+    // Appended to on every tick like Universe::population_history_ptr(), the pointers can
+    // change while the buffers grow so read them again after ticking
+    pub fn fish_history_ptr(&self) -> *const u32 {
+        self.fish_history.as_ptr()
+    }
+
+    pub fn fish_history_len(&self) -> usize {
+        self.fish_history.len()
+    }
+
+    pub fn shark_history_ptr(&self) -> *const u32 {
+        self.shark_history.as_ptr()
+    }
+
+    pub fn shark_history_len(&self) -> usize {
+        self.shark_history.len()
+    }
+
+    // All the fish move before any of the sharks, and nothing moves twice in a tick
+    pub fn tick(&mut self) {
+        let mut moved = vec![false; self.cells.len()];
+        for species in [WaTorCell::Fish, WaTorCell::Shark].iter() {
+            for index in 0..self.cells.len() {
+                if self.cells[index] == *species && !moved[index] {
+                    if let Some(to) = self.act(index) {
+                        moved[to] = true;
+                    }
+                }
+            }
+        }
+        self.generation += 1;
+
+        let (fish, sharks) = (self.fish(), self.sharks());
+        for (history, count) in [
+            (&mut self.fish_history, fish),
+            (&mut self.shark_history, sharks),
+        ] {
+            if history.len() == DEFAULT_POPULATION_HISTORY {
+                history.remove(0);
+            }
+            history.push(count);
+        }
+    }
+
+    pub fn render(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl WaTor {
+    fn get_index(&self, row: u32, column: u32) -> usize {
+        (row * self.width + column) as usize
+    }
+
+    fn count(&self, cell: WaTorCell) -> u32 {
+        self.cells.iter().filter(|state| **state == cell).count() as u32
+    }
+
+    fn place(&mut self, index: usize, cell: WaTorCell) {
+        self.cells[index] = cell;
+        self.breed_timers[index] = 0;
+        self.hunger[index] = 0;
+    }
+
+    // The 4 cells around index that hold target, wrapping around the edges
+    fn neighbors(&self, index: usize, target: WaTorCell) -> Vec<usize> {
+        let row = i64::from(index as u32 / self.width);
+        let column = i64::from(index as u32 % self.width);
+        DIRECTIONS
+            .iter()
+            .filter_map(|(row_offset, column_offset)| {
+                Topology::Torus.map(
+                    row + row_offset,
+                    column + column_offset,
+                    self.width,
+                    self.height,
+                )
+            })
+            .map(|(row, column)| self.get_index(row, column))
+            .filter(|neighbor| self.cells[*neighbor] == target)
+            .collect()
+    }
+
+    // Moves, feeds, breeds or starves the animal at index, returning where it ended up
+    fn act(&mut self, index: usize) -> Option<usize> {
+        let species = self.cells[index];
+        let breed = if species == WaTorCell::Shark {
+            self.shark_breed
+        } else {
+            self.fish_breed
+        };
+        let breed_timer = self.breed_timers[index] + 1;
+        let mut hunger = self.hunger[index] + 1;
+
+        let prey = if species == WaTorCell::Shark {
+            self.neighbors(index, WaTorCell::Fish)
+        } else {
+            vec![]
+        };
+        let to = if prey.is_empty() {
+            self.neighbors(index, WaTorCell::Water)
+                .choose(&mut self.rng)
+                .copied()
+        } else {
+            hunger = 0;
+            prey.choose(&mut self.rng).copied()
+        };
+
+        if species == WaTorCell::Shark && hunger >= self.shark_starve {
+            self.place(index, WaTorCell::Water);
+            return None;
+        }
+        let to = match to {
+            Some(to) => to,
+            None => {
+                self.breed_timers[index] = breed_timer;
+                self.hunger[index] = hunger;
+                return Some(index);
+            }
+        };
+
+        let bred = breed_timer >= breed;
+        self.place(to, species);
+        self.breed_timers[to] = if bred { 0 } else { breed_timer };
+        self.hunger[to] = hunger;
+        self.place(index, if bred { species } else { WaTorCell::Water });
+        Some(to)
+    }
+}
+
+impl Display for WaTor {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for line in self.cells.chunks(self.width.max(1) as usize) {
+            for cell in line {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fish_swim_and_breed() {
+        let mut wa_tor = WaTor::new(3, 3, 2, 5, 5, 1);
+        wa_tor.set_cell(1, 1, WaTorCell::Fish);
+
+        wa_tor.tick();
+        assert_eq!(1, wa_tor.fish());
+        assert_eq!(WaTorCell::Water, wa_tor.get_cell(1, 1));
+        wa_tor.tick();
+        assert_eq!(2, wa_tor.fish());
+        assert_eq!(vec![1, 2], wa_tor.fish_history);
+    }
+
+    #[test]
+    fn test_sharks_eat_and_starve() {
+        // every cell of a 3 wide ring is next to every other one
+        let mut wa_tor = WaTor::new(3, 1, 10, 10, 2, 1);
+        wa_tor.set_cell(0, 0, WaTorCell::Shark);
+        wa_tor.set_cell(0, 1, WaTorCell::Fish);
+        wa_tor.set_cell(0, 2, WaTorCell::Fish);
+
+        wa_tor.tick();
+        assert_eq!((1, 1), (wa_tor.fish(), wa_tor.sharks()));
+        wa_tor.tick();
+        assert_eq!((0, 1), (wa_tor.fish(), wa_tor.sharks()));
+        wa_tor.tick();
+        wa_tor.tick();
+        assert_eq!(0, wa_tor.sharks());
+        assert_eq!(vec![1, 1, 1, 0], wa_tor.shark_history);
+        assert_eq!(vec![1, 0, 0, 0], wa_tor.fish_history);
+    }
+
+    #[test]
+    fn test_seeded_oceans_match() {
+        let mut first = WaTor::new(20, 20, 3, 8, 4, 5);
+        first.randomize(0.3, 0.05);
+        let mut second = first.clone();
+        for _ in 0..40 {
+            first.tick();
+            second.tick();
+        }
+
+        assert_eq!(first.render(), second.render());
+        assert_eq!(40, first.fish_history.len());
+        assert_eq!("", WaTor::new(0, 2, 3, 8, 4, 5).render());
+    }
+
+    #[test]
+    fn test_randomize_ignores_nan() {
+        let mut wa_tor = WaTor::new(10, 10, 3, 8, 4, 5);
+        wa_tor.randomize(f64::NAN, 0.5);
+        assert_eq!(0, wa_tor.fish());
+        assert!(wa_tor.sharks() > 0);
+
+        wa_tor.randomize(1.0, f64::INFINITY);
+        assert_eq!((100, 0), (wa_tor.fish(), wa_tor.sharks()));
+    }
+}
@@ -0,0 +1,55 @@
+use std::collections::VecDeque;
+
+// The most changes kept for each watched cell, the oldest are dropped first
+pub const MAX_WATCH_HISTORY: usize = 1024;
+
+// A cell whose changes from tick to tick are written down, see Universe::add_watch()
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Watch {
+    pub row: u32,
+    pub column: u32,
+    // (generation, alive) for every tick that flipped the cell, oldest first
+    pub changes: VecDeque<(u32, bool)>,
+}
+
+impl Watch {
+    pub fn new(row: u32, column: u32) -> Self {
+        Self {
+            row,
+            column,
+            changes: VecDeque::new(),
+        }
+    }
+
+    pub fn record(&mut self, generation: u32, alive: bool) {
+        if self.changes.len() == MAX_WATCH_HISTORY {
+            self.changes.pop_front();
+        }
+        self.changes.push_back((generation, alive));
+    }
+
+    // Whether the cell flipped on the tick that reached generation
+    pub fn changed_at(&self, generation: u32) -> bool {
+        self.changes
+            .back()
+            .is_some_and(|(changed, _)| *changed == generation)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_record_drops_the_oldest() {
+        let mut watch = Watch::new(1, 2);
+        for generation in 0..MAX_WATCH_HISTORY as u32 + 2 {
+            watch.record(generation, generation % 2 == 0);
+        }
+
+        assert_eq!(MAX_WATCH_HISTORY, watch.changes.len());
+        assert_eq!(Some(&(2, true)), watch.changes.front());
+        assert!(watch.changed_at(MAX_WATCH_HISTORY as u32 + 1));
+        assert!(!watch.changed_at(3));
+    }
+}
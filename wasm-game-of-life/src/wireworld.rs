@@ -0,0 +1,226 @@
+use std::fmt::{self, Display, Formatter};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::formats::ParseError;
+
+// Electrons travel along conductors as a head followed by a tail
+// https://conwaylife.com/wiki/Wireworld
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WireCell {
+    Empty = 0,
+    Conductor = 1,
+    Head = 2,
+    Tail = 3,
+}
+
+impl WireCell {
+    fn next(self, neighboring_heads: u8) -> Self {
+        match self {
+            WireCell::Empty => WireCell::Empty,
+            WireCell::Head => WireCell::Tail,
+            WireCell::Tail => WireCell::Conductor,
+            WireCell::Conductor if neighboring_heads == 1 || neighboring_heads == 2 => {
+                WireCell::Head
+            }
+            WireCell::Conductor => WireCell::Conductor,
+        }
+    }
+}
+
+impl Display for WireCell {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            WireCell::Empty => write!(f, "."),
+            WireCell::Conductor => write!(f, "#"),
+            WireCell::Head => write!(f, "H"),
+            WireCell::Tail => write!(f, "T"),
+        }
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug)]
+pub struct Wireworld {
+    width: u32,
+    height: u32,
+    cells: Vec<WireCell>,
+    next: Vec<WireCell>,
+    generation: u32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Wireworld {
+    pub fn new(width: u32, height: u32) -> Self {
+        let cells = vec![WireCell::Empty; (width * height) as usize];
+
+        Self {
+            width,
+            height,
+            next: cells.clone(),
+            cells,
+            generation: 0,
+        }
+    }
+
+    // One line per row where . or a space is empty, # is a conductor, H is an electron
+    // head and T is an electron tail, lines starting with ! are comments
+    pub fn from_text(text: &str) -> Result<Wireworld, ParseError> {
+        let rows: Vec<(usize, &str)> = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim_end()))
+            .filter(|(_, line)| !line.starts_with('!'))
+            .collect();
+        let width = rows
+            .iter()
+            .map(|(_, line)| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut wireworld = Wireworld::new(width as u32, rows.len() as u32);
+
+        for (row, (line_number, line)) in rows.iter().enumerate() {
+            for (column, character) in line.chars().enumerate() {
+                let cell = match character {
+                    '.' | ' ' => WireCell::Empty,
+                    '#' => WireCell::Conductor,
+                    'H' => WireCell::Head,
+                    'T' => WireCell::Tail,
+                    other => {
+                        return Err(ParseError::new(
+                            *line_number,
+                            column + 1,
+                            format!("unexpected character '{}'", other),
+                        ))
+                    }
+                };
+                wireworld.set_cell(row as u32, column as u32, cell);
+            }
+        }
+
+        Ok(wireworld)
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    // One byte per cell with the WireCell values, in row major order like Universe
+    pub fn cells_ptr(&self) -> *const WireCell {
+        self.cells.as_ptr()
+    }
+
+    pub fn cells_len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn get_cell(&self, row: u32, column: u32) -> WireCell {
+        if row < self.height && column < self.width {
+            self.cells[self.get_index(row, column)]
+        } else {
+            WireCell::Empty
+        }
+    }
+
+    pub fn set_cell(&mut self, row: u32, column: u32, cell: WireCell) {
+        if row < self.height && column < self.width {
+            let index = self.get_index(row, column);
+            self.cells[index] = cell;
+        }
+    }
+
+    pub fn tick(&mut self) {
+        for row in 0..self.height {
+            for column in 0..self.width {
+                let index = self.get_index(row, column);
+                self.next[index] = self.cells[index].next(self.neighboring_heads(row, column));
+            }
+        }
+
+        std::mem::swap(&mut self.cells, &mut self.next);
+        self.generation += 1;
+    }
+
+    pub fn render(&self) -> String {
+        self.to_string()
+    }
+
+    fn get_index(&self, row: u32, column: u32) -> usize {
+        (row * self.width + column) as usize
+    }
+
+    // The edges of the board are treated as empty
+    fn neighboring_heads(&self, row: u32, column: u32) -> u8 {
+        let mut heads = 0;
+        for neighbor_row in row.saturating_sub(1)..=(row + 1).min(self.height - 1) {
+            for neighbor_column in column.saturating_sub(1)..=(column + 1).min(self.width - 1) {
+                if (neighbor_row, neighbor_column) != (row, column)
+                    && self.cells[self.get_index(neighbor_row, neighbor_column)] == WireCell::Head
+                {
+                    heads += 1;
+                }
+            }
+        }
+
+        heads
+    }
+}
+
+impl Display for Wireworld {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for line in self.cells.chunks(self.width.max(1) as usize) {
+            for cell in line {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_electron_moves_along_a_wire() {
+        let mut wireworld = Wireworld::from_text("TH##").unwrap();
+
+        wireworld.tick();
+        assert_eq!("#TH#\n", wireworld.render());
+
+        wireworld.tick();
+        assert_eq!("##TH\n", wireworld.render());
+        assert_eq!(2, wireworld.generation());
+        assert_eq!("", Wireworld::new(0, 2).render());
+    }
+
+    #[test]
+    fn test_conductor_with_three_heads_stays() {
+        let mut wireworld = Wireworld::from_text("HHH\n.#.").unwrap();
+
+        wireworld.tick();
+        assert_eq!(WireCell::Conductor, wireworld.get_cell(1, 1));
+        assert_eq!(WireCell::Tail, wireworld.get_cell(0, 1));
+    }
+
+    #[test]
+    fn test_from_text_errors() {
+        assert_eq!(
+            ParseError::new(2, 3, "unexpected character 'x'"),
+            Wireworld::from_text("! diode\n##x").unwrap_err()
+        );
+    }
+}
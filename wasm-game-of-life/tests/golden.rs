@@ -0,0 +1,80 @@
+// Known patterns checked against how they should look after a number of generations.
+// Each directory in tests/fixtures is one pattern: 0.rle is the board to start from and
+// every other N.rle is the same board after N ticks. A new case only needs its files,
+// the expected boards can be drawn in Golly and saved as RLE with the same dimensions.
+use std::fs;
+use std::path::Path;
+
+use wasm_game_of_life::Universe;
+
+fn load(path: &Path) -> Universe {
+    let rle = fs::read_to_string(path).unwrap();
+    Universe::from_rle(&rle).unwrap_or_else(|error| panic!("{}: {}", path.display(), error))
+}
+
+fn check_fixture(directory: &Path) {
+    let mut expected: Vec<(u32, Universe)> = fs::read_dir(directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter_map(|path| {
+            let generation = path.file_stem()?.to_str()?.parse().ok()?;
+            Some((generation, load(&path)))
+        })
+        .filter(|(generation, _)| *generation > 0)
+        .collect();
+    expected.sort_by_key(|(generation, _)| *generation);
+    assert!(
+        !expected.is_empty(),
+        "{} has nothing to compare against",
+        directory.display()
+    );
+
+    let mut universe = load(&directory.join("0.rle"));
+    for (generation, board) in expected {
+        universe.run_until_generation(generation);
+        assert_eq!(
+            board.to_rle(),
+            universe.to_rle(),
+            "{} at generation {}",
+            directory.display(),
+            generation
+        );
+    }
+}
+
+fn fixture(name: &str) {
+    check_fixture(
+        &Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name),
+    );
+}
+
+#[test]
+fn blinker_has_period_2() {
+    fixture("blinker");
+}
+
+#[test]
+fn glider_moves_diagonally() {
+    fixture("glider");
+}
+
+#[test]
+fn pulsar_has_period_3() {
+    fixture("pulsar");
+}
+
+#[test]
+fn gosper_glider_gun_emits_a_glider_every_30_generations() {
+    fixture("gosper_glider_gun");
+}
+
+// Catches a fixture directory that was added without a test of its own above
+#[test]
+fn every_fixture_passes() {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    for entry in fs::read_dir(fixtures).unwrap() {
+        check_fixture(&entry.unwrap().path());
+    }
+}
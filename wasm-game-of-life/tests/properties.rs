@@ -0,0 +1,133 @@
+// Invariants that should hold for any board, checked against random ones with proptest.
+// A failing case is shrunk to the smallest board that still breaks it.
+use proptest::prelude::*;
+use wasm_game_of_life::{BoundaryMode, Cell, Engine, Universe};
+
+const ENGINES: [Engine; 5] = [
+    Engine::Naive,
+    Engine::Packed,
+    Engine::HashLife,
+    Engine::Simd,
+    Engine::Parallel,
+];
+
+#[derive(Clone, Debug)]
+struct Board {
+    width: u32,
+    height: u32,
+    alive: Vec<bool>,
+    toroidal: bool,
+}
+
+impl Board {
+    fn universe(&self, engine: Engine) -> Universe {
+        let mut universe =
+            Universe::new_with_dimensions(self.width, self.height).with_engine(engine);
+        if self.toroidal {
+            universe.set_boundary_mode(BoundaryMode::Toroidal);
+        }
+        for (index, alive) in self.alive.iter().enumerate() {
+            if *alive {
+                let index = index as u32;
+                universe.set_cell(index / self.width, index % self.width, Cell::Alive);
+            }
+        }
+        universe
+    }
+}
+
+fn board() -> impl Strategy<Value = Board> {
+    (1u32..40, 1u32..40, any::<bool>()).prop_flat_map(|(width, height, toroidal)| {
+        prop::collection::vec(any::<bool>(), (width * height) as usize).prop_map(move |alive| {
+            Board {
+                width,
+                height,
+                alive,
+                toroidal,
+            }
+        })
+    })
+}
+
+proptest! {
+    #[test]
+    fn tick_is_deterministic(board in board(), generations in 1u32..20) {
+        let mut first = board.universe(Engine::Naive);
+        let mut second = board.universe(Engine::Naive);
+        first.tick_n(generations);
+        second.tick_n(generations);
+
+        prop_assert_eq!(first.cells(), second.cells());
+    }
+
+    #[test]
+    fn population_never_exceeds_area(board in board(), generations in 1u32..20) {
+        let mut universe = board.universe(Engine::Naive);
+        for _ in 0..generations {
+            universe.tick();
+            prop_assert!(universe.population() <= board.width * board.height);
+        }
+    }
+
+    #[test]
+    fn dead_universe_stays_dead(
+        width in 1u32..64,
+        height in 1u32..64,
+        generations in 1u32..10,
+    ) {
+        for engine in ENGINES.iter() {
+            let mut universe = Universe::new_with_dimensions(width, height).with_engine(*engine);
+            universe.tick_n(generations);
+            prop_assert_eq!(0, universe.population());
+        }
+    }
+
+    // On a torus there is no edge, so moving the board and then ticking gives the same
+    // cells as ticking and then moving it
+    #[test]
+    fn toroidal_ticks_ignore_translation(
+        board in board(),
+        row_shift in 0u32..40,
+        column_shift in 0u32..40,
+    ) {
+        let board = Board { toroidal: true, ..board };
+        let (width, height) = (board.width, board.height);
+        let mut shifted = board.clone();
+        for (index, alive) in board.alive.iter().enumerate() {
+            let (row, column) = (index as u32 / width, index as u32 % width);
+            let row = (row + row_shift) % height;
+            let column = (column + column_shift) % width;
+            shifted.alive[(row * width + column) as usize] = *alive;
+        }
+
+        let mut universe = board.universe(Engine::Naive);
+        let mut shifted = shifted.universe(Engine::Naive);
+        universe.tick();
+        shifted.tick();
+
+        for row in 0..height {
+            for column in 0..width {
+                prop_assert_eq!(
+                    universe.get_cell(row, column),
+                    shifted.get_cell((row + row_shift) % height, (column + column_shift) % width)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn engines_agree(board in board(), generations in 1u32..12) {
+        let mut expected = board.universe(Engine::Naive);
+        expected.tick_n(generations);
+
+        for engine in ENGINES[1..].iter() {
+            let mut universe = board.universe(*engine);
+            universe.tick_n(generations);
+            prop_assert!(
+                expected.diff(&universe).is_empty(),
+                "{:?} differs from Naive",
+                engine
+            );
+        }
+    }
+}
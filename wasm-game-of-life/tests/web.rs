@@ -3,11 +3,114 @@
 #![cfg(target_arch = "wasm32")]
 
 extern crate wasm_bindgen_test;
+use js_sys::{Uint32Array, Uint8Array, WebAssembly};
+use wasm_bindgen::{JsCast, JsValue};
 use wasm_bindgen_test::*;
+use wasm_game_of_life::{BoundaryMode, Cell, Pattern, Universe};
 
 wasm_bindgen_test_configure!(run_in_browser);
 
+// Reads the exported buffers the way index.js does, through a view over the wasm memory
+fn u32_view(ptr: *const u32, len: usize) -> Vec<u32> {
+    let memory: WebAssembly::Memory = wasm_bindgen::memory().unchecked_into();
+    Uint32Array::new_with_byte_offset_and_length(&memory.buffer(), ptr as u32, len as u32).to_vec()
+}
+
+fn u8_view(ptr: *const u8, len: usize) -> Vec<u8> {
+    let memory: WebAssembly::Memory = wasm_bindgen::memory().unchecked_into();
+    Uint8Array::new_with_byte_offset_and_length(&memory.buffer(), ptr as u32, len as u32).to_vec()
+}
+
 #[wasm_bindgen_test]
 fn pass() {
     assert_eq!(1 + 1, 2);
 }
+
+#[wasm_bindgen_test]
+fn new_universe_is_empty() {
+    let universe = Universe::new_with_dimensions(40, 10);
+
+    assert_eq!(40, universe.width());
+    assert_eq!(10, universe.height());
+    assert_eq!(0, universe.population());
+    assert_eq!(13, universe.bits_len());
+    assert!(u32_view(universe.bits_ptr(), universe.bits_len())
+        .iter()
+        .all(|word| *word == 0));
+}
+
+#[wasm_bindgen_test]
+fn blinker_oscillates() {
+    let mut universe = Universe::new(5);
+    universe.insert_pattern(Pattern::Blinker, 2, 1);
+
+    universe.tick();
+    assert_eq!(Cell::Alive, universe.get_cell(1, 2));
+    assert_eq!(Cell::Dead, universe.get_cell(2, 1));
+    assert_eq!(1, universe.generation());
+
+    universe.tick();
+    assert_eq!(Cell::Alive, universe.get_cell(2, 1));
+    assert_eq!(3, universe.population());
+}
+
+#[wasm_bindgen_test]
+fn bits_view_matches_cells() {
+    let mut universe = Universe::new_with_dimensions(37, 9);
+    universe.set_boundary_mode(BoundaryMode::Toroidal);
+    universe.randomize_seeded(9, 0.5);
+
+    let words = u32_view(universe.bits_ptr(), universe.bits_len());
+    for row in 0..9 {
+        for column in 0..37 {
+            let index = (row * 37 + column) as usize;
+            let alive = words[index / 32] & (1 << (index % 32)) != 0;
+            assert_eq!(Cell::from(alive), universe.get_cell(row, column));
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+fn randomize_seeded_is_repeatable() {
+    let mut first = Universe::new(32);
+    first.randomize_seeded(1234, 0.3);
+    let mut second = Universe::new(32);
+    second.randomize_seeded(1234, 0.3);
+
+    assert_eq!(
+        u32_view(first.bits_ptr(), first.bits_len()),
+        u32_view(second.bits_ptr(), second.bits_len())
+    );
+    assert!(first.population() > 0);
+}
+
+#[wasm_bindgen_test]
+fn changed_cells_view_lists_the_last_tick() {
+    let mut universe = Universe::new(5);
+    universe.insert_pattern(Pattern::Blinker, 2, 1);
+    universe.tick();
+
+    let mut changed = u32_view(universe.changed_cells_ptr(), universe.changed_cells_len());
+    changed.sort_unstable();
+    assert_eq!(vec![7, 11, 13, 17], changed);
+}
+
+#[wasm_bindgen_test]
+fn ages_and_states_views() {
+    let mut universe = Universe::new(4);
+    universe.insert_pattern(Pattern::Beacon, 0, 0);
+    universe.tick_n(2);
+
+    let ages = u32_view(universe.ages_ptr(), universe.ages_len());
+    assert_eq!(2, ages[0]);
+    let states = u8_view(universe.states_ptr(), universe.states_len());
+    assert_eq!(1, states[0]);
+    assert_eq!(16, states.len());
+}
+
+#[wasm_bindgen_test]
+fn parse_errors_become_javascript_errors() {
+    let error: JsValue = Universe::from_rle("#N no header").unwrap_err().into();
+
+    assert!(error.is_instance_of::<js_sys::Error>());
+}
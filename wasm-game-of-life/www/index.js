@@ -1,17 +1,20 @@
-import {Universe, alert_the_page} from "wasm-game-of-life";
+import {Universe, GameLoop, CanvasRenderer, InputController, alert_the_page} from "wasm-game-of-life";
+
+const CELL_SIZE = 5;
+const ALIVE_COLOR = '#000000';
+const DEAD_COLOR = '#FFFFFF';
 
-const preElement = document.querySelector('#game-of-live-canvas');
 const universe = Universe.new(32);
 universe.randomize();
+// the loop takes ownership of the universe, everything after this goes through the loop
+const gameLoop = GameLoop.new(universe);
+gameLoop.set_renderer(CanvasRenderer.new('game-of-life-canvas', CELL_SIZE, ALIVE_COLOR, DEAD_COLOR));
 
-requestAnimationFrame(renderLoop);
+// click or drag to draw, space pauses, n steps and r randomizes, see KeyBindings for the rest
+const input = new InputController(document.querySelector('#game-of-life-canvas'), gameLoop);
 
-function renderLoop() {
-    preElement.textContent = universe.render();
-    universe.tick();
-    requestAnimationFrame(renderLoop);
-}
+gameLoop.start();
 
-setInterval(() => universe.randomize(), 10000);
+setInterval(() => gameLoop.randomize(), 10000);
 
-// alert_the_page('hello world');
\ No newline at end of file
+// alert_the_page('hello world');